@@ -0,0 +1,80 @@
+//! Compares reading a large exact amount of bytes into a zeroed `Vec` with
+//! `read_exact_vec`, which reads into reserved capacity.
+//!
+//! A single 64 MiB `vec![0; n]` gets fresh zeroed pages from the OS, so the
+//! zeroing only shows when the allocator reuses memory, as in the chunked
+//! reads.
+//!
+//! Run with `cargo bench --bench read_exact_vec`.
+
+use omnom::prelude::*;
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+const LEN: usize = 64 << 20;
+const ROUNDS: u32 = 10;
+
+fn time(name: &str, mut f: impl FnMut() -> Vec<u8>) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let bytes = black_box(f());
+        best = best.min(start.elapsed());
+        drop(bytes);
+    }
+    let per_mib = best.as_secs_f64() * 1e3 / (LEN >> 20) as f64;
+    println!("{:<32} {:>10.2?} ({:.3} ms/MiB)", name, best, per_mib);
+}
+
+fn main() {
+    let bytes: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+    let path = std::env::temp_dir().join("omnom-read-exact-vec.bin");
+    fs::write(&path, &bytes).unwrap();
+
+    time("vec![0; n] + read_exact (slice)", || {
+        let mut out = vec![0; LEN];
+        (&bytes[..]).read_exact(&mut out).unwrap();
+        out
+    });
+    time("read_exact_vec (slice)", || {
+        (&bytes[..]).read_exact_vec(LEN).unwrap()
+    });
+    time("vec![0; n] + read_exact (file)", || {
+        let mut out = vec![0; LEN];
+        BufReader::new(File::open(&path).unwrap())
+            .read_exact(&mut out)
+            .unwrap();
+        out
+    });
+    time("read_exact_vec (file)", || {
+        BufReader::new(File::open(&path).unwrap())
+            .read_exact_vec(LEN)
+            .unwrap()
+    });
+
+    // Allocations of this size are recycled by the allocator, which then
+    // has to zero them for `vec![0; n]`, unlike fresh pages from the OS.
+    const CHUNK: usize = 256 << 10;
+    time("vec![0; n] + read_exact (chunks)", || {
+        let mut reader = &bytes[..];
+        let mut last = vec![];
+        for _ in 0..LEN / CHUNK {
+            let mut out = vec![0; CHUNK];
+            reader.read_exact(&mut out).unwrap();
+            last = black_box(out);
+        }
+        last
+    });
+    time("read_exact_vec (chunks)", || {
+        let mut reader = &bytes[..];
+        let mut last = vec![];
+        for _ in 0..LEN / CHUNK {
+            last = black_box(reader.read_exact_vec(CHUNK).unwrap());
+        }
+        last
+    });
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,46 @@
+//! Compares reading floats one at a time with the bulk methods.
+//!
+//! Run with `cargo bench --bench read_floats`.
+
+use omnom::prelude::*;
+use std::hint::black_box;
+use std::io::{BufReader, Cursor};
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1 << 20;
+const ROUNDS: u32 = 20;
+
+fn time(name: &str, mut f: impl FnMut() -> f32) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        black_box(f());
+        best = best.min(start.elapsed());
+    }
+    let per_value = best.as_secs_f64() * 1e9 / LEN as f64;
+    println!("{:<28} {:>10.2?} ({:.2} ns/value)", name, best, per_value);
+}
+
+fn main() {
+    let bytes: Vec<u8> = (0..LEN)
+        .flat_map(|i| (i as f32).to_be_bytes().to_vec())
+        .collect();
+    let mut out = vec![0.0_f32; LEN];
+
+    time("read_be::<u32> per value", || {
+        let mut reader = BufReader::new(&bytes[..]);
+        for value in out.iter_mut() {
+            *value = f32::from_bits(reader.read_be::<u32>().unwrap());
+        }
+        out[LEN - 1]
+    });
+    time("read_f32_into_be", || {
+        Cursor::new(&bytes).read_f32_into_be(&mut out).unwrap();
+        out[LEN - 1]
+    });
+    time("read_f32_into_be_buffered", || {
+        let mut reader = BufReader::new(&bytes[..]);
+        reader.read_f32_into_be_buffered(&mut out).unwrap();
+        out[LEN - 1]
+    });
+}
@@ -0,0 +1,40 @@
+//! Compares draining a reader with `read_while` and `read_remaining` against
+//! `Read::read_to_end`.
+//!
+//! Run with `cargo bench --bench read_while`.
+
+use omnom::prelude::*;
+use std::hint::black_box;
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+const LEN: usize = 64 << 20;
+const ROUNDS: u32 = 10;
+
+fn time(name: &str, mut f: impl FnMut(&mut Vec<u8>) -> usize) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let mut out = Vec::new();
+        let start = Instant::now();
+        black_box(f(black_box(&mut out)));
+        best = best.min(start.elapsed());
+    }
+    let per_mib = best.as_secs_f64() * 1e3 / (LEN >> 20) as f64;
+    println!("{:<24} {:>10.2?} ({:.3} ms/MiB)", name, best, per_mib);
+}
+
+fn main() {
+    let bytes: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+
+    time("read_to_end", |out| {
+        BufReader::new(&bytes[..]).read_to_end(out).unwrap()
+    });
+    time("read_while(|_| true)", |out| {
+        BufReader::new(&bytes[..])
+            .read_while(out, |_| true)
+            .unwrap()
+    });
+    time("read_remaining", |out| {
+        BufReader::new(&bytes[..]).read_remaining(out).unwrap()
+    });
+}
@@ -0,0 +1,50 @@
+//! Compares writing floats as text through `format!` with
+//! `write_ascii_float`.
+//!
+//! Run with `cargo bench --bench write_ascii_float --features ryu`.
+
+use omnom::prelude::*;
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1 << 18;
+const ROUNDS: u32 = 20;
+
+fn time(name: &str, mut f: impl FnMut() -> usize) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        black_box(f());
+        best = best.min(start.elapsed());
+    }
+    let per_value = best.as_secs_f64() * 1e9 / LEN as f64;
+    println!("{:<28} {:>10.2?} ({:.2} ns/value)", name, best, per_value);
+}
+
+fn main() {
+    let values: Vec<f64> = (0..LEN).map(|i| (i as f64).sqrt() * 1.1e-3).collect();
+    let mut out = Vec::with_capacity(LEN * 24);
+
+    time("write_all(format!(\"{}\"))", || {
+        out.clear();
+        for value in &values {
+            out.write_all(format!("{}", value).as_bytes()).unwrap();
+        }
+        out.len()
+    });
+    time("write!(\"{:?}\")", || {
+        out.clear();
+        for value in &values {
+            write!(out, "{:?}", value).unwrap();
+        }
+        out.len()
+    });
+    time("write_ascii_float", || {
+        out.clear();
+        for value in &values {
+            out.write_ascii_float(*value).unwrap();
+        }
+        out.len()
+    });
+}
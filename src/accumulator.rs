@@ -0,0 +1,179 @@
+use std::io::{self, BufRead, ErrorKind};
+
+/// A buffer that assembles tokens from a non-blocking reader, across as many
+/// reads as they take to arrive.
+///
+/// A non-blocking reader returns `ErrorKind::WouldBlock` whenever it has no
+/// more data for now, which often happens in the middle of a token. [`feed`]
+/// appends whatever data is available to the accumulator, and [`take_until`]
+/// and [`take_exact`] remove complete tokens from the front of it. A token
+/// that's still incomplete stays stored until a later `feed` completes it.
+///
+/// The accumulator holds at most `max_size` bytes, so a peer that never sends
+/// a delimiter can't make it grow without bound. Once it's full, `feed`
+/// stops reading and returns `FeedStatus::Full`.
+///
+/// [`feed`]: #method.feed
+/// [`take_until`]: #method.take_until
+/// [`take_exact`]: #method.take_exact
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{Accumulator, FeedStatus};
+///
+/// let mut acc = Accumulator::new(1024);
+/// assert_eq!(acc.feed(&mut &b"GET / HT"[..]).unwrap(), FeedStatus::Eof);
+/// assert_eq!(acc.take_until(b'\n'), None);
+///
+/// acc.feed(&mut &b"TP/1.1\nHost"[..]).unwrap();
+/// assert_eq!(acc.take_until(b'\n').unwrap(), b"GET / HTTP/1.1\n");
+/// assert_eq!(acc.as_slice(), b"Host");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    buf: Vec<u8>,
+    max_size: usize,
+}
+
+/// The outcome of [`Accumulator::feed`].
+///
+/// [`Accumulator::feed`]: struct.Accumulator.html#method.feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// The reader returned `ErrorKind::WouldBlock`, and has no more data for
+    /// now.
+    WouldBlock,
+    /// The end of the stream was reached.
+    Eof,
+    /// The accumulator holds its maximum size. The reader may have more data.
+    Full,
+}
+
+impl Accumulator {
+    /// Create a new, empty accumulator that holds at most `max_size` bytes.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            max_size,
+        }
+    }
+
+    /// Append all data the reader has available.
+    ///
+    /// This reads until the reader returns `ErrorKind::WouldBlock`, reaches
+    /// EOF, or the accumulator is full, and returns which of those happened.
+    /// Reads that fail with `ErrorKind::Interrupted` are retried, and any other
+    /// error is returned. The data read until then stays accumulated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::{Accumulator, FeedStatus};
+    ///
+    /// let mut acc = Accumulator::new(4);
+    /// assert_eq!(acc.feed(&mut &b"lorem"[..]).unwrap(), FeedStatus::Full);
+    /// assert_eq!(acc.as_slice(), b"lore");
+    /// ```
+    pub fn feed<R: BufRead + ?Sized>(&mut self, reader: &mut R) -> io::Result<FeedStatus> {
+        loop {
+            let room = self.max_size.saturating_sub(self.buf.len());
+            if room == 0 {
+                return Ok(FeedStatus::Full);
+            }
+            let available = match reader.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    return Ok(FeedStatus::WouldBlock)
+                }
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                return Ok(FeedStatus::Eof);
+            }
+
+            let len = available.len().min(room);
+            self.buf.extend_from_slice(&available[..len]);
+            reader.consume(len);
+        }
+    }
+
+    /// Remove and return the bytes up to, and including, the first
+    /// occurrence of the delimiter `byte`.
+    ///
+    /// Returns `None` if the accumulated data doesn't contain the delimiter
+    /// yet, and leaves the data in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Accumulator;
+    ///
+    /// let mut acc = Accumulator::new(64);
+    /// acc.feed(&mut &b"a,b"[..]).unwrap();
+    /// assert_eq!(acc.take_until(b',').unwrap(), b"a,");
+    /// assert_eq!(acc.take_until(b','), None);
+    /// ```
+    pub fn take_until(&mut self, byte: u8) -> Option<Vec<u8>> {
+        let i = memchr::memchr(byte, &self.buf)?;
+        Some(self.buf.drain(..=i).collect())
+    }
+
+    /// Remove and return the first `n` bytes.
+    ///
+    /// Returns `None` if fewer than `n` bytes are accumulated yet, and leaves
+    /// the data in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Accumulator;
+    ///
+    /// let mut acc = Accumulator::new(64);
+    /// acc.feed(&mut &b"\x00\x02hi"[..]).unwrap();
+    /// assert_eq!(acc.take_exact(2).unwrap(), [0, 2]);
+    /// assert_eq!(acc.take_exact(3), None);
+    /// ```
+    pub fn take_exact(&mut self, n: usize) -> Option<Vec<u8>> {
+        if self.buf.len() < n {
+            return None;
+        }
+        Some(self.buf.drain(..n).collect())
+    }
+
+    /// Returns the accumulated bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the amount of bytes accumulated.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if no bytes are accumulated.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns `true` if the accumulator holds its maximum size.
+    pub fn is_full(&self) -> bool {
+        self.buf.len() >= self.max_size
+    }
+
+    /// Returns the maximum amount of bytes the accumulator holds.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Remove all accumulated bytes.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    /// Returns the accumulated bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buf
+    }
+}
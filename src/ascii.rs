@@ -0,0 +1,431 @@
+//! Helpers for parsing ASCII text from a `BufRead`.
+
+use std::convert::TryFrom;
+use std::io::{self, BufRead, ErrorKind};
+use std::net::Ipv4Addr;
+
+use crate::mac_addr::hex_value;
+use crate::{BoolStyle, MacAddr};
+
+/// Returns the next byte without consuming it, or `None` at EOF.
+pub(crate) fn peek<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Option<u8>> {
+    loop {
+        match reader.fill_buf() {
+            Ok(available) => return Ok(available.first().copied()),
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Consume the next byte if it's one of `bytes`, returning it.
+pub(crate) fn next_if<R: BufRead + ?Sized>(reader: &mut R, bytes: &[u8]) -> io::Result<Option<u8>> {
+    match peek(reader)? {
+        Some(byte) if bytes.contains(&byte) => {
+            reader.consume(1);
+            Ok(Some(byte))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The magnitude of an integer read by `read_digits`.
+pub(crate) struct Digits {
+    /// The value of the digits, or `None` if it overflowed a `u128`.
+    pub(crate) value: Option<u128>,
+    /// The amount of digits read, not counting underscores.
+    pub(crate) count: usize,
+}
+
+/// Read a run of digits in `radix`, stopping at the first other byte without
+/// consuming it.
+///
+/// If `underscores` is set, underscores are consumed and ignored once a digit
+/// has been read, or from the start if `leading_underscores` is also set. The
+/// whole run is consumed even if its value overflows.
+pub(crate) fn read_digits<R: BufRead + ?Sized>(
+    reader: &mut R,
+    radix: u32,
+    underscores: bool,
+    leading_underscores: bool,
+) -> io::Result<Digits> {
+    let mut digits = Digits {
+        value: Some(0),
+        count: 0,
+    };
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            break;
+        }
+
+        let mut len = 0;
+        for &byte in available {
+            if byte == b'_' && underscores && (digits.count > 0 || leading_underscores) {
+                len += 1;
+                continue;
+            }
+            let digit = match (byte as char).to_digit(radix) {
+                Some(digit) => digit,
+                None => break,
+            };
+            digits.value = digits
+                .value
+                .and_then(|v| v.checked_mul(u128::from(radix)))
+                .and_then(|v| v.checked_add(u128::from(digit)));
+            digits.count += 1;
+            len += 1;
+        }
+        let done = len < available.len();
+        reader.consume(len);
+        if done {
+            break;
+        }
+    }
+    Ok(digits)
+}
+
+/// Read an integer literal with an optional radix prefix.
+///
+/// Returns whether a `-` sign was read, which is only accepted if `signed`
+/// is set, together with the magnitude.
+pub(crate) fn read_radix_literal<R: BufRead + ?Sized>(
+    reader: &mut R,
+    signed: bool,
+) -> io::Result<(bool, u64)> {
+    let negative = signed && next_if(reader, b"+-")? == Some(b'-');
+
+    let mut leading_zero = false;
+    let mut radix = 10;
+    if next_if(reader, b"0")?.is_some() {
+        radix = match next_if(reader, b"xXoObB")? {
+            Some(b'x') | Some(b'X') => 16,
+            Some(b'o') | Some(b'O') => 8,
+            Some(b'b') | Some(b'B') => 2,
+            _ => {
+                leading_zero = true;
+                10
+            }
+        };
+    }
+
+    let prefixed = radix != 10;
+    let mut digits = read_digits(reader, radix, true, prefixed || leading_zero)?;
+    if leading_zero {
+        digits.count += 1;
+    }
+    if digits.count == 0 {
+        let msg = if prefixed {
+            "expected digits after the radix prefix"
+        } else {
+            "expected an integer"
+        };
+        return Err(io::Error::new(ErrorKind::InvalidData, msg));
+    }
+    match digits.value.and_then(|v| u64::try_from(v).ok()) {
+        Some(value) => Ok((negative, value)),
+        None => Err(overflow()),
+    }
+}
+
+/// Read an optionally signed decimal integer, as an `i128`.
+///
+/// Returns `Ok(None)` if the integer doesn't fit in an `i128`.
+pub(crate) fn read_decimal<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Option<i128>> {
+    let negative = next_if(reader, b"+-")? == Some(b'-');
+    let digits = read_digits(reader, 10, false, false)?;
+    if digits.count == 0 {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "expected an integer",
+        ));
+    }
+    Ok(digits.value.and_then(|magnitude| {
+        if negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }))
+}
+
+pub(crate) fn overflow() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "integer is out of range")
+}
+
+/// Read one of the literals accepted by `style`, followed by a byte that isn't
+/// ASCII alphanumeric or by EOF.
+///
+/// Nothing is consumed on a mismatch, unless the input straddles the reader's
+/// buffer or ends: the bytes from earlier windows stay consumed then.
+pub(crate) fn read_bool<R: BufRead + ?Sized>(reader: &mut R, style: BoolStyle) -> io::Result<bool> {
+    let eq = |a: &[u8], b: &[u8]| {
+        if style.is_case_insensitive() {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    };
+    let matches_exactly = |read: &[u8]| {
+        style
+            .literals()
+            .find(|(literal, _)| eq(literal, read))
+            .map(|(_, value)| value)
+    };
+    let is_prefix = |read: &[u8]| {
+        style
+            .literals()
+            .any(|(literal, _)| literal.len() >= read.len() && eq(&literal[..read.len()], read))
+    };
+
+    // Long enough for the longest literal, `false`.
+    let mut read = [0; 5];
+    let mut len = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return matches_exactly(&read[..len]).ok_or_else(|| expected_bool(style));
+        }
+        for (i, &byte) in available.iter().enumerate() {
+            if !byte.is_ascii_alphanumeric() {
+                if let Some(value) = matches_exactly(&read[..len]) {
+                    reader.consume(i);
+                    return Ok(value);
+                }
+            }
+            if len == read.len() {
+                return Err(expected_bool(style));
+            }
+            read[len] = byte;
+            len += 1;
+            if !is_prefix(&read[..len]) {
+                return Err(expected_bool(style));
+            }
+        }
+        let n = available.len();
+        reader.consume(n);
+    }
+}
+
+fn expected_bool(style: BoolStyle) -> io::Error {
+    let literals: Vec<_> = style
+        .literals()
+        .map(|(literal, _)| String::from_utf8_lossy(literal))
+        .collect();
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("expected one of {}", literals.join(", ")),
+    )
+}
+
+/// Read a dotted-quad IPv4 address, without consuming the byte that failed to
+/// parse on error.
+pub(crate) fn read_ipv4<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Ipv4Addr> {
+    let mut octets = [0; 4];
+    for (i, octet) in octets.iter_mut().enumerate() {
+        if i > 0 && next_if(reader, b".")?.is_none() {
+            return Err(invalid_ipv4("expected 4 octets"));
+        }
+        *octet = read_octet(reader)?;
+    }
+    match peek(reader)? {
+        Some(b'.') => Err(invalid_ipv4("expected 4 octets")),
+        Some(byte) if byte.is_ascii_digit() => Err(invalid_ipv4("octet is out of range")),
+        _ => Ok(Ipv4Addr::from(octets)),
+    }
+}
+
+/// Read a decimal octet without leading zeros.
+fn read_octet<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<u8> {
+    let mut value: Option<u8> = None;
+    while let Some(byte) = peek(reader)? {
+        if !byte.is_ascii_digit() {
+            break;
+        }
+        value = match value {
+            None => Some(byte - b'0'),
+            Some(0) => return Err(invalid_ipv4("octet has a leading zero")),
+            Some(v) => match v.checked_mul(10).and_then(|v| v.checked_add(byte - b'0')) {
+                Some(v) => Some(v),
+                None => return Err(invalid_ipv4("octet is out of range")),
+            },
+        };
+        reader.consume(1);
+    }
+    value.ok_or_else(|| invalid_ipv4("expected an octet"))
+}
+
+fn invalid_ipv4(reason: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid IPv4 address: {}", reason),
+    )
+}
+
+/// Read a MAC address separated by either colons or hyphens.
+pub(crate) fn read_mac<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<MacAddr> {
+    let mut octets = [0; 6];
+    let mut separator = None;
+    for (i, octet) in octets.iter_mut().enumerate() {
+        if i > 0 {
+            // The first separator decides which one the others must be.
+            let allowed = match separator {
+                Some(b':') => &b":"[..],
+                Some(_) => &b"-"[..],
+                None => &b":-"[..],
+            };
+            separator = next_if(reader, allowed)?;
+            if separator.is_none() {
+                return Err(invalid_mac("expected 6 octets"));
+            }
+        }
+        for _ in 0..2 {
+            match peek(reader)?.and_then(hex_value) {
+                Some(digit) => *octet = *octet << 4 | digit,
+                None => return Err(invalid_mac("expected 2 hex digits per octet")),
+            }
+            reader.consume(1);
+        }
+    }
+    match peek(reader)? {
+        Some(byte) if Some(byte) == separator => Err(invalid_mac("expected 6 octets")),
+        Some(byte) if byte.is_ascii_hexdigit() => {
+            Err(invalid_mac("expected 2 hex digits per octet"))
+        }
+        _ => Ok(MacAddr(octets)),
+    }
+}
+
+fn invalid_mac(reason: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid MAC address: {}", reason),
+    )
+}
+
+/// The characters outside of ASCII for which `char::is_whitespace` holds.
+const UNICODE_WHITESPACE: [char; 19] = [
+    '\u{85}', '\u{a0}', '\u{1680}', '\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}',
+    '\u{2005}', '\u{2006}', '\u{2007}', '\u{2008}', '\u{2009}', '\u{200a}', '\u{2028}', '\u{2029}',
+    '\u{202f}', '\u{205f}', '\u{3000}',
+];
+
+/// Skip UTF-8 encoded whitespace, stopping before the first character that
+/// isn't whitespace or isn't valid UTF-8.
+///
+/// A character that straddles the reader's buffer is only read if its leading
+/// bytes could start a whitespace character. If it then turns out not to be
+/// whitespace, or is cut off by EOF, an error is returned since its leading
+/// bytes were consumed.
+pub(crate) fn skip_unicode_whitespace<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<usize> {
+    let mut skipped = 0;
+    // The leading bytes of a character that straddles the reader's buffer.
+    let mut partial = [0; 4];
+    let mut partial_len = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            if partial_len > 0 {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "stream ended within a character, after consuming {} bytes of it",
+                        partial_len
+                    ),
+                ));
+            }
+            return Ok(skipped);
+        }
+
+        let mut i = 0;
+        if partial_len > 0 {
+            let width = utf8_width(partial[0]);
+            let n = (width - partial_len).min(available.len());
+            partial[partial_len..partial_len + n].copy_from_slice(&available[..n]);
+            if !is_whitespace_prefix(&partial[..partial_len + n]) {
+                return Err(split_char(partial_len));
+            }
+            partial_len += n;
+            if partial_len == width {
+                partial_len = 0;
+            }
+            i = n;
+        }
+
+        while i < available.len() {
+            let width = utf8_width(available[i]);
+            let end = i + width;
+            if width == 0 {
+                break;
+            } else if end > available.len() {
+                let tail = &available[i..];
+                if !is_whitespace_prefix(tail) {
+                    break;
+                }
+                partial[..tail.len()].copy_from_slice(tail);
+                partial_len = tail.len();
+                i = available.len();
+            } else if is_whitespace(&available[i..end]) {
+                i = end;
+            } else {
+                break;
+            }
+        }
+        let done = i < available.len();
+        reader.consume(i);
+        skipped += i;
+        if done {
+            return Ok(skipped);
+        }
+    }
+}
+
+/// Returns the length of the UTF-8 sequence starting with `byte`, or `0` if it
+/// can't start one.
+fn utf8_width(byte: u8) -> usize {
+    match byte {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => 0,
+    }
+}
+
+/// Returns `true` if `bytes` encode a single whitespace character.
+fn is_whitespace(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.chars().all(char::is_whitespace),
+        Err(_) => false,
+    }
+}
+
+/// Returns `true` if `bytes` start the encoding of a non-ASCII whitespace
+/// character.
+fn is_whitespace_prefix(bytes: &[u8]) -> bool {
+    UNICODE_WHITESPACE
+        .iter()
+        .any(|c| c.encode_utf8(&mut [0; 4]).as_bytes().starts_with(bytes))
+}
+
+fn split_char(consumed: usize) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "character split across reads isn't whitespace, after consuming {} bytes of it",
+            consumed
+        ),
+    )
+}
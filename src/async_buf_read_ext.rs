@@ -0,0 +1,339 @@
+use std::cmp;
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use futures_io::AsyncBufRead;
+
+/// Extend `AsyncBufRead` with methods for streaming parsing.
+///
+/// The peeking methods never consume bytes, which means they can only look at
+/// the bytes the reader has buffered: the slice returned by a single
+/// `poll_fill_buf` call. Peeking further ahead requires consuming bytes, for
+/// example through an [`AsyncCheckpoint`] that can roll them back.
+///
+/// [`AsyncCheckpoint`]: struct.AsyncCheckpoint.html
+pub trait AsyncBufReadExt: AsyncBufRead {
+    /// Returns the next byte without consuming it, or `None` at EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor};
+    /// use omnom::AsyncBufReadExt;
+    ///
+    /// let mut reader = Cursor::new(b"\x16\x03\x01");
+    /// assert_eq!(reader.peek_byte().await.unwrap(), Some(0x16));
+    ///
+    /// let mut buf = Vec::new();
+    /// reader.read_to_end(&mut buf).await.unwrap();
+    /// assert_eq!(buf, b"\x16\x03\x01");
+    /// assert_eq!(reader.peek_byte().await.unwrap(), None);
+    /// # });
+    /// ```
+    fn peek_byte(&mut self) -> PeekByte<'_, Self>
+    where
+        Self: Unpin,
+    {
+        PeekByte { reader: self }
+    }
+
+    /// Copy the next bytes into `buf` without consuming them.
+    ///
+    /// Returns the amount of bytes copied, which is less than `buf.len()` if
+    /// the reader has fewer bytes buffered, and `0` at EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::Cursor;
+    /// use omnom::AsyncBufReadExt;
+    ///
+    /// let mut reader = Cursor::new(b"GET / HTTP/1.1");
+    /// let mut buf = [0; 4];
+    /// assert_eq!(reader.peek(&mut buf).await.unwrap(), 4);
+    /// assert_eq!(&buf, b"GET ");
+    /// # });
+    /// ```
+    fn peek<'a>(&'a mut self, buf: &'a mut [u8]) -> Peek<'a, Self>
+    where
+        Self: Unpin,
+    {
+        Peek { reader: self, buf }
+    }
+
+    /// Copy the next bytes up to and including `byte` into `buf`, without
+    /// consuming them.
+    ///
+    /// Returns the amount of bytes copied. The delimiter was found if the
+    /// last byte appended to `buf` is `byte`; otherwise all buffered bytes
+    /// were copied without encountering it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::Cursor;
+    /// use omnom::AsyncBufReadExt;
+    ///
+    /// let mut reader = Cursor::new(b"lorem-ipsum");
+    /// let mut buf = vec![];
+    /// assert_eq!(reader.fill_until(b'-', &mut buf).await.unwrap(), 6);
+    /// assert_eq!(buf, b"lorem-");
+    /// # });
+    /// ```
+    fn fill_until<'a>(&'a mut self, byte: u8, buf: &'a mut Vec<u8>) -> FillUntil<'a, Self>
+    where
+        Self: Unpin,
+    {
+        FillUntil {
+            reader: self,
+            byte,
+            buf,
+        }
+    }
+
+    /// Returns a stream over the records of this reader, split on `delim`.
+    ///
+    /// Each record is yielded without its delimiter. A trailing record that
+    /// isn't terminated by `delim` is yielded once EOF is reached, unless it's
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::Cursor;
+    /// use futures::stream::TryStreamExt;
+    /// use omnom::AsyncBufReadExt;
+    ///
+    /// let reader = Cursor::new(b"lorem\nipsum\ndolor");
+    /// let records: Vec<Vec<u8>> = reader.records(b'\n').try_collect().await.unwrap();
+    /// assert_eq!(records, vec![b"lorem".to_vec(), b"ipsum".to_vec(), b"dolor".to_vec()]);
+    /// # });
+    /// ```
+    fn records(self, delim: u8) -> RecordStream<Self>
+    where
+        Self: Sized,
+    {
+        RecordStream {
+            reader: self,
+            delim,
+            buf: Vec::new(),
+            max_len: None,
+            discarding: false,
+            done: false,
+        }
+    }
+}
+
+impl<T: AsyncBufRead + ?Sized> AsyncBufReadExt for T {}
+
+fn poll_fill_buf<'a, R>(reader: &'a mut R, cx: &mut Context<'_>) -> Poll<io::Result<&'a [u8]>>
+where
+    R: AsyncBufRead + Unpin + ?Sized,
+{
+    Pin::new(reader).poll_fill_buf(cx)
+}
+
+/// Future for the [`AsyncBufReadExt::peek_byte`] method.
+///
+/// [`AsyncBufReadExt::peek_byte`]: trait.AsyncBufReadExt.html#method.peek_byte
+#[derive(Debug)]
+pub struct PeekByte<'a, R: ?Sized> {
+    reader: &'a mut R,
+}
+
+impl<R: AsyncBufRead + Unpin + ?Sized> Future for PeekByte<'_, R> {
+    type Output = io::Result<Option<u8>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match poll_fill_buf(this.reader, cx) {
+            Poll::Ready(Ok(available)) => Poll::Ready(Ok(available.first().copied())),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Future for the [`AsyncBufReadExt::peek`] method.
+///
+/// [`AsyncBufReadExt::peek`]: trait.AsyncBufReadExt.html#method.peek
+#[derive(Debug)]
+pub struct Peek<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+}
+
+impl<R: AsyncBufRead + Unpin + ?Sized> Future for Peek<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match poll_fill_buf(this.reader, cx) {
+            Poll::Ready(Ok(available)) => {
+                let n = cmp::min(available.len(), this.buf.len());
+                this.buf[..n].copy_from_slice(&available[..n]);
+                Poll::Ready(Ok(n))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Future for the [`AsyncBufReadExt::fill_until`] method.
+///
+/// [`AsyncBufReadExt::fill_until`]: trait.AsyncBufReadExt.html#method.fill_until
+#[derive(Debug)]
+pub struct FillUntil<'a, R: ?Sized> {
+    reader: &'a mut R,
+    byte: u8,
+    buf: &'a mut Vec<u8>,
+}
+
+impl<R: AsyncBufRead + Unpin + ?Sized> Future for FillUntil<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match poll_fill_buf(this.reader, cx) {
+            Poll::Ready(Ok(available)) => {
+                let n = match memchr::memchr(this.byte, available) {
+                    Some(i) => i + 1,
+                    None => available.len(),
+                };
+                this.buf.extend_from_slice(&available[..n]);
+                Poll::Ready(Ok(n))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A stream over the delimited records of an `AsyncBufRead`.
+///
+/// This stream is created by [`AsyncBufReadExt::records`].
+///
+/// [`AsyncBufReadExt::records`]: trait.AsyncBufReadExt.html#method.records
+#[derive(Debug)]
+pub struct RecordStream<R> {
+    reader: R,
+    delim: u8,
+    buf: Vec<u8>,
+    max_len: Option<usize>,
+    discarding: bool,
+    done: bool,
+}
+
+impl<R> RecordStream<R> {
+    /// Limit the length of a single record, not counting the delimiter.
+    ///
+    /// A record exceeding the limit yields an error of the kind
+    /// `ErrorKind::InvalidData`. The rest of the overlong record is then
+    /// skipped, and the stream resumes with the record that follows it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use futures::io::Cursor;
+    /// use futures::stream::StreamExt;
+    /// use omnom::AsyncBufReadExt;
+    ///
+    /// let reader = Cursor::new(b"lorem ipsum\ndolor\n");
+    /// let mut records = reader.records(b'\n').max_len(5);
+    /// assert!(records.next().await.unwrap().is_err());
+    /// assert_eq!(records.next().await.unwrap().unwrap(), b"dolor");
+    /// # });
+    /// ```
+    pub fn max_len(mut self, max: usize) -> Self {
+        self.max_len = Some(max);
+        self
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Unwraps this `RecordStream`, returning the underlying reader.
+    ///
+    /// The bytes of a partially read record are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn check_len(&mut self) -> io::Result<()> {
+        match self.max_len {
+            Some(max) if self.buf.len() > max => {
+                self.buf.clear();
+                Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("record exceeds the maximum length of {} bytes", max),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for RecordStream<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let available = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+                Poll::Ready(Ok(available)) => available,
+                Poll::Ready(Err(ref e)) if e.kind() == ErrorKind::Interrupted => continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if available.is_empty() {
+                this.done = true;
+                if this.discarding || this.buf.is_empty() {
+                    return Poll::Ready(None);
+                }
+                return Poll::Ready(Some(Ok(mem::take(&mut this.buf))));
+            }
+
+            match memchr::memchr(this.delim, available) {
+                Some(i) => {
+                    if !this.discarding {
+                        this.buf.extend_from_slice(&available[..i]);
+                    }
+                    Pin::new(&mut this.reader).consume(i + 1);
+                    if mem::replace(&mut this.discarding, false) {
+                        continue;
+                    }
+                    let record = this.check_len().map(|_| mem::take(&mut this.buf));
+                    return Poll::Ready(Some(record));
+                }
+                None => {
+                    let len = available.len();
+                    if !this.discarding {
+                        this.buf.extend_from_slice(available);
+                    }
+                    Pin::new(&mut this.reader).consume(len);
+                    if let Err(e) = this.check_len() {
+                        this.discarding = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+        }
+    }
+}
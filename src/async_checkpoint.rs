@@ -0,0 +1,187 @@
+use std::cmp;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncBufRead, AsyncRead};
+
+/// An `AsyncBufRead` wrapper that can roll back to a checkpoint.
+///
+/// While a checkpoint is active every byte read from the inner reader is
+/// recorded in a replay buffer, so that a parser can speculatively try one
+/// grammar production and fall back to another on failure. After a
+/// [`rollback`] the recorded bytes are yielded again, in order, by subsequent
+/// `poll_fill_buf` calls before any new bytes are read from the inner reader.
+///
+/// Checkpoints nest: each call to [`checkpoint`] must be paired with either
+/// [`commit`] or [`rollback`], which apply to the most recent checkpoint. The
+/// replay buffer is released once the outermost checkpoint is resolved.
+///
+/// [`checkpoint`]: #method.checkpoint
+/// [`commit`]: #method.commit
+/// [`rollback`]: #method.rollback
+///
+/// # Examples
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncReadExt, Cursor};
+/// use omnom::AsyncCheckpoint;
+///
+/// let mut reader = AsyncCheckpoint::new(Cursor::new(b"POST /"));
+///
+/// reader.checkpoint();
+/// let mut method = [0; 4];
+/// reader.read_exact(&mut method).await.unwrap();
+/// assert_ne!(&method, b"GET ");
+/// reader.rollback();
+///
+/// let mut method = [0; 5];
+/// reader.read_exact(&mut method).await.unwrap();
+/// assert_eq!(&method, b"POST ");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncCheckpoint<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    checkpoints: Vec<usize>,
+}
+
+impl<R> AsyncCheckpoint<R> {
+    /// Create a new instance.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Start recording consumed bytes so they can be rolled back.
+    pub fn checkpoint(&mut self) {
+        self.checkpoints.push(self.pos);
+    }
+
+    /// Rewind the reader to the most recent checkpoint.
+    ///
+    /// All bytes consumed since the checkpoint will be yielded again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no checkpoint is active.
+    pub fn rollback(&mut self) {
+        self.pos = self
+            .checkpoints
+            .pop()
+            .expect("rollback called without an active checkpoint");
+        self.release();
+    }
+
+    /// Keep all bytes consumed since the most recent checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no checkpoint is active.
+    pub fn commit(&mut self) {
+        self.checkpoints
+            .pop()
+            .expect("commit called without an active checkpoint");
+        self.release();
+    }
+
+    /// Returns the number of active checkpoints.
+    pub fn depth(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Returns the number of bytes held in the replay buffer.
+    pub fn replay_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader bypasses the replay buffer.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `AsyncCheckpoint`, returning the underlying reader.
+    ///
+    /// Bytes that were rolled back but not yet consumed again are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Drop recorded bytes once no checkpoint needs them anymore.
+    fn release(&mut self) {
+        if self.checkpoints.is_empty() {
+            self.buf.drain(..self.pos);
+            self.buf.shrink_to_fit();
+            self.pos = 0;
+        }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncRead for AsyncCheckpoint<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(available)) => available,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let n = cmp::min(available.len(), out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for AsyncCheckpoint<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.pos < this.buf.len() {
+            return Poll::Ready(Ok(&this.buf[this.pos..]));
+        }
+
+        if this.checkpoints.is_empty() {
+            this.buf.clear();
+            this.pos = 0;
+            return Pin::new(&mut this.inner).poll_fill_buf(cx);
+        }
+
+        // Move the next chunk into the replay buffer so it can be yielded
+        // again after a rollback.
+        let len = match Pin::new(&mut this.inner).poll_fill_buf(cx) {
+            Poll::Ready(Ok(chunk)) => {
+                this.buf.extend_from_slice(chunk);
+                chunk.len()
+            }
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        Pin::new(&mut this.inner).consume(len);
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        if this.pos < this.buf.len() {
+            this.pos = cmp::min(this.pos + amt, this.buf.len());
+        } else {
+            Pin::new(&mut this.inner).consume(amt);
+        }
+    }
+}
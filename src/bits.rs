@@ -0,0 +1,320 @@
+use std::io::{self, BufRead, ErrorKind, Write};
+
+/// The most leading zeros an Exp-Golomb code of a `u32` has.
+const MAX_LEADING_ZEROS: u32 = 31;
+
+/// A reader of the bits of a `BufRead`, most significant bit first.
+///
+/// This is the bit order of video bitstreams such as H.264 and H.265, whose
+/// headers [`read_ue`] and [`read_se`] parse. Bits are taken from one byte
+/// of the underlying reader at a time, so values may span bytes and
+/// `fill_buf` chunks.
+///
+/// [`read_ue`]: #method.read_ue
+/// [`read_se`]: #method.read_se
+///
+/// # Examples
+///
+/// ```
+/// use omnom::BitReader;
+///
+/// let mut reader = BitReader::new(&[0b1011_0010][..]);
+/// assert_eq!(reader.read_bit().unwrap(), true);
+/// assert_eq!(reader.read_bits(3).unwrap(), 0b011);
+/// assert_eq!(reader.read_bits(4).unwrap(), 0b0010);
+/// ```
+#[derive(Debug)]
+pub struct BitReader<R> {
+    inner: R,
+    /// The bits of the current byte that are left, in the high bits.
+    byte: u8,
+    bits_left: u32,
+}
+
+impl<R: BufRead> BitReader<R> {
+    /// Create a new `BitReader`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            bits_left: 0,
+        }
+    }
+
+    /// Read a bit.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` if the reader has no bits left.
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        if self.bits_left == 0 {
+            self.byte = self.next_byte()?;
+            self.bits_left = 8;
+        }
+        let bit = self.byte & 0x80 != 0;
+        self.byte <<= 1;
+        self.bits_left -= 1;
+        Ok(bit)
+    }
+
+    /// Read `n` bits as an unsigned integer, the first bit being the most
+    /// significant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 32.
+    pub fn read_bits(&mut self, n: u32) -> io::Result<u32> {
+        assert!(n <= 32, "can't read {} bits into a u32", n);
+        let mut value = 0_u64;
+        for _ in 0..n {
+            value = value << 1 | u64::from(self.read_bit()?);
+        }
+        Ok(value as u32)
+    }
+
+    /// Read an unsigned Exp-Golomb code, the `ue(v)` of H.264 and H.265.
+    ///
+    /// The code is a number of zero bits, a one bit, and as many bits as
+    /// there were zeros. Codes with more than 31 zeros don't fit a `u32`,
+    /// and return `ErrorKind::InvalidData`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BitReader;
+    ///
+    /// // 1, 010, 011, 00100
+    /// let mut reader = BitReader::new(&[0b1010_0110, 0b0100_0000][..]);
+    /// assert_eq!(reader.read_ue().unwrap(), 0);
+    /// assert_eq!(reader.read_ue().unwrap(), 1);
+    /// assert_eq!(reader.read_ue().unwrap(), 2);
+    /// assert_eq!(reader.read_ue().unwrap(), 3);
+    /// ```
+    pub fn read_ue(&mut self) -> io::Result<u32> {
+        let mut zeros = 0;
+        while !self.read_bit()? {
+            zeros += 1;
+            if zeros > MAX_LEADING_ZEROS {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Exp-Golomb code has more than {} leading zeros",
+                        MAX_LEADING_ZEROS
+                    ),
+                ));
+            }
+        }
+        let suffix = self.read_bits(zeros)?;
+        Ok(((1_u64 << zeros) - 1 + u64::from(suffix)) as u32)
+    }
+
+    /// Read a signed Exp-Golomb code, the `se(v)` of H.264 and H.265.
+    ///
+    /// The unsigned codes 0, 1, 2, 3, 4 map to 0, 1, -1, 2, -2, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BitReader;
+    ///
+    /// // 1, 010, 011
+    /// let mut reader = BitReader::new(&[0b1010_0110][..]);
+    /// assert_eq!(reader.read_se().unwrap(), 0);
+    /// assert_eq!(reader.read_se().unwrap(), 1);
+    /// assert_eq!(reader.read_se().unwrap(), -1);
+    /// ```
+    pub fn read_se(&mut self) -> io::Result<i32> {
+        let code = i64::from(self.read_ue()?);
+        let value = if code % 2 == 1 {
+            (code + 1) / 2
+        } else {
+            -code / 2
+        };
+        Ok(value as i32)
+    }
+
+    /// Returns `true` if the reader is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.bits_left == 0
+    }
+
+    /// Skip the rest of the current byte, returning the amount of bits
+    /// skipped.
+    pub fn align(&mut self) -> u32 {
+        let skipped = self.bits_left;
+        self.bits_left = 0;
+        skipped
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// The bits of the current byte that weren't read yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn next_byte(&mut self) -> io::Result<u8> {
+        loop {
+            let available = match self.inner.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let byte = *available.first().ok_or_else(|| {
+                io::Error::new(ErrorKind::UnexpectedEof, "bit reader reached EOF")
+            })?;
+            self.inner.consume(1);
+            return Ok(byte);
+        }
+    }
+}
+
+/// A writer of bits to a `Write`, most significant bit first.
+///
+/// This writes what [`BitReader`] reads. Bits are collected into a byte,
+/// which is written once it's full. [`finish`] pads the last byte with zero
+/// bits and writes it, and a `BitWriter` that's dropped without calling it
+/// loses the bits of that byte.
+///
+/// [`BitReader`]: struct.BitReader.html
+/// [`finish`]: #method.finish
+///
+/// # Examples
+///
+/// ```
+/// use omnom::BitWriter;
+///
+/// let mut writer = BitWriter::new(vec![]);
+/// writer.write_bit(true).unwrap();
+/// writer.write_bits(0b011, 3).unwrap();
+/// writer.write_bits(0b00, 2).unwrap();
+/// assert_eq!(writer.finish().unwrap(), [0b1011_0000]);
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<W> {
+    inner: W,
+    /// The bits written to the current byte, in the high bits.
+    byte: u8,
+    bits: u32,
+}
+
+impl<W: Write> BitWriter<W> {
+    /// Create a new `BitWriter`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            bits: 0,
+        }
+    }
+
+    /// Write a bit.
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.byte |= u8::from(bit) << (7 - self.bits);
+        self.bits += 1;
+        if self.bits == 8 {
+            self.inner.write_all(&[self.byte])?;
+            self.byte = 0;
+            self.bits = 0;
+        }
+        Ok(())
+    }
+
+    /// Write the low `n` bits of `value`, the most significant one first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 32.
+    pub fn write_bits(&mut self, value: u32, n: u32) -> io::Result<()> {
+        assert!(n <= 32, "can't write {} bits of a u32", n);
+        for i in (0..n).rev() {
+            self.write_bit(value >> i & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Write an unsigned Exp-Golomb code, the `ue(v)` of H.264 and H.265.
+    ///
+    /// Returns `ErrorKind::InvalidInput` for `u32::MAX`, whose code has 32
+    /// leading zeros, which [`BitReader::read_ue`] rejects.
+    ///
+    /// [`BitReader::read_ue`]: struct.BitReader.html#method.read_ue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BitWriter;
+    ///
+    /// let mut writer = BitWriter::new(vec![]);
+    /// for value in 0..4 {
+    ///     writer.write_ue(value).unwrap();
+    /// }
+    /// assert_eq!(writer.finish().unwrap(), [0b1010_0110, 0b0100_0000]);
+    /// ```
+    pub fn write_ue(&mut self, value: u32) -> io::Result<()> {
+        self.write_exp_golomb(u64::from(value))
+    }
+
+    /// Write a signed Exp-Golomb code, the `se(v)` of H.264 and H.265.
+    ///
+    /// Returns `ErrorKind::InvalidInput` for `i32::MIN`, whose code has 32
+    /// leading zeros, which [`BitReader::read_se`] rejects.
+    ///
+    /// [`BitReader::read_se`]: struct.BitReader.html#method.read_se
+    pub fn write_se(&mut self, value: i32) -> io::Result<()> {
+        let value = i64::from(value);
+        let code = if value > 0 { 2 * value - 1 } else { -2 * value };
+        self.write_exp_golomb(code as u64)
+    }
+
+    /// Returns `true` if the writer is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Pad the current byte with zero bits and write it, returning the
+    /// amount of bits of padding.
+    pub fn align(&mut self) -> io::Result<u32> {
+        if self.bits == 0 {
+            return Ok(0);
+        }
+        let padding = 8 - self.bits;
+        self.inner.write_all(&[self.byte])?;
+        self.byte = 0;
+        self.bits = 0;
+        Ok(padding)
+    }
+
+    /// Pad the current byte with zero bits, write it, and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.align()?;
+        Ok(self.inner)
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Write `code` as an Exp-Golomb code.
+    fn write_exp_golomb(&mut self, code: u64) -> io::Result<()> {
+        let value = code + 1;
+        let zeros = 63 - value.leading_zeros();
+        if zeros > MAX_LEADING_ZEROS {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} takes more than {} leading zeros as an Exp-Golomb code",
+                    code, MAX_LEADING_ZEROS
+                ),
+            ));
+        }
+        self.write_bits(0, zeros)?;
+        self.write_bit(true)?;
+        self.write_bits((value - (1 << zeros)) as u32, zeros)
+    }
+}
@@ -0,0 +1,149 @@
+use std::convert::TryFrom;
+use std::io::{self, BufRead, ErrorKind, Read};
+
+/// A reader of exactly `n` bytes of an underlying reader, such as a body
+/// with a known `Content-Length`.
+///
+/// This is created by [`BufReadExt::body_exact`]. Unlike `Read::take`, it
+/// returns `ErrorKind::UnexpectedEof` if the underlying reader ends before
+/// `n` bytes were read, and [`finish`] checks that the whole body was read,
+/// so the underlying reader is left right after it.
+///
+/// Dropping a `BodyReader` that has bytes left, without calling `finish`,
+/// panics in debug builds, unless a read failed before.
+///
+/// [`BufReadExt::body_exact`]: trait.BufReadExt.html#method.body_exact
+/// [`finish`]: #method.finish
+#[derive(Debug)]
+pub struct BodyReader<'a, R: ?Sized> {
+    inner: &'a mut R,
+    remaining: u64,
+    drain_on_finish: bool,
+    /// Set by `finish`, or when a read fails, to silence the drop check.
+    done: bool,
+}
+
+impl<'a, R: BufRead + ?Sized> BodyReader<'a, R> {
+    pub(crate) fn new(inner: &'a mut R, n: u64) -> Self {
+        Self {
+            inner,
+            remaining: n,
+            drain_on_finish: false,
+            done: false,
+        }
+    }
+
+    /// Skip the unread rest of the body in [`finish`], rather than return an
+    /// error.
+    ///
+    /// [`finish`]: #method.finish
+    pub fn drain_on_finish(mut self, drain: bool) -> Self {
+        self.drain_on_finish = drain;
+        self
+    }
+
+    /// Returns the amount of bytes of the body that are left to read.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Check that the whole body was read.
+    ///
+    /// Returns `ErrorKind::InvalidData` if bytes are left, or skips them if
+    /// [`drain_on_finish`] is set. Skipping fails with
+    /// `ErrorKind::UnexpectedEof` if the underlying reader ends first.
+    ///
+    /// [`drain_on_finish`]: #method.drain_on_finish
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = io::Cursor::new(b"hello world");
+    /// let mut body = reader.body_exact(5);
+    /// assert_eq!(body.fill_buf().unwrap(), b"hello");
+    /// assert!(body.finish().is_err());
+    ///
+    /// let body = reader.body_exact(5).drain_on_finish(true);
+    /// body.finish().unwrap();
+    /// assert_eq!(reader.fill_buf().unwrap(), b" world");
+    /// ```
+    pub fn finish(mut self) -> io::Result<()> {
+        self.done = true;
+        if self.remaining == 0 {
+            return Ok(());
+        }
+        if !self.drain_on_finish {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("body has {} bytes left unread", self.remaining),
+            ));
+        }
+        while self.remaining > 0 {
+            let len = match self.fill_buf() {
+                Ok(buf) => buf.len(),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.consume(len);
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead + ?Sized> Read for BodyReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead + ?Sized> BufRead for BodyReader<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.remaining == 0 {
+            return Ok(&[]);
+        }
+        match self.inner.fill_buf() {
+            Ok([]) => {
+                self.done = true;
+                Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "stream ended with {} bytes of the body left",
+                        self.remaining
+                    ),
+                ))
+            }
+            Ok(buf) => {
+                let len = usize::try_from(self.remaining).map_or(buf.len(), |n| n.min(buf.len()));
+                Ok(&buf[..len])
+            }
+            Err(e) => {
+                self.done |= e.kind() != ErrorKind::Interrupted;
+                Err(e)
+            }
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = usize::try_from(self.remaining).map_or(amt, |n| n.min(amt));
+        self.inner.consume(amt);
+        self.remaining -= amt as u64;
+    }
+}
+
+impl<R: ?Sized> Drop for BodyReader<'_, R> {
+    fn drop(&mut self) {
+        if cfg!(debug_assertions) && !self.done && self.remaining > 0 && !std::thread::panicking() {
+            panic!(
+                "`BodyReader` dropped with {} bytes of the body left; call `finish`",
+                self.remaining
+            );
+        }
+    }
+}
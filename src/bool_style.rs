@@ -0,0 +1,95 @@
+use std::ops::BitOr;
+
+/// The literals accepted by [`BufReadExt::read_bool_ascii`].
+///
+/// Each constant selects one pair of literals, and styles combine with `|`.
+/// Literals match in lowercase only, unless [`ignore_case`] is set.
+///
+/// [`BufReadExt::read_bool_ascii`]: trait.BufReadExt.html#method.read_bool_ascii
+/// [`ignore_case`]: #method.ignore_case
+///
+/// # Examples
+///
+/// ```
+/// use omnom::BoolStyle;
+///
+/// let style = (BoolStyle::YES_NO | BoolStyle::ON_OFF).ignore_case();
+/// assert!(style.contains(BoolStyle::ON_OFF));
+/// assert!(!style.contains(BoolStyle::TRUE_FALSE));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoolStyle {
+    sets: u8,
+    ignore_case: bool,
+}
+
+/// Every literal, with the set it belongs to and the value it stands for.
+const LITERALS: [(u8, &[u8], bool); 8] = [
+    (1, b"true", true),
+    (1, b"false", false),
+    (2, b"yes", true),
+    (2, b"no", false),
+    (4, b"on", true),
+    (4, b"off", false),
+    (8, b"1", true),
+    (8, b"0", false),
+];
+
+impl BoolStyle {
+    /// Accept `true` and `false`.
+    pub const TRUE_FALSE: Self = Self::new(1);
+    /// Accept `yes` and `no`.
+    pub const YES_NO: Self = Self::new(2);
+    /// Accept `on` and `off`.
+    pub const ON_OFF: Self = Self::new(4);
+    /// Accept `1` and `0`.
+    pub const ONE_ZERO: Self = Self::new(8);
+    /// Accept all of the above.
+    pub const ALL: Self = Self::new(15);
+
+    const fn new(sets: u8) -> Self {
+        Self {
+            sets,
+            ignore_case: false,
+        }
+    }
+
+    /// Match the literals regardless of ASCII case.
+    pub const fn ignore_case(self) -> Self {
+        Self {
+            sets: self.sets,
+            ignore_case: true,
+        }
+    }
+
+    /// Returns `true` if every literal accepted by `other` is accepted by
+    /// `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.sets & other.sets == other.sets
+    }
+
+    /// Returns `true` if the literals match regardless of ASCII case.
+    pub const fn is_case_insensitive(self) -> bool {
+        self.ignore_case
+    }
+
+    /// Returns the accepted literals and the values they stand for.
+    pub(crate) fn literals(self) -> impl Iterator<Item = (&'static [u8], bool)> {
+        LITERALS
+            .iter()
+            .filter(move |(set, _, _)| self.sets & set != 0)
+            .map(|&(_, literal, value)| (literal, value))
+    }
+}
+
+impl BitOr for BoolStyle {
+    type Output = Self;
+
+    /// Accept the literals of both styles, ignoring case if either does.
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            sets: self.sets | other.sets,
+            ignore_case: self.ignore_case || other.ignore_case,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::{self, BufRead, ErrorKind, Read};
+
+/// A reader that gives up after too many `ErrorKind::Interrupted` errors in
+/// a row.
+///
+/// The methods of [`BufReadExt`] and [`ReadExt`] retry reads that fail with
+/// `ErrorKind::Interrupted`, as the standard library does. A reader that keeps
+/// returning that error, such as a misbehaving filesystem, makes them loop
+/// forever. Wrapping it in a `BoundedRetry` passes the first `max_retries`
+/// consecutive interruptions through to be retried, and turns the next one
+/// into an error of the kind `ErrorKind::Other`, which stops the retrying.
+/// Any successful read resets the count.
+///
+/// [`BufReadExt`]: trait.BufReadExt.html
+/// [`ReadExt`]: trait.ReadExt.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, BufReader, ErrorKind, Read};
+/// use omnom::prelude::*;
+/// use omnom::BoundedRetry;
+///
+/// struct AlwaysInterrupted;
+///
+/// impl Read for AlwaysInterrupted {
+///     fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+///         Err(ErrorKind::Interrupted.into())
+///     }
+/// }
+///
+/// let mut reader = BoundedRetry::new(BufReader::new(AlwaysInterrupted), 3);
+/// let err = reader.skip_while(|_| true).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Other);
+/// ```
+#[derive(Debug)]
+pub struct BoundedRetry<R> {
+    inner: R,
+    max_retries: usize,
+    retries: usize,
+}
+
+impl<R> BoundedRetry<R> {
+    /// Create a new `BoundedRetry` that allows `max_retries` interruptions in
+    /// a row.
+    pub fn new(inner: R, max_retries: usize) -> Self {
+        Self {
+            inner,
+            max_retries,
+            retries: 0,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Count the result of a read against `retries`, turning an interruption past
+/// `max_retries` into an error that isn't retried.
+fn check<T>(retries: &mut usize, max_retries: usize, res: io::Result<T>) -> io::Result<T> {
+    match res {
+        Err(ref e) if e.kind() == ErrorKind::Interrupted => {
+            if *retries < max_retries {
+                *retries += 1;
+                return res;
+            }
+            *retries = 0;
+            Err(io::Error::other(format!(
+                "read was interrupted more than {} times in a row",
+                max_retries
+            )))
+        }
+        _ => {
+            *retries = 0;
+            res
+        }
+    }
+}
+
+impl<R: Read> Read for BoundedRetry<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res = self.inner.read(buf);
+        check(&mut self.retries, self.max_retries, res)
+    }
+}
+
+impl<R: BufRead> BufRead for BoundedRetry<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let res = self.inner.fill_buf();
+        check(&mut self.retries, self.max_retries, res)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
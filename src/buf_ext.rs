@@ -0,0 +1,413 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::{ReadBytes, WriteBytes};
+
+/// Extend `bytes::Buf` with methods to read bytes using endianness.
+///
+/// Values are copied out through a stack array, so they may straddle the
+/// chunks of a non-contiguous `Buf`.
+pub trait BufExt: Buf {
+    /// Read bytes as big endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer bytes remain than the value needs, like `Buf`'s own
+    /// `get_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let mut buf = &[0, 12][..];
+    /// let num: u16 = buf.get_be();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn get_be<B: ReadBytes>(&mut self) -> B {
+        self.try_get_be()
+            .expect("not enough bytes remaining in buffer")
+    }
+
+    /// Read bytes as little endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer bytes remain than the value needs, like `Buf`'s own
+    /// `get_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let mut buf = &[12, 0][..];
+    /// let num: u16 = buf.get_le();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn get_le<B: ReadBytes>(&mut self) -> B {
+        self.try_get_le()
+            .expect("not enough bytes remaining in buffer")
+    }
+
+    /// Read bytes using native endianness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer bytes remain than the value needs, like `Buf`'s own
+    /// `get_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// let mut buf = &bytes[..];
+    /// let num: u16 = buf.get_ne();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn get_ne<B: ReadBytes>(&mut self) -> B {
+        self.try_get_ne()
+            .expect("not enough bytes remaining in buffer")
+    }
+
+    /// Read bytes as big endian.
+    ///
+    /// Returns `None` and leaves the buffer untouched if fewer bytes remain
+    /// than the value needs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let mut buf = &[0, 12, 1][..];
+    /// assert_eq!(buf.try_get_be::<u16>(), Some(12));
+    /// assert_eq!(buf.try_get_be::<u16>(), None);
+    /// assert_eq!(buf, &[1]);
+    /// ```
+    fn try_get_be<B: ReadBytes>(&mut self) -> Option<B> {
+        copy_array::<B, _>(self).map(B::from_be_array)
+    }
+
+    /// Read bytes as little endian.
+    ///
+    /// Returns `None` and leaves the buffer untouched if fewer bytes remain
+    /// than the value needs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let mut buf = &[12, 0][..];
+    /// assert_eq!(buf.try_get_le::<u16>(), Some(12));
+    /// ```
+    fn try_get_le<B: ReadBytes>(&mut self) -> Option<B> {
+        copy_array::<B, _>(self).map(B::from_le_array)
+    }
+
+    /// Read bytes using native endianness.
+    ///
+    /// Returns `None` and leaves the buffer untouched if fewer bytes remain
+    /// than the value needs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BufExt;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// let mut buf = &bytes[..];
+    /// assert_eq!(buf.try_get_ne::<u16>(), Some(12));
+    /// ```
+    fn try_get_ne<B: ReadBytes>(&mut self) -> Option<B> {
+        copy_array::<B, _>(self).map(B::from_ne_array)
+    }
+}
+
+impl<T: Buf + ?Sized> BufExt for T {}
+
+/// Copy the byte array of `B` out of `buf`, if enough bytes remain.
+fn copy_array<B: ReadBytes, T: Buf + ?Sized>(buf: &mut T) -> Option<B::Bytes> {
+    let mut bytes = B::Bytes::default();
+    if buf.remaining() < bytes.as_ref().len() {
+        return None;
+    }
+    buf.copy_to_slice(bytes.as_mut());
+    Some(bytes)
+}
+
+/// Extend `bytes::BufMut` with methods to write bytes using endianness.
+pub trait BufMutExt: BufMut {
+    /// Write bytes as big endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't enough capacity remaining, like `BufMut`'s own
+    /// `put_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use omnom::BufMutExt;
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_be(12_u16);
+    /// assert_eq!(&buf[..], &[0, 12]);
+    /// ```
+    fn put_be<B: WriteBytes>(&mut self, value: B) {
+        self.put_slice(value.to_be_array().as_ref());
+    }
+
+    /// Write bytes as little endian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't enough capacity remaining, like `BufMut`'s own
+    /// `put_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use omnom::BufMutExt;
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_le(12_u16);
+    /// assert_eq!(&buf[..], &[12, 0]);
+    /// ```
+    fn put_le<B: WriteBytes>(&mut self, value: B) {
+        self.put_slice(value.to_le_array().as_ref());
+    }
+
+    /// Write bytes using native endianness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't enough capacity remaining, like `BufMut`'s own
+    /// `put_*` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use omnom::BufMutExt;
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_ne(12_u16);
+    /// assert_eq!(&buf[..], &12_u16.to_ne_bytes());
+    /// ```
+    fn put_ne<B: WriteBytes>(&mut self, value: B) {
+        self.put_slice(value.to_ne_array().as_ref());
+    }
+}
+
+impl<T: BufMut + ?Sized> BufMutExt for T {}
+
+/// Extend `bytes::Buf` with methods for scanning.
+///
+/// This provides the scanning vocabulary of `BufReadExt` for buffers that are
+/// already in memory. Nothing can fail except running out of bytes, so the
+/// return types differ from the `io` versions:
+///
+/// - `skip_until` returns the amount of bytes skipped together with whether
+///   the delimiter was found, where the `io` version only returns the amount.
+/// - `read_while` returns the bytes read as `Bytes`, rather than appending
+///   them to a `Vec<u8>`.
+/// - `consume_prefix` returns a plain `bool`.
+///
+/// Every method walks the chunks of non-contiguous buffers such as
+/// `bytes::buf::Chain`.
+///
+/// `&[u8]` implements both `Buf` and `BufRead`, so importing this trait and
+/// `BufReadExt` together makes the shared method names ambiguous for slices.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use omnom::BufScanExt;
+///
+/// let mut buf = Bytes::from_static(b"text/html; charset=utf-8");
+/// assert_eq!(buf.read_while(|b| b != b'/'), "text");
+/// assert!(buf.consume_prefix(b"/"));
+/// assert_eq!(buf.skip_until(b';'), (5, true));
+/// assert_eq!(buf, " charset=utf-8");
+/// ```
+pub trait BufScanExt: Buf {
+    /// Skip bytes until the delimiter `byte` or the end of the buffer is
+    /// reached.
+    ///
+    /// Returns the amount of bytes skipped, including the delimiter if found,
+    /// and whether it was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use omnom::BufScanExt;
+    ///
+    /// let mut buf = (&b"lor"[..]).chain(&b"em-ipsum"[..]);
+    /// assert_eq!(buf.skip_until(b'-'), (6, true));
+    /// assert_eq!(buf.skip_until(b'-'), (5, false));
+    /// ```
+    fn skip_until(&mut self, byte: u8) -> (usize, bool) {
+        let mut skipped = 0;
+        while self.has_remaining() {
+            let chunk = self.chunk();
+            match memchr::memchr(byte, chunk) {
+                Some(i) => {
+                    self.advance(i + 1);
+                    return (skipped + i + 1, true);
+                }
+                None => {
+                    let len = chunk.len();
+                    self.advance(len);
+                    skipped += len;
+                }
+            }
+        }
+        (skipped, false)
+    }
+
+    /// Read bytes while the predicate is true.
+    ///
+    /// If the bytes read lie within a single chunk they're split off with
+    /// `Buf::copy_to_bytes`, which doesn't copy for `Bytes` and `BytesMut`.
+    /// Bytes spanning several chunks are copied into a new buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use omnom::BufScanExt;
+    ///
+    /// let mut buf = Bytes::from_static(b"1234abc");
+    /// let digits = buf.read_while(|b| b.is_ascii_digit());
+    /// assert_eq!(digits, "1234");
+    /// assert_eq!(buf, "abc");
+    /// ```
+    fn read_while<P>(&mut self, mut predicate: P) -> Bytes
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let chunk = self.chunk();
+        let len = chunk
+            .iter()
+            .position(|b| !predicate(*b))
+            .unwrap_or(chunk.len());
+        if len < chunk.len() || len == self.remaining() {
+            return self.copy_to_bytes(len);
+        }
+
+        let mut out = BytesMut::with_capacity(len);
+        out.extend_from_slice(chunk);
+        self.advance(len);
+        while self.has_remaining() {
+            let chunk = self.chunk();
+            let len = chunk
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(chunk.len());
+            let done = len < chunk.len();
+            out.extend_from_slice(&chunk[..len]);
+            self.advance(len);
+            if done {
+                break;
+            }
+        }
+        out.freeze()
+    }
+
+    /// Skip `prefix` if the buffer starts with it.
+    ///
+    /// Returns `false` without advancing the buffer if it doesn't, also if
+    /// `prefix` straddles chunks: with the `std` feature, the chunks after
+    /// the first one are compared through `Buf::chunks_vectored`, as
+    /// `Chain` and `VecDeque` expose them, before anything is advanced.
+    ///
+    /// Without the `std` feature, or for a `Buf` that exposes fewer chunks
+    /// than `prefix` spans, the matching part of each chunk is consumed
+    /// before the next one can be compared, and stays consumed if that one
+    /// doesn't match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use omnom::BufScanExt;
+    ///
+    /// let mut buf = &b"GET /"[..];
+    /// assert!(!buf.consume_prefix(b"POST"));
+    /// assert!(buf.consume_prefix(b"GET "));
+    /// assert_eq!(buf, b"/");
+    ///
+    /// let mut buf = (&b"HT"[..]).chain(&b"ML"[..]);
+    /// assert!(!buf.consume_prefix(b"HTTP"));
+    /// assert_eq!(buf.remaining(), 4);
+    /// ```
+    fn consume_prefix(&mut self, prefix: &[u8]) -> bool {
+        if self.remaining() < prefix.len() {
+            return false;
+        }
+        if let Some(matches) = starts_with(self, prefix) {
+            if matches {
+                self.advance(prefix.len());
+            }
+            return matches;
+        }
+        let mut rest = prefix;
+        while !rest.is_empty() {
+            let chunk = self.chunk();
+            let len = chunk.len().min(rest.len());
+            if chunk[..len] != rest[..len] {
+                return false;
+            }
+            self.advance(len);
+            rest = &rest[len..];
+        }
+        true
+    }
+}
+
+/// Returns whether `buf` starts with `prefix`, or `None` if the chunks it
+/// exposes without advancing are too short to tell.
+fn starts_with<B: Buf + ?Sized>(buf: &B, prefix: &[u8]) -> Option<bool> {
+    let chunk = buf.chunk();
+    if let Some(start) = chunk.get(..prefix.len()) {
+        return Some(start == prefix);
+    }
+    if !prefix.starts_with(chunk) {
+        return Some(false);
+    }
+    starts_with_vectored(buf, prefix)
+}
+
+#[cfg(feature = "std")]
+fn starts_with_vectored<B: Buf + ?Sized>(buf: &B, prefix: &[u8]) -> Option<bool> {
+    use std::io::IoSlice;
+
+    let mut slices = [IoSlice::new(&[]); 16];
+    let n = buf.chunks_vectored(&mut slices);
+    let mut rest = prefix;
+    for slice in &slices[..n] {
+        let len = slice.len().min(rest.len());
+        if slice[..len] != rest[..len] {
+            return Some(false);
+        }
+        rest = &rest[len..];
+        if rest.is_empty() {
+            return Some(true);
+        }
+    }
+    None
+}
+
+#[cfg(not(feature = "std"))]
+fn starts_with_vectored<B: Buf + ?Sized>(_: &B, _: &[u8]) -> Option<bool> {
+    None
+}
+
+impl<T: Buf + ?Sized> BufScanExt for T {}
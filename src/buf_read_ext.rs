@@ -1,7 +1,69 @@
-use std::io::{self, BufRead, ErrorKind, Read};
-use std::slice;
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Read, Write};
+use std::net::Ipv4Addr;
+use std::ops::{ControlFlow, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::read_ext::convert_floats;
+use crate::{
+    ascii, timestamp, BodyReader, BoolStyle, BufferAhead, BufferTooSmall, ByteSet, ByteSink,
+    DataReady, MacAddr, ParseError, ParseErrorKind, ReadBytes, ShortRead, Timestamp,
+};
+use crate::{leb128, partial_error};
+
+macro_rules! read_floats_buffered_impl {
+    ($($name:ident, $F:ty, $from_bytes:ident, $to_bytes:ident, $unbuffered:ident;)*) => { $(
+        #[doc = concat!("Fill `out` like [`ReadExt::", stringify!($unbuffered), "`], converting")]
+        /// straight out of the reader's buffer.
+        ///
+        /// Values are converted from each slice returned by `fill_buf`
+        /// without copying them into a separate chunk first. Only a value
+        /// that straddles two buffers is assembled byte by byte.
+        ///
+        /// Returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the
+        /// reader runs out of bytes first, in which case the contents of `out`
+        /// are unspecified and the bytes read so far are consumed. Other read
+        /// errors carry a [`PartialError`] whose `completed` is the amount of
+        /// values filled in until then.
+        ///
+        /// [`ShortRead`]: struct.ShortRead.html
+        /// [`PartialError`]: struct.PartialError.html
+        #[doc = concat!("[`ReadExt::", stringify!($unbuffered), "`]: trait.ReadExt.html#method.", stringify!($unbuffered))]
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::io::BufReader;
+        /// use omnom::prelude::*;
+        ///
+        #[doc = concat!("let values = [1.5, -0.0, ", stringify!($F), "::NAN];")]
+        /// let bytes: Vec<u8> = values
+        ///     .iter()
+        #[doc = concat!("    .flat_map(|v| v.", stringify!($to_bytes), "().to_vec())")]
+        ///     .collect();
+        ///
+        #[doc = concat!("let mut out = [0.0_", stringify!($F), "; 3];")]
+        /// let mut reader = BufReader::with_capacity(5, &bytes[..]);
+        #[doc = concat!("reader.", stringify!($name), "(&mut out).unwrap();")]
+        /// assert_eq!(out[..2], values[..2]);
+        /// assert!(out[2].is_nan());
+        /// ```
+        fn $name(&mut self, out: &mut [$F]) -> io::Result<()> {
+            read_floats_buffered(self, out, <$F>::$from_bytes)
+        }
+    )* };
+}
 
 /// Extend `BufRead` with methods for streaming parsing.
+///
+/// The methods only consume bytes returned by the last call to `fill_buf`,
+/// so they work on readers that limit what `fill_buf` returns, such as the
+/// `io::Take` of `Read::take`. The limit reads as the end of the stream: a
+/// delimiter right after it isn't found, and the method stops as it would at
+/// EOF. A delimiter that is the last byte before the limit is found and
+/// consumed, which leaves the limit at zero.
 pub trait BufReadExt: BufRead {
     /// Read bytes based on a predicate.
     ///
@@ -9,15 +71,26 @@ pub trait BufReadExt: BufRead {
     /// It will call this on each byte, and copy it to the slice if the
     /// predicate evaluates to `true`. Returns the amount of bytes read.
     ///
+    /// `buf` can be any [`ByteSink`], such as a `Vec<u8>` or a [`Utf8Sink`]
+    /// appending to a `String`. The same holds for the other methods that
+    /// append to a `buf`, except those that need to look at or modify what
+    /// was appended.
+    ///
+    /// [`ByteSink`]: trait.ByteSink.html
+    /// [`Utf8Sink`]: struct.Utf8Sink.html
+    ///
     /// # Errors
     ///
     /// If this function encounters an error of the kind
     /// `ErrorKind::Interrupted` then the error is ignored and the operation
     /// will continue.
     ///
-    /// If any other read error is encountered then this function immediately
-    /// returns. Any bytes which have already been read will be appended to
-    /// `buf`.
+    /// If any other read error is encountered, or `buf` rejects the bytes,
+    /// then this function immediately returns. Any bytes which have already
+    /// been read will be appended to `buf`, and the error carries a
+    /// [`PartialError`] whose `completed` is the amount of them.
+    ///
+    /// [`PartialError`]: struct.PartialError.html
     ///
     /// # Examples
     ///
@@ -36,82 +109,416 @@ pub trait BufReadExt: BufRead {
     ///     .expect("reading from cursor won't fail");
     /// assert_eq!(buf, b"lorem");
     /// ```
-    fn read_while<P>(&mut self, buf: &mut Vec<u8>, mut predicate: P) -> io::Result<usize>
+    fn read_while<S, P>(&mut self, buf: &mut S, predicate: P) -> io::Result<usize>
     where
+        S: ByteSink + ?Sized,
         P: FnMut(u8) -> bool,
     {
-        let mut read = 0;
-        let mut inner_read = 0;
+        self.read_while_peek(buf, predicate).map(|(read, _)| read)
+    }
+
+    /// Read bytes based on a predicate, and return the byte that stopped it.
+    ///
+    /// This behaves like [`read_while`], and returns the amount of bytes read
+    /// together with the first byte the predicate rejected. That byte isn't
+    /// consumed, so it's also the next byte of the stream. It's `None` if the
+    /// reader reached EOF instead.
+    ///
+    /// [`read_while`]: #method.read_while
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"12.5");
+    /// let mut buf = vec![];
+    /// let res = cursor.read_while_peek(&mut buf, |b| b.is_ascii_digit()).unwrap();
+    /// assert_eq!(res, (2, Some(b'.')));
+    /// assert_eq!(cursor.read_be::<u8>().unwrap(), b'.');
+    ///
+    /// let res = cursor.read_while_peek(&mut buf, |b| b.is_ascii_digit()).unwrap();
+    /// assert_eq!(res, (1, None));
+    /// assert_eq!(buf, b"125");
+    /// ```
+    fn read_while_peek<S, P>(
+        &mut self,
+        buf: &mut S,
+        predicate: P,
+    ) -> io::Result<(usize, Option<u8>)>
+    where
+        S: ByteSink + ?Sized,
+        P: FnMut(u8) -> bool,
+    {
+        while_peek(self, Some(buf), predicate)
+    }
+
+    /// Read bytes based on a predicate, reserving capacity in `buf` first.
+    ///
+    /// This behaves like [`read_while`], but reserves room for at least
+    /// `min_reserve` more bytes in `buf` before reading. Use it when the
+    /// expected length of the bytes read is known, to avoid growing `buf`
+    /// step by step. Past the reservation `buf` grows by whole buffered
+    /// chunks.
+    ///
+    /// [`read_while`]: #method.read_while
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem-ipsum");
+    /// let mut buf = vec![];
+    ///
+    /// cursor.read_while_with_capacity(&mut buf, 64, |b| b != b'-').unwrap();
+    /// assert_eq!(buf, b"lorem");
+    /// assert!(buf.capacity() >= 64);
+    /// ```
+    fn read_while_with_capacity<P>(
+        &mut self,
+        buf: &mut Vec<u8>,
+        min_reserve: usize,
+        predicate: P,
+    ) -> io::Result<usize>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        buf.reserve(min_reserve);
+        self.read_while(buf, predicate)
+    }
 
-        'outer: loop {
+    /// Read bytes while a predicate over chunks accepts them.
+    ///
+    /// `accept` is called with each chunk of buffered bytes, and returns how
+    /// many of its leading bytes to read. Those bytes are appended to `buf`
+    /// with a single copy and consumed, and the operation continues with the
+    /// next chunk only if the whole chunk was accepted. This allows scanning
+    /// with `memchr` or SIMD routines rather than a per-byte predicate. Return
+    /// values larger than the chunk are treated as accepting the whole chunk.
+    ///
+    /// Returns the amount of bytes read. If reading or appending fails, the
+    /// error carries a [`PartialError`] whose `completed` is the amount of
+    /// bytes read and appended until then.
+    ///
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem\"ipsum");
+    /// let mut buf = vec![];
+    /// let read = cursor
+    ///     .read_while_chunk(&mut buf, |chunk| {
+    ///         chunk.iter().position(|b| *b == b'"').unwrap_or(chunk.len())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(read, 5);
+    /// assert_eq!(buf, b"lorem");
+    /// ```
+    fn read_while_chunk<S, P>(&mut self, buf: &mut S, mut accept: P) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+        P: FnMut(&[u8]) -> usize,
+    {
+        let mut read = 0;
+        loop {
             let available = match self.fill_buf() {
-                Ok(n) => n,
+                Ok(b) => b,
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-                Err(e) => {
-                    self.consume(read);
-                    return Err(e);
-                }
+                Err(e) => return Err(partial_error::wrap(read as u64, e)),
             };
-            if available.len() == 0 {
+            if available.is_empty() {
                 break;
             }
 
-            for byte in available.bytes() {
-                let byte = byte?;
-                if predicate(byte) {
-                    buf.extend_from_slice(&[byte]);
-                    inner_read += 1;
-                    read += 1;
-                } else {
-                    break 'outer;
-                }
+            let len = accept(available).min(available.len());
+            let done = len < available.len();
+            if let Err(e) = buf.extend_from_slice(&available[..len]) {
+                return Err(partial_error::wrap(read as u64, e));
+            }
+            self.consume(len);
+            read += len;
+            if done {
+                break;
             }
-            self.consume(inner_read);
-            inner_read = 0;
         }
-        self.consume(inner_read);
 
         Ok(read)
     }
 
-    /// Skip the first `n` bytes.
-    fn skip(&mut self, n: usize) -> io::Result<()> {
+    /// Read all bytes until EOF.
+    ///
+    /// This is `read_while(buf, |_| true)` without the predicate: each
+    /// buffered chunk is appended to `buf` with a single copy and consumed
+    /// at once, as `Read::read_to_end` does, but into any [`ByteSink`].
+    ///
+    /// A `BufRead` doesn't know the length of the rest of the stream, so the
+    /// only size hint is the buffered chunk: room for it is reserved in
+    /// `buf` up front, and `buf` then grows by whole chunks, as a `Vec`
+    /// does. Reserve room first, as [`read_while_with_capacity`] does, if the
+    /// length of the rest of the stream is known.
+    ///
+    /// Returns the amount of bytes read. If reading or appending fails, the
+    /// error carries a [`PartialError`] whose `completed` is the amount of
+    /// bytes read and appended until then.
+    ///
+    /// [`ByteSink`]: trait.ByteSink.html
+    /// [`read_while_with_capacity`]: #method.read_while_with_capacity
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"GET /index.html");
+    /// let mut method = vec![];
+    /// cursor.read_while(&mut method, |b| b != b' ').unwrap();
+    ///
+    /// let mut rest = vec![];
+    /// assert_eq!(cursor.read_remaining(&mut rest).unwrap(), 12);
+    /// assert_eq!(rest, b" /index.html");
+    /// ```
+    fn read_remaining<S>(&mut self, buf: &mut S) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let hint = loop {
+            match self.fill_buf() {
+                Ok(available) => break available.len(),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(partial_error::wrap(0, e)),
+            }
+        };
+        buf.reserve(hint);
+        self.read_while_chunk(buf, |chunk| chunk.len())
+    }
+
+    /// Read bytes based on a predicate, stopping once `buf` is full.
+    ///
+    /// This behaves like [`read_while_peek`], but never appends more bytes
+    /// than `buf` has room for. That makes it suited to sinks with a fixed
+    /// capacity, such as a `heapless::Vec` or an `ArrayVec` with the
+    /// `heapless` and `arrayvec` features. Returns the amount of bytes read
+    /// together with the reason it stopped:
+    ///
+    /// - `StopReason::Rejected` with the first byte the predicate rejected.
+    /// - `StopReason::Eof` if the reader reached EOF.
+    /// - `StopReason::SinkFull` if `buf` is full and the predicate would
+    ///   accept the next byte.
+    ///
+    /// The byte that stopped it isn't consumed, so in each case reading can
+    /// resume with the next byte of the stream. A sink that's filled exactly
+    /// reports why the read would have stopped anyway, if it would.
+    ///
+    /// Errors carry a [`PartialError`] like those of [`read_while`].
+    ///
+    /// [`read_while_peek`]: #method.read_while_peek
+    /// [`read_while`]: #method.read_while
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::StopReason;
+    ///
+    /// let mut cursor = io::Cursor::new(b"12.5");
+    /// let mut buf = vec![];
+    /// let res = cursor.read_while_capped(&mut buf, |b| b.is_ascii_digit()).unwrap();
+    /// assert_eq!(res, (2, StopReason::Rejected(b'.')));
+    /// ```
+    fn read_while_capped<S, P>(
+        &mut self,
+        buf: &mut S,
+        mut predicate: P,
+    ) -> io::Result<(usize, StopReason)>
+    where
+        S: ByteSink + ?Sized,
+        P: FnMut(u8) -> bool,
+    {
         let mut read = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(partial_error::wrap(read as u64, e)),
+            };
+            if available.is_empty() {
+                return Ok((read, StopReason::Eof));
+            }
 
-        while read < n {
+            let room = buf.remaining_capacity().unwrap_or(usize::MAX);
+            let max = available.len().min(room);
+            let len = available[..max]
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(max);
+            let stop = match available.get(len) {
+                None => None,
+                Some(&b) if len < max || !predicate(b) => Some(StopReason::Rejected(b)),
+                Some(_) => Some(StopReason::SinkFull),
+            };
+            if let Err(e) = buf.extend_from_slice(&available[..len]) {
+                return Err(partial_error::wrap(read as u64, e));
+            }
+            self.consume(len);
+            read += len;
+            if let Some(stop) = stop {
+                return Ok((read, stop));
+            }
+        }
+    }
+
+    /// Read bytes until the delimiter `byte` or EOF is reached, stopping once
+    /// `buf` is full.
+    ///
+    /// All bytes up to, and including, the delimiter (if found) are appended
+    /// to `buf`, but never more than it has room for, like with
+    /// [`read_while_capped`]. Returns the amount of bytes read together with
+    /// `StopReason::Delimiter` if the delimiter was appended,
+    /// `StopReason::Eof` if the reader reached EOF, or `StopReason::SinkFull`
+    /// if `buf` filled up first. The bytes that didn't fit, the delimiter
+    /// included, are left unconsumed.
+    ///
+    /// [`read_while_capped`]: #method.read_while_capped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::StopReason;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem\nipsum");
+    /// let mut buf = vec![];
+    /// let res = cursor.read_until_capped(b'\n', &mut buf).unwrap();
+    /// assert_eq!(res, (6, StopReason::Delimiter));
+    /// assert_eq!(buf, b"lorem\n");
+    /// ```
+    fn read_until_capped<S>(&mut self, byte: u8, buf: &mut S) -> io::Result<(usize, StopReason)>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let mut read = 0;
+        loop {
             let available = match self.fill_buf() {
                 Ok(b) => b,
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-                Err(e) => return Err(e),
+                Err(e) => return Err(partial_error::wrap(read as u64, e)),
             };
+            if available.is_empty() {
+                return Ok((read, StopReason::Eof));
+            }
 
-            if available.len() == 0 {
-                break;
-            } else {
-                self.consume(1);
-                read += 1;
+            let room = buf.remaining_capacity().unwrap_or(usize::MAX);
+            let (len, stop) = match memchr::memchr(byte, available) {
+                Some(i) if i < room => (i + 1, Some(StopReason::Delimiter)),
+                _ if room < available.len() => (room, Some(StopReason::SinkFull)),
+                _ => (available.len(), None),
+            };
+            if let Err(e) = buf.extend_from_slice(&available[..len]) {
+                return Err(partial_error::wrap(read as u64, e));
+            }
+            self.consume(len);
+            read += len;
+            if let Some(stop) = stop {
+                return Ok((read, stop));
             }
+        }
+    }
 
-            let mut byte = 0;
-            match self.read(slice::from_mut(&mut byte)) {
-                Ok(0) => break,
-                Ok(_) => {
-                    read += 1;
-                    continue;
-                }
+    /// Read bytes through the `n`th occurrence of the delimiter `byte`, or
+    /// until EOF is reached.
+    ///
+    /// All bytes up to, and including, the `n`th delimiter (if found) are
+    /// appended to `buf`. Returns the amount of bytes read together with the
+    /// amount of delimiters found, which is less than `n` only at EOF. If `n`
+    /// is `0` nothing is read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"a,b,c,d");
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.read_until_nth(b',', 2, &mut buf).unwrap(), (4, 2));
+    /// assert_eq!(buf, b"a,b,");
+    /// assert_eq!(cursor.read_until_nth(b',', 2, &mut buf).unwrap(), (3, 1));
+    /// ```
+    fn read_until_nth<S>(&mut self, byte: u8, n: usize, buf: &mut S) -> io::Result<(usize, usize)>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let mut read = 0;
+        let mut found = 0;
+        while found < n {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
                 Err(e) => return Err(e),
             };
+            if available.is_empty() {
+                break;
+            }
+
+            let len = nth_end(byte, available, n, &mut found);
+            buf.extend_from_slice(&available[..len])?;
+            self.consume(len);
+            read += len;
         }
 
-        self.consume(read);
-        Ok(())
+        Ok((read, found))
     }
 
-    /// Skip bytes while the predicate is true.
-    fn skip_while<P>(&mut self, mut predicate: P) -> io::Result<usize>
+    /// Read a line of at most `max` bytes, not counting the newline.
+    ///
+    /// The line is appended to `buf` including its newline, if any. Returns
+    /// `LineResult::Line` with the amount of bytes read if the line ended with
+    /// a newline, and `LineResult::Eof` if the stream ended first; at EOF that
+    /// amount is `0`.
+    ///
+    /// If the line is longer than `max`, exactly `max` bytes are read and
+    /// appended to `buf`, and an error of the kind `ErrorKind::InvalidData` is
+    /// returned, wrapping a [`ParseError`] of the kind
+    /// `ParseErrorKind::LimitExceeded` at offset `max`. The rest of the line
+    /// stays in the reader, so it remains usable: call [`skip_line`] to
+    /// resume at the next line.
+    ///
+    /// [`ParseError`]: struct.ParseError.html
+    /// [`skip_line`]: #method.skip_line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::{LineResult, ParseError, ParseErrorKind};
+    ///
+    /// let mut cursor = io::Cursor::new(b"a very long line\nok");
+    /// let mut buf = vec![];
+    /// let err = cursor.read_line_max(&mut buf, 8).unwrap_err();
+    /// assert_eq!(ParseError::from(err).kind(), &ParseErrorKind::LimitExceeded);
+    /// assert_eq!(buf, b"a very l");
+    ///
+    /// cursor.skip_line().unwrap();
+    /// buf.clear();
+    /// assert_eq!(cursor.read_line_max(&mut buf, 8).unwrap(), LineResult::Eof(2));
+    /// assert_eq!(buf, b"ok");
+    /// ```
+    fn read_line_max<S>(&mut self, buf: &mut S, max: usize) -> io::Result<LineResult>
     where
-        P: FnMut(u8) -> bool,
+        S: ByteSink + ?Sized,
     {
         let mut read = 0;
         loop {
@@ -120,89 +527,2795 @@ pub trait BufReadExt: BufRead {
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
                 Err(e) => return Err(e),
             };
+            if available.is_empty() {
+                return Ok(LineResult::Eof(read));
+            }
 
-            if available.len() == 0 {
-                break;
+            // Look one byte past the limit, which may be the newline.
+            let window = &available[..available.len().min(max - read + 1)];
+            let (len, found) = match memchr::memchr(b'\n', window) {
+                Some(i) => (i + 1, true),
+                None => (window.len().min(max - read), false),
+            };
+            let over = !found && window.len() > len;
+            buf.extend_from_slice(&available[..len])?;
+            self.consume(len);
+            read += len;
+            if found {
+                return Ok(LineResult::Line(read));
             }
-            if predicate(available[0]) {
-                self.consume(1);
-                read += 1;
-            } else {
-                break;
+            if over {
+                return Err(ParseError::new(max, ParseErrorKind::LimitExceeded).into());
             }
         }
-
-        Ok(read)
     }
 
-    /// Skip bytes until the delimiter `byte` or EOF is reached.
+    /// Call `f` on each line of the rest of the stream.
     ///
-    /// This function will read bytes from the underlying stream until the
-    /// delimiter or EOF is found. Once found, all bytes up to, and including,
-    /// the delimiter (if found) will be skipped.
+    /// Lines end with `\n` or `\r\n`, and are passed to `f` without their
+    /// line ending. The last line of the stream doesn't need a line ending; if
+    /// it has none, it's passed as is. Returns the amount of lines passed to
+    /// `f`.
     ///
-    /// If successful, this function will return the total number of bytes read.
+    /// Lines that fit within the reader's buffer are passed to `f` directly,
+    /// without copying them. Lines that span multiple buffer refills are
+    /// collected in a single buffer that's reused for every line, so no
+    /// allocation happens per line.
     ///
-    /// # Errors
+    /// Returning `ControlFlow::Break` from `f` stops reading after that line,
+    /// as does returning an error. Either way the line passed to `f` is
+    /// consumed, and the lines after it aren't.
     ///
-    /// This function will ignore all instances of [`ErrorKind::Interrupted`] and
-    /// will otherwise return any errors returned by [`BufRead::fill_buf`].
+    /// # Examples
     ///
-    /// If an I/O error is encountered then all bytes read so far will be
-    /// present in `buf` and its length will have been adjusted appropriately.
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use std::ops::ControlFlow;
+    /// use omnom::prelude::*;
     ///
-    /// [`BufRead::consume`]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.consume
-    /// [`BufRead::fill_buf`]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.consume
-    /// [`ErrorKind::Interrupted`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.Interrupted
+    /// let mut cursor = io::Cursor::new(b"a=1\r\nb=2\n\nc=3");
+    /// let mut keys = vec![];
+    /// let lines = cursor
+    ///     .for_each_line(|line| {
+    ///         if line.is_empty() {
+    ///             return Ok(ControlFlow::Break(()));
+    ///         }
+    ///         keys.push(line[0]);
+    ///         Ok(ControlFlow::Continue(()))
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(lines, 3);
+    /// assert_eq!(keys, b"ab");
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"c=3");
+    /// ```
+    fn for_each_line<F>(&mut self, mut f: F) -> io::Result<u64>
+    where
+        F: FnMut(&[u8]) -> io::Result<ControlFlow<()>>,
+    {
+        // The start of a line that spans buffer refills.
+        let mut line = Vec::new();
+        let mut count = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                if !line.is_empty() {
+                    count += 1;
+                    // This is the last line, so it doesn't matter if `f` breaks.
+                    let _ = f(&line)?;
+                }
+                return Ok(count);
+            }
+
+            let mut start = 0;
+            let mut stop = None;
+            for end in memchr::memchr_iter(b'\n', available) {
+                let flow = if line.is_empty() {
+                    f(strip_cr(&available[start..end]))
+                } else {
+                    line.extend_from_slice(&available[start..end]);
+                    let flow = f(strip_cr(&line));
+                    line.clear();
+                    flow
+                };
+                start = end + 1;
+                count += 1;
+                match flow {
+                    Ok(ControlFlow::Continue(())) => {}
+                    Ok(ControlFlow::Break(())) => {
+                        stop = Some(Ok(count));
+                        break;
+                    }
+                    Err(e) => {
+                        stop = Some(Err(e));
+                        break;
+                    }
+                }
+            }
+            if let Some(result) = stop {
+                self.consume(start);
+                return result;
+            }
+            line.extend_from_slice(&available[start..]);
+            let len = available.len();
+            self.consume(len);
+        }
+    }
+
+    /// Skip the rest of the current line, including its newline.
+    ///
+    /// Returns the amount of bytes skipped.
     ///
     /// # Examples
     ///
-    /// [`std::io::Cursor`][`Cursor`] is a type that implements `BufRead`. In
-    /// this example, we use [`Cursor`] to read all the bytes in a byte slice
-    /// in hyphen delimited segments:
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
     ///
-    /// [`Cursor`]: https://doc.rust-lang.org/std/io/struct.Cursor.html
+    /// let mut cursor = io::Cursor::new(b"# comment\nkey=value");
+    /// assert_eq!(cursor.skip_line().unwrap(), 10);
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"key=value");
+    /// ```
+    fn skip_line(&mut self) -> io::Result<usize> {
+        BufReadExt::skip_until(self, b'\n')
+    }
+
+    /// Read a record up to the first delimiter `byte` that isn't preceded by
+    /// the `escape` byte.
+    ///
+    /// The record is appended to `buf` with its escape sequences resolved:
+    /// each `escape` byte is removed and the byte after it is kept literally,
+    /// so an escaped delimiter or an escaped `escape` byte become part of the
+    /// record. The delimiter is consumed but not appended. An `escape` byte
+    /// that's the last byte of the stream has nothing to escape, and is
+    /// appended as is.
+    ///
+    /// Returns the amount of bytes read from the reader, including escape
+    /// bytes and the delimiter, together with whether a delimiter was found
+    /// before EOF.
+    ///
+    /// # Examples
     ///
     /// ```
-    /// use std::io::{self, BufRead, Read};
+    /// use std::io;
     /// use omnom::prelude::*;
     ///
-    /// let mut cursor = io::Cursor::new(b"lorem-ipsum");
-    ///
-    /// // skip up to and including '-'
-    /// let num_bytes = cursor.skip_until(b'-').unwrap();
-    /// assert_eq!(num_bytes, 6);
+    /// let mut cursor = io::Cursor::new(br"foo\,bar,baz");
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.read_record(b',', b'\\', &mut buf).unwrap(), (9, true));
+    /// assert_eq!(buf, b"foo,bar");
     ///
-    /// // read the rest of the bytes
-    /// let mut buf = [0; 5];
-    /// cursor.read_exact(&mut buf).unwrap();
-    /// assert_eq!(&buf, b"ipsum");
+    /// buf.clear();
+    /// assert_eq!(cursor.read_record(b',', b'\\', &mut buf).unwrap(), (3, false));
+    /// assert_eq!(buf, b"baz");
     /// ```
-    fn skip_until(&mut self, byte: u8) -> io::Result<usize> {
+    fn read_record<S>(&mut self, byte: u8, escape: u8, buf: &mut S) -> io::Result<(usize, bool)>
+    where
+        S: ByteSink + ?Sized,
+    {
         let mut read = 0;
+        let mut escaped = false;
         loop {
             let available = match self.fill_buf() {
                 Ok(b) => b,
                 Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
                 Err(e) => return Err(e),
             };
-
-            if available.len() == 0 {
-                break;
+            if available.is_empty() {
+                if escaped {
+                    buf.extend_from_slice(&[escape])?;
+                }
+                return Ok((read, false));
             }
 
-            if available[0] == byte {
-                self.consume(1);
-                read += 1;
-                break;
-            } else {
-                self.consume(1);
-                read += 1;
+            let mut start = 0;
+            if escaped {
+                buf.extend_from_slice(&[available[0]])?;
+                escaped = false;
+                start = 1;
+            }
+            let mut found = false;
+            let mut end = available.len();
+            while let Some(i) = memchr::memchr2(byte, escape, &available[start..]) {
+                let i = start + i;
+                buf.extend_from_slice(&available[start..i])?;
+                if available[i] == escape {
+                    match available.get(i + 1) {
+                        Some(&next) => {
+                            buf.extend_from_slice(&[next])?;
+                            start = i + 2;
+                        }
+                        None => {
+                            escaped = true;
+                            start = available.len();
+                            break;
+                        }
+                    }
+                } else {
+                    found = true;
+                    end = i + 1;
+                    break;
+                }
+            }
+            if !found {
+                buf.extend_from_slice(&available[start..])?;
+            }
+            self.consume(end);
+            read += end;
+            if found {
+                return Ok((read, true));
             }
         }
-
-        Ok(read)
+    }
+
+    /// Skip Unicode whitespace, decoding the stream as UTF-8.
+    ///
+    /// This skips every character for which `char::is_whitespace` holds,
+    /// including non-breaking and ideographic spaces, and returns the amount
+    /// of bytes skipped. Skipping stops before the first character that isn't
+    /// whitespace, without consuming any of its bytes. Invalid UTF-8 also
+    /// stops the skip, and isn't consumed either.
+    ///
+    /// A character that straddles the end of the reader's buffer can only be
+    /// decoded by consuming its leading bytes. This is done only if those bytes
+    /// start the encoding of a whitespace character. If the character then
+    /// turns out not to be whitespace, such as `—` after the first two bytes
+    /// of U+2000, `ErrorKind::InvalidData` is returned and the leading bytes
+    /// stay consumed. Likewise, if the stream ends after such leading bytes,
+    /// `ErrorKind::UnexpectedEof` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new("\u{a0} \u{3000}ねこ".as_bytes());
+    /// assert_eq!(cursor.skip_unicode_whitespace().unwrap(), 6);
+    /// assert_eq!(cursor.fill_buf().unwrap(), "ねこ".as_bytes());
+    /// ```
+    fn skip_unicode_whitespace(&mut self) -> io::Result<usize> {
+        ascii::skip_unicode_whitespace(self)
+    }
+
+    /// Read until the `close` byte that matches an `open` byte that was
+    /// already consumed.
+    ///
+    /// Reading starts at a nesting depth of one. Each `open` byte increases
+    /// the depth and each `close` byte decreases it, until the `close` byte
+    /// that brings it to zero. The bytes before that byte are appended to
+    /// `buf`, including any nested delimiters, and the matching `close` byte
+    /// is consumed but not appended, so `buf` holds exactly the block's
+    /// contents. Returns the amount of bytes appended to `buf`.
+    ///
+    /// Returns `ErrorKind::InvalidData` if the reader reaches EOF before the
+    /// matching `close` byte. The bytes read until then are appended to `buf`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"(+ 1 (* 2 3)) rest");
+    /// assert!(cursor.consume_prefix(b"(").unwrap());
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.read_balanced(b'(', b')', &mut buf).unwrap(), 11);
+    /// assert_eq!(buf, b"+ 1 (* 2 3)");
+    /// assert_eq!(cursor.fill_buf().unwrap(), b" rest");
+    /// ```
+    fn read_balanced<S>(&mut self, open: u8, close: u8, buf: &mut S) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+    {
+        read_balanced(self, open, close, false, buf)
+    }
+
+    /// Read until the `close` byte that matches an `open` byte that was
+    /// already consumed, ignoring delimiters inside string literals.
+    ///
+    /// This behaves like [`read_balanced`], except that `open` and `close`
+    /// bytes between double quotes don't count. Inside a string literal a
+    /// backslash escapes the byte after it, so `"\""` is a complete literal.
+    ///
+    /// [`read_balanced`]: #method.read_balanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(br#"{"a": "}", "b": {}} rest"#);
+    /// assert!(cursor.consume_prefix(b"{").unwrap());
+    /// let mut buf = vec![];
+    /// cursor.read_balanced_quoted(b'{', b'}', &mut buf).unwrap();
+    /// assert_eq!(buf, br#""a": "}", "b": {}"#);
+    /// ```
+    fn read_balanced_quoted<S>(&mut self, open: u8, close: u8, buf: &mut S) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+    {
+        read_balanced(self, open, close, true, buf)
+    }
+
+    /// Read percent-encoded bytes until one of `delims` or EOF is reached,
+    /// decoding them into `out`.
+    ///
+    /// Each `%XX` escape is decoded to the byte with hex value `XX`, in either
+    /// case. If `form_mode` is set `+` is decoded to a space, as in
+    /// `application/x-www-form-urlencoded` bodies; otherwise it's kept as is.
+    /// The first delimiter is consumed but not appended to `out`, and is
+    /// returned together with the amount of bytes appended. The delimiter is
+    /// `None` if the reader reached EOF instead. Escapes are decoded correctly
+    /// when they span multiple buffer refills.
+    ///
+    /// Returns `ErrorKind::InvalidData` if a `%` isn't followed by two hex
+    /// digits, with the raw bytes of the escape in the error message. The
+    /// escape is consumed up to, and not including, the offending byte. The
+    /// error of a sink that's full is returned the same way, with the input
+    /// consumed up to the byte whose decoded byte didn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"q=caf%C3%A9+au+lait&page=2");
+    /// let mut key = vec![];
+    /// let mut value = vec![];
+    /// let res = cursor.read_percent_decoded_until(b"=&", &mut key, true).unwrap();
+    /// assert_eq!(res, (1, Some(b'=')));
+    /// let res = cursor.read_percent_decoded_until(b"&", &mut value, true).unwrap();
+    /// assert_eq!(res, (13, Some(b'&')));
+    /// assert_eq!(value, "café au lait".as_bytes());
+    /// ```
+    fn read_percent_decoded_until<S>(
+        &mut self,
+        delims: &[u8],
+        out: &mut S,
+        form_mode: bool,
+    ) -> io::Result<(usize, Option<u8>)>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let mut written = 0;
+        // The `%` and hex digits of an escape that's being read.
+        let mut escape = [0; 3];
+        let mut escape_len = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                if escape_len > 0 {
+                    return Err(invalid_escape(&escape[..escape_len]));
+                }
+                return Ok((written, None));
+            }
+
+            for (i, &byte) in available.iter().enumerate() {
+                let decoded = if escape_len > 0 {
+                    if !byte.is_ascii_hexdigit() {
+                        let err = invalid_escape(&[&escape[..escape_len], &[byte]].concat());
+                        self.consume(i);
+                        return Err(err);
+                    }
+                    escape[escape_len] = byte;
+                    escape_len += 1;
+                    if escape_len < 3 {
+                        continue;
+                    }
+                    escape_len = 0;
+                    hex_value(escape[1]) << 4 | hex_value(escape[2])
+                } else if delims.contains(&byte) {
+                    self.consume(i + 1);
+                    return Ok((written, Some(byte)));
+                } else if byte == b'%' {
+                    escape[0] = byte;
+                    escape_len = 1;
+                    continue;
+                } else if byte == b'+' && form_mode {
+                    b' '
+                } else {
+                    byte
+                };
+                if let Err(e) = out.extend_from_slice(&[decoded]) {
+                    self.consume(i);
+                    return Err(e);
+                }
+                written += 1;
+            }
+            let len = available.len();
+            self.consume(len);
+        }
+    }
+
+    /// Read a header line, joining any folded continuation lines onto it.
+    ///
+    /// A line ends with CRLF or a bare LF. After each line the first byte of
+    /// the next line is peeked at without consuming it: if it's a space or a
+    /// tab, that line continues the current one and is read as well. The
+    /// line ending and leading whitespace of each fold are replaced by a
+    /// single space. Any other byte, including the CR of the empty line that
+    /// ends a header block, stays in the reader.
+    ///
+    /// The unfolded line is appended to `buf` without its final line ending.
+    /// Returns the amount of bytes read from the reader, which is `0` only at
+    /// EOF.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` if the reader reaches EOF in the
+    /// middle of a line. The bytes read until then are appended to `buf`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"Subject: a long\r\n  subject\r\n\r\nbody");
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.read_unfolded_line(&mut buf).unwrap(), 28);
+    /// assert_eq!(buf, b"Subject: a long subject");
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"\r\nbody");
+    /// ```
+    fn read_unfolded_line(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let mut read = 0;
+        loop {
+            let n = self.read_until(b'\n', buf)?;
+            if n == 0 && read == 0 {
+                return Ok(0);
+            }
+            read += n;
+            if n == 0 || buf.last() != Some(&b'\n') {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "header line ended without a line ending",
+                ));
+            }
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+            }
+
+            match ascii::peek(self)? {
+                Some(b' ') | Some(b'\t') => {
+                    read += self.skip_while(|b| b == b' ' || b == b'\t')?;
+                    buf.push(b' ');
+                }
+                _ => return Ok(read),
+            }
+        }
+    }
+
+    /// Returns an iterator over chunks of exactly `n` bytes.
+    ///
+    /// Each item is a new `Vec` of `n` bytes. If the length of the stream
+    /// isn't a multiple of `n`, the last bytes don't form a whole chunk: the
+    /// iterator ends without yielding them, and they're available through
+    /// [`ChunksExact::remainder`] instead. Errors from the reader are yielded
+    /// as they occur.
+    ///
+    /// [`for_each_chunk`] visits the chunks without allocating for each one.
+    ///
+    /// [`ChunksExact::remainder`]: struct.ChunksExact.html#method.remainder
+    /// [`for_each_chunk`]: #method.for_each_chunk
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut chunks = io::Cursor::new(b"abcdefg").chunks_exact(3);
+    /// assert_eq!(chunks.next().unwrap().unwrap(), b"abc");
+    /// assert_eq!(chunks.next().unwrap().unwrap(), b"def");
+    /// assert!(chunks.next().is_none());
+    /// assert_eq!(chunks.remainder(), b"g");
+    /// ```
+    fn chunks_exact(self, n: usize) -> ChunksExact<Self>
+    where
+        Self: Sized,
+    {
+        assert!(n != 0, "chunk size must be non-zero");
+        ChunksExact {
+            reader: self,
+            n,
+            remainder: Vec::new(),
+        }
+    }
+
+    /// Call `f` on each chunk of exactly `n` bytes, until EOF.
+    ///
+    /// This reads the same chunks as [`chunks_exact`], but reuses a single
+    /// buffer for all of them. Stops at the first error returned by either
+    /// the reader or `f`. Returns the last bytes of the stream that don't form
+    /// a whole chunk, which may be empty.
+    ///
+    /// [`chunks_exact`]: #method.chunks_exact
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new([1, 2, 3, 4, 5]);
+    /// let mut sums = vec![];
+    /// let rest = cursor
+    ///     .for_each_chunk(2, |chunk| {
+    ///         sums.push(chunk[0] + chunk[1]);
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(sums, [3, 7]);
+    /// assert_eq!(rest, [5]);
+    /// ```
+    fn for_each_chunk<F>(&mut self, n: usize, mut f: F) -> io::Result<Vec<u8>>
+    where
+        F: FnMut(&[u8]) -> io::Result<()>,
+    {
+        assert!(n != 0, "chunk size must be non-zero");
+        let mut chunk = Vec::with_capacity(n);
+        loop {
+            fill_chunk(self, n, &mut chunk)?;
+            if chunk.len() < n {
+                return Ok(chunk);
+            }
+            f(&chunk)?;
+            chunk.clear();
+        }
+    }
+
+    /// Skip the first `n` bytes.
+    ///
+    /// Stops early if the reader reaches EOF.
+    fn skip(&mut self, n: usize) -> io::Result<()> {
+        let mut read = 0;
+        while read < n {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                break;
+            }
+
+            let len = available.len().min(n - read);
+            self.consume(len);
+            read += len;
+        }
+
+        Ok(())
+    }
+
+    /// Skip bytes while the predicate is true.
+    fn skip_while<P>(&mut self, predicate: P) -> io::Result<usize>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        self.skip_while_peek(predicate).map(|(read, _)| read)
+    }
+
+    /// Skip bytes while the predicate is true, and return the byte that
+    /// stopped it.
+    ///
+    /// Returns the amount of bytes skipped together with the first byte the
+    /// predicate rejected. That byte isn't consumed, so it's also the next
+    /// byte of the stream. It's `None` if the reader reached EOF instead.
+    ///
+    /// A read error carries a [`PartialError`] whose `completed` is the
+    /// amount of bytes skipped until then.
+    ///
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"   x");
+    /// let res = cursor.skip_while_peek(|b| b == b' ').unwrap();
+    /// assert_eq!(res, (3, Some(b'x')));
+    /// assert_eq!(cursor.skip_while_peek(|_| true).unwrap(), (1, None));
+    /// ```
+    fn skip_while_peek<P>(&mut self, predicate: P) -> io::Result<(usize, Option<u8>)>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        while_peek::<_, Vec<u8>, _>(self, None, predicate)
+    }
+
+    /// Skip bytes while a predicate over chunks accepts them.
+    ///
+    /// `accept` is called with each chunk of buffered bytes, and returns how
+    /// many of its leading bytes to skip. Those bytes are consumed, and the
+    /// operation continues with the next chunk only if the whole chunk was
+    /// accepted. This allows skipping with `memchr` or SIMD routines rather
+    /// than a per-byte predicate. Return values larger than the chunk are
+    /// treated as accepting the whole chunk.
+    ///
+    /// Returns the amount of bytes skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"    lorem");
+    /// let skipped = cursor
+    ///     .skip_while_chunk(|chunk| chunk.iter().take_while(|b| **b == b' ').count())
+    ///     .unwrap();
+    /// assert_eq!(skipped, 4);
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"lorem");
+    /// ```
+    fn skip_while_chunk<P>(&mut self, mut accept: P) -> io::Result<usize>
+    where
+        P: FnMut(&[u8]) -> usize,
+    {
+        let mut read = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                break;
+            }
+
+            let len = accept(available).min(available.len());
+            let done = len < available.len();
+            self.consume(len);
+            read += len;
+            if done {
+                break;
+            }
+        }
+
+        Ok(read)
+    }
+
+    /// Skip at most `max` bytes while the predicate is true.
+    ///
+    /// This bounds how long a skip over an untrusted stream can run, like
+    /// the `_capped` methods bound a read. Returns why the skip stopped,
+    /// together with the amount of bytes skipped:
+    ///
+    /// - `SkipOutcome::PredicateFailed` if the predicate rejected a byte,
+    ///   which is left unconsumed.
+    /// - `SkipOutcome::Eof` if the reader reached EOF.
+    /// - `SkipOutcome::LimitReached` if `max` bytes were skipped and the
+    ///   next byte matches too. That byte is left unconsumed, so the skip
+    ///   can be continued with another call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    /// use omnom::SkipOutcome;
+    ///
+    /// let mut cursor = io::Cursor::new(b"aaaaab");
+    /// let res = cursor.skip_while_limited(3, |b| b == b'a').unwrap();
+    /// assert_eq!(res, SkipOutcome::LimitReached(3));
+    /// let res = cursor.skip_while_limited(3, |b| b == b'a').unwrap();
+    /// assert_eq!(res, SkipOutcome::PredicateFailed(2));
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"b");
+    /// ```
+    fn skip_while_limited<P>(&mut self, max: u64, mut predicate: P) -> io::Result<SkipOutcome>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let mut skipped = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                return Ok(SkipOutcome::Eof(skipped));
+            }
+            if skipped == max {
+                return Ok(if predicate(available[0]) {
+                    SkipOutcome::LimitReached(skipped)
+                } else {
+                    SkipOutcome::PredicateFailed(skipped)
+                });
+            }
+
+            let budget = usize::try_from(max - skipped).unwrap_or(usize::MAX);
+            let window = &available[..available.len().min(budget)];
+            match window.iter().position(|b| !predicate(*b)) {
+                Some(len) => {
+                    self.consume(len);
+                    return Ok(SkipOutcome::PredicateFailed(skipped + len as u64));
+                }
+                None => {
+                    let len = window.len();
+                    self.consume(len);
+                    skipped += len as u64;
+                }
+            }
+        }
+    }
+
+    /// Skip bytes until the delimiter `byte` or EOF is reached.
+    ///
+    /// This function will read bytes from the underlying stream until the
+    /// delimiter or EOF is found. Once found, all bytes up to, and including,
+    /// the delimiter (if found) will be skipped.
+    ///
+    /// If successful, this function will return the total number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// This function will ignore all instances of [`ErrorKind::Interrupted`] and
+    /// will otherwise return any errors returned by [`BufRead::fill_buf`].
+    ///
+    /// If an I/O error is encountered then all bytes read so far will be
+    /// present in `buf` and its length will have been adjusted appropriately.
+    ///
+    /// [`BufRead::consume`]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.consume
+    /// [`BufRead::fill_buf`]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.consume
+    /// [`ErrorKind::Interrupted`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.Interrupted
+    ///
+    /// # Examples
+    ///
+    /// [`std::io::Cursor`][`Cursor`] is a type that implements `BufRead`. In
+    /// this example, we use [`Cursor`] to read all the bytes in a byte slice
+    /// in hyphen delimited segments:
+    ///
+    /// [`Cursor`]: https://doc.rust-lang.org/std/io/struct.Cursor.html
+    ///
+    /// ```
+    /// use std::io::{self, BufRead, Read};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem-ipsum");
+    ///
+    /// // skip up to and including '-'
+    /// let num_bytes = BufReadExt::skip_until(&mut cursor, b'-').unwrap();
+    /// assert_eq!(num_bytes, 6);
+    ///
+    /// // read the rest of the bytes
+    /// let mut buf = [0; 5];
+    /// cursor.read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"ipsum");
+    /// ```
+    fn skip_until(&mut self, byte: u8) -> io::Result<usize> {
+        let mut read = 0;
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if available.is_empty() {
+                break;
+            }
+
+            let (len, found) = match memchr::memchr(byte, available) {
+                Some(i) => (i + 1, true),
+                None => (available.len(), false),
+            };
+            self.consume(len);
+            read += len;
+            if found {
+                break;
+            }
+        }
+
+        Ok(read)
+    }
+
+    /// Skip bytes through the `n`th occurrence of the delimiter `byte`, or
+    /// until EOF is reached.
+    ///
+    /// Returns the amount of bytes skipped together with the amount of
+    /// delimiters found, which is less than `n` only at EOF. If `n` is `0`
+    /// nothing is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"id\tname\tage\n");
+    /// assert_eq!(cursor.skip_until_nth(b'\t', 2).unwrap(), (8, 2));
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"age\n");
+    /// ```
+    fn skip_until_nth(&mut self, byte: u8, n: usize) -> io::Result<(usize, usize)> {
+        let mut read = 0;
+        let mut found = 0;
+        while found < n {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                break;
+            }
+
+            let len = nth_end(byte, available, n, &mut found);
+            self.consume(len);
+            read += len;
+        }
+
+        Ok((read, found))
+    }
+
+    /// Read bytes while they're in `set`.
+    ///
+    /// This is [`read_while`] with a predicate that checks a [`ByteSet`].
+    /// Returns the amount of bytes read.
+    ///
+    /// [`read_while`]: #method.read_while
+    /// [`ByteSet`]: struct.ByteSet.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"0x1f;");
+    /// let mut buf = vec![];
+    /// cursor.read_any_of(b"0x", &mut buf).unwrap();
+    /// assert_eq!(buf, b"0x");
+    /// ```
+    fn read_any_of<S>(&mut self, set: impl Into<ByteSet>, buf: &mut S) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let set = set.into();
+        self.read_while(buf, |byte| set.contains(byte))
+    }
+
+    /// Skip bytes while they're in `set`.
+    ///
+    /// Returns the amount of bytes skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b" \t\r\nbody");
+    /// assert_eq!(cursor.skip_any_of(b" \t\r\n").unwrap(), 4);
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"body");
+    /// ```
+    fn skip_any_of(&mut self, set: impl Into<ByteSet>) -> io::Result<usize> {
+        let set = set.into();
+        self.skip_while(|byte| set.contains(byte))
+    }
+
+    /// Read bytes into `buf` until a byte in `set` or EOF is reached.
+    ///
+    /// Like `read_until`, the delimiter is consumed and appended to `buf` as
+    /// well. Returns the amount of bytes read, including the delimiter,
+    /// together with the delimiter, which is `None` at EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"a=1;b=2");
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.read_until_any(b"=;", &mut buf).unwrap(), (2, Some(b'=')));
+    /// assert_eq!(cursor.read_until_any(b"=;", &mut buf).unwrap(), (2, Some(b';')));
+    /// assert_eq!(buf, b"a=1;");
+    /// ```
+    fn read_until_any<S>(
+        &mut self,
+        set: impl Into<ByteSet>,
+        buf: &mut S,
+    ) -> io::Result<(usize, Option<u8>)>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let set = set.into();
+        let (read, found) = self.read_while_peek(buf, |byte| !set.contains(byte))?;
+        if let Some(byte) = found {
+            buf.extend_from_slice(&[byte])?;
+            self.consume(1);
+            return Ok((read + 1, found));
+        }
+        Ok((read, None))
+    }
+
+    /// Skip bytes until a byte in `set` or EOF is reached.
+    ///
+    /// Like [`skip_until`], the delimiter is skipped as well. Returns the
+    /// amount of bytes skipped, including the delimiter, together with the
+    /// delimiter, which is `None` at EOF.
+    ///
+    /// [`skip_until`]: #method.skip_until
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"junk\r\nline");
+    /// assert_eq!(cursor.skip_until_any(b"\r\n").unwrap(), (5, Some(b'\r')));
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"\nline");
+    /// ```
+    fn skip_until_any(&mut self, set: impl Into<ByteSet>) -> io::Result<(usize, Option<u8>)> {
+        let set = set.into();
+        let (skipped, found) = self.skip_while_peek(|byte| !set.contains(byte))?;
+        if found.is_some() {
+            self.consume(1);
+            return Ok((skipped + 1, found));
+        }
+        Ok((skipped, None))
+    }
+
+    /// Skip `prefix` if the stream starts with it.
+    ///
+    /// Returns `false` without consuming anything if the stream continues
+    /// with other bytes, or ends before `prefix` was seen.
+    ///
+    /// Bytes are compared against what `fill_buf` returns. If `prefix`
+    /// straddles two `fill_buf` calls, the matching part of the first one is
+    /// consumed before the rest can be compared. A mismatch after that point
+    /// can't be undone, and is returned as an error of the kind
+    /// `ErrorKind::InvalidData`, or `ErrorKind::UnexpectedEof` with a
+    /// [`ShortRead`] if the stream ends instead.
+    ///
+    /// [`ShortRead`]: struct.ShortRead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"GET /");
+    /// assert!(!cursor.consume_prefix(b"POST").unwrap());
+    /// assert!(cursor.consume_prefix(b"GET ").unwrap());
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"/");
+    /// ```
+    fn consume_prefix(&mut self, prefix: &[u8]) -> io::Result<bool> {
+        let mut matched = 0;
+        while matched < prefix.len() {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            let rest = &prefix[matched..];
+            let n = available.len().min(rest.len());
+            if n > 0 && available[..n] == rest[..n] {
+                self.consume(n);
+                matched += n;
+            } else if matched == 0 {
+                return Ok(false);
+            } else if n == 0 {
+                return Err(ShortRead::new(prefix.len(), matched).into());
+            } else {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("prefix mismatch after consuming {} bytes", matched),
+                ));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Compare the next `other.len()` bytes of the stream with `other`,
+    /// without consuming anything.
+    ///
+    /// The bytes are compared lexicographically, like slices are. If the
+    /// stream ends before `other.len()` bytes and its bytes equal the start
+    /// of `other`, the stream is shorter and compares as `Less`.
+    ///
+    /// Only the bytes of a single `fill_buf` call can be compared without
+    /// consuming them, and fewer bytes than `other.len()` are taken as the
+    /// end of the stream. A `BufReader` may return only the tail of its
+    /// buffer, so wrap the reader in a [`Lookahead`] whose `K` is at least
+    /// the length of the longest `other`, which always shows the next `K`
+    /// bytes.
+    ///
+    /// [`Lookahead`]: struct.Lookahead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use std::io::BufReader;
+    /// use omnom::prelude::*;
+    /// use omnom::Lookahead;
+    ///
+    /// let reader = BufReader::with_capacity(2, &b"banana"[..]);
+    /// let mut reader = Lookahead::<_, 8>::new(reader).unwrap();
+    /// assert_eq!(reader.compare_buffered(b"apple").unwrap(), Ordering::Greater);
+    /// assert_eq!(reader.compare_buffered(b"banana").unwrap(), Ordering::Equal);
+    /// assert_eq!(reader.compare_buffered(b"bananas").unwrap(), Ordering::Less);
+    /// assert_eq!(reader.read_be::<[u8; 6]>().unwrap(), *b"banana");
+    /// ```
+    fn compare_buffered(&mut self, other: &[u8]) -> io::Result<cmp::Ordering> {
+        let available = loop {
+            match self.fill_buf() {
+                Ok(b) => break b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        let len = available.len().min(other.len());
+        Ok(available[..len].cmp(other))
+    }
+
+    /// Returns the next byte without consuming it, or `None` at EOF.
+    ///
+    /// This retries `fill_buf` on `ErrorKind::Interrupted`, and peeking
+    /// repeatedly returns the same byte until it's consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufRead, Cursor};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new(b"-1");
+    /// assert_eq!(cursor.peek_byte().unwrap(), Some(b'-'));
+    /// assert_eq!(cursor.peek_byte().unwrap(), Some(b'-'));
+    /// cursor.consume(2);
+    /// assert_eq!(cursor.peek_byte().unwrap(), None);
+    /// ```
+    fn peek_byte(&mut self) -> io::Result<Option<u8>> {
+        ascii::peek(self)
+    }
+
+    /// Returns the next `n` bytes without consuming them.
+    ///
+    /// `n` may be larger than the reader's current buffer, so this requires a
+    /// reader that can buffer further ahead, such as a [`Lookahead`] or an
+    /// [`Unread`]. The slice stays valid until the reader is next used.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the stream
+    /// ends first. The bytes that were peeked stay in the stream.
+    ///
+    /// [`Lookahead`]: struct.Lookahead.html
+    /// [`Unread`]: struct.Unread.html
+    /// [`ShortRead`]: struct.ShortRead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use omnom::prelude::*;
+    /// use omnom::Unread;
+    ///
+    /// let input = &b"\x89PNG\r\n\x1a\n"[..];
+    /// let mut reader = Unread::new(BufReader::with_capacity(2, input));
+    /// assert_eq!(reader.peek(4).unwrap(), b"\x89PNG");
+    /// assert!(reader.peek(9).is_err());
+    /// assert_eq!(reader.read_be::<u32>().unwrap(), 0x8950_4e47);
+    /// ```
+    fn peek(&mut self, n: usize) -> io::Result<&[u8]>
+    where
+        Self: BufferAhead,
+    {
+        let available = self.buffer_ahead(n)?;
+        if available.len() < n {
+            return Err(ShortRead::new(n, available.len()).into());
+        }
+        Ok(&available[..n])
+    }
+
+    /// Copy the next `buf.len()` bytes into `buf`, without consuming them.
+    ///
+    /// This is the counterpart of `read_exact` that leaves the bytes in the
+    /// stream, so a later read sees them again.
+    ///
+    /// The bytes are buffered ahead as far as needed, so this fails only if
+    /// the stream ends first, with `ErrorKind::UnexpectedEof` and a
+    /// [`ShortRead`] inside. [`fill_exact_buffered`] works on any `BufRead`
+    /// instead, but can only copy the bytes of a single `fill_buf` call.
+    ///
+    /// [`ShortRead`]: struct.ShortRead.html
+    /// [`fill_exact_buffered`]: #method.fill_exact_buffered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufReader, Read};
+    /// use omnom::prelude::*;
+    /// use omnom::Lookahead;
+    ///
+    /// let reader = BufReader::with_capacity(2, &b"\x89PNG\r\n"[..]);
+    /// let mut reader = Lookahead::<_, 4>::new(reader).unwrap();
+    /// let mut magic = [0; 4];
+    /// reader.fill_exact(&mut magic).unwrap();
+    /// assert_eq!(&magic, b"\x89PNG");
+    ///
+    /// let mut buf = [0; 6];
+    /// reader.read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"\x89PNG\r\n");
+    /// assert!(reader.fill_exact(&mut [0; 1]).is_err());
+    /// ```
+    fn fill_exact(&mut self, buf: &mut [u8]) -> io::Result<()>
+    where
+        Self: BufferAhead,
+    {
+        let len = buf.len();
+        buf.copy_from_slice(self.peek(len)?);
+        Ok(())
+    }
+
+    /// Copy the next `buf.len()` bytes of the reader's buffer into `buf`,
+    /// without consuming them.
+    ///
+    /// Only the bytes of a single `fill_buf` call can be copied without
+    /// consuming them. If it returns no bytes, the stream has ended, and this
+    /// returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] inside. If it
+    /// returns some but fewer than `buf.len()` bytes, whether more follow
+    /// can't be told without consuming them, and this returns a
+    /// [`BufferTooSmall`] instead, such as with
+    /// `BufReader::with_capacity(2, ..)`. [`fill_exact`] peeks past the
+    /// underlying buffer, for readers that can buffer ahead.
+    ///
+    /// [`BufferTooSmall`]: struct.BufferTooSmall.html
+    /// [`ShortRead`]: struct.ShortRead.html
+    /// [`fill_exact`]: #method.fill_exact
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufReader, ErrorKind};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = BufReader::with_capacity(4, &b"\x89PNG\r\n"[..]);
+    /// let mut magic = [0; 4];
+    /// reader.fill_exact_buffered(&mut magic).unwrap();
+    /// assert_eq!(&magic, b"\x89PNG");
+    ///
+    /// let err = reader.fill_exact_buffered(&mut [0; 6]).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::Other);
+    /// ```
+    fn fill_exact_buffered(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let available = loop {
+            match self.fill_buf() {
+                Ok(b) => break b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        match available.get(..buf.len()) {
+            Some(bytes) => {
+                buf.copy_from_slice(bytes);
+                Ok(())
+            }
+            None if available.is_empty() => Err(ShortRead::new(buf.len(), 0).into()),
+            None => Err(BufferTooSmall::new(buf.len(), available.len()).into()),
+        }
+    }
+
+    /// Decode the next bytes as a big endian `B`, without consuming them.
+    ///
+    /// This is [`peek`] for a value, for sniffing a header before committing
+    /// to it. The value may straddle the end of the reader's current buffer,
+    /// so like `peek` this requires a reader that can buffer further ahead,
+    /// such as a [`Lookahead`] or an [`Unread`]. Returns
+    /// `ErrorKind::UnexpectedEof` with a [`ShortRead`] inside if fewer than
+    /// `size_of::<B>()` bytes are left in the stream.
+    ///
+    /// [`peek`]: #method.peek
+    /// [`Lookahead`]: struct.Lookahead.html
+    /// [`Unread`]: struct.Unread.html
+    /// [`ShortRead`]: struct.ShortRead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = Cursor::new(b"\x89PNG\r\n\x1a\n");
+    /// let magic: u32 = reader.fill_be().unwrap();
+    /// if magic == 0x8950_4e47 {
+    ///     reader.read_be::<u64>().unwrap();
+    /// }
+    /// assert!(reader.fill_be::<u8>().is_err());
+    /// ```
+    fn fill_be<B: ReadBytes>(&mut self) -> io::Result<B>
+    where
+        Self: BufferAhead,
+    {
+        fill_array::<Self, B>(self).map(B::from_be_array)
+    }
+
+    /// Decode the next bytes as a little endian `B`, without consuming them.
+    ///
+    /// See [`fill_be`] for details.
+    ///
+    /// [`fill_be`]: #method.fill_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = Cursor::new([0x2a, 0x00]);
+    /// assert_eq!(reader.fill_le::<u16>().unwrap(), 42);
+    /// assert_eq!(reader.read_le::<u16>().unwrap(), 42);
+    /// ```
+    fn fill_le<B: ReadBytes>(&mut self) -> io::Result<B>
+    where
+        Self: BufferAhead,
+    {
+        fill_array::<Self, B>(self).map(B::from_le_array)
+    }
+
+    /// Decode the next bytes as a `B` in native endianness, without
+    /// consuming them.
+    ///
+    /// See [`fill_be`] for details.
+    ///
+    /// [`fill_be`]: #method.fill_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use omnom::prelude::*;
+    /// use omnom::Unread;
+    ///
+    /// let bytes = 0x0102_0304_u32.to_ne_bytes();
+    /// let mut reader = Unread::new(BufReader::with_capacity(1, &bytes[..]));
+    /// assert_eq!(reader.fill_ne::<u32>().unwrap(), 0x0102_0304);
+    /// assert_eq!(reader.read_ne::<u32>().unwrap(), 0x0102_0304);
+    /// ```
+    fn fill_ne<B: ReadBytes>(&mut self) -> io::Result<B>
+    where
+        Self: BufferAhead,
+    {
+        fill_array::<Self, B>(self).map(B::from_ne_array)
+    }
+
+    /// Copy the bytes up to and including the delimiter `byte` into `buf`,
+    /// without consuming them.
+    ///
+    /// This is the counterpart of `read_until` that leaves the bytes in the
+    /// stream, so a `read_until` that follows returns the same bytes.
+    /// Returns the amount of bytes copied, and whether the delimiter was
+    /// found and copied. It's only `false` at EOF, in which case all bytes
+    /// up to EOF were copied.
+    ///
+    /// The delimiter may be past the end of the reader's current buffer, so
+    /// this requires a reader that can buffer further ahead, such as a
+    /// [`Lookahead`] or an [`Unread`].
+    ///
+    /// [`Lookahead`]: struct.Lookahead.html
+    /// [`Unread`]: struct.Unread.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"key: value\r\n");
+    /// let mut peeked = vec![];
+    /// assert_eq!(cursor.fill_until(b':', &mut peeked).unwrap(), (4, true));
+    /// assert_eq!(peeked, b"key:");
+    ///
+    /// let mut line = vec![];
+    /// cursor.read_until(b'\n', &mut line).unwrap();
+    /// assert_eq!(line, b"key: value\r\n");
+    /// ```
+    fn fill_until<S>(&mut self, byte: u8, buf: &mut S) -> io::Result<(usize, bool)>
+    where
+        Self: BufferAhead,
+        S: ByteSink + ?Sized,
+    {
+        let mut len = 0;
+        loop {
+            let available = self.buffer_ahead(len + 1)?;
+            if available.len() <= len {
+                return Ok((len, false));
+            }
+            let chunk = &available[len..];
+            match memchr::memchr(byte, chunk) {
+                Some(i) => {
+                    buf.extend_from_slice(&chunk[..=i])?;
+                    return Ok((len + i + 1, true));
+                }
+                None => {
+                    buf.extend_from_slice(chunk)?;
+                    len += chunk.len();
+                }
+            }
+        }
+    }
+
+    /// Copy bytes into `buf` while the predicate is true, without consuming
+    /// them.
+    ///
+    /// This is the counterpart of [`read_while`] that leaves the bytes in
+    /// the stream, to look at a token before deciding how to parse it.
+    /// Returns the amount of bytes copied.
+    ///
+    /// The token may go on past the end of the reader's current buffer, so
+    /// this requires a reader that can buffer further ahead, such as a
+    /// [`Lookahead`] or an [`Unread`]. It stops at the first byte that
+    /// doesn't match, or at EOF.
+    ///
+    /// [`read_while`]: #method.read_while
+    /// [`Lookahead`]: struct.Lookahead.html
+    /// [`Unread`]: struct.Unread.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem-ipsum");
+    /// let mut buf = vec![];
+    /// assert_eq!(cursor.fill_while(&mut buf, |b| b != b'-').unwrap(), 5);
+    /// assert_eq!(buf, b"lorem");
+    /// assert_eq!(cursor.fill_buf().unwrap(), b"lorem-ipsum");
+    /// ```
+    fn fill_while<S, P>(&mut self, buf: &mut S, mut predicate: P) -> io::Result<usize>
+    where
+        Self: BufferAhead,
+        S: ByteSink + ?Sized,
+        P: FnMut(u8) -> bool,
+    {
+        let mut len = 0;
+        loop {
+            let available = self.buffer_ahead(len + 1)?;
+            if available.len() <= len {
+                return Ok(len);
+            }
+            let chunk = &available[len..];
+            let matched = chunk
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(chunk.len());
+            buf.extend_from_slice(&chunk[..matched])?;
+            len += matched;
+            if matched < chunk.len() {
+                return Ok(len);
+            }
+        }
+    }
+
+    /// Move the bytes that are already buffered into `out`, without reading
+    /// from the underlying source.
+    ///
+    /// This is for handing a connection over to another layer, such as after
+    /// an HTTP upgrade or `STARTTLS`, where the buffered bytes belong to the
+    /// next protocol and the socket may have nothing more to read. Returns
+    /// the amount of bytes moved, which is `0` if the buffer is empty.
+    ///
+    /// `fill_buf` is only called while [`DataReady::has_data_ready`] returns
+    /// `true`, so this never reads from the underlying source of a
+    /// `BufReader`. A [`Lookahead`] tops up its buffer from the reader it
+    /// wraps when consumed, so it's not suited for this.
+    ///
+    /// [`DataReady::has_data_ready`]: trait.DataReady.html#tymethod.has_data_ready
+    /// [`Lookahead`]: struct.Lookahead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufRead, BufReader};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = BufReader::new(&b"HTTP/1.1 101\r\n\r\n\x81\x05hello"[..]);
+    /// reader.fill_buf().unwrap();
+    /// reader.consume(16);
+    ///
+    /// let mut frames = vec![];
+    /// assert_eq!(reader.drain_buffered(&mut frames), 7);
+    /// assert_eq!(frames, b"\x81\x05hello");
+    /// ```
+    fn drain_buffered(&mut self, out: &mut Vec<u8>) -> usize
+    where
+        Self: DataReady,
+    {
+        let mut drained = 0;
+        while self.has_data_ready() {
+            // Buffered bytes are returned without reading, so there's no
+            // error to expect.
+            let len = match self.fill_buf() {
+                Ok(buf) => {
+                    out.extend_from_slice(buf);
+                    buf.len()
+                }
+                Err(_) => break,
+            };
+            self.consume(len);
+            drained += len;
+        }
+        drained
+    }
+
+    /// Copy bytes to `writer` until the delimiter `byte` or EOF is reached.
+    ///
+    /// The delimiter is copied as well, if found. The bytes are copied one
+    /// buffered chunk at a time, without an intermediate buffer. Returns the
+    /// amount of bytes copied.
+    ///
+    /// If reading or writing fails, the error carries a [`PartialError`]
+    /// whose `completed` is the amount of bytes consumed from the reader,
+    /// which have all been written. A failed write may have written some of
+    /// the bytes after those too, which stay unconsumed. The same holds for
+    /// the other `copy_` methods.
+    ///
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem\nipsum");
+    /// let mut out = vec![];
+    /// assert_eq!(cursor.copy_until(b'\n', &mut out).unwrap(), 6);
+    /// assert_eq!(out, b"lorem\n");
+    /// ```
+    fn copy_until<W>(&mut self, byte: u8, writer: &mut W) -> io::Result<u64>
+    where
+        W: Write + ?Sized,
+    {
+        copy_until(self, byte, writer, None, |_| {}).map(|(copied, _)| copied)
+    }
+
+    /// Copy bytes to `writer` until the delimiter `byte` or EOF is reached, or
+    /// until `cancel` is set.
+    ///
+    /// This behaves like [`copy_until`], but checks `cancel` before reading
+    /// each buffered chunk. Once it's set, copying stops and
+    /// `CopyEnd::Cancelled` is returned together with the amount of bytes
+    /// copied so far. Every byte consumed from the reader has been written by
+    /// then, so calling this method again resumes the copy where it left off.
+    ///
+    /// [`copy_until`]: #method.copy_until
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use omnom::prelude::*;
+    /// use omnom::CopyEnd;
+    ///
+    /// let cancel = AtomicBool::new(true);
+    /// let mut cursor = io::Cursor::new(b"lorem\nipsum");
+    /// let mut out = vec![];
+    /// let res = cursor.copy_until_cancellable(b'\n', &mut out, &cancel).unwrap();
+    /// assert_eq!(res, (0, CopyEnd::Cancelled));
+    ///
+    /// cancel.store(false, Ordering::Relaxed);
+    /// let res = cursor.copy_until_cancellable(b'\n', &mut out, &cancel).unwrap();
+    /// assert_eq!(res, (6, CopyEnd::Delimiter));
+    /// ```
+    fn copy_until_cancellable<W>(
+        &mut self,
+        byte: u8,
+        writer: &mut W,
+        cancel: &AtomicBool,
+    ) -> io::Result<(u64, CopyEnd)>
+    where
+        W: Write + ?Sized,
+    {
+        copy_until(self, byte, writer, Some(cancel), |_| {})
+    }
+
+    /// Read a message delimited by its length, as a Protocol Buffers varint.
+    ///
+    /// This is the framing of protobuf's `parseDelimitedFrom`, which
+    /// [`WriteExt::write_delimited_message`] writes. The payload is appended
+    /// to `buf`, and its length is returned.
+    ///
+    /// Returns `Ok(None)` if the reader is at EOF before the message starts.
+    /// If it ends in the middle of the length or the payload, this returns
+    /// `ErrorKind::UnexpectedEof`, and a length over `max` returns
+    /// `ErrorKind::InvalidData` before anything is allocated. On errors,
+    /// `buf` is left as it was.
+    ///
+    /// [`WriteExt::write_delimited_message`]: trait.WriteExt.html#method.write_delimited_message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = &b"\x03\x08\x96\x01\x00"[..];
+    /// let mut buf = vec![];
+    /// assert_eq!(reader.read_delimited_message(&mut buf, 1024).unwrap(), Some(3));
+    /// assert_eq!(buf, [0x08, 0x96, 0x01]);
+    /// assert_eq!(reader.read_delimited_message(&mut buf, 1024).unwrap(), Some(0));
+    /// assert_eq!(reader.read_delimited_message(&mut buf, 1024).unwrap(), None);
+    /// ```
+    fn read_delimited_message(
+        &mut self,
+        buf: &mut Vec<u8>,
+        max: usize,
+    ) -> io::Result<Option<usize>> {
+        let len = match leb128::decode(self)? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        if len > max as u64 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "message of {} bytes exceeds the maximum of {} bytes",
+                    len, max
+                ),
+            ));
+        }
+
+        let len = len as usize;
+        let start = buf.len();
+        buf.reserve(len);
+        let read = match Read::take(&mut *self, len as u64).read_to_end(buf) {
+            Ok(read) => read,
+            Err(e) => {
+                buf.truncate(start);
+                return Err(e);
+            }
+        };
+        if read < len {
+            buf.truncate(start);
+            return Err(ShortRead::new(len, read).into());
+        }
+        Ok(Some(len))
+    }
+
+    /// Read a sequence of length-prefixed labels, such as a DNS name.
+    ///
+    /// Each label is a length byte followed by that many bytes, and a length
+    /// of zero ends the sequence. The labels are appended to `out`, joined by
+    /// `separator`, so the root name, which has no labels, appends nothing.
+    /// Returns the amount of bytes read, including the length bytes.
+    ///
+    /// As in DNS, labels may hold at most 63 bytes, and the whole sequence
+    /// at most 255 bytes, or this returns `ErrorKind::InvalidData`. DNS
+    /// compression pointers, length bytes starting with the bits `11`,
+    /// aren't supported, and return `ErrorKind::Unsupported`. On errors,
+    /// `out` is left as it was.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = &b"\x03www\x07example\x03com\x00"[..];
+    /// let mut name = vec![];
+    /// assert_eq!(reader.read_label_sequence(&mut name, b'.').unwrap(), 17);
+    /// assert_eq!(name, b"www.example.com");
+    /// ```
+    fn read_label_sequence(&mut self, out: &mut Vec<u8>, separator: u8) -> io::Result<usize> {
+        let start = out.len();
+        let res = read_labels(self, out, separator);
+        if res.is_err() {
+            out.truncate(start);
+        }
+        res
+    }
+
+    /// Read a body of exactly `n` bytes, such as one with a known
+    /// `Content-Length`.
+    ///
+    /// The returned [`BodyReader`] reads at most `n` bytes, and returns
+    /// `ErrorKind::UnexpectedEof` if the stream ends before that. Call its
+    /// [`finish`] method once done, to check that the whole body was read, so
+    /// the next read from this reader starts right after it.
+    ///
+    /// [`BodyReader`]: struct.BodyReader.html
+    /// [`finish`]: struct.BodyReader.html#method.finish
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead, Read};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut conn = io::Cursor::new(b"hello\nGET /");
+    /// let mut body = conn.body_exact(6);
+    /// let mut text = String::new();
+    /// body.read_to_string(&mut text).unwrap();
+    /// body.finish().unwrap();
+    /// assert_eq!(text, "hello\n");
+    /// assert_eq!(conn.fill_buf().unwrap(), b"GET /");
+    /// ```
+    fn body_exact(&mut self, n: u64) -> BodyReader<'_, Self> {
+        BodyReader::new(self, n)
+    }
+
+    /// Copy exactly `n` bytes to `writer`.
+    ///
+    /// The bytes are copied one buffered chunk at a time, without an
+    /// intermediate buffer. Returns `ErrorKind::UnexpectedEof` if the stream
+    /// ends before `n` bytes were copied; the bytes read until then have been
+    /// written. Like with [`copy_until`], errors carry a [`PartialError`]
+    /// with the amount of bytes copied.
+    ///
+    /// [`copy_until`]: #method.copy_until
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem ipsum");
+    /// let mut out = vec![];
+    /// cursor.copy_exact(5, &mut out).unwrap();
+    /// assert_eq!(out, b"lorem");
+    /// assert!(cursor.copy_exact(10, &mut out).is_err());
+    /// ```
+    fn copy_exact<W>(&mut self, n: u64, writer: &mut W) -> io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        copy_exact(self, n, writer, |_| {})
+    }
+
+    /// Copy exactly `n` bytes to `writer`, reporting progress.
+    ///
+    /// This behaves like [`copy_exact`], and calls `progress` with the amount
+    /// of bytes copied so far after each buffered chunk. It's called once more
+    /// with the final amount when copying ends, including when it ends with an
+    /// error or after zero bytes, so the last call always reflects the end
+    /// state. The amounts never decrease, but the last one may repeat the one
+    /// before it.
+    ///
+    /// [`copy_exact`]: #method.copy_exact
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut reader = io::BufReader::with_capacity(4, &b"lorem ipsum"[..]);
+    /// let mut out = vec![];
+    /// let mut reports = vec![];
+    /// reader
+    ///     .copy_exact_with_progress(10, &mut out, |n| reports.push(n))
+    ///     .unwrap();
+    /// assert_eq!(reports, [4, 8, 10, 10]);
+    /// ```
+    fn copy_exact_with_progress<W, F>(
+        &mut self,
+        n: u64,
+        writer: &mut W,
+        progress: F,
+    ) -> io::Result<()>
+    where
+        W: Write + ?Sized,
+        F: FnMut(u64),
+    {
+        copy_exact(self, n, writer, progress)
+    }
+
+    /// Copy bytes to `writer` until the delimiter `byte` or EOF is reached,
+    /// reporting progress.
+    ///
+    /// This behaves like [`copy_until`], and calls `progress` like
+    /// [`copy_exact_with_progress`] does.
+    ///
+    /// [`copy_until`]: #method.copy_until
+    /// [`copy_exact_with_progress`]: #method.copy_exact_with_progress
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem\nipsum");
+    /// let mut out = vec![];
+    /// let mut last = 0;
+    /// cursor.copy_until_with_progress(b'\n', &mut out, |n| last = n).unwrap();
+    /// assert_eq!(last, 6);
+    /// ```
+    fn copy_until_with_progress<W, F>(
+        &mut self,
+        byte: u8,
+        writer: &mut W,
+        progress: F,
+    ) -> io::Result<u64>
+    where
+        W: Write + ?Sized,
+        F: FnMut(u64),
+    {
+        copy_until(self, byte, writer, None, progress).map(|(copied, _)| copied)
+    }
+
+    /// Skip the rest of the stream.
+    ///
+    /// Returns the amount of bytes skipped. A read error carries a
+    /// [`PartialError`] whose `completed` is the amount of bytes skipped
+    /// until then, here and in the other `skip_to_end` methods.
+    ///
+    /// [`PartialError`]: struct.PartialError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem ipsum");
+    /// assert_eq!(cursor.skip_to_end().unwrap(), 11);
+    /// assert_eq!(cursor.skip_to_end().unwrap(), 0);
+    /// ```
+    fn skip_to_end(&mut self) -> io::Result<u64> {
+        skip_to_end(self, None, |_| {}).map(|(skipped, _)| skipped)
+    }
+
+    /// Skip the rest of the stream, reporting progress.
+    ///
+    /// This behaves like [`skip_to_end`], and calls `progress` like
+    /// [`copy_exact_with_progress`] does.
+    ///
+    /// [`skip_to_end`]: #method.skip_to_end
+    /// [`copy_exact_with_progress`]: #method.copy_exact_with_progress
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem ipsum");
+    /// let mut last = 0;
+    /// cursor.skip_to_end_with_progress(|n| last = n).unwrap();
+    /// assert_eq!(last, 11);
+    /// ```
+    fn skip_to_end_with_progress<F>(&mut self, progress: F) -> io::Result<u64>
+    where
+        F: FnMut(u64),
+    {
+        skip_to_end(self, None, progress).map(|(skipped, _)| skipped)
+    }
+
+    /// Skip the rest of the stream, or until `cancel` is set.
+    ///
+    /// This behaves like [`skip_to_end`], but checks `cancel` before reading
+    /// each buffered chunk. Returns the amount of bytes skipped, together with
+    /// `CopyEnd::Eof` if the end of the stream was reached, or
+    /// `CopyEnd::Cancelled` if `cancel` was set first.
+    ///
+    /// [`skip_to_end`]: #method.skip_to_end
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use std::sync::atomic::AtomicBool;
+    /// use omnom::prelude::*;
+    /// use omnom::CopyEnd;
+    ///
+    /// let cancel = AtomicBool::new(false);
+    /// let mut cursor = io::Cursor::new(b"lorem ipsum");
+    /// let res = cursor.skip_to_end_cancellable(&cancel).unwrap();
+    /// assert_eq!(res, (11, CopyEnd::Eof));
+    /// ```
+    fn skip_to_end_cancellable(&mut self, cancel: &AtomicBool) -> io::Result<(u64, CopyEnd)> {
+        skip_to_end(self, Some(cancel), |_| {})
+    }
+
+    /// Count the occurrences of `byte` in the rest of the stream.
+    ///
+    /// This consumes the stream until EOF, one buffered chunk at a time,
+    /// without retaining any of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"lorem\nipsum\ndolor\n");
+    /// assert_eq!(cursor.count_byte(b'\n').unwrap(), 3);
+    /// ```
+    fn count_byte(&mut self, byte: u8) -> io::Result<u64> {
+        count_chunks(self, |chunk| memchr::memchr_iter(byte, chunk).count())
+    }
+
+    /// Count the bytes matching a predicate in the rest of the stream.
+    ///
+    /// This consumes the stream until EOF, one buffered chunk at a time,
+    /// without retaining any of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"1 lorem, 2 ipsum");
+    /// assert_eq!(cursor.count_matching(|b| b.is_ascii_digit()).unwrap(), 2);
+    /// ```
+    fn count_matching<P>(&mut self, mut predicate: P) -> io::Result<u64>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        count_chunks(self, |chunk| {
+            chunk.iter().filter(|b| predicate(**b)).count()
+        })
+    }
+
+    /// Count the occurrences of `needle` in the rest of the stream.
+    ///
+    /// This consumes the stream until EOF, one buffered chunk at a time,
+    /// retaining only the last `needle.len() - 1` bytes of each chunk so that
+    /// occurrences spanning buffer refills are counted too. Occurrences don't
+    /// overlap: after a match, searching resumes after its last byte, so
+    /// `aa` occurs twice in `aaaa` and once in `aaa`.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `needle` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"a\r\n--sep\r\nb\r\n--sep--");
+    /// assert_eq!(cursor.count_pattern(b"\r\n--sep").unwrap(), 2);
+    /// ```
+    fn count_pattern(&mut self, needle: &[u8]) -> io::Result<u64> {
+        let mut count = 0;
+        scan_pattern(self, needle, |_, matched| {
+            count += u64::from(matched);
+            Ok(())
+        })?;
+        Ok(count)
+    }
+
+    /// Copy the rest of the stream to `writer`, replacing each occurrence of
+    /// `needle` with `replacement`.
+    ///
+    /// The stream is copied one buffered chunk at a time, retaining only the
+    /// last `needle.len() - 1` bytes of each chunk so that occurrences
+    /// spanning buffer refills are replaced too. Occurrences don't overlap,
+    /// as in [`count_pattern`]. Returns the amount of bytes written together
+    /// with the amount of replacements made.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `needle` is empty.
+    ///
+    /// [`count_pattern`]: #method.count_pattern
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"a\r\nb\r\n");
+    /// let mut out = vec![];
+    /// assert_eq!(cursor.copy_replace(b"\r\n", b"\n", &mut out).unwrap(), (4, 2));
+    /// assert_eq!(out, b"a\nb\n");
+    /// ```
+    fn copy_replace<W>(
+        &mut self,
+        needle: &[u8],
+        replacement: &[u8],
+        writer: &mut W,
+    ) -> io::Result<(u64, u64)>
+    where
+        W: Write + ?Sized,
+    {
+        let mut written = 0;
+        let mut replaced = 0;
+        scan_pattern(self, needle, |bytes, matched| {
+            writer.write_all(bytes)?;
+            written += bytes.len() as u64;
+            if matched {
+                writer.write_all(replacement)?;
+                written += replacement.len() as u64;
+                replaced += 1;
+            }
+            Ok(())
+        })?;
+        Ok((written, replaced))
+    }
+
+    /// Read a signed integer, in the radix given by its prefix.
+    ///
+    /// Reads an optional `+` or `-` sign, followed by digits in hexadecimal
+    /// after `0x`, octal after `0o`, binary after `0b`, and decimal otherwise.
+    /// Prefixes are case insensitive. A leading `0` not followed by a prefix
+    /// is read as a decimal digit, so `010` is ten. Underscores are accepted
+    /// as digit separators after the prefix or the first digit, and ignored.
+    ///
+    /// Reading stops at the first byte that isn't a digit of the radix, which
+    /// isn't consumed: `0b102` reads as two, leaving `2` in the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the kind `ErrorKind::InvalidData` if there are no
+    /// digits, including after a prefix, or if the value doesn't fit in an
+    /// `i64`. The sign, prefix and digits read stay consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufRead};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"-0xff_ff,0b101");
+    /// assert_eq!(cursor.read_number_radix().unwrap(), -0xffff);
+    /// assert!(cursor.consume_prefix(b",").unwrap());
+    /// assert_eq!(cursor.read_number_radix().unwrap(), 5);
+    /// ```
+    fn read_number_radix(&mut self) -> io::Result<i64> {
+        let (negative, magnitude) = ascii::read_radix_literal(self, true)?;
+        if negative {
+            if magnitude > i64::MIN.unsigned_abs() {
+                return Err(ascii::overflow());
+            }
+            Ok((magnitude as i64).wrapping_neg())
+        } else {
+            i64::try_from(magnitude).map_err(|_| ascii::overflow())
+        }
+    }
+
+    /// Read an unsigned integer, in the radix given by its prefix.
+    ///
+    /// This behaves like [`read_number_radix`], but doesn't accept a sign.
+    ///
+    /// [`read_number_radix`]: #method.read_number_radix
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"0o777");
+    /// assert_eq!(cursor.read_unsigned_radix().unwrap(), 0o777);
+    /// ```
+    fn read_unsigned_radix(&mut self) -> io::Result<u64> {
+        ascii::read_radix_literal(self, false).map(|(_, magnitude)| magnitude)
+    }
+
+    /// Read a boolean literal, such as `true` or `off`.
+    ///
+    /// `style` selects which literals are accepted. A literal only matches if
+    /// it's followed by a byte that isn't ASCII alphanumeric, or by EOF, so
+    /// `online` doesn't match `on`. Exactly the literal is consumed, and the
+    /// byte after it stays in the reader.
+    ///
+    /// Returns `ErrorKind::InvalidData` listing the accepted literals if the
+    /// input doesn't start with one of them. Nothing is consumed then if the
+    /// reader's buffer holds the mismatching byte. Otherwise the input
+    /// straddles the end of the buffer, or ends in the middle of a literal,
+    /// and the bytes read before it stay consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::BoolStyle;
+    ///
+    /// let style = BoolStyle::ALL.ignore_case();
+    /// let mut cursor = io::Cursor::new(b"Yes,off,online");
+    /// assert_eq!(cursor.read_bool_ascii(style).unwrap(), true);
+    /// assert!(cursor.consume_prefix(b",").unwrap());
+    /// assert_eq!(cursor.read_bool_ascii(style).unwrap(), false);
+    /// assert!(cursor.consume_prefix(b",").unwrap());
+    /// assert!(cursor.read_bool_ascii(style).is_err());
+    /// ```
+    fn read_bool_ascii(&mut self, style: BoolStyle) -> io::Result<bool> {
+        ascii::read_bool(self, style)
+    }
+
+    /// Read an IPv4 address in dotted-quad notation, such as `192.168.0.1`.
+    ///
+    /// Each of the four octets must be a decimal number from 0 to 255, and
+    /// only the octet `0` itself may start with a zero: `1.2.3.04` is
+    /// rejected rather than read as octal or decimal. Reading stops before the
+    /// first byte that isn't part of the address, which stays in the reader.
+    ///
+    /// Returns `ErrorKind::InvalidData` if the input isn't a valid address,
+    /// including when the address is directly followed by a `.` or a digit,
+    /// as in `1.2.3.4.5`. The bytes read before the offending byte stay
+    /// consumed, and the offending byte doesn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use std::net::Ipv4Addr;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"10.0.0.1:8080");
+    /// assert_eq!(cursor.read_ipv4_ascii().unwrap(), Ipv4Addr::new(10, 0, 0, 1));
+    /// assert!(cursor.consume_prefix(b":").unwrap());
+    /// ```
+    fn read_ipv4_ascii(&mut self) -> io::Result<Ipv4Addr> {
+        ascii::read_ipv4(self)
+    }
+
+    /// Read a MAC address, such as `00:1b:44:11:3a:b7` or `00-1B-44-11-3A-B7`.
+    ///
+    /// The address is 6 octets of 2 hex digits each, in either case,
+    /// separated by either colons or hyphens but not a mix of both. Reading
+    /// stops before the first byte that isn't part of the address, which
+    /// stays in the reader.
+    ///
+    /// Returns `ErrorKind::InvalidData` if the input isn't a valid address,
+    /// including when the address is directly followed by another separator
+    /// or hex digit. The bytes read before the offending byte stay consumed,
+    /// and the offending byte doesn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::MacAddr;
+    ///
+    /// let mut cursor = io::Cursor::new(b"02-42-AC-11-00-02 eth0");
+    /// let mac = cursor.read_mac_ascii().unwrap();
+    /// assert_eq!(mac, MacAddr([0x02, 0x42, 0xac, 0x11, 0x00, 0x02]));
+    /// assert!(cursor.consume_prefix(b" eth0").unwrap());
+    /// ```
+    fn read_mac_ascii(&mut self) -> io::Result<MacAddr> {
+        ascii::read_mac(self)
+    }
+
+    /// Read an RFC 3339 timestamp, such as `2024-05-01T12:34:56.789Z`.
+    ///
+    /// The fractional seconds are optional and may have 1 to 9 digits. The
+    /// offset is either `Z` or `+HH:MM`/`-HH:MM`. The `T` and `Z` may also be
+    /// lowercase. Exactly the timestamp is consumed, and the byte after it
+    /// stays in the reader.
+    ///
+    /// Dates are validated against the calendar, so `2023-02-29` is rejected.
+    /// A leap second, `:60`, is accepted and read as the first second of the
+    /// next minute, since `Timestamp` doesn't count leap seconds.
+    ///
+    /// Returns `ErrorKind::InvalidData` if the input isn't a valid timestamp.
+    /// A field that's out of range is consumed before the error is returned;
+    /// otherwise the bytes up to the first unexpected byte are consumed, and
+    /// that byte isn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"2024-05-01T12:34:56.789Z INFO");
+    /// let ts = cursor.read_rfc3339().unwrap();
+    /// assert_eq!(ts.secs_since_epoch, 1_714_566_896);
+    /// assert_eq!(ts.nanos, 789_000_000);
+    /// assert_eq!(ts.offset_minutes, 0);
+    /// assert!(cursor.consume_prefix(b" INFO").unwrap());
+    /// ```
+    fn read_rfc3339(&mut self) -> io::Result<Timestamp> {
+        timestamp::read_rfc3339(self)
+    }
+
+    /// Read an optionally signed decimal integer.
+    ///
+    /// Reads an optional `+` or `-` followed by ASCII digits, and consumes
+    /// exactly those bytes: the byte that ends the digits stays in the reader.
+    /// Leading zeros are allowed.
+    ///
+    /// Returns `ErrorKind::InvalidData` if there are no digits, or if the value
+    /// doesn't fit in `T`. In both cases the bytes read so far stay consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"-128;300");
+    /// assert_eq!(cursor.read_ascii_int::<i8>().unwrap(), -128);
+    /// assert!(cursor.consume_prefix(b";").unwrap());
+    /// assert!(cursor.read_ascii_int::<u8>().is_err());
+    /// ```
+    fn read_ascii_int<T>(&mut self) -> io::Result<T>
+    where
+        T: TryFrom<i128>,
+    {
+        ascii::read_decimal(self)?
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(ascii::overflow)
+    }
+
+    /// Read an optionally signed decimal integer that must lie within `range`.
+    ///
+    /// This behaves like [`read_ascii_int`], and additionally returns
+    /// `ErrorKind::InvalidData` if the value is outside of `range`. The error
+    /// message names both the value and the allowed range.
+    ///
+    /// [`read_ascii_int`]: #method.read_ascii_int
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = io::Cursor::new(b"8080 70000");
+    /// assert_eq!(cursor.read_ascii_int_in(1..=65535_u32).unwrap(), 8080);
+    /// assert!(cursor.consume_prefix(b" ").unwrap());
+    /// let err = cursor.read_ascii_int_in(1..=65535_u32).unwrap_err();
+    /// assert_eq!(err.to_string(), "integer 70000 is out of range 1..=65535");
+    /// ```
+    fn read_ascii_int_in<T>(&mut self, range: RangeInclusive<T>) -> io::Result<T>
+    where
+        T: TryFrom<i128> + PartialOrd + fmt::Display,
+    {
+        let value = ascii::read_decimal(self)?.ok_or_else(ascii::overflow)?;
+        match T::try_from(value) {
+            Ok(n) if range.contains(&n) => Ok(n),
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "integer {} is out of range {}..={}",
+                    value,
+                    range.start(),
+                    range.end()
+                ),
+            )),
+        }
+    }
+
+    read_floats_buffered_impl! {
+        read_f32_into_be_buffered, f32, from_be_bytes, to_be_bytes, read_f32_into_be;
+        read_f32_into_le_buffered, f32, from_le_bytes, to_le_bytes, read_f32_into_le;
+        read_f32_into_ne_buffered, f32, from_ne_bytes, to_ne_bytes, read_f32_into_ne;
+        read_f64_into_be_buffered, f64, from_be_bytes, to_be_bytes, read_f64_into_be;
+        read_f64_into_le_buffered, f64, from_le_bytes, to_le_bytes, read_f64_into_le;
+        read_f64_into_ne_buffered, f64, from_ne_bytes, to_ne_bytes, read_f64_into_ne;
     }
 }
 
 impl<T: BufRead> BufReadExt for T {}
+
+/// The most bytes a label of a label sequence may hold.
+pub(crate) const MAX_LABEL_LEN: usize = 63;
+
+/// The most bytes a label sequence may take, including the length bytes.
+pub(crate) const MAX_LABELS_LEN: usize = 255;
+
+fn read_labels<R>(reader: &mut R, out: &mut Vec<u8>, separator: u8) -> io::Result<usize>
+where
+    R: BufRead + ?Sized,
+{
+    let mut read = 0;
+    loop {
+        let mut len = [0];
+        reader.read_exact(&mut len)?;
+        read += 1;
+        let len = len[0];
+        if len & 0xc0 == 0xc0 {
+            return Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "label compression pointers aren't supported",
+            ));
+        }
+        let len = usize::from(len);
+        if len > MAX_LABEL_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("label of {} bytes exceeds the maximum of 63", len),
+            ));
+        }
+        if read + len > MAX_LABELS_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "label sequence exceeds the maximum of 255 bytes",
+            ));
+        }
+        if len == 0 {
+            return Ok(read);
+        }
+
+        if read > 1 {
+            out.push(separator);
+        }
+        let end = out.len() + len;
+        out.resize(end, 0);
+        reader.read_exact(&mut out[end - len..])?;
+        read += len;
+    }
+}
+
+/// How a copy or skip ended.
+///
+/// This is returned by [`BufReadExt::copy_until_cancellable`] and
+/// [`BufReadExt::skip_to_end_cancellable`].
+///
+/// [`BufReadExt::copy_until_cancellable`]: trait.BufReadExt.html#method.copy_until_cancellable
+/// [`BufReadExt::skip_to_end_cancellable`]: trait.BufReadExt.html#method.skip_to_end_cancellable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyEnd {
+    /// The delimiter was found and copied.
+    Delimiter,
+    /// The end of the stream was reached.
+    Eof,
+    /// The cancellation flag was set.
+    Cancelled,
+}
+
+/// Why [`BufReadExt::read_while_capped`] or [`BufReadExt::read_until_capped`]
+/// stopped reading.
+///
+/// [`BufReadExt::read_while_capped`]: trait.BufReadExt.html#method.read_while_capped
+/// [`BufReadExt::read_until_capped`]: trait.BufReadExt.html#method.read_until_capped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The predicate rejected this byte, which is left unconsumed.
+    Rejected(u8),
+    /// The delimiter was found and appended.
+    Delimiter,
+    /// The end of the stream was reached.
+    Eof,
+    /// The sink ran out of capacity. The byte that didn't fit is left
+    /// unconsumed.
+    SinkFull,
+}
+
+/// Why [`BufReadExt::skip_while_limited`] stopped skipping, with the amount
+/// of bytes skipped.
+///
+/// [`BufReadExt::skip_while_limited`]: trait.BufReadExt.html#method.skip_while_limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipOutcome {
+    /// The predicate rejected the next byte, which is left unconsumed.
+    PredicateFailed(u64),
+    /// The end of the stream was reached.
+    Eof(u64),
+    /// The limit was reached while the next byte still matched. That byte
+    /// is left unconsumed.
+    LimitReached(u64),
+}
+
+/// The result of [`BufReadExt::read_line_max`].
+///
+/// [`BufReadExt::read_line_max`]: trait.BufReadExt.html#method.read_line_max
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineResult {
+    /// A line ending with a newline was read, of this many bytes including
+    /// the newline.
+    Line(usize),
+    /// The stream ended after this many bytes, without a newline.
+    Eof(usize),
+}
+
+/// An iterator over chunks of exactly `n` bytes of a `BufRead`.
+///
+/// This iterator is created by [`BufReadExt::chunks_exact`].
+///
+/// [`BufReadExt::chunks_exact`]: trait.BufReadExt.html#method.chunks_exact
+#[derive(Debug)]
+pub struct ChunksExact<R> {
+    reader: R,
+    n: usize,
+    remainder: Vec<u8>,
+}
+
+impl<R> ChunksExact<R> {
+    /// Returns the last bytes of the stream, which don't form a whole chunk.
+    ///
+    /// This is empty until the iterator has returned `None`.
+    pub fn remainder(&self) -> &[u8] {
+        &self.remainder
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for ChunksExact<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = std::mem::take(&mut self.remainder);
+        chunk.reserve_exact(self.n - chunk.len());
+        if let Err(e) = fill_chunk(&mut self.reader, self.n, &mut chunk) {
+            self.remainder = chunk;
+            return Some(Err(e));
+        }
+        if chunk.len() < self.n {
+            self.remainder = chunk;
+            return None;
+        }
+        Some(Ok(chunk))
+    }
+}
+
+/// Consume bytes while `predicate` holds, appending them to `buf` if given.
+///
+/// Returns the amount of bytes consumed and the byte that stopped it, which
+/// is left unconsumed.
+fn while_peek<R, S, P>(
+    reader: &mut R,
+    mut buf: Option<&mut S>,
+    mut predicate: P,
+) -> io::Result<(usize, Option<u8>)>
+where
+    R: BufRead + ?Sized,
+    S: ByteSink + ?Sized,
+    P: FnMut(u8) -> bool,
+{
+    let mut read = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(partial_error::wrap(read as u64, e)),
+        };
+        if available.is_empty() {
+            return Ok((read, None));
+        }
+
+        let len = available
+            .iter()
+            .position(|b| !predicate(*b))
+            .unwrap_or(available.len());
+        let stop = available.get(len).copied();
+        if let Some(buf) = buf.as_mut() {
+            buf.extend_from_slice(&available[..len])
+                .map_err(|e| partial_error::wrap(read as u64, e))?;
+        }
+        reader.consume(len);
+        read += len;
+        if stop.is_some() {
+            return Ok((read, stop));
+        }
+    }
+}
+
+/// Count occurrences of `byte` in `chunk` onto `found`, stopping at the `n`th.
+///
+/// Returns the length of `chunk` up to and including the `n`th occurrence, or
+/// the whole length if it doesn't hold that many.
+fn nth_end(byte: u8, chunk: &[u8], n: usize, found: &mut usize) -> usize {
+    for i in memchr::memchr_iter(byte, chunk) {
+        *found += 1;
+        if *found == n {
+            return i + 1;
+        }
+    }
+    chunk.len()
+}
+
+/// Read until the `close` byte at depth one, ignoring delimiters inside string
+/// literals if `quotes` is set.
+fn read_balanced<R, S>(
+    reader: &mut R,
+    open: u8,
+    close: u8,
+    quotes: bool,
+    buf: &mut S,
+) -> io::Result<usize>
+where
+    R: BufRead + ?Sized,
+    S: ByteSink + ?Sized,
+{
+    let mut depth = 1_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut read = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unbalanced delimiters: reached EOF at depth {}", depth),
+            ));
+        }
+
+        let mut end = None;
+        for (i, &byte) in available.iter().enumerate() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+            } else if quotes && byte == b'"' {
+                in_string = true;
+            } else if byte == close {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(i);
+                    break;
+                }
+            } else if byte == open {
+                depth += 1;
+            }
+        }
+
+        let len = end.unwrap_or(available.len());
+        buf.extend_from_slice(&available[..len])?;
+        read += len;
+        match end {
+            Some(_) => {
+                reader.consume(len + 1);
+                return Ok(read);
+            }
+            None => reader.consume(len),
+        }
+    }
+}
+
+/// Returns `true` if `cancel` is given and set.
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+}
+
+/// Copy bytes to `writer` through the delimiter `byte`, checking `cancel`
+/// before each chunk.
+///
+/// `progress` is called with the amount of bytes copied after each chunk, and
+/// once more when done, whether successfully or not.
+fn copy_until<R, W, P>(
+    reader: &mut R,
+    byte: u8,
+    writer: &mut W,
+    cancel: Option<&AtomicBool>,
+    mut progress: P,
+) -> io::Result<(u64, CopyEnd)>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+    P: FnMut(u64),
+{
+    let mut copied = 0;
+    let res = loop {
+        if is_cancelled(cancel) {
+            break Ok(CopyEnd::Cancelled);
+        }
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        if available.is_empty() {
+            break Ok(CopyEnd::Eof);
+        }
+
+        let (len, found) = match memchr::memchr(byte, available) {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        if let Err(e) = writer.write_all(&available[..len]) {
+            break Err(e);
+        }
+        reader.consume(len);
+        copied += len as u64;
+        progress(copied);
+        if found {
+            break Ok(CopyEnd::Delimiter);
+        }
+    };
+    progress(copied);
+    res.map(|end| (copied, end))
+        .map_err(|e| partial_error::wrap(copied, e))
+}
+
+/// Copy exactly `n` bytes to `writer`.
+///
+/// `progress` is called with the amount of bytes copied after each chunk, and
+/// once more when done, whether successfully or not.
+fn copy_exact<R, W, P>(reader: &mut R, n: u64, writer: &mut W, mut progress: P) -> io::Result<()>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+    P: FnMut(u64),
+{
+    let mut copied = 0;
+    let res = loop {
+        if copied == n {
+            break Ok(());
+        }
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        if available.is_empty() {
+            break Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "stream ended before all bytes were copied",
+            ));
+        }
+
+        let len =
+            usize::try_from(n - copied).map_or(available.len(), |rest| rest.min(available.len()));
+        if let Err(e) = writer.write_all(&available[..len]) {
+            break Err(e);
+        }
+        reader.consume(len);
+        copied += len as u64;
+        progress(copied);
+    };
+    progress(copied);
+    res.map_err(|e| partial_error::wrap(copied, e))
+}
+
+/// Skip until EOF, checking `cancel` before each chunk.
+///
+/// `progress` is called with the amount of bytes skipped after each chunk, and
+/// once more when done, whether successfully or not.
+fn skip_to_end<R, P>(
+    reader: &mut R,
+    cancel: Option<&AtomicBool>,
+    mut progress: P,
+) -> io::Result<(u64, CopyEnd)>
+where
+    R: BufRead + ?Sized,
+    P: FnMut(u64),
+{
+    let mut skipped = 0;
+    let res = loop {
+        if is_cancelled(cancel) {
+            break Ok(CopyEnd::Cancelled);
+        }
+        let len = match reader.fill_buf() {
+            Ok(b) => b.len(),
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        if len == 0 {
+            break Ok(CopyEnd::Eof);
+        }
+        reader.consume(len);
+        skipped += len as u64;
+        progress(skipped);
+    };
+    progress(skipped);
+    res.map(|end| (skipped, end))
+        .map_err(|e| partial_error::wrap(skipped, e))
+}
+
+/// Strip a trailing `\r` from a line.
+fn strip_cr(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Returns the value of an ASCII hex digit.
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        _ => (digit | 0x20) - b'a' + 10,
+    }
+}
+
+fn invalid_escape(raw: &[u8]) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid percent escape `{}`", raw.escape_ascii()),
+    )
+}
+
+/// Copy the next bytes into the byte array of `B`, without consuming them.
+fn fill_array<R, B>(reader: &mut R) -> io::Result<B::Bytes>
+where
+    R: BufReadExt + BufferAhead + ?Sized,
+    B: ReadBytes,
+{
+    let mut bytes = B::Bytes::default();
+    let len = bytes.as_ref().len();
+    bytes.as_mut().copy_from_slice(reader.peek(len)?);
+    Ok(bytes)
+}
+
+/// Append bytes to `chunk` until it holds `n` bytes, or the reader is at EOF.
+fn fill_chunk<R: BufRead + ?Sized>(
+    reader: &mut R,
+    n: usize,
+    chunk: &mut Vec<u8>,
+) -> io::Result<()> {
+    while chunk.len() < n {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            break;
+        }
+        let len = available.len().min(n - chunk.len());
+        chunk.extend_from_slice(&available[..len]);
+        reader.consume(len);
+    }
+    Ok(())
+}
+
+/// Consume a reader until EOF, finding the non-overlapping occurrences of
+/// `needle`.
+///
+/// The bytes between occurrences are passed to `visit` in order, together
+/// with whether an occurrence follows them. Up to `needle.len() - 1` bytes at
+/// the end of each chunk are carried over to the next, to find occurrences
+/// that span chunks.
+fn scan_pattern<R, F>(reader: &mut R, needle: &[u8], mut visit: F) -> io::Result<()>
+where
+    R: BufRead + ?Sized,
+    F: FnMut(&[u8], bool) -> io::Result<()>,
+{
+    if needle.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "cannot search for an empty pattern",
+        ));
+    }
+    let finder = memchr::memmem::Finder::new(needle);
+    let carry_len = needle.len() - 1;
+    let mut carry = Vec::with_capacity(carry_len * 2);
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if chunk.is_empty() {
+            return visit(&carry, false);
+        }
+        let len = chunk.len();
+
+        // Look for an occurrence that starts in the carried bytes.
+        let mut start = 0;
+        if !carry.is_empty() {
+            let carried = carry.len();
+            let head = len.min(carry_len);
+            carry.extend_from_slice(&chunk[..head]);
+            match finder.find(&carry) {
+                Some(i) if i < carried => {
+                    visit(&carry[..i], true)?;
+                    start = i + needle.len() - carried;
+                    carry.clear();
+                }
+                _ if head == carry_len => {
+                    visit(&carry[..carried], false)?;
+                    carry.clear();
+                }
+                _ => {
+                    // The chunk is too short to rule the carried bytes out, so
+                    // carry it over entirely.
+                    let keep = carry.len().saturating_sub(carry_len);
+                    visit(&carry[..keep], false)?;
+                    carry.drain(..keep);
+                    reader.consume(len);
+                    continue;
+                }
+            }
+        }
+
+        let rest = &chunk[start..];
+        let mut emitted = 0;
+        for i in finder.find_iter(rest) {
+            visit(&rest[emitted..i], true)?;
+            emitted = i + needle.len();
+        }
+        let keep = emitted.max(rest.len().saturating_sub(carry_len));
+        visit(&rest[emitted..keep], false)?;
+        carry.extend_from_slice(&rest[keep..]);
+        reader.consume(len);
+    }
+}
+
+/// Consume a reader until EOF, summing `count` over its chunks.
+fn count_chunks<R, F>(reader: &mut R, mut count: F) -> io::Result<u64>
+where
+    R: BufRead + ?Sized,
+    F: FnMut(&[u8]) -> usize,
+{
+    let mut total = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            break;
+        }
+
+        total += count(available) as u64;
+        let len = available.len();
+        reader.consume(len);
+    }
+
+    Ok(total)
+}
+
+/// Fill `out` by converting values straight out of the reader's buffer.
+fn read_floats_buffered<R, F, C, const N: usize>(
+    reader: &mut R,
+    out: &mut [F],
+    from_bytes: C,
+) -> io::Result<()>
+where
+    R: BufRead + ?Sized,
+    C: Fn([u8; N]) -> F + Copy,
+{
+    let mut filled = 0;
+    // The leading bytes of a value that straddles the reader's buffer.
+    let mut partial = [0; N];
+    let mut partial_len = 0;
+    while filled < out.len() {
+        let available = match reader.fill_buf() {
+            Ok(b) => b,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(partial_error::wrap(filled as u64, e)),
+        };
+        if available.is_empty() {
+            return Err(ShortRead::new(out.len() * N, filled * N + partial_len).into());
+        }
+
+        let mut used = 0;
+        if partial_len > 0 {
+            used = (N - partial_len).min(available.len());
+            partial[partial_len..partial_len + used].copy_from_slice(&available[..used]);
+            partial_len += used;
+            if partial_len == N {
+                out[filled] = from_bytes(partial);
+                filled += 1;
+                partial_len = 0;
+            }
+        }
+
+        let rest = &available[used..];
+        let count = (rest.len() / N).min(out.len() - filled);
+        convert_floats(rest, &mut out[filled..filled + count], from_bytes);
+        filled += count;
+        used += count * N;
+        if filled < out.len() {
+            let tail = &rest[count * N..];
+            partial[partial_len..partial_len + tail.len()].copy_from_slice(tail);
+            partial_len += tail.len();
+            used += tail.len();
+        }
+        reader.consume(used);
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Cursor, Take};
+
+/// A `BufRead` that can buffer a given amount of bytes ahead, without
+/// consuming them.
+///
+/// `fill_buf` returns whatever the reader has buffered, which at a refill
+/// boundary may be a single byte, and a plain `BufRead` can't show more
+/// without consuming it. The readers that implement this trait keep their
+/// bytes in a buffer they can grow, so they can peek any distance ahead.
+/// The `BufReadExt` methods that look past the current buffer, such as
+/// [`fill_while`] and [`fill_until`], require it.
+///
+/// [`Lookahead`] and [`Unread`] can be wrapped around any `BufRead` to
+/// implement it. Readers over an in-memory slice implement it as is.
+///
+/// [`fill_while`]: trait.BufReadExt.html#method.fill_while
+/// [`fill_until`]: trait.BufReadExt.html#method.fill_until
+/// [`Lookahead`]: struct.Lookahead.html
+/// [`Unread`]: struct.Unread.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::BufReader;
+/// use omnom::{BufferAhead, Unread};
+///
+/// // An underlying buffer of 2 bytes can't show the whole tag.
+/// let reader = BufReader::with_capacity(2, &b"<html>"[..]);
+/// let mut reader = Unread::new(reader);
+/// assert_eq!(&reader.buffer_ahead(6).unwrap()[..6], b"<html>");
+/// ```
+pub trait BufferAhead: BufRead {
+    /// Buffer at least `n` bytes without consuming them, and return all
+    /// buffered bytes.
+    ///
+    /// The returned slice starts at the next byte to be read, like the one
+    /// `fill_buf` returns. It holds fewer than `n` bytes only if the stream
+    /// ends before that.
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]>;
+}
+
+impl<T: AsRef<[u8]>> BufferAhead for Cursor<T> {
+    fn buffer_ahead(&mut self, _n: usize) -> io::Result<&[u8]> {
+        self.fill_buf()
+    }
+}
+
+impl BufferAhead for &[u8] {
+    fn buffer_ahead(&mut self, _n: usize) -> io::Result<&[u8]> {
+        Ok(*self)
+    }
+}
+
+impl<R: BufferAhead> BufferAhead for Take<R> {
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]> {
+        let limit = usize::try_from(self.limit()).unwrap_or(usize::MAX);
+        if limit == 0 {
+            return Ok(&[]);
+        }
+        let available = self.get_mut().buffer_ahead(n.min(limit))?;
+        Ok(&available[..available.len().min(limit)])
+    }
+}
+
+impl<R: BufferAhead + ?Sized> BufferAhead for &mut R {
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]> {
+        (**self).buffer_ahead(n)
+    }
+}
+
+impl<R: BufferAhead + ?Sized> BufferAhead for Box<R> {
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]> {
+        (**self).buffer_ahead(n)
+    }
+}
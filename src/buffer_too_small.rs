@@ -0,0 +1,77 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error for a peek at more bytes than the reader has buffered.
+///
+/// A `BufRead` can only show the bytes of its current buffer without
+/// consuming them, and can't tell whether more follow without reading past
+/// them. Methods that peek at a known amount of bytes, such as
+/// [`BufReadExt::fill_exact_buffered`], return `ErrorKind::UnexpectedEof` only when
+/// the reader has no bytes left, and this, in an error of the kind
+/// `ErrorKind::Other`, when it has some but fewer than needed. The stream
+/// may continue after them, or end.
+///
+/// Wrap the reader in a [`Lookahead`] or an [`Unread`] to peek past the
+/// underlying reader's buffer.
+///
+/// [`BufReadExt::fill_exact_buffered`]: trait.BufReadExt.html#method.fill_exact_buffered
+/// [`Lookahead`]: struct.Lookahead.html
+/// [`Unread`]: struct.Unread.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufReader, ErrorKind};
+/// use omnom::prelude::*;
+/// use omnom::BufferTooSmall;
+///
+/// let mut reader = BufReader::with_capacity(2, &b"lorem"[..]);
+/// let err = reader.fill_exact_buffered(&mut [0; 4]).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Other);
+///
+/// let small = err.get_ref().unwrap().downcast_ref::<BufferTooSmall>().unwrap();
+/// assert_eq!((small.needed(), small.available()), (4, 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    needed: usize,
+    available: usize,
+}
+
+impl BufferTooSmall {
+    /// Create a new error for `needed` bytes, of which only `available` were
+    /// buffered.
+    pub fn new(needed: usize, available: usize) -> Self {
+        Self { needed, available }
+    }
+
+    /// Returns the amount of bytes that were needed.
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// Returns the amount of bytes that were buffered.
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} buffered bytes, found {}",
+            self.needed, self.available
+        )
+    }
+}
+
+impl Error for BufferTooSmall {}
+
+impl From<BufferTooSmall> for io::Error {
+    /// Wraps the `BufferTooSmall` in an error of the kind `ErrorKind::Other`.
+    fn from(err: BufferTooSmall) -> Self {
+        io::Error::other(err)
+    }
+}
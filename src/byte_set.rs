@@ -0,0 +1,194 @@
+use core::fmt;
+use core::ops::{BitAnd, BitOr, Not, RangeInclusive};
+
+/// A set of bytes, stored as a 256-bit table.
+///
+/// Checking whether a byte is in the set takes constant time, regardless of
+/// the size of the set. Methods that take a set of bytes, such as
+/// [`BufReadExt::skip_any_of`] and [`predicates::any_of`], accept anything
+/// that converts into a `ByteSet`, including byte slices and arrays. Passing
+/// a `ByteSet` itself builds the table once, to reuse it across calls.
+///
+/// The constructors are `const fn`s, so sets can be built at compile time.
+///
+/// [`BufReadExt::skip_any_of`]: trait.BufReadExt.html#method.skip_any_of
+/// [`predicates::any_of`]: predicates/fn.any_of.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::ByteSet;
+///
+/// static HEX: ByteSet = ByteSet::range(b'0'..=b'9')
+///     .union(ByteSet::range(b'a'..=b'f'))
+///     .union(ByteSet::range(b'A'..=b'F'));
+///
+/// assert!(HEX.contains(b'c'));
+/// assert!(!HEX.contains(b'g'));
+/// assert!((!HEX).contains(b'g'));
+/// assert_eq!(HEX.len(), 22);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ByteSet([u64; 4]);
+
+impl ByteSet {
+    /// The set of no bytes.
+    pub const EMPTY: ByteSet = ByteSet([0; 4]);
+
+    /// The set of all bytes.
+    pub const ALL: ByteSet = ByteSet([u64::MAX; 4]);
+
+    /// Create a set of the given bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ByteSet;
+    ///
+    /// const DELIMITERS: ByteSet = ByteSet::new(b",;");
+    /// assert!(DELIMITERS.contains(b';'));
+    /// assert!(!DELIMITERS.contains(b' '));
+    /// ```
+    pub const fn new(bytes: &[u8]) -> Self {
+        let mut table = [0; 4];
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            table[(byte >> 6) as usize] |= 1 << (byte & 63);
+            i += 1;
+        }
+        ByteSet(table)
+    }
+
+    /// Create a set of the bytes in `range`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ByteSet;
+    ///
+    /// const DIGITS: ByteSet = ByteSet::range(b'0'..=b'9');
+    /// assert!(DIGITS.contains(b'0'));
+    /// assert!(DIGITS.contains(b'9'));
+    /// assert!(!DIGITS.contains(b'a'));
+    /// ```
+    pub const fn range(range: RangeInclusive<u8>) -> Self {
+        let mut table = [0; 4];
+        let mut byte = *range.start() as usize;
+        while byte <= *range.end() as usize {
+            table[byte >> 6] |= 1 << (byte & 63);
+            byte += 1;
+        }
+        ByteSet(table)
+    }
+
+    /// Returns `true` if `byte` is in the set.
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.0[(byte >> 6) as usize] & (1 << (byte & 63)) != 0
+    }
+
+    /// Returns the set of bytes in either set.
+    ///
+    /// This is the same as the `|` operator, usable in constants.
+    pub const fn union(self, other: ByteSet) -> Self {
+        let [a, b, c, d] = self.0;
+        let [e, f, g, h] = other.0;
+        ByteSet([a | e, b | f, c | g, d | h])
+    }
+
+    /// Returns the set of bytes in both sets.
+    ///
+    /// This is the same as the `&` operator, usable in constants.
+    pub const fn intersection(self, other: ByteSet) -> Self {
+        let [a, b, c, d] = self.0;
+        let [e, f, g, h] = other.0;
+        ByteSet([a & e, b & f, c & g, d & h])
+    }
+
+    /// Returns the set of bytes not in this set.
+    ///
+    /// This is the same as the `!` operator, usable in constants.
+    pub const fn complement(self) -> Self {
+        let [a, b, c, d] = self.0;
+        ByteSet([!a, !b, !c, !d])
+    }
+
+    /// Returns the amount of bytes in the set.
+    pub const fn len(&self) -> usize {
+        let [a, b, c, d] = self.0;
+        (a.count_ones() + b.count_ones() + c.count_ones() + d.count_ones()) as usize
+    }
+
+    /// Returns `true` if the set holds no bytes.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the bytes in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(move |byte| self.contains(*byte))
+    }
+}
+
+impl BitOr for ByteSet {
+    type Output = ByteSet;
+
+    fn bitor(self, other: ByteSet) -> ByteSet {
+        self.union(other)
+    }
+}
+
+impl BitAnd for ByteSet {
+    type Output = ByteSet;
+
+    fn bitand(self, other: ByteSet) -> ByteSet {
+        self.intersection(other)
+    }
+}
+
+impl Not for ByteSet {
+    type Output = ByteSet;
+
+    fn not(self) -> ByteSet {
+        self.complement()
+    }
+}
+
+impl From<&[u8]> for ByteSet {
+    fn from(bytes: &[u8]) -> Self {
+        ByteSet::new(bytes)
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for ByteSet {
+    fn from(bytes: &[u8; N]) -> Self {
+        ByteSet::new(bytes)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ByteSet {
+    fn from(bytes: [u8; N]) -> Self {
+        ByteSet::new(&bytes)
+    }
+}
+
+impl From<RangeInclusive<u8>> for ByteSet {
+    fn from(range: RangeInclusive<u8>) -> Self {
+        ByteSet::range(range)
+    }
+}
+
+impl fmt::Debug for ByteSet {
+    /// Formats the set as its bytes, escaped like byte string literals.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Byte(u8);
+
+        impl fmt::Debug for Byte {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "b'{}'", core::ascii::escape_default(self.0))
+            }
+        }
+
+        f.debug_set().entries(self.iter().map(Byte)).finish()
+    }
+}
@@ -0,0 +1,247 @@
+use std::io::{self, ErrorKind};
+use std::str;
+
+/// A destination that bytes read from a stream can be appended to.
+///
+/// Methods such as [`BufReadExt::read_while`] append whole buffered chunks at
+/// a time, rather than pushing byte by byte. `ByteSink` is implemented for
+/// `Vec<u8>`, for `SmallVec` and `BytesMut` with the `smallvec` and `bytes`
+/// features, and for [`Utf8Sink`], which appends to a `String` after checking
+/// the bytes are valid UTF-8.
+///
+/// With the `heapless` and `arrayvec` features it's implemented for the
+/// fixed-capacity `heapless::Vec<u8, N>` and `ArrayVec<u8, N>` as well.
+/// Appending more than fits to those returns an error, so read into them with
+/// [`BufReadExt::read_while_capped`] and [`BufReadExt::read_until_capped`],
+/// which stop once they're full instead.
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+///
+/// [`BufReadExt::read_while`]: trait.BufReadExt.html#method.read_while
+/// [`BufReadExt::read_while_capped`]: trait.BufReadExt.html#method.read_while_capped
+/// [`BufReadExt::read_until_capped`]: trait.BufReadExt.html#method.read_until_capped
+/// [`Utf8Sink`]: struct.Utf8Sink.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use omnom::prelude::*;
+/// use omnom::Utf8Sink;
+///
+/// let mut cursor = io::Cursor::new("naïve café");
+/// let mut word = String::new();
+/// let mut sink = Utf8Sink::new(&mut word);
+/// cursor.read_while(&mut sink, |b| b != b' ').unwrap();
+/// sink.finish().unwrap();
+/// assert_eq!(word, "naïve");
+/// ```
+pub trait ByteSink: private::Sealed {
+    /// Append `bytes`.
+    ///
+    /// Either all of `bytes` are appended, or an error is returned and the
+    /// sink is left unchanged.
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Returns how many more bytes can be appended, or `None` if the sink
+    /// grows as needed.
+    fn remaining_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Reserve room for at least `additional` more bytes, if the sink grows
+    /// as needed.
+    ///
+    /// This is a hint: sinks with a fixed capacity ignore it.
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+}
+
+mod private {
+    #[allow(unreachable_pub)]
+    pub trait Sealed {}
+
+    impl Sealed for Vec<u8> {}
+    impl Sealed for super::Utf8Sink<'_> {}
+    #[cfg(feature = "smallvec")]
+    impl<A: smallvec::Array<Item = u8>> Sealed for smallvec::SmallVec<A> {}
+    #[cfg(feature = "bytes")]
+    impl Sealed for bytes::BytesMut {}
+    #[cfg(feature = "heapless")]
+    impl<const N: usize> Sealed for heapless::Vec<u8, N> {}
+    #[cfg(feature = "arrayvec")]
+    impl<const N: usize> Sealed for arrayvec::ArrayVec<u8, N> {}
+}
+
+impl ByteSink for Vec<u8> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        Vec::extend_from_slice(self, bytes);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional);
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = u8>> ByteSink for smallvec::SmallVec<A> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        smallvec::SmallVec::extend_from_slice(self, bytes);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        smallvec::SmallVec::reserve(self, additional);
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ByteSink for bytes::BytesMut {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        bytes::BytesMut::extend_from_slice(self, bytes);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        bytes::BytesMut::reserve(self, additional);
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> ByteSink for heapless::Vec<u8, N> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        heapless::Vec::extend_from_slice(self, bytes).map_err(|()| sink_full(bytes.len()))
+    }
+
+    fn remaining_capacity(&self) -> Option<usize> {
+        Some(N - self.len())
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> ByteSink for arrayvec::ArrayVec<u8, N> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.try_extend_from_slice(bytes)
+            .map_err(|_| sink_full(bytes.len()))
+    }
+
+    fn remaining_capacity(&self) -> Option<usize> {
+        Some(arrayvec::ArrayVec::remaining_capacity(self))
+    }
+}
+
+/// A [`ByteSink`] that appends to a `String`, rejecting invalid UTF-8.
+///
+/// A character may be split across two appends, such as when it straddles
+/// the reader's buffer. Its leading bytes are then held back until the rest
+/// arrives. Call [`finish`] once done, to check that no character was left
+/// incomplete.
+///
+/// Appending bytes that aren't valid UTF-8 returns an error of the kind
+/// `ErrorKind::InvalidData`, and leaves the `String` unchanged.
+///
+/// [`ByteSink`]: trait.ByteSink.html
+/// [`finish`]: #method.finish
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use omnom::prelude::*;
+/// use omnom::Utf8Sink;
+///
+/// let mut cursor = io::Cursor::new(b"ok\xff");
+/// let mut s = String::new();
+/// let mut sink = Utf8Sink::new(&mut s);
+/// let err = cursor.read_while(&mut sink, |_| true).unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+/// assert!(s.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct Utf8Sink<'a> {
+    string: &'a mut String,
+    /// The leading bytes of a character that's split across appends.
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl<'a> Utf8Sink<'a> {
+    /// Create a new sink appending to `string`.
+    pub fn new(string: &'a mut String) -> Self {
+        Self {
+            string,
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// Check that the bytes appended didn't end in an incomplete character.
+    ///
+    /// Returns `ErrorKind::InvalidData` if they did.
+    pub fn finish(self) -> io::Result<()> {
+        if self.pending_len > 0 {
+            return Err(invalid_utf8());
+        }
+        Ok(())
+    }
+}
+
+impl ByteSink for Utf8Sink<'_> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> io::Result<()> {
+        // Complete a character held back from the previous append first.
+        let mut head = self.pending;
+        let mut head_len = self.pending_len;
+        let mut rest = bytes;
+        if head_len > 0 {
+            let take = (char_len(head[0]) - head_len).min(rest.len());
+            head[head_len..head_len + take].copy_from_slice(&rest[..take]);
+            head_len += take;
+            rest = &rest[take..];
+            match str::from_utf8(&head[..head_len]) {
+                Ok(_) => {}
+                Err(e) if e.error_len().is_none() => {
+                    self.pending = head;
+                    self.pending_len = head_len;
+                    return Ok(());
+                }
+                Err(_) => return Err(invalid_utf8()),
+            }
+        }
+
+        let (valid, tail) = match str::from_utf8(rest) {
+            Ok(s) => (s, &[][..]),
+            Err(e) if e.error_len().is_none() => {
+                let (valid, tail) = rest.split_at(e.valid_up_to());
+                let valid = str::from_utf8(valid).expect("prefix is valid UTF-8");
+                (valid, tail)
+            }
+            Err(_) => return Err(invalid_utf8()),
+        };
+        let head = str::from_utf8(&head[..head_len]).expect("head is valid UTF-8");
+        self.string.push_str(head);
+        self.string.push_str(valid);
+        self.pending[..tail.len()].copy_from_slice(tail);
+        self.pending_len = tail.len();
+        Ok(())
+    }
+}
+
+/// Returns the length of the character whose encoding starts with `lead`.
+fn char_len(lead: u8) -> usize {
+    match lead {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+fn sink_full(len: usize) -> io::Error {
+    io::Error::other(format!("{} bytes don't fit in the sink", len))
+}
@@ -0,0 +1,241 @@
+use std::io::{self, Write};
+
+use crate::WriteBytes;
+
+/// A growable buffer to assemble typed fields in, and write out in one go.
+///
+/// Writing a header as a series of `write_be` calls to an unbuffered writer
+/// issues one small write per field. A `BytesBuilder` collects the fields in
+/// memory instead, so the header's length is known before it's written, and
+/// [`write_to`] emits it with a single `write_all`.
+///
+/// Fields are appended with the chainable [`be`], [`le`], [`ne`] and
+/// [`bytes`] methods. A field whose value is only known later, such as a
+/// length, can be appended as zeroes and filled in with [`patch_be`].
+/// `BytesBuilder` implements `Write` too, so the `WriteExt` methods work on it
+/// as well.
+///
+/// [`write_to`]: #method.write_to
+/// [`be`]: #method.be
+/// [`le`]: #method.le
+/// [`ne`]: #method.ne
+/// [`bytes`]: #method.bytes
+/// [`patch_be`]: #method.patch_be
+///
+/// # Examples
+///
+/// ```
+/// use omnom::BytesBuilder;
+///
+/// let mut header = BytesBuilder::new();
+/// header.bytes(b"RIFF").le(36_u32).bytes(b"WAVE");
+/// assert_eq!(header.len(), 12);
+///
+/// let mut out = vec![];
+/// assert_eq!(header.write_to(&mut out).unwrap(), 12);
+/// assert_eq!(out, b"RIFF\x24\x00\x00\x00WAVE");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BytesBuilder {
+    buf: Vec<u8>,
+}
+
+impl BytesBuilder {
+    /// Create a new, empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty builder with room for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Append a value as big endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.be(1_u16).be(2_u8);
+    /// assert_eq!(builder.as_slice(), [0, 1, 2]);
+    /// ```
+    pub fn be<T: WriteBytes>(&mut self, value: T) -> &mut Self {
+        self.bytes(value.to_be_array().as_ref())
+    }
+
+    /// Append a value as little endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.le(1_u16).le(2_u8);
+    /// assert_eq!(builder.as_slice(), [1, 0, 2]);
+    /// ```
+    pub fn le<T: WriteBytes>(&mut self, value: T) -> &mut Self {
+        self.bytes(value.to_le_array().as_ref())
+    }
+
+    /// Append a value using native endianness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.ne(1_u32);
+    /// assert_eq!(builder.as_slice(), 1_u32.to_ne_bytes());
+    /// ```
+    pub fn ne<T: WriteBytes>(&mut self, value: T) -> &mut Self {
+        self.bytes(value.to_ne_array().as_ref())
+    }
+
+    /// Append raw bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.bytes(b"GIF").bytes(b"89a");
+    /// assert_eq!(builder.as_slice(), b"GIF89a");
+    /// ```
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(bytes);
+        self
+    }
+
+    /// Overwrite the bytes at `offset` with a value as big endian.
+    ///
+    /// This fills in a placeholder appended earlier, such as a length that's
+    /// only known once the fields after it are appended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value doesn't fit in the builder at `offset`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.be(0_u16).bytes(b"hello");
+    /// let len = builder.len() as u16;
+    /// builder.patch_be(0, len);
+    /// assert_eq!(builder.as_slice(), b"\x00\x07hello");
+    /// ```
+    pub fn patch_be<T: WriteBytes>(&mut self, offset: usize, value: T) -> &mut Self {
+        self.patch(offset, value.to_be_array().as_ref())
+    }
+
+    /// Overwrite the bytes at `offset` with a value as little endian.
+    ///
+    /// See [`patch_be`] for details.
+    ///
+    /// [`patch_be`]: #method.patch_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.bytes(b"ab").le(0_u16);
+    /// builder.patch_le(2, 0x0102_u16);
+    /// assert_eq!(builder.as_slice(), b"ab\x02\x01");
+    /// ```
+    pub fn patch_le<T: WriteBytes>(&mut self, offset: usize, value: T) -> &mut Self {
+        self.patch(offset, value.to_le_array().as_ref())
+    }
+
+    /// Overwrite the bytes at `offset` with a value using native endianness.
+    ///
+    /// See [`patch_be`] for details.
+    ///
+    /// [`patch_be`]: #method.patch_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::BytesBuilder;
+    ///
+    /// let mut builder = BytesBuilder::new();
+    /// builder.ne(0_u32);
+    /// builder.patch_ne(0, 7_u32);
+    /// assert_eq!(builder.as_slice(), 7_u32.to_ne_bytes());
+    /// ```
+    pub fn patch_ne<T: WriteBytes>(&mut self, offset: usize, value: T) -> &mut Self {
+        self.patch(offset, value.to_ne_array().as_ref())
+    }
+
+    fn patch(&mut self, offset: usize, bytes: &[u8]) -> &mut Self {
+        self.buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    /// Returns the amount of bytes appended so far.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if no bytes have been appended.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the bytes appended so far.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Remove all bytes, keeping the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    /// Returns the underlying `Vec`.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Write all bytes to `writer` with a single `write_all`.
+    ///
+    /// Returns the amount of bytes written.
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.buf)?;
+        Ok(self.buf.len())
+    }
+}
+
+impl AsRef<[u8]> for BytesBuilder {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl From<BytesBuilder> for Vec<u8> {
+    fn from(builder: BytesBuilder) -> Self {
+        builder.buf
+    }
+}
+
+impl Write for BytesBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Read, Write};
+
+use crate::{crc32, ShortRead};
+
+/// A writer of frames with a length prefix and a checksum trailer.
+///
+/// Each frame is the length of the payload as a big-endian `u32`, the
+/// payload, and the CRC-32 (IEEE) of the payload as a big-endian `u32`.
+/// [`ChecksummedFrameReader`] reads the frames back and verifies them.
+///
+/// [`ChecksummedFrameReader`]: struct.ChecksummedFrameReader.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{ChecksummedFrameReader, FrameWriter};
+///
+/// let mut writer = FrameWriter::new(vec![]);
+/// assert_eq!(writer.write_frame(b"hello").unwrap(), 13);
+///
+/// let bytes = writer.into_inner();
+/// let mut reader = ChecksummedFrameReader::new(&bytes[..], 1024);
+/// let mut buf = vec![];
+/// assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(5));
+/// assert_eq!(buf, b"hello");
+/// ```
+#[derive(Debug)]
+pub struct FrameWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> FrameWriter<W> {
+    /// Create a new `FrameWriter`.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Write `payload` as a frame.
+    ///
+    /// Returns the amount of bytes written, which is the length of the
+    /// payload plus 8. Payloads of more than `u32::MAX` bytes return
+    /// `ErrorKind::InvalidInput`, before anything is written.
+    ///
+    /// If writing fails, part of the frame may have been written. Reading
+    /// such a torn frame back returns `ErrorKind::UnexpectedEof` if it ends
+    /// the stream, so an interrupted append to a log is detected as such.
+    pub fn write_frame(&mut self, payload: &[u8]) -> io::Result<usize> {
+        let len = u32::try_from(payload.len()).map_err(|_| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "frame payload of {} bytes exceeds the maximum of {} bytes",
+                    payload.len(),
+                    u32::MAX
+                ),
+            )
+        })?;
+        self.inner.write_all(&len.to_be_bytes())?;
+        self.inner.write_all(payload)?;
+        self.inner
+            .write_all(&crc32::checksum(payload).to_be_bytes())?;
+        Ok(payload.len() + 8)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// A reader of the frames written by [`FrameWriter`], which verifies their
+/// checksums.
+///
+/// A frame whose payload doesn't match its checksum returns
+/// `ErrorKind::InvalidData`, with a [`ChecksumMismatch`] inside. The whole
+/// frame is consumed first, so the next call reads the frame after it, and
+/// a log can be scanned past a corrupt record.
+///
+/// [`FrameWriter`]: struct.FrameWriter.html
+/// [`ChecksumMismatch`]: struct.ChecksumMismatch.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::ErrorKind;
+/// use omnom::{ChecksummedFrameReader, FrameWriter};
+///
+/// let mut writer = FrameWriter::new(vec![]);
+/// writer.write_frame(b"first").unwrap();
+/// writer.write_frame(b"second").unwrap();
+/// let mut bytes = writer.into_inner();
+/// bytes[4] ^= 0x01;
+///
+/// let mut reader = ChecksummedFrameReader::new(&bytes[..], 1024);
+/// let mut buf = vec![];
+/// let err = reader.read_frame(&mut buf).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(6));
+/// assert_eq!(buf, b"second");
+/// ```
+#[derive(Debug)]
+pub struct ChecksummedFrameReader<R> {
+    inner: R,
+    max_len: usize,
+}
+
+impl<R: BufRead> ChecksummedFrameReader<R> {
+    /// Create a new `ChecksummedFrameReader` of frames with payloads of at
+    /// most `max_len` bytes.
+    pub fn new(inner: R, max_len: usize) -> Self {
+        Self { inner, max_len }
+    }
+
+    /// Read a frame, verify its checksum, and append its payload to `buf`.
+    ///
+    /// Returns the length of the payload, or `Ok(None)` if the reader is at
+    /// EOF before the frame starts.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InvalidData` with a [`ChecksumMismatch`] if the
+    ///   checksum doesn't match. The frame is consumed.
+    /// - `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the reader ends
+    ///   in the middle of the frame, including its trailer.
+    /// - `ErrorKind::InvalidData` if the length is over the maximum. This is
+    ///   returned before anything is allocated, and leaves the reader after
+    ///   the length.
+    ///
+    /// On errors, `buf` is left as it was.
+    ///
+    /// [`ChecksumMismatch`]: struct.ChecksumMismatch.html
+    /// [`ShortRead`]: struct.ShortRead.html
+    pub fn read_frame(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<usize>> {
+        let mut len = [0; 4];
+        match read_full(&mut self.inner, &mut len)? {
+            0 => return Ok(None),
+            4 => {}
+            read => return Err(ShortRead::new(4, read).into()),
+        }
+        let len = u32::from_be_bytes(len);
+        if u64::from(len) > self.max_len as u64 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes exceeds the maximum of {} bytes",
+                    len, self.max_len
+                ),
+            ));
+        }
+
+        let len = len as usize;
+        let start = buf.len();
+        buf.reserve(len);
+        match self.read_payload(buf, len) {
+            Ok(()) => Ok(Some(len)),
+            Err(e) => {
+                buf.truncate(start);
+                Err(e)
+            }
+        }
+    }
+
+    /// Read a payload of `len` bytes and its trailer, appending the payload
+    /// to `buf`.
+    fn read_payload(&mut self, buf: &mut Vec<u8>, len: usize) -> io::Result<()> {
+        let start = buf.len();
+        let read = Read::take(&mut self.inner, len as u64).read_to_end(buf)?;
+        if read < len {
+            return Err(ShortRead::new(len + 4, read).into());
+        }
+
+        let mut trailer = [0; 4];
+        let read = read_full(&mut self.inner, &mut trailer)?;
+        if read < 4 {
+            return Err(ShortRead::new(len + 4, len + read).into());
+        }
+        let expected = u32::from_be_bytes(trailer);
+        let actual = crc32::checksum(&buf[start..]);
+        if expected != actual {
+            return Err(ChecksumMismatch { expected, actual }.into());
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Read into `buf` until it's full or the reader ends, returning the amount
+/// of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(read)
+}
+
+/// An error for a frame whose payload doesn't match its checksum.
+///
+/// [`ChecksummedFrameReader::read_frame`] returns this inside an error of
+/// the kind `ErrorKind::InvalidData`, which can be recovered through
+/// `io::Error::get_ref`.
+///
+/// [`ChecksummedFrameReader::read_frame`]: struct.ChecksummedFrameReader.html#method.read_frame
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{ChecksumMismatch, ChecksummedFrameReader};
+///
+/// let bytes = b"\x00\x00\x00\x02hi\x00\x00\x00\x00";
+/// let mut reader = ChecksummedFrameReader::new(&bytes[..], 1024);
+/// let err = reader.read_frame(&mut vec![]).unwrap_err();
+///
+/// let mismatch = err.get_ref().unwrap().downcast_ref::<ChecksumMismatch>().unwrap();
+/// assert_eq!(mismatch.expected(), 0);
+/// assert_eq!(mismatch.actual(), 0xd8932aac);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    expected: u32,
+    actual: u32,
+}
+
+impl ChecksumMismatch {
+    /// Returns the checksum stored in the frame.
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    /// Returns the checksum of the payload that was read.
+    pub fn actual(&self) -> u32 {
+        self.actual
+    }
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame checksum mismatch: expected {:#010x}, found {:#010x}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for ChecksumMismatch {}
+
+impl From<ChecksumMismatch> for io::Error {
+    /// Wraps the `ChecksumMismatch` in an error of the kind
+    /// `ErrorKind::InvalidData`.
+    fn from(err: ChecksumMismatch) -> Self {
+        io::Error::new(ErrorKind::InvalidData, err)
+    }
+}
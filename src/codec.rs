@@ -0,0 +1,319 @@
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::io::{self, Cursor, ErrorKind};
+use std::marker::PhantomData;
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{ReadBytes, WriteBytes};
+
+/// The default maximum frame length: 8 MiB.
+const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endian {
+    Big,
+    Little,
+    Native,
+}
+
+impl Endian {
+    fn read<T: ReadBytes, R: io::Read>(self, reader: &mut R) -> io::Result<T> {
+        match self {
+            Endian::Big => T::read_be_bytes(reader),
+            Endian::Little => T::read_le_bytes(reader),
+            Endian::Native => T::read_ne_bytes(reader),
+        }
+    }
+
+    fn write<T: WriteBytes, W: io::Write>(self, value: &T, writer: &mut W) -> io::Result<usize> {
+        match self {
+            Endian::Big => value.write_be_bytes(writer),
+            Endian::Little => value.write_le_bytes(writer),
+            Endian::Native => value.write_ne_bytes(writer),
+        }
+    }
+}
+
+/// Try to decode a value from the start of `src`.
+///
+/// Returns `Ok(None)` if `src` doesn't hold enough bytes yet, and otherwise
+/// the value together with the amount of bytes it occupied.
+fn decode_value<T: ReadBytes>(src: &[u8], endian: Endian) -> io::Result<Option<(T, usize)>> {
+    let mut cursor = Cursor::new(src);
+    match endian.read(&mut cursor) {
+        Ok(value) => Ok(Some((value, cursor.position() as usize))),
+        Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// A `tokio_util` codec for streams of fixed-size values.
+///
+/// Each frame is a single `T`, encoded with [`WriteBytes`] and decoded with
+/// [`ReadBytes`] in the codec's endianness. Incomplete values are left in the
+/// buffer until more bytes arrive.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use omnom::FixedCodec;
+/// use tokio_util::codec::Decoder;
+///
+/// let mut codec = FixedCodec::<u16>::new_be();
+/// let mut buf = BytesMut::from(&[0, 12, 0][..]);
+///
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(12));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+/// assert_eq!(&buf[..], &[0]);
+/// ```
+pub struct FixedCodec<T> {
+    endian: Endian,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> FixedCodec<T> {
+    /// Create a codec that reads and writes values as big endian.
+    pub fn new_be() -> Self {
+        Self::with_endian(Endian::Big)
+    }
+
+    /// Create a codec that reads and writes values as little endian.
+    pub fn new_le() -> Self {
+        Self::with_endian(Endian::Little)
+    }
+
+    /// Create a codec that reads and writes values using native endianness.
+    pub fn new_ne() -> Self {
+        Self::with_endian(Endian::Native)
+    }
+
+    fn with_endian(endian: Endian) -> Self {
+        Self {
+            endian,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for FixedCodec<T> {
+    fn default() -> Self {
+        Self::new_be()
+    }
+}
+
+impl<T> Clone for FixedCodec<T> {
+    fn clone(&self) -> Self {
+        Self::with_endian(self.endian)
+    }
+}
+
+impl<T> fmt::Debug for FixedCodec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedCodec")
+            .field("endian", &self.endian)
+            .finish()
+    }
+}
+
+impl<T: ReadBytes> Decoder for FixedCodec<T> {
+    type Item = T;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<T>> {
+        match decode_value(src, self.endian)? {
+            Some((value, len)) => {
+                src.advance(len);
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: WriteBytes> Encoder<T> for FixedCodec<T> {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        self.endian.write(&item, &mut dst.writer())?;
+        Ok(())
+    }
+}
+
+/// A `tokio_util` codec for length-prefixed values.
+///
+/// Each frame is a length prefix `L` holding the payload's size in bytes,
+/// followed by a payload holding exactly one `T`. Both are read and written
+/// in the codec's endianness. Frames longer than the maximum frame length
+/// (8 MiB by default) are rejected with `ErrorKind::InvalidData` when
+/// decoding, and `ErrorKind::InvalidInput` when encoding.
+///
+/// Incomplete frames are left in the buffer until more bytes arrive.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use omnom::LengthDelimitedValueCodec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = LengthDelimitedValueCodec::<u8, u32>::new_be();
+/// let mut buf = BytesMut::new();
+/// codec.encode(12, &mut buf).unwrap();
+/// assert_eq!(&buf[..], &[4, 0, 0, 0, 12]);
+///
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(12));
+/// assert!(buf.is_empty());
+/// ```
+pub struct LengthDelimitedValueCodec<L, T> {
+    endian: Endian,
+    max_frame_length: usize,
+    _marker: PhantomData<fn() -> (L, T)>,
+}
+
+impl<L, T> LengthDelimitedValueCodec<L, T> {
+    /// Create a codec that reads and writes frames as big endian.
+    pub fn new_be() -> Self {
+        Self::with_endian(Endian::Big)
+    }
+
+    /// Create a codec that reads and writes frames as little endian.
+    pub fn new_le() -> Self {
+        Self::with_endian(Endian::Little)
+    }
+
+    /// Create a codec that reads and writes frames using native endianness.
+    pub fn new_ne() -> Self {
+        Self::with_endian(Endian::Native)
+    }
+
+    fn with_endian(endian: Endian) -> Self {
+        Self {
+            endian,
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the maximum payload length in bytes.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Sets the maximum payload length in bytes.
+    pub fn set_max_frame_length(&mut self, max: usize) {
+        self.max_frame_length = max;
+    }
+}
+
+impl<L, T> Default for LengthDelimitedValueCodec<L, T> {
+    fn default() -> Self {
+        Self::new_be()
+    }
+}
+
+impl<L, T> Clone for LengthDelimitedValueCodec<L, T> {
+    fn clone(&self) -> Self {
+        Self {
+            endian: self.endian,
+            max_frame_length: self.max_frame_length,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L, T> fmt::Debug for LengthDelimitedValueCodec<L, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LengthDelimitedValueCodec")
+            .field("endian", &self.endian)
+            .field("max_frame_length", &self.max_frame_length)
+            .finish()
+    }
+}
+
+impl<L, T> Decoder for LengthDelimitedValueCodec<L, T>
+where
+    L: ReadBytes + TryInto<usize>,
+    T: ReadBytes,
+{
+    type Item = T;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<T>> {
+        let (len, header_len) = match decode_value::<L>(src, self.endian)? {
+            Some(prefix) => prefix,
+            None => return Ok(None),
+        };
+        let len: usize = len.try_into().map_err(|_| {
+            io::Error::new(ErrorKind::InvalidData, "length prefix doesn't fit in usize")
+        })?;
+        if len > self.max_frame_length {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes exceeds the maximum frame length of {} bytes",
+                    len, self.max_frame_length
+                ),
+            ));
+        }
+
+        let frame_len = header_len + len;
+        if src.len() < frame_len {
+            src.reserve(frame_len - src.len());
+            return Ok(None);
+        }
+
+        let mut payload = &src[header_len..frame_len];
+        let value = self.endian.read(&mut payload).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => {
+                io::Error::new(ErrorKind::InvalidData, "frame payload is too short")
+            }
+            _ => e,
+        })?;
+        if !payload.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("frame payload has {} trailing bytes", payload.len()),
+            ));
+        }
+
+        src.advance(frame_len);
+        Ok(Some(value))
+    }
+}
+
+impl<L, T> Encoder<T> for LengthDelimitedValueCodec<L, T>
+where
+    L: WriteBytes + TryFrom<usize>,
+    T: WriteBytes,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        let mut payload = Vec::new();
+        self.endian.write(&item, &mut payload)?;
+        if payload.len() > self.max_frame_length {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "frame of {} bytes exceeds the maximum frame length of {} bytes",
+                    payload.len(),
+                    self.max_frame_length
+                ),
+            ));
+        }
+        let len = L::try_from(payload.len()).map_err(|_| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "payload length doesn't fit in the length prefix",
+            )
+        })?;
+
+        let mut writer = dst.writer();
+        self.endian.write(&len, &mut writer)?;
+        writer.get_mut().put_slice(&payload);
+        Ok(())
+    }
+}
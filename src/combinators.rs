@@ -0,0 +1,122 @@
+//! Combinators built on `BufReadExt`.
+//!
+//! These compose the extension methods into the sequences that come up in
+//! most parsers: a value between two delimiters, after a prefix, or before a
+//! terminator. They don't allocate beyond what the inner operations need.
+//!
+//! A delimiter that doesn't match is reported as an `io::Error` wrapping a
+//! [`ParseError`] whose context names the part that failed: `"opening
+//! delimiter"`, `"closing delimiter"`, `"prefix"` or `"terminator"`. Its kind
+//! is `ParseErrorKind::UnexpectedEof` if the stream ended, and
+//! `ParseErrorKind::Mismatch` otherwise. Errors from inner parsers are
+//! returned unchanged.
+//!
+//! [`ParseError`]: ../struct.ParseError.html
+//!
+//! # Examples
+//!
+//! ```
+//! use std::io::Cursor;
+//! use omnom::combinators::{delimited, preceded};
+//!
+//! let mut reader = Cursor::new(b"name=\"omnom\"");
+//! let mut value = vec![];
+//! preceded(&mut reader, b"name=", |r| delimited(r, b"\"", b'"', &mut value)).unwrap();
+//! assert_eq!(value, b"omnom");
+//! ```
+
+use std::io::{self, BufRead};
+
+use crate::{BufReadExt, ParseError, ParseErrorKind, ParseResultExt};
+
+/// Read the bytes between `open` and the `close` delimiter into `buf`.
+///
+/// Neither delimiter is appended to `buf`, but both are consumed. Returns the
+/// amount of bytes appended.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use omnom::combinators::delimited;
+///
+/// let mut reader = Cursor::new(b"(lorem) ipsum");
+/// let mut buf = vec![];
+/// assert_eq!(delimited(&mut reader, b"(", b')', &mut buf).unwrap(), 5);
+/// assert_eq!(buf, b"lorem");
+/// ```
+pub fn delimited<R>(reader: &mut R, open: &[u8], close: u8, buf: &mut Vec<u8>) -> io::Result<usize>
+where
+    R: BufRead,
+{
+    expect(reader, open, "opening delimiter")?;
+    let n = reader.read_until(close, buf)?;
+    if buf.last() != Some(&close) || n == 0 {
+        return Err(fail(ParseErrorKind::UnexpectedEof, "closing delimiter"));
+    }
+    buf.pop();
+    Ok(n - 1)
+}
+
+/// Skip `tag`, then run `f`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use omnom::combinators::preceded;
+/// use omnom::prelude::*;
+///
+/// let mut reader = Cursor::new(b"v\x00\x02");
+/// let version: u16 = preceded(&mut reader, b"v", |r| r.read_be()).unwrap();
+/// assert_eq!(version, 2);
+/// ```
+pub fn preceded<R, T, F>(reader: &mut R, tag: &[u8], f: F) -> io::Result<T>
+where
+    R: BufRead,
+    F: FnOnce(&mut R) -> io::Result<T>,
+{
+    expect(reader, tag, "prefix")?;
+    f(reader)
+}
+
+/// Run `f`, then skip `tag`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use omnom::combinators::terminated;
+/// use omnom::prelude::*;
+///
+/// let mut reader = Cursor::new(b"\x00\x02\r\n");
+/// let len: u16 = terminated(&mut reader, |r| r.read_be(), b"\r\n").unwrap();
+/// assert_eq!(len, 2);
+/// ```
+pub fn terminated<R, T, F>(reader: &mut R, f: F, tag: &[u8]) -> io::Result<T>
+where
+    R: BufRead,
+    F: FnOnce(&mut R) -> io::Result<T>,
+{
+    let value = f(reader)?;
+    expect(reader, tag, "terminator")?;
+    Ok(value)
+}
+
+/// Skip `tag`, or fail with an error annotated with `context`.
+fn expect<R>(reader: &mut R, tag: &[u8], context: &'static str) -> io::Result<()>
+where
+    R: BufRead,
+{
+    if reader.consume_prefix(tag).context(context)? {
+        Ok(())
+    } else if reader.fill_buf()?.is_empty() {
+        Err(fail(ParseErrorKind::UnexpectedEof, context))
+    } else {
+        Err(fail(ParseErrorKind::Mismatch, context))
+    }
+}
+
+fn fail(kind: ParseErrorKind, context: &'static str) -> io::Error {
+    ParseError::from(kind).with_context(context).into()
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, Cursor, Take};
+
+use crate::{BoundedRetry, Lookahead, Unread};
+
+/// Check whether a reader has bytes buffered, without reading from the
+/// underlying source.
+///
+/// `fill_buf` on an empty buffer reads from the underlying source, which
+/// may block. In a loop that waits for readiness, such as with `poll` or
+/// `select`, it's useful to consume what's already buffered first, and only
+/// go back to waiting once the buffer is empty. `BufRead` has no way to ask
+/// for that, so this trait provides it for the readers that can tell.
+///
+/// # Examples
+///
+/// A drain-then-wait loop:
+///
+/// ```
+/// use std::io::{BufRead, BufReader};
+/// use omnom::prelude::*;
+/// use omnom::DataReady;
+///
+/// let mut reader = BufReader::new(&b"a\nb\nc"[..]);
+/// reader.fill_buf().unwrap(); // The reactor said the source is readable.
+///
+/// let mut lines = vec![];
+/// while reader.has_data_ready() {
+///     let mut line = vec![];
+///     reader.read_until(b'\n', &mut line).unwrap();
+///     lines.push(line);
+/// }
+/// // Back to waiting for the reactor.
+/// assert_eq!(lines, [&b"a\n"[..], b"b\n", b"c"]);
+/// ```
+pub trait DataReady {
+    /// Returns `true` if at least one byte can be consumed without reading
+    /// from the underlying source.
+    fn has_data_ready(&self) -> bool;
+}
+
+impl<R> DataReady for BufReader<R> {
+    fn has_data_ready(&self) -> bool {
+        !self.buffer().is_empty()
+    }
+}
+
+impl<T: AsRef<[u8]>> DataReady for Cursor<T> {
+    fn has_data_ready(&self) -> bool {
+        (self.position() as u128) < self.get_ref().as_ref().len() as u128
+    }
+}
+
+impl DataReady for &[u8] {
+    fn has_data_ready(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl DataReady for VecDeque<u8> {
+    fn has_data_ready(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<R: DataReady> DataReady for Take<R> {
+    fn has_data_ready(&self) -> bool {
+        self.limit() != 0 && self.get_ref().has_data_ready()
+    }
+}
+
+impl<R, const K: usize> DataReady for Lookahead<R, K> {
+    fn has_data_ready(&self) -> bool {
+        self.has_buffered()
+    }
+}
+
+impl<R: DataReady> DataReady for BoundedRetry<R> {
+    fn has_data_ready(&self) -> bool {
+        self.get_ref().has_data_ready()
+    }
+}
+
+impl<R: DataReady> DataReady for Unread<R> {
+    fn has_data_ready(&self) -> bool {
+        !self.pushed_back().is_empty() || self.get_ref().has_data_ready()
+    }
+}
+
+impl<R: DataReady + ?Sized> DataReady for &mut R {
+    fn has_data_ready(&self) -> bool {
+        (**self).has_data_ready()
+    }
+}
+
+impl<R: DataReady + ?Sized> DataReady for Box<R> {
+    fn has_data_ready(&self) -> bool {
+        (**self).has_data_ready()
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{self, BufRead, ErrorKind};
+
+use crate::ParseError;
+
+/// The result of a single parser run, detached from the input it borrowed.
+pub(crate) enum Outcome<O> {
+    /// The parser produced a value using this many bytes.
+    Done(O, usize),
+    /// The parser needs more bytes.
+    Incomplete,
+    /// The parser failed.
+    Failed(ParseError),
+}
+
+/// Drive a streaming parser over a `BufRead`.
+///
+/// `attempt` is first run on the reader's buffered bytes. While it returns
+/// `Outcome::Incomplete` the buffered bytes are consumed into an accumulation
+/// buffer and `attempt` is retried with the next chunk appended. On success
+/// only the bytes of the last chunk that the parser used are consumed.
+pub(crate) fn drive<R, O, F>(reader: &mut R, mut attempt: F) -> io::Result<O>
+where
+    R: BufRead + ?Sized,
+    F: FnMut(&[u8]) -> Outcome<O>,
+{
+    let mut acc = Vec::new();
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let chunk_len = available.len();
+        let prev = acc.len();
+        let outcome = if acc.is_empty() {
+            let outcome = attempt(available);
+            if let Outcome::Incomplete = outcome {
+                acc.extend_from_slice(available);
+            }
+            outcome
+        } else {
+            acc.extend_from_slice(available);
+            attempt(&acc)
+        };
+
+        match outcome {
+            Outcome::Done(value, used) if used >= prev => {
+                reader.consume(used - prev);
+                return Ok(value);
+            }
+            Outcome::Done(..) => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "parser used fewer bytes than it previously reported as incomplete",
+                ));
+            }
+            Outcome::Failed(err) => return Err(err.into()),
+            Outcome::Incomplete if chunk_len == 0 => {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            Outcome::Incomplete => reader.consume(chunk_len),
+        }
+    }
+}
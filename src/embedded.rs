@@ -0,0 +1,261 @@
+//! Extension traits for the `embedded-io` traits.
+//!
+//! These mirror the crate's `std::io` extension traits, but are generic over
+//! the reader's or writer's error type and never allocate, which makes them
+//! usable on `no_std` targets without an allocator.
+//!
+//! # Examples
+//!
+//! ```
+//! use omnom::embedded::{BufReadExt, ReadExt};
+//!
+//! let mut reader: &[u8] = b"  12ab";
+//! reader.skip_while(|b| b == b' ').unwrap();
+//! let num: u16 = reader.read_be().unwrap();
+//! assert_eq!(num, 0x3132);
+//! ```
+
+use embedded_io::{BufRead, Read, ReadExactError, Write};
+
+use crate::{ReadBytes, WriteBytes};
+
+/// Extend `embedded_io::BufRead` with methods for streaming parsing.
+pub trait BufReadExt: BufRead {
+    /// Read bytes based on a predicate.
+    ///
+    /// `read_while` takes a predicate as an argument. It will call this on
+    /// each byte, and copy it to the slice if the predicate evaluates to
+    /// `true`. Stops at the first byte that doesn't match, at EOF, or once
+    /// `buf` is full. Returns the amount of bytes read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::BufReadExt;
+    ///
+    /// let mut reader: &[u8] = b"lorem-ipsum";
+    /// let mut buf = [0; 8];
+    /// let n = reader.read_while(&mut buf, |b| b != b'-').unwrap();
+    /// assert_eq!(&buf[..n], b"lorem");
+    /// assert_eq!(reader, b"-ipsum");
+    /// ```
+    fn read_while<P>(&mut self, buf: &mut [u8], mut predicate: P) -> Result<usize, Self::Error>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let mut read = 0;
+        while read < buf.len() {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let max = available.len().min(buf.len() - read);
+            let len = available[..max]
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(max);
+            buf[read..read + len].copy_from_slice(&available[..len]);
+            self.consume(len);
+            read += len;
+            if len < max {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    /// Skip bytes while the predicate is true.
+    ///
+    /// Returns the amount of bytes skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::BufReadExt;
+    ///
+    /// let mut reader: &[u8] = b"   lorem";
+    /// assert_eq!(reader.skip_while(|b| b == b' ').unwrap(), 3);
+    /// assert_eq!(reader, b"lorem");
+    /// ```
+    fn skip_while<P>(&mut self, mut predicate: P) -> Result<usize, Self::Error>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let mut read = 0;
+        loop {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let max = available.len();
+            let len = available.iter().position(|b| !predicate(*b)).unwrap_or(max);
+            self.consume(len);
+            read += len;
+            if len < max {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    /// Skip bytes until the delimiter `byte` or EOF is reached.
+    ///
+    /// All bytes up to, and including, the delimiter (if found) will be
+    /// skipped. Returns the amount of bytes skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::BufReadExt;
+    ///
+    /// let mut reader: &[u8] = b"lorem-ipsum";
+    /// assert_eq!(reader.skip_until(b'-').unwrap(), 6);
+    /// assert_eq!(reader, b"ipsum");
+    /// ```
+    fn skip_until(&mut self, byte: u8) -> Result<usize, Self::Error> {
+        let mut read = 0;
+        loop {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            match memchr::memchr(byte, available) {
+                Some(i) => {
+                    self.consume(i + 1);
+                    read += i + 1;
+                    break;
+                }
+                None => {
+                    let len = available.len();
+                    self.consume(len);
+                    read += len;
+                }
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl<T: BufRead + ?Sized> BufReadExt for T {}
+
+/// Extension trait to `embedded_io::Read` to read bytes using endianness.
+pub trait ReadExt: Read + Sized {
+    /// Read bytes as big endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::ReadExt;
+    ///
+    /// let mut reader: &[u8] = &[0, 12];
+    /// let num: u16 = reader.read_be().unwrap();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn read_be<B: ReadBytes>(&mut self) -> Result<B, ReadExactError<Self::Error>> {
+        let mut bytes = B::Bytes::default();
+        self.read_exact(bytes.as_mut())?;
+        Ok(B::from_be_array(bytes))
+    }
+
+    /// Read bytes as little endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::ReadExt;
+    ///
+    /// let mut reader: &[u8] = &[12, 0];
+    /// let num: u16 = reader.read_le().unwrap();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn read_le<B: ReadBytes>(&mut self) -> Result<B, ReadExactError<Self::Error>> {
+        let mut bytes = B::Bytes::default();
+        self.read_exact(bytes.as_mut())?;
+        Ok(B::from_le_array(bytes))
+    }
+
+    /// Read bytes using native endianness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::ReadExt;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// let mut reader: &[u8] = &bytes;
+    /// let num: u16 = reader.read_ne().unwrap();
+    /// assert_eq!(num, 12);
+    /// ```
+    fn read_ne<B: ReadBytes>(&mut self) -> Result<B, ReadExactError<Self::Error>> {
+        let mut bytes = B::Bytes::default();
+        self.read_exact(bytes.as_mut())?;
+        Ok(B::from_ne_array(bytes))
+    }
+}
+
+impl<T: Read> ReadExt for T {}
+
+/// Extension trait to `embedded_io::Write` to write bytes using endianness.
+pub trait WriteExt: Write + Sized {
+    /// Write bytes as big endian.
+    ///
+    /// Returns the amount of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::WriteExt;
+    ///
+    /// let mut buf = [0; 2];
+    /// let mut writer: &mut [u8] = &mut buf;
+    /// writer.write_be(12_u16).unwrap();
+    /// assert_eq!(buf, [0, 12]);
+    /// ```
+    fn write_be<B: WriteBytes>(&mut self, num: B) -> Result<usize, Self::Error> {
+        let bytes = num.to_be_array();
+        self.write_all(bytes.as_ref())?;
+        Ok(bytes.as_ref().len())
+    }
+
+    /// Write bytes as little endian.
+    ///
+    /// Returns the amount of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::WriteExt;
+    ///
+    /// let mut buf = [0; 2];
+    /// let mut writer: &mut [u8] = &mut buf;
+    /// writer.write_le(12_u16).unwrap();
+    /// assert_eq!(buf, [12, 0]);
+    /// ```
+    fn write_le<B: WriteBytes>(&mut self, num: B) -> Result<usize, Self::Error> {
+        let bytes = num.to_le_array();
+        self.write_all(bytes.as_ref())?;
+        Ok(bytes.as_ref().len())
+    }
+
+    /// Write bytes using native endianness.
+    ///
+    /// Returns the amount of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::embedded::WriteExt;
+    ///
+    /// let mut buf = [0; 2];
+    /// let mut writer: &mut [u8] = &mut buf;
+    /// writer.write_ne(12_u16).unwrap();
+    /// assert_eq!(buf, 12_u16.to_ne_bytes());
+    /// ```
+    fn write_ne<B: WriteBytes>(&mut self, num: B) -> Result<usize, Self::Error> {
+        let bytes = num.to_ne_array();
+        self.write_all(bytes.as_ref())?;
+        Ok(bytes.as_ref().len())
+    }
+}
+
+impl<T: Write> WriteExt for T {}
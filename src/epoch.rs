@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "std")]
+use std::io::{self, ErrorKind, Read, Write};
+
+use crate::{ReadBytes, WriteBytes};
+
+macro_rules! epoch_impl {
+    (
+        $(#[$meta:meta])*
+        $Name:ident, $unit:expr, $to_int:expr, $from_int:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $Name(pub DateTime<Utc>);
+
+        impl $Name {
+            /// Returns the time as an integer, or `None` if it can't be
+            /// represented as an `i64`.
+            pub fn to_i64(self) -> Option<i64> {
+                let to_int: fn(DateTime<Utc>) -> Option<i64> = $to_int;
+                to_int(self.0)
+            }
+
+            /// Create a time from an integer, or return `None` if it's outside
+            /// of the range `chrono` can represent.
+            pub fn from_i64(value: i64) -> Option<Self> {
+                let from_int: fn(i64) -> Option<DateTime<Utc>> = $from_int;
+                from_int(value).map(Self)
+            }
+
+            /// Create a time from an integer, saturating at the minimum and
+            /// maximum time `chrono` can represent.
+            fn from_i64_saturating(value: i64) -> Self {
+                Self::from_i64(value).unwrap_or(if value < 0 {
+                    Self(DateTime::<Utc>::MIN_UTC)
+                } else {
+                    Self(DateTime::<Utc>::MAX_UTC)
+                })
+            }
+
+            /// Returns the time as an integer, saturating at the minimum and
+            /// maximum `i64`.
+            fn to_i64_saturating(self) -> i64 {
+                self.to_i64().unwrap_or(if self.0.timestamp() < 0 {
+                    i64::MIN
+                } else {
+                    i64::MAX
+                })
+            }
+
+            #[cfg(feature = "std")]
+            fn read_with(value: io::Result<i64>) -> io::Result<Self> {
+                let value = value?;
+                Self::from_i64(value).ok_or_else(|| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "{} {} since the Unix epoch is out of range",
+                            value, $unit
+                        ),
+                    )
+                })
+            }
+
+            #[cfg(feature = "std")]
+            fn checked_i64(self) -> io::Result<i64> {
+                self.to_i64().ok_or_else(|| {
+                    io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("{:?} can't be represented as {} in an i64", self.0, $unit),
+                    )
+                })
+            }
+        }
+
+        impl From<DateTime<Utc>> for $Name {
+            fn from(time: DateTime<Utc>) -> Self {
+                Self(time)
+            }
+        }
+
+        impl From<$Name> for DateTime<Utc> {
+            fn from(time: $Name) -> Self {
+                time.0
+            }
+        }
+
+        impl ReadBytes for $Name {
+            type Bytes = [u8; 8];
+
+            fn from_be_array(bytes: Self::Bytes) -> Self {
+                Self::from_i64_saturating(i64::from_be_bytes(bytes))
+            }
+
+            fn from_le_array(bytes: Self::Bytes) -> Self {
+                Self::from_i64_saturating(i64::from_le_bytes(bytes))
+            }
+
+            fn from_ne_array(bytes: Self::Bytes) -> Self {
+                Self::from_i64_saturating(i64::from_ne_bytes(bytes))
+            }
+
+            #[cfg(feature = "std")]
+            fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                Self::read_with(i64::read_be_bytes(reader))
+            }
+
+            #[cfg(feature = "std")]
+            fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                Self::read_with(i64::read_le_bytes(reader))
+            }
+
+            #[cfg(feature = "std")]
+            fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                Self::read_with(i64::read_ne_bytes(reader))
+            }
+        }
+
+        impl WriteBytes for $Name {
+            type Bytes = [u8; 8];
+
+            fn to_be_array(&self) -> Self::Bytes {
+                self.to_i64_saturating().to_be_bytes()
+            }
+
+            fn to_le_array(&self) -> Self::Bytes {
+                self.to_i64_saturating().to_le_bytes()
+            }
+
+            fn to_ne_array(&self) -> Self::Bytes {
+                self.to_i64_saturating().to_ne_bytes()
+            }
+
+            #[cfg(feature = "std")]
+            fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.checked_i64()?.write_be_bytes(writer)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.checked_i64()?.write_le_bytes(writer)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.checked_i64()?.write_ne_bytes(writer)
+            }
+        }
+    };
+}
+
+epoch_impl!(
+    /// A `chrono` time encoded as whole seconds since the Unix epoch in an
+    /// `i64`.
+    ///
+    /// Writing drops the fractional seconds, rounding towards the past.
+    ///
+    /// Reading through `std::io` returns `ErrorKind::InvalidData` if the
+    /// value is outside of the range `chrono` can represent. The array
+    /// conversions can't fail, and saturate at the minimum and maximum
+    /// `DateTime<Utc>` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use chrono::{TimeZone, Utc};
+    /// use omnom::prelude::*;
+    /// use omnom::EpochSeconds;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// let time = Utc.with_ymd_and_hms(2001, 9, 9, 1, 46, 40).unwrap();
+    /// cursor.write_be(EpochSeconds(time)).unwrap();
+    /// assert_eq!(cursor.get_ref(), &1_000_000_000_i64.to_be_bytes());
+    ///
+    /// cursor.set_position(0);
+    /// let read: EpochSeconds = cursor.read_be().unwrap();
+    /// assert_eq!(read.0, time);
+    /// ```
+    EpochSeconds,
+    "seconds",
+    |time| Some(time.timestamp()),
+    |secs| DateTime::from_timestamp(secs, 0)
+);
+
+epoch_impl!(
+    /// A `chrono` time encoded as milliseconds since the Unix epoch in an
+    /// `i64`.
+    ///
+    /// Writing drops the fractional milliseconds, rounding towards the past.
+    ///
+    /// Reading through `std::io` returns `ErrorKind::InvalidData` if the
+    /// value is outside of the range `chrono` can represent. The array
+    /// conversions can't fail, and saturate at the minimum and maximum
+    /// `DateTime<Utc>` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::DateTime;
+    /// use omnom::prelude::*;
+    /// use omnom::EpochMillis;
+    ///
+    /// let time = EpochMillis::from_le_array((-1_500_i64).to_le_bytes());
+    /// assert_eq!(time.0, DateTime::parse_from_rfc3339("1969-12-31T23:59:58.5Z").unwrap());
+    /// ```
+    EpochMillis,
+    "milliseconds",
+    |time| Some(time.timestamp_millis()),
+    DateTime::from_timestamp_millis
+);
+
+epoch_impl!(
+    /// A `chrono` time encoded as nanoseconds since the Unix epoch in an
+    /// `i64`.
+    ///
+    /// Every `i64` is a valid time, from 1677 to 2262. Writing through
+    /// `std::io` returns `ErrorKind::InvalidInput` for times outside of that
+    /// range, while the array conversions saturate at the minimum and maximum
+    /// `i64` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::DateTime;
+    /// use omnom::prelude::*;
+    /// use omnom::EpochNanos;
+    ///
+    /// let time = DateTime::parse_from_rfc3339("2262-04-11T23:47:16.854775807Z").unwrap();
+    /// assert_eq!(EpochNanos(time.into()).to_be_array(), i64::MAX.to_be_bytes());
+    /// ```
+    EpochNanos,
+    "nanoseconds",
+    |time| time.timestamp_nanos_opt(),
+    |nanos| Some(DateTime::from_timestamp_nanos(nanos))
+);
@@ -0,0 +1,182 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::{ReadBytes, WriteBytes};
+
+macro_rules! fixed_impl {
+    ($(#[$meta:meta])* $Name:ident($Int:ty), $frac_bits:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $Name(pub $Int);
+
+        impl $Name {
+            /// The amount of fractional bits.
+            pub const FRAC_BITS: u32 = $frac_bits;
+
+            /// The value `1.0`.
+            pub const ONE: Self = Self(1 << $frac_bits);
+
+            /// Convert to an `f32`.
+            ///
+            /// This is exact if the value fits in the 24 bits of precision of
+            /// an `f32`.
+            pub fn to_f32(self) -> f32 {
+                self.0 as f32 / (1_u32 << $frac_bits) as f32
+            }
+
+            /// Convert to an `f64`, which is exact.
+            pub fn to_f64(self) -> f64 {
+                f64::from(self.0) / f64::from(1_u32 << $frac_bits)
+            }
+
+            /// Convert from an `f32`, rounding to the nearest representable
+            /// value.
+            ///
+            /// Ties round away from zero. Values outside of the representable
+            /// range saturate to the minimum or maximum, and NaN converts to
+            /// zero.
+            pub fn from_f32(value: f32) -> Self {
+                Self::from_f64(f64::from(value))
+            }
+
+            /// Convert from an `f64`, rounding to the nearest representable
+            /// value.
+            ///
+            /// Ties round away from zero. Values outside of the representable
+            /// range saturate to the minimum or maximum, and NaN converts to
+            /// zero.
+            pub fn from_f64(value: f64) -> Self {
+                let scaled = value * f64::from(1_u32 << $frac_bits);
+                // `as` truncates towards zero and saturates, so offsetting by
+                // a half rounds to nearest with ties away from zero.
+                let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+                Self(rounded as $Int)
+            }
+        }
+
+        impl fmt::Display for $Name {
+            /// Formats the value as a decimal number, like an `f64`.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.to_f64(), f)
+            }
+        }
+
+        impl ReadBytes for $Name {
+            type Bytes = <$Int as ReadBytes>::Bytes;
+
+            fn from_be_array(bytes: Self::Bytes) -> Self {
+                Self(<$Int>::from_be_array(bytes))
+            }
+
+            fn from_le_array(bytes: Self::Bytes) -> Self {
+                Self(<$Int>::from_le_array(bytes))
+            }
+
+            fn from_ne_array(bytes: Self::Bytes) -> Self {
+                Self(<$Int>::from_ne_array(bytes))
+            }
+
+            #[cfg(feature = "std")]
+            fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                <$Int>::read_be_bytes(reader).map(Self)
+            }
+
+            #[cfg(feature = "std")]
+            fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                <$Int>::read_le_bytes(reader).map(Self)
+            }
+
+            #[cfg(feature = "std")]
+            fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+                <$Int>::read_ne_bytes(reader).map(Self)
+            }
+        }
+
+        impl WriteBytes for $Name {
+            type Bytes = <$Int as WriteBytes>::Bytes;
+
+            fn to_be_array(&self) -> Self::Bytes {
+                self.0.to_be_array()
+            }
+
+            fn to_le_array(&self) -> Self::Bytes {
+                self.0.to_le_array()
+            }
+
+            fn to_ne_array(&self) -> Self::Bytes {
+                self.0.to_ne_array()
+            }
+
+            #[cfg(feature = "std")]
+            fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.0.write_be_bytes(writer)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.0.write_le_bytes(writer)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+                self.0.write_ne_bytes(writer)
+            }
+        }
+    };
+}
+
+fixed_impl!(
+    /// A signed fixed-point number with 16 integer and 16 fractional bits.
+    ///
+    /// This is TrueType's `Fixed` type. It reads and writes as its underlying
+    /// `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::{Q16_16, ReadBytes};
+    ///
+    /// let (version, _) = Q16_16::from_be_slice(&[0, 1, 0x80, 0]).unwrap();
+    /// assert_eq!(version.to_f64(), 1.5);
+    /// assert_eq!(version.to_string(), "1.5");
+    /// ```
+    Q16_16(i32),
+    16
+);
+
+fixed_impl!(
+    /// A signed fixed-point number with 8 integer and 8 fractional bits.
+    ///
+    /// It reads and writes as its underlying `i16`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Q8_8;
+    ///
+    /// assert_eq!(Q8_8::from_f32(-0.5), Q8_8(-128));
+    /// assert_eq!(Q8_8(0x0140).to_f32(), 1.25);
+    /// ```
+    Q8_8(i16),
+    8
+);
+
+fixed_impl!(
+    /// A signed fixed-point number with 2 integer and 14 fractional bits.
+    ///
+    /// This is TrueType's `F2Dot14` type, used for transformation matrices
+    /// and variation coordinates. It reads and writes as its underlying
+    /// `i16`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Q2_14;
+    ///
+    /// assert_eq!(Q2_14(0x7fff).to_f64(), 1.99993896484375);
+    /// assert_eq!(Q2_14(-0x8000).to_f64(), -2.0);
+    /// ```
+    Q2_14(i16),
+    14
+);
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+
+/// A guard that flushes a writer when it's dropped.
+///
+/// This is created by [`WriteExt::scoped_flush`]. It dereferences to the
+/// writer, and implements `Write` itself, so a frame can be written through
+/// it, and is flushed on every path out of the scope, including early
+/// returns with `?`.
+///
+/// # Errors on drop
+///
+/// `Drop` can't return an error, so an error from the flush in `drop` is
+/// ignored, as `BufWriter` does when it's dropped. Call [`finish`] to flush
+/// and get the error instead; the guard then doesn't flush again when it's
+/// dropped. The guard also doesn't flush if it's dropped while the thread
+/// panics, as the writer may be in the middle of a write.
+///
+/// [`WriteExt::scoped_flush`]: trait.WriteExt.html#method.scoped_flush
+/// [`finish`]: #method.finish
+#[derive(Debug)]
+pub struct FlushGuard<'a, W: Write + ?Sized> {
+    inner: &'a mut W,
+    /// Set by `finish`, so the flush isn't repeated in `drop`.
+    done: bool,
+}
+
+impl<'a, W: Write + ?Sized> FlushGuard<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner, done: false }
+    }
+
+    /// Flush the writer and return the error, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufWriter, Write};
+    /// use omnom::prelude::*;
+    ///
+    /// let mut writer = BufWriter::new(vec![]);
+    /// let mut frame = writer.scoped_flush();
+    /// frame.write_be(5_u16).unwrap();
+    /// frame.write_all(b"hello").unwrap();
+    /// frame.finish().unwrap();
+    /// assert_eq!(writer.get_ref(), b"\x00\x05hello");
+    /// ```
+    pub fn finish(mut self) -> io::Result<()> {
+        self.done = true;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + ?Sized> Deref for FlushGuard<'_, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        self.inner
+    }
+}
+
+impl<W: Write + ?Sized> DerefMut for FlushGuard<'_, W> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.inner
+    }
+}
+
+impl<W: Write + ?Sized> Write for FlushGuard<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + ?Sized> Drop for FlushGuard<'_, W> {
+    fn drop(&mut self) {
+        if !self.done && !std::thread::panicking() {
+            let _ = self.inner.flush();
+        }
+    }
+}
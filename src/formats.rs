@@ -0,0 +1,6 @@
+//! Parsers for common formats, built on the crate's own primitives.
+//!
+//! Each format is behind a `formats-` feature of its own.
+
+#[cfg(feature = "formats-mime")]
+pub mod mime;
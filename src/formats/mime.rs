@@ -0,0 +1,298 @@
+//! MIME types, such as `text/html; charset=utf-8`.
+//!
+//! [`parse`] follows the parsing algorithm of the [WHATWG MIME Sniffing
+//! standard]. Like browsers do, it's lenient where the standard is: a
+//! parameter that's invalid or a duplicate is skipped rather than rejected,
+//! and only an invalid type or subtype fails the parse.
+//!
+//! The input is read as bytes, each of which stands for the code point of
+//! the same value, as is usual for HTTP header values. Quoted parameter
+//! values may use the bytes `0x80`..=`0xff`, which end up as the characters
+//! `U+0080`..=`U+00FF`.
+//!
+//! [`parse`]: fn.parse.html
+//! [WHATWG MIME Sniffing standard]: https://mimesniff.spec.whatwg.org/#parsing-a-mime-type
+//!
+//! # Examples
+//!
+//! ```
+//! use omnom::formats::mime::{self, Mime};
+//!
+//! let mime = mime::parse(&mut &b"Text/HTML; Charset=\"utf-8\""[..]).unwrap();
+//! assert_eq!(mime.base_type, "text");
+//! assert_eq!(mime.sub_type, "html");
+//! assert_eq!(mime.param("charset"), Some("utf-8"));
+//! assert_eq!(mime.to_string(), "text/html;charset=utf-8");
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::io::{self, BufRead, ErrorKind};
+use std::str::FromStr;
+
+use crate::predicates::{http_token, http_whitespace};
+use crate::BufReadExt;
+
+/// A parsed MIME type.
+///
+/// Values returned by [`parse`] have a lowercase `base_type`, `sub_type` and
+/// parameter names, which are all valid HTTP tokens. Parameters are kept in
+/// the order they appeared in, and each name appears only once.
+///
+/// [`parse`]: fn.parse.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mime {
+    /// The type, such as `text` in `text/html`.
+    pub base_type: String,
+    /// The subtype, such as `html` in `text/html`.
+    pub sub_type: String,
+    /// The parameters, as pairs of their name and value.
+    pub parameters: Vec<(String, String)>,
+}
+
+impl Mime {
+    /// Returns the value of the parameter `name`, if present.
+    ///
+    /// Parameter names are compared case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the type and subtype without parameters, such as
+    /// `text/html`.
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.base_type, self.sub_type)
+    }
+}
+
+impl fmt::Display for Mime {
+    /// Serializes the MIME type as the WHATWG standard does, quoting
+    /// parameter values that aren't HTTP tokens.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.base_type, self.sub_type)?;
+        for (name, value) in &self.parameters {
+            write!(f, ";{}=", name)?;
+            let is_token = !value.is_empty() && value.bytes().all(http_token);
+            if is_token {
+                f.write_str(value)?;
+                continue;
+            }
+            f.write_char('"')?;
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_char('\\')?;
+                }
+                f.write_char(c)?;
+            }
+            f.write_char('"')?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Mime {
+    type Err = MimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(&mut s.as_bytes())
+    }
+}
+
+/// Parse a MIME type from the rest of `reader`.
+///
+/// This reads until EOF, and parses everything read as a single MIME type.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::formats::mime;
+///
+/// let mut reader = &b"multipart/form-data; boundary=\"a;b\""[..];
+/// let mime = mime::parse(&mut reader).unwrap();
+/// assert_eq!(mime.param("boundary"), Some("a;b"));
+///
+/// assert!(mime::parse(&mut &b"text"[..]).is_err());
+/// ```
+pub fn parse<R: BufRead + ?Sized>(reader: &mut R) -> Result<Mime, MimeParseError> {
+    let mut input = vec![];
+    reader.read_to_end(&mut input)?;
+    let start = input.iter().position(|b| !http_whitespace(*b));
+    let end = input.iter().rposition(|b| !http_whitespace(*b));
+    let mut input = match (start, end) {
+        (Some(start), Some(end)) => &input[start..=end],
+        _ => &[][..],
+    };
+    parse_trimmed(&mut input)
+}
+
+/// Parse a MIME type from input without leading or trailing whitespace.
+fn parse_trimmed(input: &mut &[u8]) -> Result<Mime, MimeParseError> {
+    let mut base_type = vec![];
+    let (_, slash) = input.read_while_peek(&mut base_type, |b| b != b'/')?;
+    if base_type.is_empty() || !base_type.iter().all(|b| http_token(*b)) {
+        return Err(MimeParseError::InvalidType);
+    }
+    if slash.is_none() {
+        return Err(MimeParseError::MissingSlash);
+    }
+    input.consume(1);
+
+    let mut sub_type = vec![];
+    input.read_while(&mut sub_type, |b| b != b';')?;
+    trim_end(&mut sub_type);
+    if sub_type.is_empty() || !sub_type.iter().all(|b| http_token(*b)) {
+        return Err(MimeParseError::InvalidSubtype);
+    }
+
+    let mut mime = Mime {
+        base_type: lowercase(base_type),
+        sub_type: lowercase(sub_type),
+        parameters: vec![],
+    };
+
+    // Each iteration starts at the `;` before a parameter.
+    while !input.is_empty() {
+        input.consume(1);
+        input.skip_while(http_whitespace)?;
+
+        let mut name = vec![];
+        let (_, stop) = input.read_while_peek(&mut name, |b| b != b';' && b != b'=')?;
+        match stop {
+            Some(b';') => continue,
+            Some(_) => input.consume(1),
+            None => break,
+        }
+        if input.is_empty() {
+            break;
+        }
+
+        let mut value = vec![];
+        if input[0] == b'"' {
+            read_quoted(input, &mut value)?;
+            input.skip_while(|b| b != b';')?;
+        } else {
+            input.read_while(&mut value, |b| b != b';')?;
+            trim_end(&mut value);
+            if value.is_empty() {
+                continue;
+            }
+        }
+
+        let name = lowercase(name);
+        let is_valid = !name.is_empty()
+            && name.bytes().all(http_token)
+            && value.iter().all(|b| http_quoted_string_token(*b));
+        if is_valid && mime.param(&name).is_none() {
+            let value = value.iter().map(|b| char::from(*b)).collect();
+            mime.parameters.push((name, value));
+        }
+    }
+
+    Ok(mime)
+}
+
+/// Read a quoted string starting at its opening `"`, without the quotes and
+/// with escapes resolved.
+///
+/// An unterminated string runs until the end of the input.
+fn read_quoted(input: &mut &[u8], value: &mut Vec<u8>) -> io::Result<()> {
+    input.consume(1);
+    loop {
+        let (_, stop) = input.read_while_peek(value, |b| b != b'"' && b != b'\\')?;
+        match stop {
+            None => return Ok(()),
+            Some(b'\\') => {
+                input.consume(1);
+                match input.first() {
+                    Some(&escaped) => {
+                        value.push(escaped);
+                        input.consume(1);
+                    }
+                    None => {
+                        value.push(b'\\');
+                        return Ok(());
+                    }
+                }
+            }
+            Some(_) => {
+                input.consume(1);
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Remove trailing HTTP whitespace.
+fn trim_end(bytes: &mut Vec<u8>) {
+    while bytes.last().is_some_and(|b| http_whitespace(*b)) {
+        bytes.pop();
+    }
+}
+
+/// Lowercase bytes that are known to be ASCII.
+fn lowercase(mut bytes: Vec<u8>) -> String {
+    bytes.make_ascii_lowercase();
+    String::from_utf8(bytes).expect("HTTP tokens are ASCII")
+}
+
+/// Returns `true` for the bytes allowed in a parameter value: tab, the
+/// printable ASCII characters and space, and `0x80`..=`0xff`.
+fn http_quoted_string_token(byte: u8) -> bool {
+    matches!(byte, b'\t' | b' '..=b'~' | 0x80..=0xff)
+}
+
+/// An error parsing a MIME type.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MimeParseError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// The type is empty or contains bytes that aren't HTTP token code
+    /// points.
+    InvalidType,
+    /// The type isn't followed by a `/`.
+    MissingSlash,
+    /// The subtype is empty or contains bytes that aren't HTTP token code
+    /// points.
+    InvalidSubtype,
+}
+
+impl fmt::Display for MimeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MimeParseError::Io(e) => write!(f, "failed to read MIME type: {}", e),
+            MimeParseError::InvalidType => f.write_str("invalid MIME type: invalid type"),
+            MimeParseError::MissingSlash => f.write_str("invalid MIME type: missing `/`"),
+            MimeParseError::InvalidSubtype => f.write_str("invalid MIME type: invalid subtype"),
+        }
+    }
+}
+
+impl Error for MimeParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MimeParseError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MimeParseError {
+    fn from(err: io::Error) -> Self {
+        MimeParseError::Io(err)
+    }
+}
+
+impl From<MimeParseError> for io::Error {
+    /// Returns read errors as they are, and wraps parse errors in an error of
+    /// the kind `ErrorKind::InvalidData`.
+    fn from(err: MimeParseError) -> Self {
+        match err {
+            MimeParseError::Io(e) => e,
+            err => io::Error::new(ErrorKind::InvalidData, err),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{self, BufRead, ErrorKind};
+
+use crate::ReadBytes;
+
+/// A type that can be decoded from a buffered stream.
+///
+/// `ReadBytes` covers fixed-size primitives. `FromStream` is the place for
+/// decoding structured types, such as a file header made of several fields,
+/// which [`ReadStructExt::read_struct`] then reads from any `BufRead`.
+///
+/// Every `ReadBytes` type implements `FromStream` by reading itself as big
+/// endian, like `ReadExt::read_be`. Tuples read their fields in order, and
+/// `Option<T>` reads a tag byte, `0` for `None` or `1` for `Some`, followed
+/// by the `T` if it's `Some`. Byte arrays are `ReadBytes` and are read as
+/// is; arrays of other types don't implement `FromStream`, since that impl
+/// would overlap with the one for byte arrays. `Vec<T>` doesn't either,
+/// because its length isn't part of its encoding. [`ToStream`] is the
+/// encoding counterpart, which round-trips with `FromStream`.
+///
+/// [`ReadStructExt::read_struct`]: trait.ReadStructExt.html#method.read_struct
+/// [`ToStream`]: trait.ToStream.html
+///
+/// # Errors
+///
+/// Decoding isn't transactional. If it fails, the bytes read until then stay
+/// consumed, and the stream is left in the middle of the value. Errors from
+/// the reader are returned as they are, and bytes that don't form a valid
+/// value should be reported as `ErrorKind::InvalidData`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, BufRead};
+/// use omnom::{FromStream, ReadStructExt};
+///
+/// struct Header {
+///     magic: [u8; 4],
+///     len: u32,
+/// }
+///
+/// impl FromStream for Header {
+///     fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self> {
+///         Ok(Header {
+///             magic: reader.read_struct()?,
+///             len: reader.read_struct()?,
+///         })
+///     }
+/// }
+///
+/// let mut reader = &b"RIFF\x00\x00\x00\x24"[..];
+/// let header: Header = reader.read_struct().unwrap();
+/// assert_eq!(&header.magic, b"RIFF");
+/// assert_eq!(header.len, 36);
+/// ```
+pub trait FromStream: Sized {
+    /// Decode a value from `reader`.
+    fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self>;
+}
+
+impl<T: ReadBytes> FromStream for T {
+    fn from_stream<R: BufRead + ?Sized>(mut reader: &mut R) -> io::Result<Self> {
+        T::read_be_bytes(&mut reader)
+    }
+}
+
+impl<T: FromStream> FromStream for Option<T> {
+    fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        match u8::from_stream(reader)? {
+            0 => Ok(None),
+            1 => T::from_stream(reader).map(Some),
+            tag => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid `Option` tag {}, expected 0 or 1", tag),
+            )),
+        }
+    }
+}
+
+macro_rules! from_stream_tuple {
+    ($($T:ident)+) => {
+        impl<$($T: FromStream),+> FromStream for ($($T,)+) {
+            fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self> {
+                Ok(($($T::from_stream(reader)?,)+))
+            }
+        }
+    };
+}
+
+from_stream_tuple!(A);
+from_stream_tuple!(A B);
+from_stream_tuple!(A B C);
+from_stream_tuple!(A B C D);
+from_stream_tuple!(A B C D E);
+from_stream_tuple!(A B C D E F);
+from_stream_tuple!(A B C D E F G);
+from_stream_tuple!(A B C D E F G H);
+
+/// Extend `BufRead` with a method to read [`FromStream`] types.
+///
+/// [`FromStream`]: trait.FromStream.html
+pub trait ReadStructExt: BufRead {
+    /// Read a value of a type that implements [`FromStream`].
+    ///
+    /// [`FromStream`]: trait.FromStream.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ReadStructExt;
+    ///
+    /// let mut reader = &b"\x00\x01\x01\x02"[..];
+    /// let value: (u16, Option<u8>) = reader.read_struct().unwrap();
+    /// assert_eq!(value, (1, Some(2)));
+    /// ```
+    fn read_struct<T: FromStream>(&mut self) -> io::Result<T> {
+        T::from_stream(self)
+    }
+}
+
+impl<R: BufRead + ?Sized> ReadStructExt for R {}
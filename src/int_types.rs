@@ -0,0 +1,10 @@
+/// Invoke the macro `$m` with the integer types.
+///
+/// They implement `ReadBytes`, `WriteBytes` and `SwapBytes` alike, so this
+/// is the one list of them that those impls, and `tests/swap_bytes.rs`
+/// through `include!`, share.
+macro_rules! int_types {
+    ($m:ident) => {
+        $m!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    };
+}
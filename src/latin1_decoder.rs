@@ -0,0 +1,93 @@
+use std::io::{self, BufRead, Read};
+
+/// A reader that decodes Latin-1 (ISO-8859-1) into UTF-8.
+///
+/// Each byte of Latin-1 is the code point of the same value, so ASCII bytes
+/// are passed through as they are, and the bytes `0x80`..=`0xff` become the
+/// two-byte UTF-8 encodings of `U+0080`..=`U+00FF`. Decoding can't fail.
+///
+/// Since decoding makes the text longer, each chunk returned by `fill_buf`
+/// of the underlying reader is decoded into a buffer of the `Latin1Decoder`
+/// itself, which `fill_buf` and `consume` work on. Consuming part of a
+/// character leaves the rest of it to be returned next.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use omnom::Latin1Decoder;
+///
+/// let mut reader = Latin1Decoder::new(&b"caf\xe9 cr\xe8me"[..]);
+/// let mut text = String::new();
+/// reader.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "café crème");
+/// ```
+#[derive(Debug)]
+pub struct Latin1Decoder<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R> Latin1Decoder<R> {
+    /// Create a new `Latin1Decoder`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Bytes that were read from it but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Read for Latin1Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Latin1Decoder<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            let available = self.inner.fill_buf()?;
+            for &byte in available {
+                if byte.is_ascii() {
+                    self.buf.push(byte);
+                } else {
+                    self.buf
+                        .extend_from_slice(&[0xc0 | byte >> 6, 0x80 | byte & 0x3f]);
+                }
+            }
+            let len = available.len();
+            self.inner.consume(len);
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
@@ -0,0 +1,59 @@
+//! The unsigned LEB128 varints used by Protocol Buffers.
+
+use std::io::{self, BufRead, ErrorKind};
+
+/// The most bytes a `u64` takes as a varint.
+pub(crate) const MAX_LEN: usize = 10;
+
+/// Encode `value` into `buf`, returning the bytes used.
+pub(crate) fn encode(mut value: u64, buf: &mut [u8; MAX_LEN]) -> &[u8] {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            return &buf[..=len];
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Decode a varint, returning `None` if the reader is at EOF before its
+/// first byte.
+///
+/// Fails with `ErrorKind::UnexpectedEof` if the reader ends in the middle of
+/// the varint, and with `ErrorKind::InvalidData` if it doesn't fit a `u64`.
+pub(crate) fn decode<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Option<u64>> {
+    let mut value = 0_u64;
+    let mut len = 0;
+    loop {
+        let byte = match reader.fill_buf() {
+            Ok([]) if len == 0 => return Ok(None),
+            Ok([]) => {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("stream ended after {} bytes of a varint", len),
+                ))
+            }
+            Ok(buf) => buf[0],
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        reader.consume(1);
+
+        // The 10th byte only holds the top bit of a `u64`.
+        if len == MAX_LEN - 1 && byte > 1 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "varint doesn't fit in 64 bits",
+            ));
+        }
+        value |= u64::from(byte & 0x7f) << (7 * len);
+        len += 1;
+        if byte & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+}
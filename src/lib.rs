@@ -18,6 +18,13 @@
 //! # Methods
 //!
 //! - [`BufReadExt::read_while`] reads bytes based on a predicate, consumes bytes.
+//! - [`BufReadExt::peek_byte`] returns the next byte, doesn't consume bytes.
+//! - [`BufReadExt::peek`] returns the next `n` bytes, doesn't consume bytes.
+//! - [`BufReadExt::fill_exact`] copies the next bytes into a buffer, doesn't consume bytes.
+//! - [`BufReadExt::fill_be`] decodes the next bytes as big-endian, doesn't consume bytes.
+//! - [`BufReadExt::fill_until`] copies bytes through a delimiter, doesn't consume bytes.
+//! - [`BufReadExt::fill_while`] copies bytes based on a predicate, doesn't consume bytes.
+//! - [`BufReadExt::consume_prefix`] Skip a prefix if the stream starts with it.
 //! - [`BufReadExt::skip`] Skip the first `n` bytes.
 //! - [`BufReadExt::skip_until`] Skip bytes until the delimiter `byte` or EOF is reached.
 //! - [`BufReadExt::skip_while`] Skip bytes while a predicate is true.
@@ -28,8 +35,14 @@
 //! - [`WriteExt::write_le`] write bytes as little-endian to a writer.
 //! - [`WriteExt::write_ne`] write bytes using native endianness to a writer.
 //!
+//! [`BufReadExt::peek_byte`]: trait.BufReadExt.html#method.peek_byte
+//! [`BufReadExt::peek`]: trait.BufReadExt.html#method.peek
+//! [`BufReadExt::fill_exact`]: trait.BufReadExt.html#method.fill_exact
+//! [`BufReadExt::fill_be`]: trait.BufReadExt.html#method.fill_be
+//! [`BufReadExt::fill_until`]: trait.BufReadExt.html#method.fill_until
 //! [`BufReadExt::fill_while`]: trait.BufReadExt.html#method.fill_while
 //! [`BufReadExt::read_while`]: trait.BufReadExt.html#method.read_while
+//! [`BufReadExt::consume_prefix`]: trait.BufReadExt.html#method.consume_prefix
 //! [`BufReadExt::skip`]: trait.BufReadExt.html#method.skip
 //! [`BufReadExt::skip_until`]: trait.BufReadExt.html#method.skip_until
 //! [`BufReadExt::skip_while`]: trait.BufReadExt.html#method.skip_while
@@ -41,6 +54,37 @@
 //! [`WriteExt::write_ne`]: trait.WriteExt.html#method.write_ne
 //! [`consume`]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.consume
 //!
+//! # Features
+//!
+//! - `std` (default): the `std::io` extension traits `BufReadExt`, `ReadExt` and
+//!   `WriteExt`. Without it the crate is `no_std`, and `ReadBytes` and
+//!   `WriteBytes` convert values from and to byte slices.
+//! - `arrayvec`: implements `ByteSink` for `ArrayVec<u8, N>`, which stops the
+//!   `_capped` reading methods of `BufReadExt` once it's full.
+//! - `async-futures`: `AsyncBufReadExt` and `AsyncCheckpoint`, streaming
+//!   parsing over `futures`' `AsyncBufRead`.
+//! - `bytes`: `BufExt` and `BufMutExt`, endianness reads and writes for
+//!   `bytes::Buf` and `bytes::BufMut`, `BufScanExt`, scanning methods for
+//!   `bytes::Buf`, and `ByteSink` for `BytesMut`.
+//! - `chrono`: `EpochSeconds`, `EpochMillis` and `EpochNanos`, `chrono` times
+//!   encoded as `i64` offsets from the Unix epoch.
+//! - `embedded-io`: the `embedded` module, extension traits for the
+//!   `embedded-io` traits that work without `std`.
+//! - `heapless`: implements `ByteSink` for `heapless::Vec<u8, N>`, like
+//!   `arrayvec` does for `ArrayVec`.
+//! - `formats-mime`: the `formats::mime` module, a MIME type parser.
+//! - `nom`: `parse_with`, which runs `nom` streaming parsers over a `BufRead`.
+//! - `ryu`: `WriteExt::write_ascii_float` and its `f32` counterpart, which
+//!   write floats as their shortest decimal text.
+//! - `smallvec`: implements `ByteSink` for `SmallVec`, so the reading methods
+//!   of `BufReadExt` can append to one.
+//! - `tokio-codec`: `FixedCodec` and `LengthDelimitedValueCodec`, `tokio_util`
+//!   codecs built on `ReadBytes` and `WriteBytes`.
+//! - `tracing`: `Traced` and `TracedWriter`, which emit a `tracing` event for
+//!   the bytes read or written through them.
+//! - `winnow`: `parse_partial`, which runs `winnow` partial parsers over a
+//!   `BufRead`.
+//!
 //! # Todos
 //!
 //! - `AsyncRead` support.
@@ -50,6 +94,7 @@
 //! Read and write integers from IO streams with a chosen endianness:
 //!
 //! ```
+//! # #[cfg(feature = "std")] {
 //! use std::io::{Cursor, Seek, SeekFrom};
 //! use omnom::prelude::*;
 //!
@@ -61,29 +106,213 @@
 //! buf.seek(SeekFrom::Start(0)).unwrap();
 //! let num: u16 = buf.read_le().unwrap();
 //! assert_eq!(num, 12);
+//! # }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code, future_incompatible, rust_2018_idioms)]
 #![deny(missing_debug_implementations, nonstandard_style)]
 #![warn(missing_docs, missing_doc_code_examples, unreachable_pub)]
 
+// Declared first, so its macro is in scope in the modules below.
+#[macro_use]
+mod int_types;
+
+#[cfg(feature = "std")]
+mod accumulator;
+#[cfg(feature = "std")]
+mod ascii;
+#[cfg(feature = "async-futures")]
+mod async_buf_read_ext;
+#[cfg(feature = "async-futures")]
+mod async_checkpoint;
+#[cfg(feature = "std")]
+mod bits;
+#[cfg(feature = "std")]
+mod body_reader;
+#[cfg(feature = "std")]
+mod bool_style;
+#[cfg(feature = "std")]
+mod bounded_retry;
+#[cfg(feature = "bytes")]
+mod buf_ext;
+#[cfg(feature = "std")]
 mod buf_read_ext;
+#[cfg(feature = "std")]
+mod buffer_ahead;
+#[cfg(feature = "std")]
+mod buffer_too_small;
+mod byte_set;
+#[cfg(feature = "std")]
+mod byte_sink;
+#[cfg(feature = "std")]
+mod bytes_builder;
+#[cfg(feature = "std")]
+mod checksummed_frame;
+#[cfg(feature = "tokio-codec")]
+mod codec;
+#[cfg(feature = "std")]
+pub mod combinators;
+#[cfg(feature = "std")]
+mod crc32;
+#[cfg(feature = "std")]
+mod data_ready;
+#[cfg(any(feature = "nom", feature = "winnow"))]
+mod drive;
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+#[cfg(feature = "chrono")]
+mod epoch;
+mod fixed;
+#[cfg(feature = "std")]
+mod flush_guard;
+#[cfg(feature = "formats-mime")]
+pub mod formats;
+#[cfg(feature = "std")]
+mod from_stream;
+#[cfg(feature = "std")]
+mod latin1_decoder;
+#[cfg(feature = "std")]
+mod leb128;
+#[cfg(feature = "std")]
+mod limits;
+#[cfg(feature = "std")]
+mod lookahead;
+mod mac_addr;
+mod ms_guid;
+#[cfg(feature = "nom")]
+mod nom_parse;
+#[cfg(feature = "std")]
+mod normalize_newlines;
+mod parse_buf;
+mod parse_error;
+#[cfg(feature = "std")]
+mod parser;
+#[cfg(feature = "std")]
+mod partial_error;
+pub mod predicates;
 mod read_bytes;
+#[cfg(feature = "std")]
 mod read_ext;
+#[cfg(feature = "std")]
+mod seek_ext;
+#[cfg(feature = "std")]
+mod short_read;
+mod slice_ext;
+mod swap_bytes;
+#[cfg(feature = "std")]
+mod timestamp;
+#[cfg(feature = "std")]
+mod to_stream;
+#[cfg(feature = "tracing")]
+mod traced;
+mod uint;
+#[cfg(feature = "std")]
+mod unread;
+#[cfg(feature = "std")]
+mod utf8_validator;
+#[cfg(feature = "winnow")]
+mod winnow_parse;
 mod write_bytes;
+#[cfg(feature = "std")]
 mod write_ext;
 
-pub use buf_read_ext::BufReadExt;
+#[cfg(feature = "std")]
+pub use accumulator::{Accumulator, FeedStatus};
+#[cfg(feature = "async-futures")]
+pub use async_buf_read_ext::{AsyncBufReadExt, FillUntil, Peek, PeekByte, RecordStream};
+#[cfg(feature = "async-futures")]
+pub use async_checkpoint::AsyncCheckpoint;
+#[cfg(feature = "std")]
+pub use bits::{BitReader, BitWriter};
+#[cfg(feature = "std")]
+pub use body_reader::BodyReader;
+#[cfg(feature = "std")]
+pub use bool_style::BoolStyle;
+#[cfg(feature = "std")]
+pub use bounded_retry::BoundedRetry;
+#[cfg(feature = "bytes")]
+pub use buf_ext::{BufExt, BufMutExt, BufScanExt};
+#[cfg(feature = "std")]
+pub use buf_read_ext::{BufReadExt, ChunksExact, CopyEnd, LineResult, SkipOutcome, StopReason};
+#[cfg(feature = "std")]
+pub use buffer_ahead::BufferAhead;
+#[cfg(feature = "std")]
+pub use buffer_too_small::BufferTooSmall;
+pub use byte_set::ByteSet;
+#[cfg(feature = "std")]
+pub use byte_sink::{ByteSink, Utf8Sink};
+#[cfg(feature = "std")]
+pub use bytes_builder::BytesBuilder;
+#[cfg(feature = "std")]
+pub use checksummed_frame::{ChecksumMismatch, ChecksummedFrameReader, FrameWriter};
+#[cfg(feature = "tokio-codec")]
+pub use codec::{FixedCodec, LengthDelimitedValueCodec};
+#[cfg(feature = "std")]
+pub use data_ready::DataReady;
+#[cfg(feature = "chrono")]
+pub use epoch::{EpochMillis, EpochNanos, EpochSeconds};
+pub use fixed::{Q16_16, Q2_14, Q8_8};
+#[cfg(feature = "std")]
+pub use flush_guard::FlushGuard;
+#[cfg(feature = "std")]
+pub use from_stream::{FromStream, ReadStructExt};
+#[cfg(feature = "std")]
+pub use latin1_decoder::Latin1Decoder;
+#[cfg(feature = "std")]
+pub use limits::{LimitExceeded, LimitKind, Limits};
+#[cfg(feature = "std")]
+pub use lookahead::Lookahead;
+pub use mac_addr::MacAddr;
+pub use ms_guid::MsGuid;
+#[cfg(feature = "nom")]
+pub use nom_parse::parse_with;
+#[cfg(feature = "std")]
+pub use normalize_newlines::NormalizeNewlines;
+pub use parse_buf::ParseBuf;
+pub use parse_error::{ParseError, ParseErrorKind, ParseResultExt};
+#[cfg(feature = "std")]
+pub use parser::{Parser, ParserError};
+#[cfg(feature = "std")]
+pub use partial_error::PartialError;
 pub use read_bytes::ReadBytes;
+#[cfg(feature = "std")]
 pub use read_ext::ReadExt;
+#[cfg(feature = "std")]
+pub use seek_ext::{Mark, Placeholder, SeekExt};
+#[cfg(feature = "std")]
+pub use short_read::ShortRead;
+pub use slice_ext::SliceExt;
+pub use swap_bytes::SwapBytes;
+#[cfg(feature = "std")]
+pub use timestamp::Timestamp;
+#[cfg(feature = "std")]
+pub use to_stream::{ToStream, WriteStructExt};
+#[cfg(feature = "tracing")]
+pub use traced::{Traced, TracedWriter};
+pub use uint::{Int, UInt};
+#[cfg(feature = "std")]
+pub use unread::{PeekCursor, Unread};
+#[cfg(feature = "std")]
+pub use utf8_validator::Utf8Validator;
+#[cfg(feature = "winnow")]
+pub use winnow_parse::parse_partial;
 pub use write_bytes::WriteBytes;
-pub use write_ext::WriteExt;
+#[cfg(feature = "std")]
+pub use write_ext::{OverflowPolicy, WriteExt};
 
 /// The `omnom` prelude.
 pub mod prelude {
+    #[cfg(feature = "std")]
     pub use crate::BufReadExt;
     pub use crate::ReadBytes;
+    #[cfg(feature = "std")]
     pub use crate::ReadExt;
+    #[cfg(feature = "std")]
+    pub use crate::ReadStructExt;
     pub use crate::WriteBytes;
+    #[cfg(feature = "std")]
     pub use crate::WriteExt;
+    #[cfg(feature = "std")]
+    pub use crate::WriteStructExt;
 }
@@ -0,0 +1,214 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Caps on the memory that reads driven by a length from the stream may
+/// allocate.
+///
+/// Methods such as [`ReadExt::read_vec_prefixed_be_with_limits`] read a length
+/// from the stream and allocate memory for it up front. A corrupt or
+/// malicious length could make them allocate far more than the stream holds.
+/// `Limits` bounds both the size of a single allocation and the amount of
+/// elements, and the same `Limits` can be passed to every such method.
+///
+/// The defaults are generous but finite: 1 GiB per allocation and 64 Mi
+/// elements. [`Limits::unlimited`] opts out of both. A [`Parser`] also
+/// applies the maximum token size, 1 MiB by default, to the tokens it reads
+/// without knowing their length up front.
+///
+/// Exceeding a limit returns an error of the kind `ErrorKind::InvalidData`
+/// with a [`LimitExceeded`] inside, which can be recovered through
+/// `io::Error::get_ref`.
+///
+/// [`ReadExt::read_vec_prefixed_be_with_limits`]: trait.ReadExt.html#method.read_vec_prefixed_be_with_limits
+/// [`Limits::unlimited`]: #method.unlimited
+/// [`LimitExceeded`]: struct.LimitExceeded.html
+/// [`Parser`]: struct.Parser.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use omnom::prelude::*;
+/// use omnom::{LimitExceeded, LimitKind, Limits};
+///
+/// let limits = Limits::new().max_alloc(1024);
+/// let mut cursor = Cursor::new([0, 0, 1, 0]);
+/// let err = cursor
+///     .read_vec_prefixed_be_with_limits::<u32, u64>(limits)
+///     .unwrap_err();
+/// assert_eq!(err.to_string(), "2048 bytes exceed the maximum of 1024 bytes");
+///
+/// let err = err.get_ref().unwrap().downcast_ref::<LimitExceeded>().unwrap();
+/// assert_eq!(err.kind(), LimitKind::Alloc);
+/// assert_eq!((err.requested(), err.limit()), (2048, 1024));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    max_alloc: usize,
+    max_elements: usize,
+    max_token: usize,
+}
+
+impl Limits {
+    /// The default maximum size of a single allocation, 1 GiB.
+    pub const DEFAULT_MAX_ALLOC: usize = 1024 * 1024 * 1024;
+
+    /// The default maximum amount of elements, 64 Mi.
+    pub const DEFAULT_MAX_ELEMENTS: usize = 64 * 1024 * 1024;
+
+    /// The default maximum size of a token, 1 MiB.
+    pub const DEFAULT_MAX_TOKEN: usize = 1024 * 1024;
+
+    /// Create new limits with the default values.
+    pub const fn new() -> Self {
+        Self {
+            max_alloc: Self::DEFAULT_MAX_ALLOC,
+            max_elements: Self::DEFAULT_MAX_ELEMENTS,
+            max_token: Self::DEFAULT_MAX_TOKEN,
+        }
+    }
+
+    /// Create limits that allow any length.
+    ///
+    /// Only use this for trusted input.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_alloc: usize::MAX,
+            max_elements: usize::MAX,
+            max_token: usize::MAX,
+        }
+    }
+
+    /// Set the maximum size in bytes of a single allocation.
+    pub const fn max_alloc(mut self, bytes: usize) -> Self {
+        self.max_alloc = bytes;
+        self
+    }
+
+    /// Set the maximum amount of elements.
+    pub const fn max_elements(mut self, elements: usize) -> Self {
+        self.max_elements = elements;
+        self
+    }
+
+    /// Set the maximum size in bytes of a token.
+    pub const fn max_token(mut self, bytes: usize) -> Self {
+        self.max_token = bytes;
+        self
+    }
+
+    /// Returns the maximum size in bytes of a single allocation.
+    pub const fn alloc_limit(&self) -> usize {
+        self.max_alloc
+    }
+
+    /// Returns the maximum amount of elements.
+    pub const fn element_limit(&self) -> usize {
+        self.max_elements
+    }
+
+    /// Returns the maximum size in bytes of a token.
+    pub const fn token_limit(&self) -> usize {
+        self.max_token
+    }
+
+    /// Check that an allocation of `bytes` bytes is allowed.
+    pub(crate) fn check_alloc(&self, bytes: usize) -> Result<(), LimitExceeded> {
+        check(LimitKind::Alloc, bytes, self.max_alloc)
+    }
+
+    /// Check that `elements` elements are allowed.
+    pub(crate) fn check_elements(&self, elements: usize) -> Result<(), LimitExceeded> {
+        check(LimitKind::Elements, elements, self.max_elements)
+    }
+
+    /// Check that a token of `bytes` bytes is allowed.
+    pub(crate) fn check_token(&self, bytes: usize) -> Result<(), LimitExceeded> {
+        check(LimitKind::Token, bytes, self.max_token)
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(kind: LimitKind, requested: usize, limit: usize) -> Result<(), LimitExceeded> {
+    if requested > limit {
+        Err(LimitExceeded {
+            kind,
+            requested,
+            limit,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Which of the [`Limits`] was exceeded.
+///
+/// [`Limits`]: struct.Limits.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LimitKind {
+    /// The size in bytes of a single allocation.
+    Alloc,
+    /// The amount of elements.
+    Elements,
+    /// The size in bytes of a token.
+    Token,
+}
+
+/// An error for a length read from the stream that exceeds one of the
+/// [`Limits`].
+///
+/// [`Limits`]: struct.Limits.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+    kind: LimitKind,
+    requested: usize,
+    limit: usize,
+}
+
+impl LimitExceeded {
+    /// Returns which limit was exceeded.
+    pub fn kind(&self) -> LimitKind {
+        self.kind
+    }
+
+    /// Returns the amount of bytes or elements that was requested.
+    pub fn requested(&self) -> usize {
+        self.requested
+    }
+
+    /// Returns the limit that was exceeded.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.kind {
+            LimitKind::Alloc | LimitKind::Token => "bytes",
+            LimitKind::Elements => "elements",
+        };
+        write!(
+            f,
+            "{} {} exceed the maximum of {} {}",
+            self.requested, unit, self.limit, unit
+        )
+    }
+}
+
+impl Error for LimitExceeded {}
+
+impl From<LimitExceeded> for io::Error {
+    /// Wraps the `LimitExceeded` in an error of the kind
+    /// `ErrorKind::InvalidData`.
+    fn from(err: LimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Read};
+
+use crate::BufferAhead;
+
+/// The default capacity of a `Lookahead`'s buffer, if `K` is smaller.
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// A reader that keeps the next `K` bytes of a `BufRead` available.
+///
+/// A plain `BufRead` can only show the bytes of its current buffer, which may
+/// hold as little as a single byte at a refill boundary. `Lookahead` copies
+/// the underlying reader's bytes into its own buffer, and tops it up whenever
+/// fewer than `K` bytes are left. [`lookahead`] is then a plain slice of the
+/// next `K` bytes, or fewer only in the last `K` bytes of the stream.
+///
+/// `Lookahead` implements `BufRead` itself, so it can be consumed one byte at
+/// a time while looking ahead, and read from with the other extension traits.
+/// It also implements [`BufferAhead`], which grows the buffer as needed to
+/// look further ahead than `K` bytes.
+///
+/// [`lookahead`]: #method.lookahead
+/// [`BufferAhead`]: trait.BufferAhead.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufRead, BufReader};
+/// use omnom::Lookahead;
+///
+/// // An underlying buffer of 1 byte can't show more than 1 byte at a time.
+/// let reader = BufReader::with_capacity(1, &b"<!--x-->"[..]);
+/// let mut reader = Lookahead::<_, 4>::new(reader).unwrap();
+/// assert_eq!(reader.lookahead(), b"<!--");
+/// reader.consume(4);
+/// assert_eq!(reader.lookahead(), b"x-->");
+/// reader.consume(2);
+/// assert_eq!(reader.lookahead(), b"->");
+/// ```
+pub struct Lookahead<R, const K: usize> {
+    inner: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    end: usize,
+    /// An error from topping up the buffer in `consume`, returned by the next
+    /// call that can fail.
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead, const K: usize> Lookahead<R, K> {
+    /// Create a new `Lookahead`, filling it with the first `K` bytes of
+    /// `inner`.
+    pub fn new(inner: R) -> io::Result<Self> {
+        let mut reader = Self {
+            inner,
+            buf: vec![0; (2 * K).max(DEFAULT_CAPACITY)].into_boxed_slice(),
+            pos: 0,
+            end: 0,
+            error: None,
+        };
+        reader.top_up()?;
+        Ok(reader)
+    }
+
+    /// Returns the next `K` bytes of the stream, or fewer if the stream ends
+    /// before that.
+    ///
+    /// If an error occurred while topping up the buffer, this may hold fewer
+    /// bytes until the error has been returned by a read.
+    pub fn lookahead(&self) -> &[u8] {
+        &self.buf[self.pos..self.end.min(self.pos + K)]
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Any bytes buffered by the `Lookahead` are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Fill the buffer until it holds at least `K` bytes, or the underlying
+    /// reader is at EOF.
+    fn top_up(&mut self) -> io::Result<()> {
+        self.fill_to(K)
+    }
+
+    /// Fill the buffer until it holds at least `min` bytes, or the underlying
+    /// reader is at EOF, growing it if it can't hold them.
+    fn fill_to(&mut self, min: usize) -> io::Result<()> {
+        if self.end - self.pos >= min {
+            return Ok(());
+        }
+        if self.buf.len() < min {
+            let mut buf = vec![0; 2 * min].into_boxed_slice();
+            buf[..self.end - self.pos].copy_from_slice(&self.buf[self.pos..self.end]);
+            self.buf = buf;
+            self.end -= self.pos;
+            self.pos = 0;
+        }
+        // Only move the bytes to the front once there's no room for `min`
+        // bytes after them, so each byte is moved at most once.
+        if self.buf.len() - self.pos < min {
+            self.buf.copy_within(self.pos..self.end, 0);
+            self.end -= self.pos;
+            self.pos = 0;
+        }
+        while self.end - self.pos < min {
+            let available = match self.inner.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                break;
+            }
+            let len = available.len().min(self.buf.len() - self.end);
+            self.buf[self.end..self.end + len].copy_from_slice(&available[..len]);
+            self.inner.consume(len);
+            self.end += len;
+        }
+        Ok(())
+    }
+}
+
+impl<R, const K: usize> Lookahead<R, K> {
+    /// Returns `true` if `fill_buf` would return bytes without reading from
+    /// the underlying reader.
+    pub(crate) fn has_buffered(&self) -> bool {
+        self.error.is_none() && self.pos < self.end
+    }
+}
+
+impl<R: BufRead, const K: usize> Read for Lookahead<R, K> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead, const K: usize> BufRead for Lookahead<R, K> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.top_up()?;
+        Ok(&self.buf[self.pos..self.end])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.end);
+        if self.error.is_none() {
+            self.error = self.top_up().err();
+        }
+    }
+}
+
+impl<R: BufRead, const K: usize> BufferAhead for Lookahead<R, K> {
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.fill_to(n.max(K))?;
+        Ok(&self.buf[self.pos..self.end])
+    }
+}
+
+impl<R: fmt::Debug, const K: usize> fmt::Debug for Lookahead<R, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lookahead")
+            .field("inner", &self.inner)
+            .field(
+                "lookahead",
+                &&self.buf[self.pos..self.end.min(self.pos + K)],
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,241 @@
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::{ParseError, ParseErrorKind, ReadBytes, WriteBytes};
+
+/// An EUI-48 address, better known as a MAC address.
+///
+/// A MAC address is a sequence of 6 bytes rather than a number, so it has no
+/// endianness: the big endian, little endian and native endian variants of
+/// `ReadBytes` and `WriteBytes` all read and write the bytes in order.
+///
+/// It formats as lowercase hex separated by colons, such as
+/// `aa:bb:cc:dd:ee:ff`, or separated by hyphens with the alternate flag
+/// (`{:#}`). It parses from either form, in either case.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{MacAddr, ReadBytes};
+///
+/// let (mac, _) = MacAddr::from_be_slice(&[0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7]).unwrap();
+/// assert_eq!(mac.to_string(), "00:1b:44:11:3a:b7");
+/// assert_eq!(format!("{:#}", mac), "00-1b-44-11-3a-b7");
+/// assert_eq!("00-1B-44-11-3A-B7".parse::<MacAddr>().unwrap(), mac);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddr(pub [u8; 6]);
+
+/// The length of a MAC address in text form.
+const TEXT_LEN: usize = 17;
+
+impl MacAddr {
+    /// The broadcast address, `ff:ff:ff:ff:ff:ff`.
+    pub const BROADCAST: Self = Self([0xff; 6]);
+
+    /// Returns the bytes of the address.
+    pub const fn octets(self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Returns `true` if this is the broadcast address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::MacAddr;
+    ///
+    /// assert!(MacAddr::BROADCAST.is_broadcast());
+    /// assert!(!MacAddr([0xff, 0xff, 0xff, 0xff, 0xff, 0xfe]).is_broadcast());
+    /// ```
+    pub fn is_broadcast(self) -> bool {
+        self == Self::BROADCAST
+    }
+
+    /// Returns `true` if this is a group address, which is the case when the
+    /// lowest bit of the first byte is set.
+    ///
+    /// The broadcast address is a multicast address too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::MacAddr;
+    ///
+    /// assert!(MacAddr([0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb]).is_multicast());
+    /// assert!(!MacAddr([0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7]).is_multicast());
+    /// ```
+    pub fn is_multicast(self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Returns `true` if this is an individual address, the opposite of
+    /// [`is_multicast`].
+    ///
+    /// [`is_multicast`]: #method.is_multicast
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::MacAddr;
+    ///
+    /// assert!(MacAddr([0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7]).is_unicast());
+    /// ```
+    pub fn is_unicast(self) -> bool {
+        !self.is_multicast()
+    }
+
+    /// Returns `true` if the address was assigned locally rather than by the
+    /// manufacturer, which is the case when the second-lowest bit of the first
+    /// byte is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::MacAddr;
+    ///
+    /// assert!(MacAddr([0x02, 0x42, 0xac, 0x11, 0x00, 0x02]).is_local());
+    /// assert!(!MacAddr([0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7]).is_local());
+    /// ```
+    pub fn is_local(self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+impl From<MacAddr> for [u8; 6] {
+    fn from(mac: MacAddr) -> Self {
+        mac.0
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if f.alternate() { '-' } else { ':' };
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                fmt::Write::write_char(f, separator)?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = ParseError;
+
+    /// Parse a MAC address separated by either colons or hyphens.
+    ///
+    /// The error holds the offset of the first byte that isn't part of a
+    /// valid address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        let mut octets = [0; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            let start = i * 3;
+            if i > 0 {
+                // The first separator decides which one the others must be.
+                match bytes.get(start - 1) {
+                    Some(&byte) if (byte == b':' || byte == b'-') && byte == bytes[2] => {}
+                    Some(_) => return Err(invalid(start - 1, ParseErrorKind::Mismatch)),
+                    None => return Err(invalid(start - 1, ParseErrorKind::UnexpectedEof)),
+                }
+            }
+            for offset in start..start + 2 {
+                let digit = match bytes.get(offset) {
+                    Some(&byte) => {
+                        hex_value(byte).ok_or_else(|| invalid(offset, ParseErrorKind::Mismatch))?
+                    }
+                    None => return Err(invalid(offset, ParseErrorKind::UnexpectedEof)),
+                };
+                *octet = *octet << 4 | digit;
+            }
+        }
+        if bytes.len() > TEXT_LEN {
+            return Err(invalid(TEXT_LEN, ParseErrorKind::Mismatch));
+        }
+        Ok(Self(octets))
+    }
+}
+
+/// Returns the value of an ASCII hex digit.
+pub(crate) fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+fn invalid(offset: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError::new(offset, kind).with_context("invalid MAC address")
+}
+
+impl ReadBytes for MacAddr {
+    type Bytes = [u8; 6];
+
+    fn from_be_array(bytes: Self::Bytes) -> Self {
+        Self(bytes)
+    }
+
+    fn from_le_array(bytes: Self::Bytes) -> Self {
+        Self(bytes)
+    }
+
+    fn from_ne_array(bytes: Self::Bytes) -> Self {
+        Self(bytes)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; 6];
+        reader.read_exact(&mut buf)?;
+        Ok(Self(buf))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_be_bytes(reader)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_be_bytes(reader)
+    }
+}
+
+impl WriteBytes for MacAddr {
+    type Bytes = [u8; 6];
+
+    fn to_be_array(&self) -> Self::Bytes {
+        self.0
+    }
+
+    fn to_le_array(&self) -> Self::Bytes {
+        self.0
+    }
+
+    fn to_ne_array(&self) -> Self::Bytes {
+        self.0
+    }
+
+    #[cfg(feature = "std")]
+    fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.0)?;
+        Ok(self.0.len())
+    }
+
+    #[cfg(feature = "std")]
+    fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.write_be_bytes(writer)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.write_be_bytes(writer)
+    }
+}
@@ -0,0 +1,163 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::{ReadBytes, WriteBytes};
+
+/// A GUID as stored by Microsoft formats such as NTFS, MSI and the registry.
+///
+/// A GUID is a structure of a `u32`, two `u16`s and 8 bytes. Microsoft
+/// formats store those fields little endian, so only the first three are
+/// byte-swapped compared to the canonical RFC 4122 byte order. Reading and
+/// writing little endian uses that mixed layout, and big endian uses the
+/// canonical byte order. Native endian uses the one matching the target.
+///
+/// The wrapped bytes are always in canonical order, which is the order they're
+/// displayed in: uppercase hex in braces, such as
+/// `{00112233-4455-6677-8899-AABBCCDDEEFF}`.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{MsGuid, ReadBytes};
+///
+/// let bytes = [
+///     0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66,
+///     0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+/// ];
+/// let (guid, _) = MsGuid::from_le_slice(&bytes).unwrap();
+/// assert_eq!(guid.to_string(), "{00112233-4455-6677-8899-AABBCCDDEEFF}");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MsGuid(pub [u8; 16]);
+
+impl MsGuid {
+    /// Create a GUID from its fields, as they're usually written in C.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::MsGuid;
+    ///
+    /// let guid = MsGuid::from_fields(
+    ///     0x21ec2020,
+    ///     0x3aea,
+    ///     0x1069,
+    ///     [0xa2, 0xdd, 0x08, 0x00, 0x2b, 0x30, 0x30, 0x9d],
+    /// );
+    /// assert_eq!(guid.to_string(), "{21EC2020-3AEA-1069-A2DD-08002B30309D}");
+    /// ```
+    pub const fn from_fields(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+        let [a, b, c, d] = data1.to_be_bytes();
+        let [e, f] = data2.to_be_bytes();
+        let [g, h] = data3.to_be_bytes();
+        let [i, j, k, l, m, n, o, p] = data4;
+        Self([a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p])
+    }
+
+    /// Returns the bytes of the GUID in canonical order.
+    pub const fn to_bytes(self) -> [u8; 16] {
+        self.0
+    }
+}
+
+/// Convert between the canonical and the Microsoft byte order, in either
+/// direction.
+fn swap_fields(mut bytes: [u8; 16]) -> [u8; 16] {
+    bytes[0..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    bytes
+}
+
+impl fmt::Display for MsGuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, byte) in self.0.iter().enumerate() {
+            if let 4 | 6 | 8 | 10 = i {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl ReadBytes for MsGuid {
+    type Bytes = [u8; 16];
+
+    fn from_be_array(bytes: Self::Bytes) -> Self {
+        Self(bytes)
+    }
+
+    fn from_le_array(bytes: Self::Bytes) -> Self {
+        Self(swap_fields(bytes))
+    }
+
+    fn from_ne_array(bytes: Self::Bytes) -> Self {
+        if cfg!(target_endian = "big") {
+            Self::from_be_array(bytes)
+        } else {
+            Self::from_le_array(bytes)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; 16];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_be_array(buf))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; 16];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_le_array(buf))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; 16];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_ne_array(buf))
+    }
+}
+
+impl WriteBytes for MsGuid {
+    type Bytes = [u8; 16];
+
+    fn to_be_array(&self) -> Self::Bytes {
+        self.0
+    }
+
+    fn to_le_array(&self) -> Self::Bytes {
+        swap_fields(self.0)
+    }
+
+    fn to_ne_array(&self) -> Self::Bytes {
+        if cfg!(target_endian = "big") {
+            self.to_be_array()
+        } else {
+            self.to_le_array()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_be_array())?;
+        Ok(16)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_le_array())?;
+        Ok(16)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_ne_array())?;
+        Ok(16)
+    }
+}
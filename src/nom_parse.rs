@@ -0,0 +1,67 @@
+use std::io::{self, BufRead};
+
+use nom::IResult;
+
+use crate::drive::{drive, Outcome};
+use crate::{ParseError, ParseErrorKind};
+
+/// Run a `nom` streaming parser over a `BufRead`.
+///
+/// The parser is first run on the reader's buffered bytes. While it returns
+/// `Err::Incomplete`, the buffered bytes are consumed into an accumulation
+/// buffer and the parser is retried with the next chunk appended, so a value
+/// may span any number of `fill_buf` calls. On success exactly the bytes the
+/// parser used are consumed.
+///
+/// `Err::Error` and `Err::Failure` are returned as `ErrorKind::InvalidData`
+/// wrapping a [`ParseError`] of kind `ParseErrorKind::Mismatch`. Its offset
+/// counts from the reader's position when `parse_with` was called, and its
+/// source is the `nom` error, with the offset in place of the input.
+/// Running out of input while the parser is incomplete returns
+/// `ErrorKind::UnexpectedEof`.
+///
+/// A `BufRead` can't take bytes back once they're consumed. So if the parser
+/// fails, or succeeds using fewer bytes than an earlier attempt reported as
+/// incomplete, the bytes moved into the accumulation buffer stay consumed:
+/// the reader is left at the start of the last chunk the parser saw. If the
+/// value fits within a single `fill_buf` call nothing is consumed on failure.
+///
+/// [`ParseError`]: struct.ParseError.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufReader, Read};
+/// use nom::number::streaming::be_u16;
+///
+/// let mut reader = BufReader::with_capacity(1, &[0, 12, 1][..]);
+/// let num = omnom::parse_with(&mut reader, |i| be_u16(i)).unwrap();
+/// assert_eq!(num, 12);
+///
+/// let mut rest = vec![];
+/// reader.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, [1]);
+/// ```
+pub fn parse_with<R, O, P>(reader: &mut R, mut parser: P) -> io::Result<O>
+where
+    R: BufRead + ?Sized,
+    P: FnMut(&[u8]) -> IResult<&[u8], O>,
+{
+    drive(reader, |input| attempt(&mut parser, input))
+}
+
+fn attempt<O, P>(parser: &mut P, input: &[u8]) -> Outcome<O>
+where
+    P: FnMut(&[u8]) -> IResult<&[u8], O>,
+{
+    match parser(input) {
+        Ok((rest, value)) => Outcome::Done(value, input.len() - rest.len()),
+        Err(nom::Err::Incomplete(_)) => Outcome::Incomplete,
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            let offset = input.len() - e.input.len();
+            let err = ParseError::new(offset, ParseErrorKind::Mismatch)
+                .with_source(nom::error::Error::new(offset, e.code));
+            Outcome::Failed(err)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::io::{self, BufRead, Read};
+
+/// A reader that turns `\r\n` and lone `\r` into `\n`.
+///
+/// This is for feeding text with Windows, classic Mac OS or Unix line
+/// endings to a parser that only expects `\n`. All other bytes are passed
+/// through as they are.
+///
+/// A `\r` is written as `\n` straight away, and a `\n` right after it is
+/// dropped, also if it's only returned by the next `fill_buf` of the
+/// underlying reader. Since that changes the amount of bytes, the rewritten
+/// bytes are copied into a buffer of the `NormalizeNewlines` itself, which
+/// `fill_buf` and `consume` work on.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::BufRead;
+/// use omnom::NormalizeNewlines;
+///
+/// let reader = NormalizeNewlines::new(&b"dos\r\nmac\runix\n"[..]);
+/// let lines: Vec<_> = reader.lines().map(Result::unwrap).collect();
+/// assert_eq!(lines, ["dos", "mac", "unix"]);
+/// ```
+#[derive(Debug)]
+pub struct NormalizeNewlines<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    /// Whether the last byte read was a `\r`, so a `\n` that follows it is
+    /// part of the same newline.
+    after_cr: bool,
+}
+
+impl<R> NormalizeNewlines<R> {
+    /// Create a new `NormalizeNewlines`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            after_cr: false,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Bytes that were read from it but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Read for NormalizeNewlines<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for NormalizeNewlines<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        // A chunk that's only the `\n` of a `\r\n` rewrites to nothing, so
+        // keep reading until there's a byte to return or the stream ends.
+        while self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            let available = self.inner.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            for &byte in available {
+                match byte {
+                    b'\n' if self.after_cr => {}
+                    b'\r' => self.buf.push(b'\n'),
+                    byte => self.buf.push(byte),
+                }
+                self.after_cr = byte == b'\r';
+            }
+            let len = available.len();
+            self.inner.consume(len);
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{ParseError, ParseErrorKind, ReadBytes};
+
+/// A cursor over an in-memory slice that tracks its position.
+///
+/// All methods that return bytes return subslices of the original input, so
+/// parsing with a `ParseBuf` never copies. Failures are reported as
+/// [`ParseError`]s carrying the offset at which they happened. A failed call
+/// leaves the position untouched.
+///
+/// [`ParseError`]: struct.ParseError.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::ParseBuf;
+///
+/// let mut buf = ParseBuf::new(b"\x00\x05hello world");
+/// let len: u16 = buf.read_be().unwrap();
+/// assert_eq!(buf.take(len as usize).unwrap(), b"hello");
+/// buf.expect(b" ").unwrap();
+/// assert_eq!(buf.remaining(), b"world");
+/// assert_eq!(buf.pos(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseBuf<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ParseBuf<'a> {
+    /// Create a new instance positioned at the start of `input`.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// Returns the current offset into the input.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the bytes that haven't been parsed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.input[self.pos..]
+    }
+
+    /// Returns `true` if the whole input has been parsed.
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    /// Take the next `n` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(b"lorem");
+    /// assert_eq!(buf.take(3).unwrap(), b"lor");
+    /// assert!(buf.take(3).is_err());
+    /// assert_eq!(buf.take(2).unwrap(), b"em");
+    /// ```
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.remaining().len() < n {
+            return Err(self.error_kind(ParseErrorKind::UnexpectedEof));
+        }
+        Ok(self.advance(n))
+    }
+
+    /// Read bytes as big endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(&[0, 12]);
+    /// assert_eq!(buf.read_be::<u16>().unwrap(), 12);
+    /// ```
+    pub fn read_be<B: ReadBytes>(&mut self) -> Result<B, ParseError> {
+        let (value, len) = B::from_be_slice(self.remaining())
+            .ok_or_else(|| self.error_kind(ParseErrorKind::UnexpectedEof))?;
+        self.advance(len);
+        Ok(value)
+    }
+
+    /// Read bytes as little endian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(&[12, 0]);
+    /// assert_eq!(buf.read_le::<u16>().unwrap(), 12);
+    /// ```
+    pub fn read_le<B: ReadBytes>(&mut self) -> Result<B, ParseError> {
+        let (value, len) = B::from_le_slice(self.remaining())
+            .ok_or_else(|| self.error_kind(ParseErrorKind::UnexpectedEof))?;
+        self.advance(len);
+        Ok(value)
+    }
+
+    /// Read bytes using native endianness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// let mut buf = ParseBuf::new(&bytes);
+    /// assert_eq!(buf.read_ne::<u16>().unwrap(), 12);
+    /// ```
+    pub fn read_ne<B: ReadBytes>(&mut self) -> Result<B, ParseError> {
+        let (value, len) = B::from_ne_slice(self.remaining())
+            .ok_or_else(|| self.error_kind(ParseErrorKind::UnexpectedEof))?;
+        self.advance(len);
+        Ok(value)
+    }
+
+    /// Skip `expected` if the input continues with it.
+    ///
+    /// Fails with `ParseErrorKind::Mismatch` if the input continues with
+    /// other bytes, and `ParseErrorKind::UnexpectedEof` if it ends first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(b"GET /");
+    /// assert!(buf.expect(b"POST").is_err());
+    /// buf.expect(b"GET ").unwrap();
+    /// assert_eq!(buf.remaining(), b"/");
+    /// ```
+    pub fn expect(&mut self, expected: &[u8]) -> Result<(), ParseError> {
+        let remaining = self.remaining();
+        if remaining.starts_with(expected) {
+            self.advance(expected.len());
+            Ok(())
+        } else if expected.starts_with(remaining) {
+            Err(self.error_kind(ParseErrorKind::UnexpectedEof))
+        } else {
+            Err(self.error_kind(ParseErrorKind::Mismatch))
+        }
+    }
+
+    /// Read bytes until the delimiter `byte` or the end of the input is
+    /// reached.
+    ///
+    /// Returns the bytes read, including the delimiter if found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(b"lorem-ipsum");
+    /// assert_eq!(buf.read_until(b'-'), b"lorem-");
+    /// assert_eq!(buf.read_until(b'-'), b"ipsum");
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn read_until(&mut self, byte: u8) -> &'a [u8] {
+        let len = match memchr::memchr(byte, self.remaining()) {
+            Some(i) => i + 1,
+            None => self.remaining().len(),
+        };
+        self.advance(len)
+    }
+
+    /// Create an error with a custom message at the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ParseBuf;
+    ///
+    /// let mut buf = ParseBuf::new(&[0xff, 0x07]);
+    /// buf.take(1).unwrap();
+    /// let err = buf.error("unknown record type");
+    /// assert_eq!(err.offset(), Some(1));
+    /// assert_eq!(err.to_string(), "unknown record type at byte 1");
+    /// ```
+    pub fn error(&self, msg: &'static str) -> ParseError {
+        self.error_kind(ParseErrorKind::Message(msg))
+    }
+
+    fn error_kind(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(self.pos, kind)
+    }
+
+    fn advance(&mut self, n: usize) -> &'a [u8] {
+        let bytes = &self.input[self.pos..self.pos + n];
+        self.pos += n;
+        bytes
+    }
+}
@@ -0,0 +1,251 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+
+/// An error produced while parsing, along with where it happened and what
+/// the parser was doing.
+///
+/// Every part is optional: an offset is known when parsing from a
+/// [`ParseBuf`], and can be attached to other failures together with a context
+/// string through [`ParseResultExt`].
+///
+/// With the `std` feature a `ParseError` converts to and from `io::Error`
+/// losslessly: converting it to an `io::Error` wraps it, and converting that
+/// `io::Error` back unwraps it again. This means parsers can keep returning
+/// `io::Result`, and callers can downcast to recover the details.
+///
+/// [`ParseBuf`]: struct.ParseBuf.html
+/// [`ParseResultExt`]: trait.ParseResultExt.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{ParseBuf, ParseErrorKind};
+///
+/// let mut buf = ParseBuf::new(&[0, 1, 2]);
+/// buf.take(2).unwrap();
+/// let err = buf.read_be::<u16>().unwrap_err();
+/// assert_eq!(err.offset(), Some(2));
+/// assert_eq!(err.kind(), &ParseErrorKind::UnexpectedEof);
+/// assert_eq!(err.to_string(), "unexpected end of file at byte 2");
+/// ```
+#[derive(Debug)]
+pub struct ParseError {
+    offset: Option<usize>,
+    context: Option<&'static str>,
+    kind: ParseErrorKind,
+    #[cfg(feature = "std")]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+/// The kind of a [`ParseError`].
+///
+/// [`ParseError`]: struct.ParseError.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input ended before the parser was done with it.
+    UnexpectedEof,
+    /// The input didn't match the bytes the parser expected.
+    Mismatch,
+    /// The input exceeded a length limit set by the caller.
+    LimitExceeded,
+    /// A parser-defined error.
+    Message(&'static str),
+    /// An I/O error of the given kind, available through the error's
+    /// `source`.
+    #[cfg(feature = "std")]
+    Io(io::ErrorKind),
+}
+
+impl ParseError {
+    /// Create a new error at byte `offset` of the input.
+    pub fn new(offset: usize, kind: ParseErrorKind) -> Self {
+        Self {
+            offset: Some(offset),
+            ..Self::from(kind)
+        }
+    }
+
+    /// Returns the offset in the input at which the error occurred, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns what the parser was doing when the error occurred, if known.
+    pub fn context(&self) -> Option<&'static str> {
+        self.context
+    }
+
+    /// Returns the kind of error.
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Sets the offset at which the error occurred.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets what the parser was doing when the error occurred.
+    ///
+    /// This replaces any context set earlier.
+    pub fn with_context(mut self, context: &'static str) -> Self {
+        self.context = Some(context);
+        self
+    }
+}
+
+impl From<ParseErrorKind> for ParseError {
+    fn from(kind: ParseErrorKind) -> Self {
+        Self {
+            offset: None,
+            context: None,
+            kind,
+            #[cfg(feature = "std")]
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEof => f.write_str("unexpected end of file"),
+            ParseErrorKind::Mismatch => f.write_str("unexpected bytes"),
+            ParseErrorKind::LimitExceeded => f.write_str("length limit exceeded"),
+            ParseErrorKind::Message(msg) => f.write_str(msg),
+            #[cfg(feature = "std")]
+            ParseErrorKind::Io(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.context, self.offset) {
+            (Some(context), Some(offset)) => {
+                write!(f, "{} at byte {}: {}", context, offset, self.kind)
+            }
+            (Some(context), None) => write!(f, "{}: {}", context, self.kind),
+            (None, Some(offset)) => write!(f, "{} at byte {}", self.kind, offset),
+            (None, None) => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParseError {
+    /// Sets the underlying error that caused this one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use omnom::{ParseError, ParseErrorKind};
+    ///
+    /// let cause = "x".parse::<u8>().unwrap_err();
+    /// let err = ParseError::new(4, ParseErrorKind::Message("invalid port")).with_source(cause);
+    /// assert_eq!(err.source().unwrap().to_string(), "invalid digit found in string");
+    /// ```
+    pub fn with_source<E>(mut self, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.source = Some(source.into());
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ParseError {
+    /// Unwraps an `io::Error` created from a `ParseError`, or wraps any other
+    /// `io::Error` as `ParseErrorKind::Io`.
+    fn from(err: io::Error) -> Self {
+        if matches!(err.get_ref(), Some(inner) if inner.is::<ParseError>()) {
+            let inner = err.into_inner().expect("io::Error has an inner error");
+            return *inner.downcast().expect("inner error is a ParseError");
+        }
+        Self::from(ParseErrorKind::Io(err.kind())).with_source(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for io::Error {
+    /// Wraps the `ParseError`, so it can be recovered through
+    /// `io::Error::into_inner` or `io::Error::get_ref`.
+    fn from(err: ParseError) -> Self {
+        let kind = match err.kind {
+            ParseErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            ParseErrorKind::Io(kind) => kind,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+/// Extend `Result` with methods to annotate errors as [`ParseError`]s.
+///
+/// This works for any error that converts into a `ParseError`, which includes
+/// `io::Error` with the `std` feature.
+///
+/// [`ParseError`]: struct.ParseError.html
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use std::io::{self, Cursor};
+/// use omnom::prelude::*;
+/// use omnom::{ParseError, ParseResultExt};
+///
+/// let mut reader = Cursor::new(vec![0; 134]);
+/// reader.set_position(132);
+///
+/// let err = reader
+///     .read_be::<u32>()
+///     .with_context(132, "reading chunk length")
+///     .unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "reading chunk length at byte 132: unexpected end of file",
+/// );
+///
+/// // Passing the error on as an `io::Error` keeps the details.
+/// let err = io::Error::from(err);
+/// let err = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+/// assert_eq!(err.offset(), Some(132));
+/// # }
+/// ```
+pub trait ParseResultExt<T> {
+    /// Annotate an error with what the parser was doing.
+    ///
+    /// This replaces any context set earlier, and keeps the offset.
+    fn context(self, context: &'static str) -> Result<T, ParseError>;
+
+    /// Annotate an error with the offset at which it happened and what the
+    /// parser was doing.
+    ///
+    /// This replaces any offset and context set earlier.
+    fn with_context(self, offset: usize, context: &'static str) -> Result<T, ParseError>;
+}
+
+impl<T, E: Into<ParseError>> ParseResultExt<T> for Result<T, E> {
+    fn context(self, context: &'static str) -> Result<T, ParseError> {
+        self.map_err(|e| e.into().with_context(context))
+    }
+
+    fn with_context(self, offset: usize, context: &'static str) -> Result<T, ParseError> {
+        self.map_err(|e| e.into().with_offset(offset).with_context(context))
+    }
+}
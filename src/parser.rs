@@ -0,0 +1,370 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Read};
+
+use crate::{Limits, ReadExt};
+
+/// A reader that keeps track of where it is and what it's parsing.
+///
+/// `Parser` brings together what a parser built on this crate usually needs.
+/// It implements `BufRead`, so all methods of [`BufReadExt`], [`ReadExt`] and
+/// the other extension traits work on it, and it counts the bytes consumed
+/// through it, and optionally the lines and columns. [`context`] names what
+/// the parser is doing, and errors returned from it become a
+/// [`ParserError`] that reports the names of all enclosing contexts and the
+/// position of the failure.
+///
+/// The parser's [`Limits`] are applied by its own length-driven methods,
+/// [`read_bytes`] and [`read_token`], and [`limits`] returns them to be
+/// passed to the `_with_limits` methods of `ReadExt`.
+///
+/// [`BufReadExt`]: trait.BufReadExt.html
+/// [`ReadExt`]: trait.ReadExt.html
+/// [`context`]: #method.context
+/// [`ParserError`]: struct.ParserError.html
+/// [`Limits`]: struct.Limits.html
+/// [`read_bytes`]: #method.read_bytes
+/// [`read_token`]: #method.read_token
+/// [`limits`]: #method.limits
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, ErrorKind};
+/// use omnom::prelude::*;
+/// use omnom::Parser;
+///
+/// let mut p = Parser::new(&b"\x00\x03abc\x00\x09ab"[..]);
+/// let mut strings = vec![];
+/// let err = loop {
+///     let res = p.context("string", |p| -> io::Result<String> {
+///         let len: u16 = p.context("length", |p| p.read_be())?;
+///         let bytes = p.read_bytes(usize::from(len))?;
+///         String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+///     });
+///     match res {
+///         Ok(s) => strings.push(s),
+///         Err(e) => break e,
+///     }
+/// };
+/// assert_eq!(strings, ["abc"]);
+/// assert_eq!(err.contexts(), ["string"]);
+/// assert_eq!(err.offset(), 9);
+/// assert_eq!(err.to_string(), "in string at byte 9: expected 9 bytes, found 2");
+/// ```
+#[derive(Debug)]
+pub struct Parser<R> {
+    inner: R,
+    offset: u64,
+    /// The line and column of the next byte, if lines are tracked.
+    line_column: Option<(u64, u64)>,
+    contexts: Vec<&'static str>,
+    limits: Limits,
+}
+
+impl<R: BufRead> Parser<R> {
+    /// Create a new `Parser` with the default [`Limits`], which doesn't
+    /// track lines.
+    ///
+    /// [`Limits`]: struct.Limits.html
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            line_column: None,
+            contexts: Vec::new(),
+            limits: Limits::new(),
+        }
+    }
+
+    /// Set the limits to apply to length-driven reads.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Track the line and column of the position, for text formats.
+    ///
+    /// Lines are separated by `\n`, and columns count bytes. Both start at 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::BufRead;
+    /// use omnom::prelude::*;
+    /// use omnom::Parser;
+    ///
+    /// let mut p = Parser::new(&b"key = 1\nvalue"[..]).track_lines();
+    /// p.read_until(b'\n', &mut vec![]).unwrap();
+    /// p.read_be::<u16>().unwrap();
+    /// assert_eq!(p.line_column(), Some((2, 3)));
+    /// ```
+    pub fn track_lines(mut self) -> Self {
+        self.line_column = Some((1, 1));
+        self
+    }
+
+    /// Returns the amount of bytes consumed through the parser.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the line and column of the next byte, if lines are tracked.
+    pub fn line_column(&self) -> Option<(u64, u64)> {
+        self.line_column
+    }
+
+    /// Returns the limits applied to length-driven reads.
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Run `f` as a part of the input called `name`.
+    ///
+    /// If `f` fails, its error becomes a [`ParserError`] that records the
+    /// position of the parser and the names of this and all enclosing
+    /// contexts. Inner contexts return `ParserError`s, which can be passed
+    /// on with `?` as either a `ParserError` or an `io::Error`; the outer
+    /// contexts return them as they are.
+    ///
+    /// [`ParserError`]: struct.ParserError.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use omnom::prelude::*;
+    /// use omnom::Parser;
+    ///
+    /// let mut p = Parser::new(&b"\x00\x00\x00"[..]);
+    /// let err = p
+    ///     .context("header", |p| -> io::Result<()> {
+    ///         p.context("magic", |p| p.read_be::<u16>())?;
+    ///         p.context("version", |p| p.read_be::<u16>())?;
+    ///         Ok(())
+    ///     })
+    ///     .unwrap_err();
+    /// assert_eq!(err.contexts(), ["header", "version"]);
+    /// assert_eq!(err.offset(), 3);
+    /// ```
+    pub fn context<T, E, F>(&mut self, name: &'static str, f: F) -> Result<T, ParserError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: Into<io::Error>,
+    {
+        self.contexts.push(name);
+        let res = f(self).map_err(|e| self.error(e.into()));
+        self.contexts.pop();
+        res
+    }
+
+    /// Read exactly `len` bytes.
+    ///
+    /// Fails with a [`LimitExceeded`] if `len` exceeds the allocation limit,
+    /// before anything is allocated or read, and with `ErrorKind::UnexpectedEof`
+    /// and a [`ShortRead`] if the stream ends first.
+    ///
+    /// [`LimitExceeded`]: struct.LimitExceeded.html
+    /// [`ShortRead`]: struct.ShortRead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::{Limits, Parser};
+    ///
+    /// let limits = Limits::new().max_alloc(4);
+    /// let mut p = Parser::new(&b"\x89PNG\r\n"[..]).with_limits(limits);
+    /// assert_eq!(p.read_bytes(4).unwrap(), b"\x89PNG");
+    /// assert!(p.read_bytes(5).is_err());
+    /// assert!(p.read_bytes(3).is_err());
+    /// ```
+    pub fn read_bytes(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let limits = self.limits;
+        self.read_exact_vec_with_limits(len, limits)
+    }
+
+    /// Read bytes while the predicate is true.
+    ///
+    /// Fails with a [`LimitExceeded`] once more bytes than the token limit
+    /// are known to match. The bytes of the token read until then stay
+    /// consumed.
+    ///
+    /// [`LimitExceeded`]: struct.LimitExceeded.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::{Limits, Parser};
+    ///
+    /// let limits = Limits::new().max_token(4);
+    /// let mut p = Parser::new(&b"GET /index.html"[..]).with_limits(limits);
+    /// assert_eq!(p.read_token(|b| b != b' ').unwrap(), b"GET");
+    /// p.read_token(|b| b == b' ').unwrap();
+    /// assert!(p.read_token(|b| b != b' ').is_err());
+    /// ```
+    pub fn read_token<P>(&mut self, mut predicate: P) -> io::Result<Vec<u8>>
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let limits = self.limits;
+        let mut token = Vec::new();
+        loop {
+            let available = match self.fill_buf() {
+                Ok(b) => b,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let len = available
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(available.len());
+            let done = len < available.len() || available.is_empty();
+            limits.check_token(token.len() + len)?;
+            token.extend_from_slice(&available[..len]);
+            self.consume(len);
+            if done {
+                return Ok(token);
+            }
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Turn `err` into a `ParserError` at the current position, unless it
+    /// already is one.
+    fn error(&self, err: io::Error) -> ParserError {
+        if matches!(err.get_ref(), Some(inner) if inner.is::<ParserError>()) {
+            let inner = err.into_inner().expect("io::Error has an inner error");
+            return *inner.downcast().expect("inner error is a ParserError");
+        }
+        ParserError {
+            offset: self.offset,
+            line_column: self.line_column,
+            contexts: self.contexts.clone(),
+            source: err,
+        }
+    }
+}
+
+impl<R: BufRead> Read for Parser<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Parser<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some((line, column)) = self.line_column.as_mut().filter(|_| amt > 0) {
+            // The consumed bytes are buffered, so this doesn't read.
+            if let Ok(consumed) = self.inner.fill_buf() {
+                let consumed = &consumed[..amt.min(consumed.len())];
+                match consumed.iter().rposition(|b| *b == b'\n') {
+                    Some(i) => {
+                        *line += consumed.iter().filter(|b| **b == b'\n').count() as u64;
+                        *column = (consumed.len() - i) as u64;
+                    }
+                    None => *column += consumed.len() as u64,
+                }
+            }
+        }
+        self.inner.consume(amt);
+        self.offset += amt as u64;
+    }
+}
+
+/// An error returned by [`Parser::context`], with the position it happened
+/// at and what the parser was doing.
+///
+/// It formats as the names of the contexts from the outermost to the
+/// innermost, the position, and the error that caused it, such as
+/// `in chunk > length at byte 8: failed to fill whole buffer`. The position
+/// includes the line and column if the parser tracks them.
+///
+/// Converting a `ParserError` to an `io::Error` keeps its kind, and wraps
+/// it so it can be recovered through `io::Error::get_ref`.
+///
+/// [`Parser::context`]: struct.Parser.html#method.context
+#[derive(Debug)]
+pub struct ParserError {
+    offset: u64,
+    line_column: Option<(u64, u64)>,
+    contexts: Vec<&'static str>,
+    source: io::Error,
+}
+
+impl ParserError {
+    /// Returns the offset in bytes at which the error happened.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the line and column at which the error happened, if the
+    /// parser tracks them.
+    pub fn line_column(&self) -> Option<(u64, u64)> {
+        self.line_column
+    }
+
+    /// Returns the names of the contexts the error happened in, from the
+    /// outermost to the innermost.
+    pub fn contexts(&self) -> &[&'static str] {
+        &self.contexts
+    }
+
+    /// Returns the kind of the error that caused this one.
+    pub fn kind(&self) -> ErrorKind {
+        self.source.kind()
+    }
+
+    /// Returns the error that caused this one.
+    pub fn get_ref(&self) -> &io::Error {
+        &self.source
+    }
+
+    /// Returns the error that caused this one.
+    pub fn into_inner(self) -> io::Error {
+        self.source
+    }
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "in {} at byte {}",
+            self.contexts.join(" > "),
+            self.offset
+        )?;
+        if let Some((line, column)) = self.line_column {
+            write!(f, " (line {}, column {})", line, column)?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl Error for ParserError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl From<ParserError> for io::Error {
+    fn from(err: ParserError) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
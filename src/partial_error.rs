@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error that interrupted a bulk operation, along with how far the
+/// operation got.
+///
+/// Bulk operations such as [`BufReadExt::copy_exact`] return their errors
+/// with a `PartialError` inside, which can be recovered through
+/// `io::Error::get_ref`. Its [`completed`] count makes it possible to resume
+/// the operation or to report where a stream was corrupt. What it counts
+/// depends on the operation, and is documented with each.
+///
+/// The `io::Error` has the same kind and message as the error that
+/// interrupted the operation, which is the `PartialError`'s `source`.
+///
+/// [`BufReadExt::copy_exact`]: trait.BufReadExt.html#method.copy_exact
+/// [`completed`]: #method.completed
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, BufReader, ErrorKind, Write};
+/// use omnom::prelude::*;
+/// use omnom::PartialError;
+///
+/// let mut reader = BufReader::with_capacity(4, &b"lorem ipsum"[..]);
+/// let mut out = [0; 6];
+/// let err = reader.copy_exact(11, &mut &mut out[..]).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::WriteZero);
+///
+/// let partial = err.get_ref().unwrap().downcast_ref::<PartialError>().unwrap();
+/// assert_eq!(partial.completed(), 4);
+/// ```
+#[derive(Debug)]
+pub struct PartialError {
+    completed: u64,
+    source: io::Error,
+}
+
+impl PartialError {
+    /// Create a new error for an operation that completed `completed` units
+    /// of work before failing with `source`.
+    pub fn new(completed: u64, source: io::Error) -> Self {
+        Self { completed, source }
+    }
+
+    /// Returns how much of the operation completed before the error.
+    pub fn completed(&self) -> u64 {
+        self.completed
+    }
+
+    /// Returns the error that interrupted the operation.
+    pub fn get_ref(&self) -> &io::Error {
+        &self.source
+    }
+
+    /// Returns the error that interrupted the operation.
+    pub fn into_inner(self) -> io::Error {
+        self.source
+    }
+}
+
+impl fmt::Display for PartialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.source, f)
+    }
+}
+
+impl Error for PartialError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl From<PartialError> for io::Error {
+    /// Wraps the `PartialError` in an error of the same kind as its source.
+    fn from(err: PartialError) -> Self {
+        io::Error::new(err.source.kind(), err)
+    }
+}
+
+/// Wrap `err` in a `PartialError` with `completed` units of work.
+pub(crate) fn wrap(completed: u64, err: io::Error) -> io::Error {
+    PartialError::new(completed, err).into()
+}
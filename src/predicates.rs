@@ -0,0 +1,150 @@
+//! Reusable byte predicates.
+//!
+//! These are plain `fn(u8) -> bool`s and closure factories, which can be
+//! passed directly to methods taking a predicate such as
+//! `BufReadExt::read_while` and `BufReadExt::skip_while`.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use std::io::Cursor;
+//! use omnom::predicates::{ascii_digit, http_whitespace, not};
+//! use omnom::prelude::*;
+//!
+//! let mut reader = Cursor::new(b"  123abc");
+//! reader.skip_while(http_whitespace).unwrap();
+//! let mut digits = vec![];
+//! reader.read_while(&mut digits, ascii_digit).unwrap();
+//! let mut rest = vec![];
+//! reader.read_while(&mut rest, not(ascii_digit)).unwrap();
+//! assert_eq!(digits, b"123");
+//! assert_eq!(rest, b"abc");
+//! # }
+//! ```
+
+use crate::ByteSet;
+
+/// Returns `true` for the ASCII digits `0`..=`9`.
+pub fn ascii_digit(byte: u8) -> bool {
+    byte.is_ascii_digit()
+}
+
+/// Returns `true` for the ASCII hex digits `0`..=`9`, `a`..=`f` and
+/// `A`..=`F`.
+pub fn ascii_hexdigit(byte: u8) -> bool {
+    byte.is_ascii_hexdigit()
+}
+
+/// Returns `true` for the ASCII letters `a`..=`z` and `A`..=`Z`.
+pub fn ascii_alpha(byte: u8) -> bool {
+    byte.is_ascii_alphabetic()
+}
+
+/// Returns `true` for ASCII letters and digits.
+pub fn ascii_alphanumeric(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+}
+
+/// Returns `true` for ASCII whitespace: space, tab, line feed, form feed and
+/// carriage return.
+///
+/// Like `u8::is_ascii_whitespace`, this follows the WHATWG Infra standard and
+/// doesn't include the vertical tab.
+pub fn ascii_whitespace(byte: u8) -> bool {
+    byte.is_ascii_whitespace()
+}
+
+/// Returns `true` for bytes allowed in an HTTP token (`tchar` in RFC 9110).
+///
+/// These are ASCII letters, digits, and ``!#$%&'*+-.^_`|~``. Header names,
+/// methods, and MIME types and subtypes are tokens.
+pub fn http_token(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'!' | b'#'
+            | b'$'
+            | b'%'
+            | b'&'
+            | b'\''
+            | b'*'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~'
+    ) || byte.is_ascii_alphanumeric()
+}
+
+/// Returns `true` for HTTP whitespace as defined by the WHATWG Fetch
+/// standard: space, tab, carriage return and line feed.
+///
+/// The optional whitespace (`OWS`) of RFC 9110 is only space and tab; use
+/// `any_of(b" \t")` for that.
+pub fn http_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Negate a predicate.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::predicates::{ascii_digit, not};
+///
+/// let mut p = not(ascii_digit);
+/// assert!(p(b'a'));
+/// assert!(!p(b'1'));
+/// ```
+pub fn not<P>(mut predicate: P) -> impl FnMut(u8) -> bool
+where
+    P: FnMut(u8) -> bool,
+{
+    move |byte| !predicate(byte)
+}
+
+/// Match either of two predicates.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::predicates::{ascii_digit, ascii_alpha, or};
+///
+/// let mut p = or(ascii_digit, ascii_alpha);
+/// assert!(p(b'a'));
+/// assert!(p(b'1'));
+/// assert!(!p(b'-'));
+/// ```
+pub fn or<P, Q>(mut first: P, mut second: Q) -> impl FnMut(u8) -> bool
+where
+    P: FnMut(u8) -> bool,
+    Q: FnMut(u8) -> bool,
+{
+    move |byte| first(byte) || second(byte)
+}
+
+/// Match any of the given bytes.
+///
+/// The bytes are stored in a [`ByteSet`], so matching takes constant time
+/// regardless of how many bytes are given. Pass a `ByteSet` to build it only
+/// once for predicates that are created over and over.
+///
+/// [`ByteSet`]: ../struct.ByteSet.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::predicates::any_of;
+///
+/// let mut p = any_of(b",;");
+/// assert!(p(b','));
+/// assert!(p(b';'));
+/// assert!(!p(b' '));
+/// ```
+pub fn any_of(bytes: impl Into<ByteSet>) -> impl FnMut(u8) -> bool {
+    let set = bytes.into();
+    move |byte| set.contains(byte)
+}
@@ -1,24 +1,106 @@
+use core::mem;
+#[cfg(feature = "std")]
 use std::io::{self, Read};
-use std::mem;
 
 /// Trait to enable writing bytes to a reader.
 pub trait ReadBytes: Sized {
+    /// The byte array this type is decoded from, e.g. `[u8; 4]` for `u32`.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Create a value from its representation as a big endian byte array.
+    fn from_be_array(bytes: Self::Bytes) -> Self;
+
+    /// Create a value from its representation as a little endian byte array.
+    fn from_le_array(bytes: Self::Bytes) -> Self;
+
+    /// Create a value from its representation as a byte array in native
+    /// endianness.
+    fn from_ne_array(bytes: Self::Bytes) -> Self;
+
+    /// Read bytes from the start of a slice as big endian.
+    ///
+    /// Returns the value together with the amount of bytes read, or `None` if
+    /// the slice is too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ReadBytes;
+    ///
+    /// assert_eq!(u16::from_be_slice(&[0, 12, 99]), Some((12, 2)));
+    /// assert_eq!(u16::from_be_slice(&[0]), None);
+    /// ```
+    fn from_be_slice(slice: &[u8]) -> Option<(Self, usize)> {
+        let bytes = read_array::<Self>(slice)?;
+        let len = bytes.as_ref().len();
+        Some((Self::from_be_array(bytes), len))
+    }
+
+    /// Read bytes from the start of a slice as little endian.
+    ///
+    /// Returns the value together with the amount of bytes read, or `None` if
+    /// the slice is too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ReadBytes;
+    ///
+    /// assert_eq!(u16::from_le_slice(&[12, 0, 99]), Some((12, 2)));
+    /// assert_eq!(u16::from_le_slice(&[12]), None);
+    /// ```
+    fn from_le_slice(slice: &[u8]) -> Option<(Self, usize)> {
+        let bytes = read_array::<Self>(slice)?;
+        let len = bytes.as_ref().len();
+        Some((Self::from_le_array(bytes), len))
+    }
+
+    /// Read bytes from the start of a slice using native endianness.
+    ///
+    /// Returns the value together with the amount of bytes read, or `None` if
+    /// the slice is too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::ReadBytes;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// assert_eq!(u16::from_ne_slice(&bytes), Some((12, 2)));
+    /// ```
+    fn from_ne_slice(slice: &[u8]) -> Option<(Self, usize)> {
+        let bytes = read_array::<Self>(slice)?;
+        let len = bytes.as_ref().len();
+        Some((Self::from_ne_array(bytes), len))
+    }
+
     /// Read bytes from a reader as big endian.
     ///
     /// Returns the amount of bytes read.
+    #[cfg(feature = "std")]
     fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self>;
 
     /// Read bytes from a reader as little endian.
     ///
     /// Returns the amount of bytes read.
+    #[cfg(feature = "std")]
     fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self>;
 
     /// Read bytes from a reader using native endianness.
     ///
     /// Returns the amount of bytes read.
+    #[cfg(feature = "std")]
     fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self>;
 }
 
+/// Copy the first bytes of `slice` into the byte array of `B`.
+fn read_array<B: ReadBytes>(slice: &[u8]) -> Option<B::Bytes> {
+    let mut bytes = B::Bytes::default();
+    let len = bytes.as_ref().len();
+    bytes.as_mut().copy_from_slice(slice.get(..len)?);
+    Some(bytes)
+}
+
 macro_rules! doc_comment {
     ($x:expr, $($tt:tt)*) => {
         #[doc = $x]
@@ -29,6 +111,20 @@ macro_rules! doc_comment {
 macro_rules! read_bytes_impl {
     ($($SelfT:ty),* $(,)?) => { $(
         impl ReadBytes for $SelfT {
+        type Bytes = [u8; mem::size_of::<$SelfT>()];
+
+        fn from_be_array(bytes: Self::Bytes) -> Self {
+            <$SelfT>::from_be_bytes(bytes)
+        }
+
+        fn from_le_array(bytes: Self::Bytes) -> Self {
+            <$SelfT>::from_le_bytes(bytes)
+        }
+
+        fn from_ne_array(bytes: Self::Bytes) -> Self {
+            <$SelfT>::from_ne_bytes(bytes)
+        }
+
         doc_comment! {
             concat!("Read bytes from a reader as big endian.
 
@@ -47,6 +143,7 @@ buf.seek(SeekFrom::Start(0)).unwrap();
 let num: ", stringify!($SelfT), " = buf.read_be().unwrap();
 assert_eq!(num, 12);
 ```"),
+            #[cfg(feature = "std")]
             fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
                 let mut buf = [0; mem::size_of::<$SelfT>()];
                 reader.read_exact(&mut buf)?;
@@ -72,6 +169,7 @@ buf.seek(SeekFrom::Start(0)).unwrap();
 let num: ", stringify!($SelfT), " = buf.read_le().unwrap();
 assert_eq!(num, 12);
 ```"),
+            #[cfg(feature = "std")]
             fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
                 let mut buf = [0; mem::size_of::<$SelfT>()];
                 reader.read_exact(&mut buf)?;
@@ -104,6 +202,7 @@ buf.seek(SeekFrom::Start(0)).unwrap();
 let num: ", stringify!($SelfT), " = buf.read_ne().unwrap();
 assert_eq!(num, 12);
 ```"),
+            #[cfg(feature = "std")]
             fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
                 let mut buf = [0; mem::size_of::<$SelfT>()];
                 reader.read_exact(&mut buf)?;
@@ -113,5 +212,41 @@ assert_eq!(num, 12);
     }
 )*}}
 
-read_bytes_impl!(u8, u16, u32, u64, u128, usize);
-read_bytes_impl!(i8, i16, i32, i64, i128, isize);
+int_types!(read_bytes_impl);
+
+/// Byte arrays are read as-is, regardless of endianness.
+impl<const N: usize> ReadBytes for [u8; N]
+where
+    [u8; N]: Default,
+{
+    type Bytes = [u8; N];
+
+    fn from_be_array(bytes: Self::Bytes) -> Self {
+        bytes
+    }
+
+    fn from_le_array(bytes: Self::Bytes) -> Self {
+        bytes
+    }
+
+    fn from_ne_array(bytes: Self::Bytes) -> Self {
+        bytes
+    }
+
+    #[cfg(feature = "std")]
+    fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; N];
+        reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_be_bytes(reader)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_be_bytes(reader)
+    }
+}
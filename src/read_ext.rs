@@ -1,6 +1,55 @@
-use std::io::{self, Read};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::hash::Hash;
+use std::io::{self, ErrorKind, Read};
 
-use crate::ReadBytes;
+use crate::partial_error;
+use crate::{Limits, ReadBytes, ShortRead, SwapBytes};
+
+/// The amount of floats converted per read, chosen so a chunk of `f64`s
+/// fits in 512 bytes on the stack.
+const FLOAT_CHUNK: usize = 64;
+
+/// The most bytes `read_exact_vec` reserves before reading any.
+const MAX_RESERVE: usize = 64 * 1024;
+
+macro_rules! read_floats_impl {
+    ($($name:ident, $F:ty, $from_bytes:ident, $to_bytes:ident, $endian:expr;)*) => { $(
+        #[doc = concat!("Fill `out` with ", $endian, " `", stringify!($F), "`s.")]
+        ///
+        /// The bytes are read in chunks of 64 values, which are each converted
+        /// in a tight loop, rather than with a `read_exact` per value. This
+        /// gives the same values, NaN payloads included, as reading them one
+        /// at a time.
+        ///
+        /// Returns `ErrorKind::UnexpectedEof` if the reader runs out of bytes
+        /// first, in which case the contents of `out` are unspecified, as with
+        /// `read_exact`. Errors carry a [`PartialError`] whose `completed` is
+        /// the amount of values filled in before the chunk that failed.
+        ///
+        /// [`PartialError`]: struct.PartialError.html
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::io::Cursor;
+        /// use omnom::prelude::*;
+        ///
+        #[doc = concat!("let values = [1.5, -0.0, ", stringify!($F), "::INFINITY];")]
+        /// let bytes: Vec<u8> = values
+        ///     .iter()
+        #[doc = concat!("    .flat_map(|v| v.", stringify!($to_bytes), "().to_vec())")]
+        ///     .collect();
+        ///
+        #[doc = concat!("let mut out = [0.0_", stringify!($F), "; 3];")]
+        #[doc = concat!("Cursor::new(bytes).", stringify!($name), "(&mut out).unwrap();")]
+        /// assert_eq!(out, values);
+        /// ```
+        fn $name(&mut self, out: &mut [$F]) -> io::Result<()> {
+            read_floats(self, out, <$F>::$from_bytes)
+        }
+    )* };
+}
 
 /// Extension trait to `Read` to read bytes using endianness.
 pub trait ReadExt: Read + Sized {
@@ -18,6 +67,1004 @@ pub trait ReadExt: Read + Sized {
     fn read_ne<B: ReadBytes>(&mut self) -> io::Result<B> {
         <B>::read_ne_bytes(self)
     }
+
+    /// Read bytes using the opposite of native endianness.
+    ///
+    /// This reads what [`WriteExt::write_ne`] wrote on a machine of the
+    /// other endianness: big endian on little endian machines, and little
+    /// endian on big endian ones.
+    ///
+    /// [`WriteExt::write_ne`]: trait.WriteExt.html#method.write_ne
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    /// use omnom::SwapBytes;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_ne(0x0102_u16).unwrap();
+    /// let num: u16 = buf.as_slice().read_swapped().unwrap();
+    /// assert_eq!(num, 0x0102_u16.to_opposite_endian());
+    /// ```
+    fn read_swapped<B: ReadBytes + SwapBytes>(&mut self) -> io::Result<B> {
+        if cfg!(target_endian = "little") {
+            <B>::read_be_bytes(self)
+        } else {
+            <B>::read_le_bytes(self)
+        }
+    }
+
+    /// Read a little endian value, zero-padding it if the stream ends early.
+    ///
+    /// This reads up to `size_of::<B>()` bytes, and decodes them as if the
+    /// stream continued with zero bytes. For little endian values those are
+    /// the high bytes, so a truncated value keeps its low bytes. Returns the
+    /// value together with the amount of bytes that were actually read: less
+    /// than the size of `B` if the value was truncated, and `0` if the stream
+    /// was already at EOF.
+    ///
+    /// This is for recovering what's left of truncated or corrupt files. To
+    /// fail on truncated values instead, use [`read_le`].
+    ///
+    /// [`read_le`]: #method.read_le
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0x01, 0x02, 0x03, 0x04, 0x05]);
+    /// assert_eq!(cursor.read_le_padded::<u32>().unwrap(), (0x0403_0201, 4));
+    /// assert_eq!(cursor.read_le_padded::<u32>().unwrap(), (0x05, 1));
+    /// assert_eq!(cursor.read_le_padded::<u32>().unwrap(), (0, 0));
+    /// ```
+    fn read_le_padded<B: ReadBytes>(&mut self) -> io::Result<(B, usize)> {
+        read_padded(self, B::from_le_array)
+    }
+
+    /// Read a big endian value, zero-padding it if the stream ends early.
+    ///
+    /// Like [`read_le_padded`], this decodes the bytes as if the stream
+    /// continued with zero bytes. For big endian values those are the _low_
+    /// bytes, so a truncated value keeps its high bytes, and is scaled up by
+    /// the missing bytes rather than equal to the bytes read as a smaller
+    /// integer: `[0x01, 0x02]` read as a `u32` gives `0x0102_0000`. Shift the
+    /// value right by `8 * (size - read)` bits to get `0x0102` instead.
+    ///
+    /// [`read_le_padded`]: #method.read_le_padded
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0x01, 0x02]);
+    /// let (value, read) = cursor.read_be_padded::<u32>().unwrap();
+    /// assert_eq!((value, read), (0x0102_0000, 2));
+    /// assert_eq!(value >> (8 * (4 - read)), 0x0102);
+    /// ```
+    fn read_be_padded<B: ReadBytes>(&mut self) -> io::Result<(B, usize)> {
+        read_padded(self, B::from_be_array)
+    }
+
+    /// Read a big endian count of type `L`, followed by that many big endian
+    /// values of type `T`.
+    ///
+    /// The values are read with a single read of `count * size_of::<T>()`
+    /// bytes, and converted with [`ReadBytes::from_be_array`]. Since that
+    /// memory is allocated up front, `max` caps the count to guard against
+    /// corrupt or malicious input.
+    ///
+    /// Returns `ErrorKind::InvalidData` if the count exceeds `max` or doesn't
+    /// fit in a `usize`, and `ErrorKind::UnexpectedEof` if the input ends
+    /// before all values are read.
+    ///
+    /// [`ReadBytes::from_be_array`]: trait.ReadBytes.html#tymethod.from_be_array
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0, 2, 0, 0, 0, 1, 0, 0, 0, 2]);
+    /// let values = cursor.read_vec_prefixed_be::<u16, u32>(16).unwrap();
+    /// assert_eq!(values, [1, 2]);
+    /// ```
+    fn read_vec_prefixed_be<L, T>(&mut self, max: usize) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        self.read_vec_prefixed_be_with_limits::<L, T>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a little endian count of type `L`, followed by that many little
+    /// endian values of type `T`.
+    ///
+    /// See [`read_vec_prefixed_be`] for details.
+    ///
+    /// [`read_vec_prefixed_be`]: #method.read_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([2, b'a', b'b', b'c', b'd']);
+    /// let values = cursor.read_vec_prefixed_le::<u8, [u8; 2]>(16).unwrap();
+    /// assert_eq!(values, [*b"ab", *b"cd"]);
+    /// ```
+    fn read_vec_prefixed_le<L, T>(&mut self, max: usize) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        self.read_vec_prefixed_le_with_limits::<L, T>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a count of type `L`, followed by that many values of type `T`,
+    /// using native endianness.
+    ///
+    /// See [`read_vec_prefixed_be`] for details.
+    ///
+    /// [`read_vec_prefixed_be`]: #method.read_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// cursor.write_vec_prefixed_ne::<u32, u16>(&[7, 8, 9]).unwrap();
+    /// cursor.set_position(0);
+    /// let values = cursor.read_vec_prefixed_ne::<u32, u16>(16).unwrap();
+    /// assert_eq!(values, [7, 8, 9]);
+    /// ```
+    fn read_vec_prefixed_ne<L, T>(&mut self, max: usize) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        self.read_vec_prefixed_ne_with_limits::<L, T>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a big endian count of type `L`, followed by that many pairs of a
+    /// big endian key of type `K` and a big endian value of type `V`.
+    ///
+    /// Like [`read_vec_prefixed_be`], the entries are read in one go, and
+    /// `max` caps the count. Returns `ErrorKind::InvalidData` if a key
+    /// appears more than once.
+    ///
+    /// [`read_vec_prefixed_be`]: #method.read_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([2, 1, 0, 10, 2, 0, 20]);
+    /// let map = cursor.read_map_prefixed_be::<u8, u8, u16>(16).unwrap();
+    /// assert_eq!(map[&1], 10);
+    /// assert_eq!(map[&2], 20);
+    ///
+    /// let mut cursor = Cursor::new([2, 1, 0, 10, 1, 0, 20]);
+    /// assert!(cursor.read_map_prefixed_be::<u8, u8, u16>(16).is_err());
+    /// ```
+    fn read_map_prefixed_be<L, K, V>(&mut self, max: usize) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        self.read_map_prefixed_be_with_limits::<L, K, V>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a little endian count of type `L`, followed by that many pairs of
+    /// a little endian key of type `K` and a little endian value of type `V`.
+    ///
+    /// See [`read_map_prefixed_be`] for details.
+    ///
+    /// [`read_map_prefixed_be`]: #method.read_map_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([1, 0, 7, 0, 0, 0, b'h', b'i']);
+    /// let map = cursor.read_map_prefixed_le::<u16, u32, [u8; 2]>(16).unwrap();
+    /// assert_eq!(&map[&7], b"hi");
+    /// ```
+    fn read_map_prefixed_le<L, K, V>(&mut self, max: usize) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        self.read_map_prefixed_le_with_limits::<L, K, V>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a count of type `L`, followed by that many pairs of a key of type
+    /// `K` and a value of type `V`, using native endianness.
+    ///
+    /// See [`read_map_prefixed_be`] for details.
+    ///
+    /// [`read_map_prefixed_be`]: #method.read_map_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let map: HashMap<u16, u64> = [(1, 2), (3, 4)].iter().copied().collect();
+    /// let mut cursor = Cursor::new(vec![]);
+    /// cursor.write_map_prefixed_ne::<u8, _, _>(&map).unwrap();
+    /// cursor.set_position(0);
+    /// assert_eq!(cursor.read_map_prefixed_ne::<u8, u16, u64>(16).unwrap(), map);
+    /// ```
+    fn read_map_prefixed_ne<L, K, V>(&mut self, max: usize) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        self.read_map_prefixed_ne_with_limits::<L, K, V>(Limits::unlimited().max_elements(max))
+    }
+
+    /// Read a big endian count of type `L`, followed by that many big endian
+    /// values of type `T`, within `limits`.
+    ///
+    /// This is [`read_vec_prefixed_be`], with the count capped by the element
+    /// limit and the size of the values by the allocation limit.
+    ///
+    /// [`read_vec_prefixed_be`]: #method.read_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let limits = Limits::new().max_elements(2);
+    /// let mut cursor = Cursor::new([0, 2, 0, 1, 0, 2]);
+    /// let values = cursor.read_vec_prefixed_be_with_limits::<u16, u16>(limits).unwrap();
+    /// assert_eq!(values, [1, 2]);
+    ///
+    /// let mut cursor = Cursor::new([0, 3, 0, 1, 0, 2, 0, 3]);
+    /// assert!(cursor.read_vec_prefixed_be_with_limits::<u16, u16>(limits).is_err());
+    /// ```
+    fn read_vec_prefixed_be_with_limits<L, T>(&mut self, limits: Limits) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        let len = L::read_be_bytes(self)?;
+        read_vec(self, len, limits, T::from_be_array)
+    }
+
+    /// Read a little endian count of type `L`, followed by that many little endian
+    /// values of type `T`, within `limits`.
+    ///
+    /// See [`read_vec_prefixed_be_with_limits`] for details.
+    ///
+    /// [`read_vec_prefixed_be_with_limits`]: #method.read_vec_prefixed_be_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let mut cursor = Cursor::new([2, 0, 1, 0, 2, 0]);
+    /// let values = cursor.read_vec_prefixed_le_with_limits::<u16, u16>(Limits::new()).unwrap();
+    /// assert_eq!(values, [1, 2]);
+    /// ```
+    fn read_vec_prefixed_le_with_limits<L, T>(&mut self, limits: Limits) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        let len = L::read_le_bytes(self)?;
+        read_vec(self, len, limits, T::from_le_array)
+    }
+
+    /// Read a native endian count of type `L`, followed by that many native endian
+    /// values of type `T`, within `limits`.
+    ///
+    /// See [`read_vec_prefixed_be_with_limits`] for details.
+    ///
+    /// [`read_vec_prefixed_be_with_limits`]: #method.read_vec_prefixed_be_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let mut cursor = Cursor::new([1, 9]);
+    /// let values = cursor.read_vec_prefixed_ne_with_limits::<u8, u8>(Limits::new()).unwrap();
+    /// assert_eq!(values, [9]);
+    /// ```
+    fn read_vec_prefixed_ne_with_limits<L, T>(&mut self, limits: Limits) -> io::Result<Vec<T>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        T: ReadBytes,
+    {
+        let len = L::read_ne_bytes(self)?;
+        read_vec(self, len, limits, T::from_ne_array)
+    }
+
+    /// Read a big endian count of type `L`, followed by that many pairs of a
+    /// big endian key of type `K` and a big endian value of type `V`, within
+    /// `limits`.
+    ///
+    /// This is [`read_map_prefixed_be`], with the count capped by the element
+    /// limit and the size of the entries by the allocation limit.
+    ///
+    /// [`read_map_prefixed_be`]: #method.read_map_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let limits = Limits::new().max_alloc(4);
+    /// let mut cursor = Cursor::new([2, 1, 0, 10, 2, 0, 20]);
+    /// assert!(cursor.read_map_prefixed_be_with_limits::<u8, u8, u16>(limits).is_err());
+    /// ```
+    fn read_map_prefixed_be_with_limits<L, K, V>(
+        &mut self,
+        limits: Limits,
+    ) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        let len = L::read_be_bytes(self)?;
+        read_map(self, len, limits, K::from_be_array, V::from_be_array)
+    }
+
+    /// Read a little endian count of type `L`, followed by that many pairs of
+    /// a little endian key of type `K` and a little endian value of type `V`, within
+    /// `limits`.
+    ///
+    /// See [`read_map_prefixed_be_with_limits`] for details.
+    ///
+    /// [`read_map_prefixed_be_with_limits`]: #method.read_map_prefixed_be_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let mut cursor = Cursor::new([1, 7, 10, 0]);
+    /// let map = cursor.read_map_prefixed_le_with_limits::<u8, u8, u16>(Limits::new()).unwrap();
+    /// assert_eq!(map[&7], 10);
+    /// ```
+    fn read_map_prefixed_le_with_limits<L, K, V>(
+        &mut self,
+        limits: Limits,
+    ) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        let len = L::read_le_bytes(self)?;
+        read_map(self, len, limits, K::from_le_array, V::from_le_array)
+    }
+
+    /// Read a native endian count of type `L`, followed by that many pairs of
+    /// a native endian key of type `K` and a native endian value of type `V`, within
+    /// `limits`.
+    ///
+    /// See [`read_map_prefixed_be_with_limits`] for details.
+    ///
+    /// [`read_map_prefixed_be_with_limits`]: #method.read_map_prefixed_be_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let mut cursor = Cursor::new([1, 7, 10]);
+    /// let map = cursor.read_map_prefixed_ne_with_limits::<u8, u8, u8>(Limits::new()).unwrap();
+    /// assert_eq!(map[&7], 10);
+    /// ```
+    fn read_map_prefixed_ne_with_limits<L, K, V>(
+        &mut self,
+        limits: Limits,
+    ) -> io::Result<HashMap<K, V>>
+    where
+        L: ReadBytes + TryInto<usize>,
+        K: ReadBytes + Eq + Hash,
+        V: ReadBytes,
+    {
+        let len = L::read_ne_bytes(self)?;
+        read_map(self, len, limits, K::from_ne_array, V::from_ne_array)
+    }
+
+    /// Read a big endian unsigned integer of `nbytes` bytes, from 1 to 8.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `nbytes` is out of range. When the
+    /// width is known at compile time, reading a [`UInt`] does the same.
+    ///
+    /// [`UInt`]: struct.UInt.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3]);
+    /// assert_eq!(cursor.read_uint_be(3).unwrap(), 0x010203);
+    /// ```
+    fn read_uint_be(&mut self, nbytes: usize) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[8 - int_width(nbytes)?..])?;
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    /// Read a little endian unsigned integer of `nbytes` bytes, from 1 to 8.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `nbytes` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3]);
+    /// assert_eq!(cursor.read_uint_le(3).unwrap(), 0x030201);
+    /// ```
+    fn read_uint_le(&mut self, nbytes: usize) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[..int_width(nbytes)?])?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Read a big endian two's complement integer of `nbytes` bytes, from 1
+    /// to 8, sign-extending it to an `i64`.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `nbytes` is out of range. When the
+    /// width is known at compile time, reading an [`Int`] does the same.
+    ///
+    /// [`Int`]: struct.Int.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0xff, 0xff, 0xfe]);
+    /// assert_eq!(cursor.read_int_be(3).unwrap(), -2);
+    /// ```
+    fn read_int_be(&mut self, nbytes: usize) -> io::Result<i64> {
+        let value = self.read_uint_be(nbytes)?;
+        Ok(sign_extend(value, nbytes))
+    }
+
+    /// Read a little endian two's complement integer of `nbytes` bytes, from
+    /// 1 to 8, sign-extending it to an `i64`.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if `nbytes` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0xfe, 0xff, 0xff]);
+    /// assert_eq!(cursor.read_int_le(3).unwrap(), -2);
+    /// ```
+    fn read_int_le(&mut self, nbytes: usize) -> io::Result<i64> {
+        let value = self.read_uint_le(nbytes)?;
+        Ok(sign_extend(value, nbytes))
+    }
+
+    /// Read an MQTT remaining length, a variable byte integer of 1 to 4
+    /// bytes.
+    ///
+    /// Each byte holds 7 bits of the value, least significant first, and has
+    /// its top bit set if another byte follows. Following MQTT 5.0 section
+    /// 1.5.5, this returns `ErrorKind::InvalidData` if a 4th byte has its top
+    /// bit set, or if the value isn't encoded in as few bytes as possible,
+    /// such as `0x80 0x00` for 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0xc1, 0x02, 0x80, 0x00]);
+    /// assert_eq!(cursor.read_mqtt_remaining_len().unwrap(), 321);
+    /// assert!(cursor.read_mqtt_remaining_len().is_err());
+    /// ```
+    fn read_mqtt_remaining_len(&mut self) -> io::Result<u32> {
+        let mut value = 0;
+        for i in 0..4 {
+            let mut byte = [0];
+            self.read_exact(&mut byte)?;
+            let byte = byte[0];
+            value |= u32::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                if byte == 0 && i > 0 {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("remaining length {} isn't minimally encoded", value),
+                    ));
+                }
+                return Ok(value);
+            }
+        }
+        Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "remaining length is longer than 4 bytes",
+        ))
+    }
+
+    /// Read a bitmap of `bits` booleans, packed 8 to a byte.
+    ///
+    /// Bits are stored least significant bit first, as in Arrow and Parquet:
+    /// the first boolean is the lowest bit of the first byte. The bitmap takes
+    /// `bits` rounded up to a whole number of bytes, and the padding bits of
+    /// the last byte are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([0b0000_0101, 0b1111_1110]);
+    /// let bits = cursor.read_bitmap(9).unwrap();
+    /// assert_eq!(bits, [true, false, true, false, false, false, false, false, false]);
+    /// ```
+    fn read_bitmap(&mut self, bits: usize) -> io::Result<Vec<bool>> {
+        self.read_bitmap_with_limits(bits, Limits::unlimited())
+    }
+
+    /// Read a bitmap of `bits` booleans, if `bits` is within the element and
+    /// allocation limits of `limits`.
+    ///
+    /// See [`read_bitmap`] for details.
+    ///
+    /// [`read_bitmap`]: #method.read_bitmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let limits = Limits::new().max_elements(8);
+    /// let mut cursor = Cursor::new([0xff, 0xff]);
+    /// assert!(cursor.read_bitmap_with_limits(9, limits).is_err());
+    /// assert_eq!(cursor.read_bitmap_with_limits(2, limits).unwrap(), [true, true]);
+    /// ```
+    fn read_bitmap_with_limits(&mut self, bits: usize, limits: Limits) -> io::Result<Vec<bool>> {
+        limits.check_elements(bits)?;
+        limits.check_alloc(bits)?;
+        let mut out = vec![false; bits];
+        self.read_bitmap_into(&mut out)?;
+        Ok(out)
+    }
+
+    /// Read a bitmap of `out.len()` booleans into `out`.
+    ///
+    /// This is [`read_bitmap`] without allocating a `Vec`.
+    ///
+    /// [`read_bitmap`]: #method.read_bitmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut out = [false; 4];
+    /// Cursor::new([0b1001]).read_bitmap_into(&mut out).unwrap();
+    /// assert_eq!(out, [true, false, false, true]);
+    /// ```
+    fn read_bitmap_into(&mut self, out: &mut [bool]) -> io::Result<()> {
+        let mut bytes = [0; 64];
+        for chunk in out.chunks_mut(bytes.len() * 8) {
+            let bytes = &mut bytes[..chunk.len().div_ceil(8)];
+            self.read_exact(bytes)?;
+            for (i, bit) in chunk.iter_mut().enumerate() {
+                *bit = bytes[i / 8] >> (i % 8) & 1 == 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read exactly `n` bytes into a new `Vec`.
+    ///
+    /// This reads into reserved space, rather than zeroing a buffer of `n`
+    /// bytes for `read_exact` to overwrite. Readers of the standard library,
+    /// such as `File`, `BufReader` and byte slices, fill the space without
+    /// initializing it first, which matters for reads of many megabytes.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the reader
+    /// has fewer than `n` bytes.
+    ///
+    /// The buffer grows as bytes are read, so a corrupt `n` doesn't allocate
+    /// more than the reader holds. Callers that read `n` from untrusted input
+    /// should still check it against a maximum first, or use
+    /// [`read_exact_vec_with_limits`].
+    ///
+    /// [`ShortRead`]: struct.ShortRead.html
+    /// [`read_exact_vec_with_limits`]: #method.read_exact_vec_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new(b"lorem ipsum");
+    /// assert_eq!(cursor.read_exact_vec(5).unwrap(), b"lorem");
+    ///
+    /// let err = cursor.read_exact_vec(8).unwrap_err();
+    /// assert_eq!(err.to_string(), "expected 8 bytes, found 6");
+    /// ```
+    fn read_exact_vec(&mut self, n: usize) -> io::Result<Vec<u8>> {
+        read_exact_vec(self, n)
+    }
+
+    /// Read exactly `n` bytes into a new `Vec`, if `n` is within the
+    /// allocation limit of `limits`.
+    ///
+    /// See [`read_exact_vec`] for details.
+    ///
+    /// [`read_exact_vec`]: #method.read_exact_vec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let limits = Limits::new().max_alloc(4);
+    /// let mut cursor = Cursor::new(b"lorem");
+    /// assert!(cursor.read_exact_vec_with_limits(5, limits).is_err());
+    /// assert_eq!(cursor.read_exact_vec_with_limits(4, limits).unwrap(), b"lore");
+    /// ```
+    fn read_exact_vec_with_limits(&mut self, n: usize, limits: Limits) -> io::Result<Vec<u8>> {
+        limits.check_alloc(n)?;
+        read_exact_vec(self, n)
+    }
+
+    /// Read exactly `n` bytes as a UTF-8 string.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the reader
+    /// has fewer than `n` bytes, and `ErrorKind::InvalidData` with the offset
+    /// of the first invalid byte if they aren't valid UTF-8.
+    ///
+    /// The buffer grows as bytes are read, so a corrupt `n` doesn't allocate
+    /// more than the reader holds. Callers that read `n` from untrusted input
+    /// should still check it against a maximum first, or use
+    /// [`read_to_string_exact_with_limits`].
+    ///
+    /// [`ShortRead`]: struct.ShortRead.html
+    /// [`read_to_string_exact_with_limits`]: #method.read_to_string_exact_with_limits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new("héllo world".as_bytes());
+    /// assert_eq!(cursor.read_to_string_exact(6).unwrap(), "héllo");
+    ///
+    /// let err = Cursor::new([b'a', 0xff]).read_to_string_exact(2).unwrap_err();
+    /// assert_eq!(err.to_string(), "invalid UTF-8 at byte 1");
+    /// ```
+    fn read_to_string_exact(&mut self, n: usize) -> io::Result<String> {
+        self.read_to_string_exact_with_limits(n, Limits::unlimited())
+    }
+
+    /// Read exactly `n` bytes as a UTF-8 string, if `n` is within the
+    /// allocation limit of `limits`.
+    ///
+    /// See [`read_to_string_exact`] for details.
+    ///
+    /// [`read_to_string_exact`]: #method.read_to_string_exact
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::Limits;
+    ///
+    /// let limits = Limits::new().max_alloc(4);
+    /// let mut cursor = Cursor::new("hello");
+    /// assert!(cursor.read_to_string_exact_with_limits(5, limits).is_err());
+    /// assert_eq!(cursor.read_to_string_exact_with_limits(4, limits).unwrap(), "hell");
+    /// ```
+    fn read_to_string_exact_with_limits(&mut self, n: usize, limits: Limits) -> io::Result<String> {
+        limits.check_alloc(n)?;
+        let bytes = read_exact_vec(self, n)?;
+        String::from_utf8(bytes).map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid UTF-8 at byte {}", e.utf8_error().valid_up_to()),
+            )
+        })
+    }
+
+    /// Skip the next `n` bytes by reading and discarding them.
+    ///
+    /// Bytes are read in chunks into a buffer on the stack, so this works on
+    /// any reader without allocating. Returns the amount of bytes skipped,
+    /// which is less than `n` only if the reader reached EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3, 4]);
+    /// assert_eq!(cursor.skip_bytes(3).unwrap(), 3);
+    /// assert_eq!(cursor.read_be::<u8>().unwrap(), 4);
+    /// assert_eq!(cursor.skip_bytes(3).unwrap(), 0);
+    /// ```
+    fn skip_bytes(&mut self, n: u64) -> io::Result<u64> {
+        let mut buf = [0; 8 * 1024];
+        let mut skipped = 0;
+        while skipped < n {
+            let len = buf
+                .len()
+                .min((n - skipped).try_into().unwrap_or(usize::MAX));
+            match self.read(&mut buf[..len]) {
+                Ok(0) => break,
+                Ok(read) => skipped += read as u64,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Skip exactly `n` bytes by reading and discarding them.
+    ///
+    /// This is [`skip_bytes`], but returns `ErrorKind::UnexpectedEof` if the
+    /// reader has fewer than `n` bytes.
+    ///
+    /// [`skip_bytes`]: #method.skip_bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3, 4]);
+    /// cursor.skip_bytes_exact(2).unwrap();
+    /// assert!(cursor.skip_bytes_exact(3).is_err());
+    /// ```
+    fn skip_bytes_exact(&mut self, n: u64) -> io::Result<()> {
+        let skipped = self.skip_bytes(n)?;
+        if skipped < n {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("expected {} bytes, found {}", n, skipped),
+            ));
+        }
+        Ok(())
+    }
+
+    read_floats_impl! {
+        read_f32_into_be, f32, from_be_bytes, to_be_bytes, "big endian";
+        read_f32_into_le, f32, from_le_bytes, to_le_bytes, "little endian";
+        read_f32_into_ne, f32, from_ne_bytes, to_ne_bytes, "native endian";
+        read_f64_into_be, f64, from_be_bytes, to_be_bytes, "big endian";
+        read_f64_into_le, f64, from_le_bytes, to_le_bytes, "little endian";
+        read_f64_into_ne, f64, from_ne_bytes, to_ne_bytes, "native endian";
+    }
 }
 
 impl<T: Read> ReadExt for T {}
+
+/// Check that an integer of `nbytes` bytes fits in 64 bits.
+fn int_width(nbytes: usize) -> io::Result<usize> {
+    if (1..=8).contains(&nbytes) {
+        Ok(nbytes)
+    } else {
+        Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("integer width of {} bytes is not in 1..=8", nbytes),
+        ))
+    }
+}
+
+/// Sign-extend the low `nbytes` bytes of `value`.
+fn sign_extend(value: u64, nbytes: usize) -> i64 {
+    let shift = 64 - nbytes * 8;
+    ((value << shift) as i64) >> shift
+}
+
+/// Fill `out` by reading `FLOAT_CHUNK` values at a time.
+fn read_floats<R, F, C, const N: usize>(
+    reader: &mut R,
+    out: &mut [F],
+    from_bytes: C,
+) -> io::Result<()>
+where
+    R: Read,
+    C: Fn([u8; N]) -> F + Copy,
+{
+    let mut bytes = [0; FLOAT_CHUNK * 8];
+    for (i, chunk) in out.chunks_mut(FLOAT_CHUNK).enumerate() {
+        let bytes = &mut bytes[..chunk.len() * N];
+        reader
+            .read_exact(bytes)
+            .map_err(|e| partial_error::wrap((i * FLOAT_CHUNK) as u64, e))?;
+        convert_floats(bytes, chunk, from_bytes);
+    }
+    Ok(())
+}
+
+/// Convert each `N` bytes of `bytes` into a value of `out`.
+pub(crate) fn convert_floats<F, C, const N: usize>(bytes: &[u8], out: &mut [F], from_bytes: C)
+where
+    C: Fn([u8; N]) -> F,
+{
+    for (value, window) in out.iter_mut().zip(bytes.chunks_exact(N)) {
+        let mut array = [0; N];
+        array.copy_from_slice(window);
+        *value = from_bytes(array);
+    }
+}
+
+/// Read up to a `B`'s worth of bytes, zero-filling the rest.
+fn read_padded<R, B>(reader: &mut R, from_array: fn(B::Bytes) -> B) -> io::Result<(B, usize)>
+where
+    R: Read,
+    B: ReadBytes,
+{
+    let mut bytes = B::Bytes::default();
+    let buf = bytes.as_mut();
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((from_array(bytes), filled))
+}
+
+/// Returns the size of the byte array of `T`.
+fn size_of<T: ReadBytes>() -> usize {
+    T::Bytes::default().as_ref().len()
+}
+
+/// Convert a chunk the size of `T`'s byte array with `from_array`.
+fn from_chunk<T: ReadBytes>(chunk: &[u8], from_array: fn(T::Bytes) -> T) -> T {
+    let mut array = T::Bytes::default();
+    array.as_mut().copy_from_slice(chunk);
+    from_array(array)
+}
+
+/// Read `len` records of `size` bytes in one go, converting each with
+/// `decode`.
+fn read_records<R, L, T>(
+    reader: &mut R,
+    len: L,
+    limits: Limits,
+    size: usize,
+    mut decode: impl FnMut(&[u8]) -> T,
+) -> io::Result<Vec<T>>
+where
+    R: Read,
+    L: TryInto<usize>,
+{
+    let len: usize = len.try_into().map_err(|_| {
+        io::Error::new(ErrorKind::InvalidData, "length prefix doesn't fit in usize")
+    })?;
+    limits.check_elements(len)?;
+    if size == 0 {
+        return Ok((0..len).map(|_| decode(&[])).collect());
+    }
+    let total = len
+        .checked_mul(size)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "length prefix overflows usize"))?;
+    limits.check_alloc(total)?;
+    let bytes = read_exact_vec(reader, total)?;
+    Ok(bytes.chunks_exact(size).map(decode).collect())
+}
+
+/// Read exactly `n` bytes into a `Vec`, without zeroing it first.
+///
+/// At most `MAX_RESERVE` bytes are reserved up front, and the `Vec` grows
+/// from there as bytes are read, so a corrupt `n` can't allocate more than
+/// the reader holds.
+fn read_exact_vec<R: Read + ?Sized>(reader: &mut R, n: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(n.min(MAX_RESERVE));
+    reader.take(n as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < n {
+        return Err(ShortRead::new(n, bytes.len()).into());
+    }
+    Ok(bytes)
+}
+
+/// Read `len` values of `T`, converting each with `from_array`.
+fn read_vec<R, L, T>(
+    reader: &mut R,
+    len: L,
+    limits: Limits,
+    from_array: fn(T::Bytes) -> T,
+) -> io::Result<Vec<T>>
+where
+    R: Read,
+    L: TryInto<usize>,
+    T: ReadBytes,
+{
+    read_records(reader, len, limits, size_of::<T>(), |chunk| {
+        from_chunk(chunk, from_array)
+    })
+}
+
+/// Read `len` pairs of `K` and `V` into a map, rejecting duplicate keys.
+fn read_map<R, L, K, V>(
+    reader: &mut R,
+    len: L,
+    limits: Limits,
+    key: fn(K::Bytes) -> K,
+    value: fn(V::Bytes) -> V,
+) -> io::Result<HashMap<K, V>>
+where
+    R: Read,
+    L: TryInto<usize>,
+    K: ReadBytes + Eq + Hash,
+    V: ReadBytes,
+{
+    let key_size = size_of::<K>();
+    let entries = read_records(reader, len, limits, key_size + size_of::<V>(), |chunk| {
+        let (k, v) = chunk.split_at(key_size);
+        (from_chunk(k, key), from_chunk(v, value))
+    })?;
+    let mut map = HashMap::with_capacity(entries.len());
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        if map.insert(k, v).is_some() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("entry {} repeats an earlier key", i),
+            ));
+        }
+    }
+    Ok(map)
+}
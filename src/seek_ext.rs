@@ -0,0 +1,542 @@
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+
+use crate::{ReadBytes, WriteBytes};
+
+/// Extend `Seek` with methods for streams that can move back and forth.
+pub trait SeekExt: Seek + Sized {
+    /// Write zeroes the size of a big endian `T`, to be filled in later with
+    /// [`patch`].
+    ///
+    /// This is for fields such as "length of the following section", whose
+    /// value is only known once the section is written. Any number of
+    /// placeholders can be outstanding, and they can be patched in any
+    /// order.
+    ///
+    /// [`patch`]: #method.patch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Cursor, Write};
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// let len = cursor.write_placeholder_be::<u16>().unwrap();
+    /// cursor.write_all(b"hello").unwrap();
+    /// cursor.patch(len, 5).unwrap();
+    /// cursor.write_all(b"!").unwrap();
+    /// assert_eq!(cursor.get_ref(), b"\x00\x05hello!");
+    /// ```
+    fn write_placeholder_be<T: WriteBytes>(&mut self) -> io::Result<Placeholder<T>>
+    where
+        Self: Write,
+    {
+        write_placeholder(self, Endian::Big)
+    }
+
+    /// Write zeroes the size of a little endian `T`, to be filled in later
+    /// with [`patch`].
+    ///
+    /// See [`write_placeholder_be`] for details.
+    ///
+    /// [`patch`]: #method.patch
+    /// [`write_placeholder_be`]: #method.write_placeholder_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Cursor, Write};
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// let len = cursor.write_placeholder_le::<u16>().unwrap();
+    /// cursor.write_all(b"hi").unwrap();
+    /// cursor.patch(len, 2).unwrap();
+    /// assert_eq!(cursor.get_ref(), b"\x02\x00hi");
+    /// ```
+    fn write_placeholder_le<T: WriteBytes>(&mut self) -> io::Result<Placeholder<T>>
+    where
+        Self: Write,
+    {
+        write_placeholder(self, Endian::Little)
+    }
+
+    /// Write zeroes the size of a `T` in native endianness, to be filled in
+    /// later with [`patch`].
+    ///
+    /// See [`write_placeholder_be`] for details.
+    ///
+    /// [`patch`]: #method.patch
+    /// [`write_placeholder_be`]: #method.write_placeholder_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// let value = cursor.write_placeholder_ne::<u32>().unwrap();
+    /// cursor.patch(value, 7).unwrap();
+    /// assert_eq!(cursor.get_ref(), &7_u32.to_ne_bytes());
+    /// ```
+    fn write_placeholder_ne<T: WriteBytes>(&mut self) -> io::Result<Placeholder<T>>
+    where
+        Self: Write,
+    {
+        write_placeholder(self, Endian::Native)
+    }
+
+    /// Fill in a placeholder with `value`, in the endianness it was created
+    /// with.
+    ///
+    /// This seeks back to the placeholder, writes the value, and seeks back
+    /// to the current position, also if writing the value fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Cursor, Write};
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(vec![]);
+    /// let outer = cursor.write_placeholder_be::<u8>().unwrap();
+    /// let inner = cursor.write_placeholder_be::<u8>().unwrap();
+    /// cursor.write_all(b"abc").unwrap();
+    /// cursor.patch(inner, 3).unwrap();
+    /// cursor.patch(outer, 4).unwrap();
+    /// assert_eq!(cursor.get_ref(), b"\x04\x03abc");
+    /// ```
+    fn patch<T: WriteBytes>(&mut self, placeholder: Placeholder<T>, value: T) -> io::Result<()>
+    where
+        Self: Write,
+    {
+        let current = self.stream_position()?;
+        self.seek(SeekFrom::Start(placeholder.position))?;
+        let written = match placeholder.endian {
+            Endian::Big => value.write_be_bytes(self),
+            Endian::Little => value.write_le_bytes(self),
+            Endian::Native => value.write_ne_bytes(self),
+        };
+        let restored = self.seek(SeekFrom::Start(current));
+        written?;
+        restored?;
+        Ok(())
+    }
+
+    /// Write `value` as big endian at `pos`, and return to the current
+    /// position afterwards, also if writing fails.
+    ///
+    /// Returns the amount of bytes written. This is for fix-up passes over
+    /// an image in memory, where seeking to each field by hand makes it easy
+    /// to lose the place of the main cursor.
+    ///
+    /// Returns `ErrorKind::InvalidInput` if the value doesn't fit before the
+    /// end of the stream, before writing anything. This is a fix-up of bytes
+    /// that were already written, so the stream never grows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut image = [0xff; 6];
+    /// let mut cursor = Cursor::new(&mut image[..]);
+    /// assert_eq!(cursor.write_be_at(2, 0x0102_u16).unwrap(), 2);
+    /// assert_eq!(cursor.position(), 0);
+    /// assert_eq!(image, [0xff, 0xff, 0x01, 0x02, 0xff, 0xff]);
+    /// ```
+    fn write_be_at<B: WriteBytes>(&mut self, pos: u64, value: B) -> io::Result<usize>
+    where
+        Self: Write,
+    {
+        write_at(self, pos, value.to_be_array().as_ref())
+    }
+
+    /// Write `value` as little endian at `pos`, and return to the current
+    /// position afterwards, also if writing fails.
+    ///
+    /// See [`write_be_at`] for details.
+    ///
+    /// [`write_be_at`]: #method.write_be_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(vec![0; 4]);
+    /// cursor.write_le_at(1, 0x0102_u16).unwrap();
+    /// assert_eq!(cursor.get_ref(), &[0x00, 0x02, 0x01, 0x00]);
+    /// ```
+    fn write_le_at<B: WriteBytes>(&mut self, pos: u64, value: B) -> io::Result<usize>
+    where
+        Self: Write,
+    {
+        write_at(self, pos, value.to_le_array().as_ref())
+    }
+
+    /// Read a big endian value at `pos`, and return to the current position
+    /// afterwards, also if reading fails.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` if the value doesn't fit before the
+    /// end of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new([7, 0x01, 0x02]);
+    /// assert_eq!(cursor.read_be_at::<u16>(1).unwrap(), 0x0102);
+    /// assert_eq!(cursor.read_be::<u8>().unwrap(), 7);
+    /// ```
+    fn read_be_at<B: ReadBytes>(&mut self, pos: u64) -> io::Result<B>
+    where
+        Self: Read,
+    {
+        self.peek_with(|s| {
+            s.seek(SeekFrom::Start(pos))?;
+            B::read_be_bytes(s)
+        })
+    }
+
+    /// Read a little endian value at `pos`, and return to the current
+    /// position afterwards, also if reading fails.
+    ///
+    /// See [`read_be_at`] for details.
+    ///
+    /// [`read_be_at`]: #method.read_be_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new([7, 0x01, 0x02]);
+    /// assert_eq!(cursor.read_le_at::<u16>(1).unwrap(), 0x0201);
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    fn read_le_at<B: ReadBytes>(&mut self, pos: u64) -> io::Result<B>
+    where
+        Self: Read,
+    {
+        self.peek_with(|s| {
+            s.seek(SeekFrom::Start(pos))?;
+            B::read_le_bytes(s)
+        })
+    }
+
+    /// Skip the next `n` bytes, seeking past the ones that aren't buffered.
+    ///
+    /// A skip that fits in the buffer only consumes bytes. Otherwise the
+    /// buffered bytes are consumed and the rest is skipped with a single seek,
+    /// so skipping a large section of a `BufReader<File>` doesn't read it off
+    /// disk.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` if the stream has fewer than `n`
+    /// bytes left. The stream is then positioned at its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufReader, Cursor};
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut reader = BufReader::with_capacity(2, Cursor::new([1, 2, 3, 4, 5]));
+    /// reader.skip_seek(3).unwrap();
+    /// assert_eq!(reader.read_be::<u8>().unwrap(), 4);
+    /// assert!(reader.skip_seek(2).is_err());
+    /// ```
+    fn skip_seek(&mut self, n: u64) -> io::Result<()>
+    where
+        Self: BufRead,
+    {
+        let available = loop {
+            match self.fill_buf() {
+                Ok(buf) => break buf.len(),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        if n <= available as u64 {
+            self.consume(n as usize);
+            return Ok(());
+        }
+
+        // The buffer is empty once consumed, so seeking doesn't discard
+        // any buffered bytes.
+        self.consume(available);
+        let remaining = n - available as u64;
+        let position = self.stream_position()?;
+        let end = self.seek(SeekFrom::End(0))?;
+        match position.checked_add(remaining) {
+            Some(target) if target <= end => {
+                self.seek(SeekFrom::Start(target))?;
+                Ok(())
+            }
+            _ => Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "can't skip {} bytes, only {} are left",
+                    n,
+                    available as u64 + (end - position)
+                ),
+            )),
+        }
+    }
+
+    /// Remember the current position, to return to later with
+    /// [`rewind_to`].
+    ///
+    /// [`rewind_to`]: #method.rewind_to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3]);
+    /// cursor.read_be::<u8>().unwrap();
+    /// let mark = cursor.mark().unwrap();
+    /// assert_eq!(mark.position(), 1);
+    /// ```
+    fn mark(&mut self) -> io::Result<Mark> {
+        self.stream_position().map(Mark)
+    }
+
+    /// Return to a position remembered with [`mark`].
+    ///
+    /// This doesn't seek if the stream is still at the mark, so a `BufReader`
+    /// only discards its buffer if the stream actually moved.
+    ///
+    /// [`mark`]: #method.mark
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new([1, 2, 3]);
+    /// let mark = cursor.mark().unwrap();
+    /// assert_eq!(cursor.read_be::<u16>().unwrap(), 0x0102);
+    /// cursor.rewind_to(mark).unwrap();
+    /// assert_eq!(cursor.read_be::<u8>().unwrap(), 1);
+    /// ```
+    fn rewind_to(&mut self, mark: Mark) -> io::Result<()> {
+        if self.stream_position()? != mark.0 {
+            self.seek(SeekFrom::Start(mark.0))?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` and return to the current position afterwards, also if `f`
+    /// fails.
+    ///
+    /// This allows reading ahead as far as needed, such as to try parsing a
+    /// value before committing to it. Calls can be nested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new([0, 0, 0, 42, 7]);
+    /// let value = cursor.peek_with(|c| c.read_be::<u32>()).unwrap();
+    /// assert_eq!(value, 42);
+    /// assert_eq!(cursor.read_be::<u8>().unwrap(), 0);
+    /// ```
+    fn peek_with<T, F>(&mut self, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut Self) -> io::Result<T>,
+    {
+        let mark = self.mark()?;
+        let result = f(self);
+        let restored = self.rewind_to(mark);
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Read up to `buf.len()` bytes at `offset`, and return to the current
+    /// position afterwards.
+    ///
+    /// This is for glancing at another part of a stream, such as the
+    /// directory at the end of an archive, without losing the place of the
+    /// main cursor. It reads until `buf` is full or the stream ends, and
+    /// returns the amount of bytes read, which is `0` at or past the end of
+    /// the stream.
+    ///
+    /// # Cost
+    ///
+    /// If `offset` is ahead of the current position and the peeked bytes are
+    /// all in the buffer, they're copied from it without seeking, so a
+    /// `BufReader` keeps its buffer. If the buffer is empty, this fills it
+    /// first, as the next read would.
+    ///
+    /// Otherwise this costs a seek to `offset`, the reads, and a seek back.
+    /// A `BufReader` discards its buffer on seeking, so the next read after
+    /// the peek refills it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Cursor, SeekFrom};
+    /// use omnom::prelude::*;
+    /// use omnom::SeekExt;
+    ///
+    /// let mut cursor = Cursor::new(b"head....tail");
+    /// let mut tail = [0; 4];
+    /// assert_eq!(cursor.peek_at(SeekFrom::End(-4), &mut tail).unwrap(), 4);
+    /// assert_eq!(&tail, b"tail");
+    /// assert_eq!(cursor.read_be::<[u8; 4]>().unwrap(), *b"head");
+    /// ```
+    fn peek_at(&mut self, offset: SeekFrom, buf: &mut [u8]) -> io::Result<usize>
+    where
+        Self: BufRead,
+    {
+        let position = self.stream_position()?;
+        let ahead = match offset {
+            SeekFrom::Start(n) => n.checked_sub(position),
+            SeekFrom::Current(n) if n >= 0 => Some(n as u64),
+            _ => None,
+        };
+        if let Some(ahead) = ahead {
+            let available = loop {
+                match self.fill_buf() {
+                    Ok(available) => break available,
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            };
+            let end = ahead.checked_add(buf.len() as u64);
+            if end.is_some_and(|end| end <= available.len() as u64) {
+                let ahead = ahead as usize;
+                buf.copy_from_slice(&available[ahead..ahead + buf.len()]);
+                return Ok(buf.len());
+            }
+        }
+
+        let result = self.seek(offset).and_then(|_| {
+            let mut read = 0;
+            while read < buf.len() {
+                match self.read(&mut buf[read..]) {
+                    Ok(0) => break,
+                    Ok(n) => read += n,
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(read)
+        });
+        let restored = self.seek(SeekFrom::Start(position));
+        let read = result?;
+        restored?;
+        Ok(read)
+    }
+}
+
+impl<T: Seek> SeekExt for T {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endian {
+    Big,
+    Little,
+    Native,
+}
+
+fn write_placeholder<W, T>(writer: &mut W, endian: Endian) -> io::Result<Placeholder<T>>
+where
+    W: Write + Seek,
+    T: WriteBytes,
+{
+    let position = writer.stream_position()?;
+    writer.write_all(T::Bytes::default().as_ref())?;
+    Ok(Placeholder {
+        position,
+        endian,
+        _marker: PhantomData,
+    })
+}
+
+/// Write `bytes` at `pos` if they fit before the end of `stream`, and return
+/// to the current position afterwards.
+fn write_at<S: SeekExt + Write>(stream: &mut S, pos: u64, bytes: &[u8]) -> io::Result<usize> {
+    stream.peek_with(|s| {
+        let end = s.seek(SeekFrom::End(0))?;
+        match pos.checked_add(bytes.len() as u64) {
+            Some(target) if target <= end => {}
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "can't write {} bytes at {}, the stream ends at {}",
+                        bytes.len(),
+                        pos,
+                        end
+                    ),
+                ))
+            }
+        }
+        s.seek(SeekFrom::Start(pos))?;
+        s.write_all(bytes)?;
+        Ok(bytes.len())
+    })
+}
+
+/// A position in a stream, returned by [`SeekExt::mark`].
+///
+/// [`SeekExt::mark`]: trait.SeekExt.html#method.mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mark(u64);
+
+impl Mark {
+    /// Returns the position in the stream.
+    pub fn position(self) -> u64 {
+        self.0
+    }
+}
+
+/// A field written as zeroes, to be filled in with [`SeekExt::patch`].
+///
+/// This is returned by [`SeekExt::write_placeholder_be`] and its
+/// counterparts, and records where the field is and its endianness.
+///
+/// [`SeekExt::patch`]: trait.SeekExt.html#method.patch
+/// [`SeekExt::write_placeholder_be`]: trait.SeekExt.html#method.write_placeholder_be
+#[must_use = "a placeholder stays zeroed unless it's patched"]
+pub struct Placeholder<T> {
+    position: u64,
+    endian: Endian,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Placeholder<T> {
+    /// Returns the position of the field in the stream.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<T> fmt::Debug for Placeholder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Placeholder")
+            .field("position", &self.position)
+            .field("endian", &self.endian)
+            .finish()
+    }
+}
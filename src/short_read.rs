@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error for a stream that ended before enough bytes were available.
+///
+/// A bare `ErrorKind::UnexpectedEof` doesn't say how close the stream came to
+/// having enough bytes, which matters when deciding whether to wait for more
+/// data or to reject the input as corrupt. Methods that need a known amount of
+/// bytes return their `UnexpectedEof` errors with a `ShortRead` inside, which
+/// can be recovered through `io::Error::get_ref`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use omnom::prelude::*;
+/// use omnom::ShortRead;
+///
+/// let err = Cursor::new(b"abc").read_to_string_exact(5).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+/// assert_eq!(err.to_string(), "expected 5 bytes, found 3");
+///
+/// let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+/// assert_eq!((short.needed(), short.available()), (5, 3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortRead {
+    needed: usize,
+    available: usize,
+}
+
+impl ShortRead {
+    /// Create a new error for `needed` bytes, of which only `available` were
+    /// available.
+    pub fn new(needed: usize, available: usize) -> Self {
+        Self { needed, available }
+    }
+
+    /// Returns the amount of bytes that were needed.
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// Returns the amount of bytes that were available before the stream
+    /// ended.
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl fmt::Display for ShortRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} bytes, found {}",
+            self.needed, self.available
+        )
+    }
+}
+
+impl Error for ShortRead {}
+
+impl From<ShortRead> for io::Error {
+    /// Wraps the `ShortRead` in an error of the kind
+    /// `ErrorKind::UnexpectedEof`.
+    fn from(err: ShortRead) -> Self {
+        io::Error::new(io::ErrorKind::UnexpectedEof, err)
+    }
+}
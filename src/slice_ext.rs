@@ -0,0 +1,170 @@
+use crate::ReadBytes;
+
+/// Extend `&[u8]` with methods for parsing.
+///
+/// This provides the vocabulary of `BufReadExt` for data that's already in
+/// memory. Each method advances the slice past the bytes it reads, so the
+/// slice itself is always the remaining input. Since nothing can fail except
+/// running out of bytes, the methods return plain values or `Option`s
+/// rather than `io::Result`s, and reads return subslices of the input rather
+/// than copying into a buffer. Delimiter handling and counts match the `io`
+/// counterparts, so parsers can be ported between the two.
+///
+/// `&[u8]` also implements `Read` and `BufRead`, so this trait isn't part of
+/// the prelude: importing both makes the shared method names ambiguous.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::SliceExt;
+///
+/// let mut input: &[u8] = b"text/html; charset=utf-8";
+/// assert_eq!(input.read_until(b'/'), b"text/");
+/// assert_eq!(input.read_while(|b| b != b';'), b"html");
+/// assert!(input.consume_prefix(b";"));
+/// input.skip_while(|b| b == b' ');
+/// assert_eq!(input, b"charset=utf-8");
+/// ```
+pub trait SliceExt<'a> {
+    /// Read bytes while the predicate is true.
+    ///
+    /// Returns the bytes read.
+    fn read_while<P>(&mut self, predicate: P) -> &'a [u8]
+    where
+        P: FnMut(u8) -> bool;
+
+    /// Read bytes until the delimiter `byte` or the end of the slice is
+    /// reached.
+    ///
+    /// Returns the bytes read, including the delimiter if found.
+    fn read_until(&mut self, byte: u8) -> &'a [u8];
+
+    /// Skip bytes while the predicate is true.
+    ///
+    /// Returns the amount of bytes skipped.
+    fn skip_while<P>(&mut self, predicate: P) -> usize
+    where
+        P: FnMut(u8) -> bool;
+
+    /// Skip bytes until the delimiter `byte` or the end of the slice is
+    /// reached.
+    ///
+    /// Returns the amount of bytes skipped, including the delimiter if found.
+    fn skip_until(&mut self, byte: u8) -> usize;
+
+    /// Skip `prefix` if the slice starts with it.
+    ///
+    /// Returns `false` and leaves the slice untouched otherwise.
+    fn consume_prefix(&mut self, prefix: &[u8]) -> bool;
+
+    /// Read bytes as big endian.
+    ///
+    /// Returns `None` and leaves the slice untouched if it's too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::SliceExt;
+    ///
+    /// let mut input: &[u8] = &[0, 12, 1];
+    /// assert_eq!(input.read_be::<u16>(), Some(12));
+    /// assert_eq!(input.read_be::<u16>(), None);
+    /// assert_eq!(input, &[1]);
+    /// ```
+    fn read_be<B: ReadBytes>(&mut self) -> Option<B>;
+
+    /// Read bytes as little endian.
+    ///
+    /// Returns `None` and leaves the slice untouched if it's too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::SliceExt;
+    ///
+    /// let mut input: &[u8] = &[12, 0];
+    /// assert_eq!(input.read_le::<u16>(), Some(12));
+    /// ```
+    fn read_le<B: ReadBytes>(&mut self) -> Option<B>;
+
+    /// Read bytes using native endianness.
+    ///
+    /// Returns `None` and leaves the slice untouched if it's too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::SliceExt;
+    ///
+    /// let bytes = 12_u16.to_ne_bytes();
+    /// let mut input: &[u8] = &bytes;
+    /// assert_eq!(input.read_ne::<u16>(), Some(12));
+    /// ```
+    fn read_ne<B: ReadBytes>(&mut self) -> Option<B>;
+}
+
+impl<'a> SliceExt<'a> for &'a [u8] {
+    fn read_while<P>(&mut self, mut predicate: P) -> &'a [u8]
+    where
+        P: FnMut(u8) -> bool,
+    {
+        let len = self
+            .iter()
+            .position(|b| !predicate(*b))
+            .unwrap_or(self.len());
+        split(self, len)
+    }
+
+    fn read_until(&mut self, byte: u8) -> &'a [u8] {
+        let len = match memchr::memchr(byte, self) {
+            Some(i) => i + 1,
+            None => self.len(),
+        };
+        split(self, len)
+    }
+
+    fn skip_while<P>(&mut self, predicate: P) -> usize
+    where
+        P: FnMut(u8) -> bool,
+    {
+        self.read_while(predicate).len()
+    }
+
+    fn skip_until(&mut self, byte: u8) -> usize {
+        self.read_until(byte).len()
+    }
+
+    fn consume_prefix(&mut self, prefix: &[u8]) -> bool {
+        if self.starts_with(prefix) {
+            split(self, prefix.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn read_be<B: ReadBytes>(&mut self) -> Option<B> {
+        let (value, len) = B::from_be_slice(self)?;
+        split(self, len);
+        Some(value)
+    }
+
+    fn read_le<B: ReadBytes>(&mut self) -> Option<B> {
+        let (value, len) = B::from_le_slice(self)?;
+        split(self, len);
+        Some(value)
+    }
+
+    fn read_ne<B: ReadBytes>(&mut self) -> Option<B> {
+        let (value, len) = B::from_ne_slice(self)?;
+        split(self, len);
+        Some(value)
+    }
+}
+
+/// Advance `slice` by `len` bytes, returning the bytes skipped.
+fn split<'a>(slice: &mut &'a [u8], len: usize) -> &'a [u8] {
+    let (head, tail) = slice.split_at(len);
+    *slice = tail;
+    head
+}
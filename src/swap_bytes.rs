@@ -0,0 +1,48 @@
+/// Values whose bytes can be reversed, to convert between big and little
+/// endian.
+///
+/// This is implemented for the integer and float types. It's the bound of
+/// [`ReadExt::read_swapped`] and [`WriteExt::write_swapped`], which read and
+/// write the opposite of native endianness, such as a file written with
+/// `write_ne` on a machine of the other endianness.
+///
+/// [`ReadExt::read_swapped`]: trait.ReadExt.html#method.read_swapped
+/// [`WriteExt::write_swapped`]: trait.WriteExt.html#method.write_swapped
+///
+/// # Examples
+///
+/// ```
+/// use omnom::SwapBytes;
+///
+/// assert_eq!(0x1234_u16.to_opposite_endian(), 0x3412);
+/// assert_eq!((-2_i32).to_opposite_endian(), -0x0100_0001);
+/// assert_eq!(1.0_f32.to_opposite_endian().to_bits(), 0x0000_803f);
+/// ```
+pub trait SwapBytes: Sized {
+    /// Returns the value with its bytes in reverse order.
+    fn to_opposite_endian(self) -> Self;
+}
+
+macro_rules! swap_bytes_impl {
+    ($($SelfT:ty),* $(,)?) => { $(
+        impl SwapBytes for $SelfT {
+            fn to_opposite_endian(self) -> Self {
+                self.swap_bytes()
+            }
+        }
+    )* };
+}
+
+int_types!(swap_bytes_impl);
+
+impl SwapBytes for f32 {
+    fn to_opposite_endian(self) -> Self {
+        f32::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+impl SwapBytes for f64 {
+    fn to_opposite_endian(self) -> Self {
+        f64::from_bits(self.to_bits().swap_bytes())
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::{self, BufRead, ErrorKind};
+
+use crate::ascii::{next_if, peek};
+
+/// A point in time read by [`BufReadExt::read_rfc3339`].
+///
+/// [`BufReadExt::read_rfc3339`]: trait.BufReadExt.html#method.read_rfc3339
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use omnom::prelude::*;
+///
+/// let mut cursor = io::Cursor::new(b"1970-01-01T01:00:00.5+01:00");
+/// let ts = cursor.read_rfc3339().unwrap();
+/// assert_eq!(ts.secs_since_epoch, 0);
+/// assert_eq!(ts.nanos, 500_000_000);
+/// assert_eq!(ts.offset_minutes, 60);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    /// Seconds since 1970-01-01T00:00:00Z, not counting leap seconds.
+    pub secs_since_epoch: i64,
+    /// Nanoseconds past `secs_since_epoch`, below one billion.
+    pub nanos: u32,
+    /// The offset from UTC the timestamp was written in, in minutes.
+    pub offset_minutes: i16,
+}
+
+/// Read an RFC 3339 timestamp, without consuming the byte that failed to parse
+/// on error.
+pub(crate) fn read_rfc3339<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Timestamp> {
+    let year = read_field(reader, 4, 0, 9999, "year")?;
+    expect(reader, b"-")?;
+    let month = read_field(reader, 2, 1, 12, "month")?;
+    expect(reader, b"-")?;
+    let day = read_field(reader, 2, 1, days_in_month(year, month), "day")?;
+    expect(reader, b"Tt")?;
+    let hour = read_field(reader, 2, 0, 23, "hour")?;
+    expect(reader, b":")?;
+    let minute = read_field(reader, 2, 0, 59, "minute")?;
+    expect(reader, b":")?;
+    let second = read_field(reader, 2, 0, 60, "second")?;
+
+    let mut nanos = 0;
+    if next_if(reader, b".")?.is_some() {
+        let mut digits = 0;
+        while let Some(byte) = peek(reader)? {
+            if !byte.is_ascii_digit() {
+                break;
+            }
+            if digits == 9 {
+                return Err(invalid("fractional seconds have more than 9 digits"));
+            }
+            nanos = nanos * 10 + u32::from(byte - b'0');
+            digits += 1;
+            reader.consume(1);
+        }
+        if digits == 0 {
+            return Err(invalid("expected fractional seconds"));
+        }
+        nanos *= 10_u32.pow(9 - digits);
+    }
+
+    let offset_minutes = match next_if(reader, b"Zz+-")? {
+        Some(b'Z') | Some(b'z') => 0,
+        Some(sign) => {
+            let hours = read_field(reader, 2, 0, 23, "offset hour")?;
+            expect(reader, b":")?;
+            let minutes = read_field(reader, 2, 0, 59, "offset minute")?;
+            let offset = (hours * 60 + minutes) as i16;
+            if sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        None => return Err(invalid("expected a UTC offset")),
+    };
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86_400 + i64::from(hour * 3600 + minute * 60 + second);
+    Ok(Timestamp {
+        secs_since_epoch: secs - i64::from(offset_minutes) * 60,
+        nanos,
+        offset_minutes,
+    })
+}
+
+/// Read a field of exactly `len` digits, and check it's within `min..=max`.
+fn read_field<R: BufRead + ?Sized>(
+    reader: &mut R,
+    len: usize,
+    min: u32,
+    max: u32,
+    name: &str,
+) -> io::Result<u32> {
+    let mut value = 0;
+    for _ in 0..len {
+        match peek(reader)? {
+            Some(byte) if byte.is_ascii_digit() => {
+                value = value * 10 + u32::from(byte - b'0');
+                reader.consume(1);
+            }
+            _ => {
+                return Err(invalid(&format!(
+                    "expected {} digits for the {}",
+                    len, name
+                )))
+            }
+        }
+    }
+    if value < min || value > max {
+        return Err(invalid(&format!("{} {} is out of range", name, value)));
+    }
+    Ok(value)
+}
+
+/// Consume one of `bytes`, or fail.
+fn expect<R: BufRead + ?Sized>(reader: &mut R, bytes: &[u8]) -> io::Result<()> {
+    match next_if(reader, bytes)? {
+        Some(_) => Ok(()),
+        None => Err(invalid(&format!("expected '{}'", bytes[0] as char))),
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the amount of days between 1970-01-01 and the given date in the
+/// proleptic Gregorian calendar.
+///
+/// This is Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: u32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid RFC 3339 timestamp: {}", reason),
+    )
+}
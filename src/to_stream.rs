@@ -0,0 +1,110 @@
+use std::io::{self, Write};
+
+use crate::WriteBytes;
+
+/// A type that can be encoded to a stream.
+///
+/// This is the counterpart of [`FromStream`], and encodes values the way it
+/// decodes them: every `WriteBytes` type writes itself as big endian, like
+/// `WriteExt::write_be`, tuples write their fields in order, and `Option<T>`
+/// writes a tag byte, `0` for `None` or `1` for `Some`, followed by the `T`
+/// if it's `Some`. As with `FromStream`, only byte arrays implement it among
+/// the arrays.
+///
+/// Types that implement both traits should round-trip: decoding the bytes a
+/// value was encoded to gives back an equal value, and consumes exactly the
+/// bytes that were written.
+///
+/// [`FromStream`]: trait.FromStream.html
+///
+/// # Errors
+///
+/// Encoding isn't transactional. If writing fails, the bytes written until
+/// then stay written, which can include part of a field.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Write};
+/// use omnom::{ToStream, WriteStructExt};
+///
+/// struct Header {
+///     magic: [u8; 4],
+///     len: u32,
+/// }
+///
+/// impl ToStream for Header {
+///     fn to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+///         Ok(writer.write_struct(&self.magic)? + writer.write_struct(&self.len)?)
+///     }
+/// }
+///
+/// let mut out = vec![];
+/// let header = Header { magic: *b"RIFF", len: 36 };
+/// assert_eq!(out.write_struct(&header).unwrap(), 8);
+/// assert_eq!(out, b"RIFF\x00\x00\x00\x24");
+/// ```
+pub trait ToStream {
+    /// Encode the value to `writer`, and return the amount of bytes written.
+    fn to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize>;
+}
+
+impl<T: WriteBytes> ToStream for T {
+    fn to_stream<W: Write + ?Sized>(&self, mut writer: &mut W) -> io::Result<usize> {
+        self.write_be_bytes(&mut writer)
+    }
+}
+
+impl<T: ToStream> ToStream for Option<T> {
+    fn to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        match self {
+            None => 0_u8.to_stream(writer),
+            Some(value) => Ok(1_u8.to_stream(writer)? + value.to_stream(writer)?),
+        }
+    }
+}
+
+macro_rules! to_stream_tuple {
+    ($($T:ident $i:tt)+) => {
+        impl<$($T: ToStream),+> ToStream for ($($T,)+) {
+            fn to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+                Ok(0 $(+ self.$i.to_stream(writer)?)+)
+            }
+        }
+    };
+}
+
+to_stream_tuple!(A 0);
+to_stream_tuple!(A 0 B 1);
+to_stream_tuple!(A 0 B 1 C 2);
+to_stream_tuple!(A 0 B 1 C 2 D 3);
+to_stream_tuple!(A 0 B 1 C 2 D 3 E 4);
+to_stream_tuple!(A 0 B 1 C 2 D 3 E 4 F 5);
+to_stream_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+to_stream_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+
+/// Extend `Write` with a method to write [`ToStream`] types.
+///
+/// [`ToStream`]: trait.ToStream.html
+pub trait WriteStructExt: Write {
+    /// Write a value of a type that implements [`ToStream`].
+    ///
+    /// Returns the amount of bytes written.
+    ///
+    /// [`ToStream`]: trait.ToStream.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::WriteStructExt;
+    ///
+    /// let mut out = vec![];
+    /// assert_eq!(out.write_struct(&(1_u16, Some(2_u8))).unwrap(), 4);
+    /// assert_eq!(out, [0, 1, 1, 2]);
+    /// ```
+    fn write_struct<T: ToStream + ?Sized>(&mut self, value: &T) -> io::Result<usize> {
+        value.to_stream(self)
+    }
+}
+
+impl<W: Write + ?Sized> WriteStructExt for W {}
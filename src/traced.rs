@@ -0,0 +1,254 @@
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+use tracing::level_filters::{LevelFilter, STATIC_MAX_LEVEL};
+use tracing::Level;
+
+/// The amount of bytes shown of each consume or write by default.
+const DEFAULT_PREVIEW_LEN: usize = 16;
+
+/// A reader that emits a `tracing` event for everything read through it.
+///
+/// Every `fill_buf` emits an event with the amount of bytes available, and
+/// every `consume` one with the amount of bytes consumed and a preview of
+/// them, as hex followed by ASCII. This shows which bytes each method of
+/// [`BufReadExt`] and the other extension traits took from the stream.
+///
+/// The events are at the `TRACE` level, with the target `omnom::traced`.
+/// When that level is disabled, tracing a call costs a single branch.
+///
+/// [`BufReadExt`]: trait.BufReadExt.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::prelude::*;
+/// use omnom::Traced;
+///
+/// let mut reader = Traced::new(&b"\x00\x2aok"[..]).label("request");
+/// let len: u16 = reader.read_be().unwrap();
+/// assert_eq!(len, 42);
+/// ```
+#[derive(Debug)]
+pub struct Traced<R> {
+    inner: R,
+    label: Option<&'static str>,
+    preview_len: usize,
+}
+
+impl<R> Traced<R> {
+    /// Create a new `Traced`, which shows up to 16 bytes of each consume.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            label: None,
+            preview_len: DEFAULT_PREVIEW_LEN,
+        }
+    }
+
+    /// Set a label to include in the events, to tell streams apart.
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Set the maximum amount of bytes to show of each consume.
+    pub fn preview_len(mut self, len: usize) -> Self {
+        self.preview_len = len;
+        self
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Read for Traced<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Traced<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let available = self.inner.fill_buf()?;
+        if trace_enabled() {
+            tracing::trace!(
+                target: "omnom::traced",
+                label = self.label,
+                len = available.len(),
+                "fill_buf"
+            );
+        }
+        Ok(available)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if trace_enabled() {
+            // The consumed bytes are buffered, so this doesn't read.
+            let consumed = match self.inner.fill_buf() {
+                Ok(available) => &available[..amt.min(available.len())],
+                Err(_) => &[],
+            };
+            tracing::trace!(
+                target: "omnom::traced",
+                label = self.label,
+                len = amt,
+                preview = %Preview::new(consumed, self.preview_len),
+                "consume"
+            );
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// A writer that emits a `tracing` event for everything written through it.
+///
+/// Every `write` emits an event with the amount of bytes written and a
+/// preview of them, like [`Traced`] does for consumed bytes.
+///
+/// The events are at the `TRACE` level, with the target `omnom::traced`.
+/// When that level is disabled, tracing a call costs a single branch.
+///
+/// [`Traced`]: struct.Traced.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::prelude::*;
+/// use omnom::TracedWriter;
+///
+/// let mut writer = TracedWriter::new(vec![]).label("response");
+/// writer.write_be(42u16).unwrap();
+/// assert_eq!(writer.into_inner(), [0x00, 0x2a]);
+/// ```
+#[derive(Debug)]
+pub struct TracedWriter<W> {
+    inner: W,
+    label: Option<&'static str>,
+    preview_len: usize,
+}
+
+impl<W> TracedWriter<W> {
+    /// Create a new `TracedWriter`, which shows up to 16 bytes of each
+    /// write.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            label: None,
+            preview_len: DEFAULT_PREVIEW_LEN,
+        }
+    }
+
+    /// Set a label to include in the events, to tell streams apart.
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Set the maximum amount of bytes to show of each write.
+    pub fn preview_len(mut self, len: usize) -> Self {
+        self.preview_len = len;
+        self
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for TracedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        if trace_enabled() {
+            tracing::trace!(
+                target: "omnom::traced",
+                label = self.label,
+                len,
+                preview = %Preview::new(&buf[..len], self.preview_len),
+                "write"
+            );
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns `true` if `TRACE` events may be recorded.
+///
+/// This is a compile-time comparison and the load of the global maximum
+/// level. `tracing::trace!` checks more, such as the target, but only after
+/// the preview has been set up.
+#[inline]
+fn trace_enabled() -> bool {
+    Level::TRACE <= STATIC_MAX_LEVEL && Level::TRACE <= LevelFilter::current()
+}
+
+/// Bytes formatted as hex and ASCII, cut off after `max` bytes.
+///
+/// `\x00\x2aok` formats as `00 2a 6f 6b |.*ok|`, and a preview that's cut
+/// off ends in `...`.
+struct Preview<'a> {
+    bytes: &'a [u8],
+    truncated: bool,
+}
+
+impl<'a> Preview<'a> {
+    fn new(bytes: &'a [u8], max: usize) -> Self {
+        Self {
+            bytes: &bytes[..max.min(bytes.len())],
+            truncated: bytes.len() > max,
+        }
+    }
+}
+
+impl fmt::Display for Preview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.bytes {
+            write!(f, "{:02x} ", byte)?;
+        }
+        f.write_str("|")?;
+        for &byte in self.bytes {
+            let c = if byte.is_ascii_graphic() || byte == b' ' {
+                char::from(byte)
+            } else {
+                '.'
+            };
+            write!(f, "{}", c)?;
+        }
+        f.write_str("|")?;
+        if self.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::{ReadBytes, WriteBytes};
+
+/// An unsigned integer encoded in exactly `BYTES` bytes, from 1 to 8.
+///
+/// Formats such as PCAPNG and MPEG transport streams use 5, 6 or 7 byte
+/// integers. `UInt` holds such a value in a `u64`, and reads and writes just
+/// its `BYTES` least significant bytes in the chosen endianness.
+///
+/// Using a `BYTES` outside of `1..=8` fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{ReadBytes, UInt, WriteBytes};
+///
+/// let (value, len) = UInt::<5>::from_be_slice(&[1, 2, 3, 4, 5, 6]).unwrap();
+/// assert_eq!(len, 5);
+/// assert_eq!(u64::from(value), 0x01_0203_0405);
+/// assert_eq!(value.to_le_array(), [5, 4, 3, 2, 1]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UInt<const BYTES: usize>(u64);
+
+/// A signed integer encoded in exactly `BYTES` bytes, from 1 to 8.
+///
+/// This is the two's complement counterpart of [`UInt`]: reading sign-extends
+/// the `BYTES` bytes into an `i64`, and writing keeps just the `BYTES` least
+/// significant bytes.
+///
+/// Using a `BYTES` outside of `1..=8` fails to compile.
+///
+/// [`UInt`]: struct.UInt.html
+///
+/// # Examples
+///
+/// ```
+/// use omnom::{Int, ReadBytes};
+///
+/// let value = Int::<3>::from_be_array([0xff, 0xff, 0xfe]);
+/// assert_eq!(i64::from(value), -2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Int<const BYTES: usize>(i64);
+
+impl<const BYTES: usize> UInt<BYTES> {
+    /// Fails to compile if `BYTES` is out of range, once evaluated.
+    const BITS: u32 = {
+        assert!(BYTES >= 1 && BYTES <= 8, "`BYTES` must be in 1..=8");
+        BYTES as u32 * 8
+    };
+
+    /// The smallest value, 0.
+    pub const MIN: Self = Self(0);
+
+    /// The largest value, `2^(8 * BYTES) - 1`.
+    pub const MAX: Self = Self(u64::MAX >> (64 - Self::BITS));
+
+    /// Create a new value, or return `None` if it doesn't fit in `BYTES`
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::UInt;
+    ///
+    /// assert!(UInt::<6>::try_new(0xffff_ffff_ffff).is_some());
+    /// assert!(UInt::<6>::try_new(0x1_0000_0000_0000).is_none());
+    /// ```
+    pub const fn try_new(value: u64) -> Option<Self> {
+        if value <= Self::MAX.0 {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    fn from_be(bytes: [u8; BYTES]) -> Self {
+        let mut full = [0; 8];
+        full[8 - BYTES..].copy_from_slice(&bytes);
+        Self(u64::from_be_bytes(full) & Self::MAX.0)
+    }
+
+    fn from_le(bytes: [u8; BYTES]) -> Self {
+        let mut full = [0; 8];
+        full[..BYTES].copy_from_slice(&bytes);
+        Self(u64::from_le_bytes(full) & Self::MAX.0)
+    }
+}
+
+impl<const BYTES: usize> Int<BYTES> {
+    /// The smallest value, `-2^(8 * BYTES - 1)`.
+    pub const MIN: Self = Self(i64::MIN >> (64 - UInt::<BYTES>::BITS));
+
+    /// The largest value, `2^(8 * BYTES - 1) - 1`.
+    pub const MAX: Self = Self(i64::MAX >> (64 - UInt::<BYTES>::BITS));
+
+    /// Create a new value, or return `None` if it doesn't fit in `BYTES`
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Int;
+    ///
+    /// assert!(Int::<1>::try_new(-128).is_some());
+    /// assert!(Int::<1>::try_new(128).is_none());
+    /// ```
+    pub const fn try_new(value: i64) -> Option<Self> {
+        if value >= Self::MIN.0 && value <= Self::MAX.0 {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value.
+    pub const fn get(self) -> i64 {
+        self.0
+    }
+
+    /// Sign-extend the low `BYTES` bytes of `unsigned`.
+    fn sign_extend(unsigned: UInt<BYTES>) -> Self {
+        let shift = 64 - UInt::<BYTES>::BITS;
+        Self(((unsigned.0 << shift) as i64) >> shift)
+    }
+
+    /// Returns the low `BYTES` bytes of the two's complement value.
+    fn truncate(self) -> UInt<BYTES> {
+        UInt(self.0 as u64 & UInt::<BYTES>::MAX.0)
+    }
+}
+
+impl<const BYTES: usize> From<UInt<BYTES>> for u64 {
+    fn from(value: UInt<BYTES>) -> Self {
+        value.0
+    }
+}
+
+impl<const BYTES: usize> From<Int<BYTES>> for i64 {
+    fn from(value: Int<BYTES>) -> Self {
+        value.0
+    }
+}
+
+impl<const BYTES: usize> fmt::Display for UInt<BYTES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<const BYTES: usize> fmt::Display for Int<BYTES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<const BYTES: usize> ReadBytes for UInt<BYTES>
+where
+    [u8; BYTES]: Default,
+{
+    type Bytes = [u8; BYTES];
+
+    fn from_be_array(bytes: Self::Bytes) -> Self {
+        Self::from_be(bytes)
+    }
+
+    fn from_le_array(bytes: Self::Bytes) -> Self {
+        Self::from_le(bytes)
+    }
+
+    fn from_ne_array(bytes: Self::Bytes) -> Self {
+        if cfg!(target_endian = "big") {
+            Self::from_be(bytes)
+        } else {
+            Self::from_le(bytes)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; BYTES];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_be_array(buf))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; BYTES];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_le_array(buf))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0; BYTES];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_ne_array(buf))
+    }
+}
+
+impl<const BYTES: usize> WriteBytes for UInt<BYTES>
+where
+    [u8; BYTES]: Default,
+{
+    type Bytes = [u8; BYTES];
+
+    fn to_be_array(&self) -> Self::Bytes {
+        let mut bytes = [0; BYTES];
+        bytes.copy_from_slice(&self.0.to_be_bytes()[8 - BYTES..]);
+        bytes
+    }
+
+    fn to_le_array(&self) -> Self::Bytes {
+        let mut bytes = [0; BYTES];
+        bytes.copy_from_slice(&self.0.to_le_bytes()[..BYTES]);
+        bytes
+    }
+
+    fn to_ne_array(&self) -> Self::Bytes {
+        if cfg!(target_endian = "big") {
+            self.to_be_array()
+        } else {
+            self.to_le_array()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_be_array())?;
+        Ok(BYTES)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_le_array())?;
+        Ok(BYTES)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(&self.to_ne_array())?;
+        Ok(BYTES)
+    }
+}
+
+impl<const BYTES: usize> ReadBytes for Int<BYTES>
+where
+    [u8; BYTES]: Default,
+{
+    type Bytes = [u8; BYTES];
+
+    fn from_be_array(bytes: Self::Bytes) -> Self {
+        Self::sign_extend(UInt::from_be_array(bytes))
+    }
+
+    fn from_le_array(bytes: Self::Bytes) -> Self {
+        Self::sign_extend(UInt::from_le_array(bytes))
+    }
+
+    fn from_ne_array(bytes: Self::Bytes) -> Self {
+        Self::sign_extend(UInt::from_ne_array(bytes))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_be_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        UInt::read_be_bytes(reader).map(Self::sign_extend)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_le_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        UInt::read_le_bytes(reader).map(Self::sign_extend)
+    }
+
+    #[cfg(feature = "std")]
+    fn read_ne_bytes<R: Read>(reader: &mut R) -> io::Result<Self> {
+        UInt::read_ne_bytes(reader).map(Self::sign_extend)
+    }
+}
+
+impl<const BYTES: usize> WriteBytes for Int<BYTES>
+where
+    [u8; BYTES]: Default,
+{
+    type Bytes = [u8; BYTES];
+
+    fn to_be_array(&self) -> Self::Bytes {
+        self.truncate().to_be_array()
+    }
+
+    fn to_le_array(&self) -> Self::Bytes {
+        self.truncate().to_le_array()
+    }
+
+    fn to_ne_array(&self) -> Self::Bytes {
+        self.truncate().to_ne_array()
+    }
+
+    #[cfg(feature = "std")]
+    fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.truncate().write_be_bytes(writer)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.truncate().write_le_bytes(writer)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.truncate().write_ne_bytes(writer)
+    }
+}
@@ -0,0 +1,407 @@
+use std::io::{self, BufRead, ErrorKind, Read};
+
+use crate::{BufferAhead, ByteSink, ShortRead};
+
+/// A reader that bytes can be pushed back onto.
+///
+/// [`unread`] puts bytes in front of the rest of the stream, such as bytes
+/// a protocol sniffer read before deciding the stream isn't its protocol.
+/// Reads yield the pushed-back bytes first, and then continue with the inner
+/// reader. `fill_buf` returns the pushed-back bytes on their own, so the
+/// `BufReadExt` methods work across the seam between them and the inner
+/// reader, as they do across any two buffers.
+///
+/// Bytes come out in the order they were pushed back in: the bytes of
+/// each call to `unread` come after those of earlier calls that haven't
+/// been read yet.
+///
+/// `Unread` implements `Read` if the inner reader does, and `BufRead` if the
+/// inner reader is buffered. It then also implements [`BufferAhead`], by
+/// moving the bytes to look at from the inner reader to the pushed-back
+/// bytes, so [`BufReadExt::peek`] can look past the inner reader's buffer.
+///
+/// [`unread`]: #method.unread
+/// [`BufferAhead`]: trait.BufferAhead.html
+/// [`BufReadExt::peek`]: trait.BufReadExt.html#method.peek
+///
+/// # Examples
+///
+/// ```
+/// use std::io::BufReader;
+/// use omnom::prelude::*;
+/// use omnom::Unread;
+///
+/// let mut reader = Unread::new(BufReader::new(&b"\x00\x2a rest"[..]));
+/// let magic: u16 = reader.read_be().unwrap();
+/// assert_eq!(magic, 42);
+///
+/// // Not our protocol: hand the stream on as if it was never read.
+/// reader.unread(&magic.to_be_bytes());
+/// let mut all = vec![];
+/// reader.read_while(&mut all, |_| true).unwrap();
+/// assert_eq!(all, b"\x00\x2a rest");
+/// ```
+#[derive(Debug)]
+pub struct Unread<R> {
+    inner: R,
+    pushed: Vec<u8>,
+    pos: usize,
+    /// Where the bytes in `pushed` that were moved from the inner reader to
+    /// peek at them start, after the bytes that were pushed back.
+    ahead: usize,
+}
+
+impl<R> Unread<R> {
+    /// Create a new `Unread` with no bytes pushed back.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            pushed: Vec::new(),
+            pos: 0,
+            ahead: 0,
+        }
+    }
+
+    /// Push `bytes` back onto the stream.
+    ///
+    /// They're read after any pushed-back bytes that haven't been read yet,
+    /// and before the rest of the inner reader, including bytes that were
+    /// moved from it to peek at them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use omnom::Unread;
+    ///
+    /// let mut reader = Unread::new(&b"!"[..]);
+    /// reader.unread(b"hello");
+    /// reader.unread(b" world");
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "hello world!");
+    /// ```
+    pub fn unread(&mut self, bytes: &[u8]) {
+        self.compact();
+        let ahead = self.ahead;
+        self.pushed.splice(ahead..ahead, bytes.iter().copied());
+        self.ahead += bytes.len();
+    }
+
+    /// Returns a cursor that peeks ahead in the stream without consuming it.
+    ///
+    /// The bytes the cursor peeks at are moved from the inner reader to the
+    /// pushed-back bytes, which doesn't change what's read next. This allows
+    /// peeking any distance ahead, past the end of the inner reader's buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::BufReader;
+    /// use omnom::prelude::*;
+    /// use omnom::Unread;
+    ///
+    /// let mut reader = Unread::new(BufReader::with_capacity(2, &b"abc1"[..]));
+    /// let mut cursor = reader.peek_cursor();
+    /// let mut letters = vec![];
+    /// cursor.fill_while(&mut letters, |b| b.is_ascii_alphabetic()).unwrap();
+    /// assert_eq!(letters, b"abc");
+    /// assert_eq!(cursor.commit(), 3);
+    /// assert_eq!(reader.read_be::<u8>().unwrap(), b'1');
+    /// ```
+    pub fn peek_cursor(&mut self) -> PeekCursor<'_, R>
+    where
+        R: BufRead,
+    {
+        self.compact();
+        PeekCursor {
+            reader: self,
+            offset: 0,
+        }
+    }
+
+    /// Drop the bytes of `pushed` that were read.
+    fn compact(&mut self) {
+        if self.pos > 0 {
+            self.pushed.drain(..self.pos);
+            self.ahead = self.ahead.saturating_sub(self.pos);
+            self.pos = 0;
+        }
+    }
+
+    /// Returns the pushed-back bytes that haven't been read yet.
+    pub fn pushed_back(&self) -> &[u8] {
+        &self.pushed[self.pos..]
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Pushed-back bytes that haven't been read yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for Unread<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let pushed = self.pushed_back();
+        if pushed.is_empty() {
+            return self.inner.read(buf);
+        }
+        let len = pushed.len().min(buf.len());
+        buf[..len].copy_from_slice(&pushed[..len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Unread<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos < self.pushed.len() {
+            return Ok(&self.pushed[self.pos..]);
+        }
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if self.pos < self.pushed.len() {
+            self.pos = (self.pos + amt).min(self.pushed.len());
+        } else {
+            self.inner.consume(amt);
+        }
+    }
+}
+
+impl<R: BufRead> BufferAhead for Unread<R> {
+    fn buffer_ahead(&mut self, n: usize) -> io::Result<&[u8]> {
+        self.compact();
+        // Bytes that fit in the inner reader's buffer are returned from there,
+        // without moving them.
+        if self.pushed.is_empty() {
+            let len = loop {
+                match self.inner.fill_buf() {
+                    Ok(available) => break available.len(),
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            };
+            if len >= n || len == 0 {
+                return self.inner.fill_buf();
+            }
+        }
+        while self.pushed.len() < n {
+            let available = match self.inner.fill_buf() {
+                Ok([]) => break,
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.pushed.extend_from_slice(available);
+            let len = available.len();
+            self.inner.consume(len);
+        }
+        Ok(&self.pushed)
+    }
+}
+
+/// A cursor that peeks ahead in an [`Unread`] without consuming anything.
+///
+/// This is created by [`Unread::peek_cursor`]. Each method moves the cursor
+/// forward past the bytes it returns, so a single forward pass looks at each
+/// byte once. [`commit`] then consumes the bytes up to the cursor, while
+/// dropping the cursor consumes nothing.
+///
+/// The cursor borrows the reader mutably, so the reader can't be read from
+/// until the cursor is committed or dropped.
+///
+/// [`Unread`]: struct.Unread.html
+/// [`Unread::peek_cursor`]: struct.Unread.html#method.peek_cursor
+/// [`commit`]: #method.commit
+#[derive(Debug)]
+pub struct PeekCursor<'a, R> {
+    reader: &'a mut Unread<R>,
+    /// The position of the cursor in the pushed-back bytes.
+    offset: usize,
+}
+
+impl<R: BufRead> PeekCursor<'_, R> {
+    /// Returns the amount of bytes the cursor is ahead of the reader.
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the next byte, or `None` at EOF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Unread;
+    ///
+    /// let mut reader = Unread::new(&b"ab"[..]);
+    /// let mut cursor = reader.peek_cursor();
+    /// assert_eq!(cursor.next_byte().unwrap(), Some(b'a'));
+    /// assert_eq!(cursor.next_byte().unwrap(), Some(b'b'));
+    /// assert_eq!(cursor.next_byte().unwrap(), None);
+    /// assert_eq!(cursor.position(), 2);
+    /// ```
+    pub fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.fill_buf()?.first().copied();
+        if byte.is_some() {
+            self.offset += 1;
+        }
+        Ok(byte)
+    }
+
+    /// Peek at bytes while the predicate is true, appending them to `buf`.
+    ///
+    /// Returns the amount of bytes peeked. The byte that stopped the
+    /// predicate is left after the cursor.
+    pub fn fill_while<S, P>(&mut self, buf: &mut S, mut predicate: P) -> io::Result<usize>
+    where
+        S: ByteSink + ?Sized,
+        P: FnMut(u8) -> bool,
+    {
+        let mut read = 0;
+        loop {
+            let available = self.fill_buf()?;
+            let len = available
+                .iter()
+                .position(|b| !predicate(*b))
+                .unwrap_or(available.len());
+            let done = len < available.len() || available.is_empty();
+            buf.extend_from_slice(&available[..len])?;
+            self.offset += len;
+            read += len;
+            if done {
+                return Ok(read);
+            }
+        }
+    }
+
+    /// Peek at the bytes up to and including the delimiter `byte`,
+    /// appending them to `buf`.
+    ///
+    /// Returns the amount of bytes peeked, and whether the delimiter was
+    /// found, which is `false` only if the stream ends first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufRead, BufReader};
+    /// use omnom::Unread;
+    ///
+    /// let input = &b"Host: example.com\r\n"[..];
+    /// let mut reader = Unread::new(BufReader::with_capacity(4, input));
+    /// let mut line = vec![];
+    /// let res = reader.peek_cursor().fill_until(b'\n', &mut line).unwrap();
+    /// assert_eq!(res, (19, true));
+    ///
+    /// let mut read = vec![];
+    /// reader.read_until(b'\n', &mut read).unwrap();
+    /// assert_eq!(read, line);
+    /// ```
+    pub fn fill_until<S>(&mut self, byte: u8, buf: &mut S) -> io::Result<(usize, bool)>
+    where
+        S: ByteSink + ?Sized,
+    {
+        let mut read = 0;
+        loop {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                return Ok((read, false));
+            }
+            let (len, found) = match memchr::memchr(byte, available) {
+                Some(i) => (i + 1, true),
+                None => (available.len(), false),
+            };
+            buf.extend_from_slice(&available[..len])?;
+            self.offset += len;
+            read += len;
+            if found {
+                return Ok((read, true));
+            }
+        }
+    }
+
+    /// Peek at exactly `buf.len()` bytes.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` with a [`ShortRead`] if the stream
+    /// ends first, in which case the cursor doesn't move.
+    ///
+    /// [`ShortRead`]: struct.ShortRead.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::Unread;
+    ///
+    /// let mut reader = Unread::new(&b"GET /"[..]);
+    /// let mut cursor = reader.peek_cursor();
+    /// let mut method = [0; 3];
+    /// cursor.fill_exact(&mut method).unwrap();
+    /// assert_eq!(&method, b"GET");
+    /// assert!(cursor.fill_exact(&mut [0; 3]).is_err());
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    pub fn fill_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let start = self.offset;
+        let mut filled = 0;
+        while filled < buf.len() {
+            let available = match self.fill_buf() {
+                Ok([]) => {
+                    self.offset = start;
+                    return Err(ShortRead::new(buf.len(), filled).into());
+                }
+                Ok(available) => available,
+                Err(e) => {
+                    self.offset = start;
+                    return Err(e);
+                }
+            };
+            let len = available.len().min(buf.len() - filled);
+            buf[filled..filled + len].copy_from_slice(&available[..len]);
+            self.offset += len;
+            filled += len;
+        }
+        Ok(())
+    }
+
+    /// Consume the bytes up to the cursor from the reader.
+    ///
+    /// Returns the amount of bytes consumed.
+    pub fn commit(self) -> usize {
+        self.reader.pos += self.offset;
+        self.offset
+    }
+
+    /// Returns the bytes after the cursor, moving the next chunk of the
+    /// inner reader to the pushed-back bytes once they run out.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let reader = &mut *self.reader;
+        if self.offset == reader.pushed.len() {
+            loop {
+                match reader.inner.fill_buf() {
+                    Ok(available) => {
+                        reader.pushed.extend_from_slice(available);
+                        let len = available.len();
+                        reader.inner.consume(len);
+                        break;
+                    }
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(&reader.pushed[self.offset..])
+    }
+}
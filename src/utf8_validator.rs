@@ -0,0 +1,134 @@
+use std::io::{self, BufRead, ErrorKind, Read};
+use std::str;
+
+/// A reader that checks that the bytes passing through it are valid UTF-8.
+///
+/// `fill_buf` only returns complete characters that have been validated. A
+/// character split across two chunks of the underlying reader is held back
+/// until the rest of it arrives. Once the validated bytes run out at
+/// invalid UTF-8, `fill_buf` returns an error of the kind
+/// `ErrorKind::InvalidData` with the byte offset of the first invalid byte,
+/// as does a character that's cut off by EOF. The error is returned again
+/// by later calls.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufReader, ErrorKind, Read};
+/// use omnom::Utf8Validator;
+///
+/// let mut reader = Utf8Validator::new(BufReader::with_capacity(1, "añb".as_bytes()));
+/// let mut text = String::new();
+/// reader.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "añb");
+///
+/// let mut reader = Utf8Validator::new(&b"ok\xffno"[..]);
+/// let mut bytes = vec![];
+/// let err = reader.read_to_end(&mut bytes).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// assert_eq!(err.to_string(), "invalid UTF-8 at byte 2");
+/// assert_eq!(bytes, b"ok");
+/// ```
+#[derive(Debug)]
+pub struct Utf8Validator<R> {
+    inner: R,
+    /// Validated bytes, followed by the start of a character that isn't
+    /// complete yet.
+    buf: Vec<u8>,
+    pos: usize,
+    valid: usize,
+    /// The offset in the stream of the start of `buf`.
+    offset: u64,
+    /// The offset of the invalid bytes after the validated ones, and whether
+    /// they're a character cut off by EOF.
+    invalid: Option<(u64, bool)>,
+}
+
+impl<R> Utf8Validator<R> {
+    /// Create a new `Utf8Validator`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            valid: 0,
+            offset: 0,
+            invalid: None,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Bytes that were read from it but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Read for Utf8Validator<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Utf8Validator<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos == self.valid {
+            if let Some((offset, eof)) = self.invalid {
+                let msg = if eof {
+                    format!("incomplete UTF-8 character at byte {}", offset)
+                } else {
+                    format!("invalid UTF-8 at byte {}", offset)
+                };
+                return Err(io::Error::new(ErrorKind::InvalidData, msg));
+            }
+
+            // Keep only the incomplete character.
+            self.buf.drain(..self.valid);
+            self.offset += self.valid as u64;
+            self.pos = 0;
+            self.valid = 0;
+
+            let available = self.inner.fill_buf()?;
+            if available.is_empty() {
+                if self.buf.is_empty() {
+                    break;
+                }
+                self.invalid = Some((self.offset, true));
+                continue;
+            }
+            self.buf.extend_from_slice(available);
+            let len = available.len();
+            self.inner.consume(len);
+
+            match str::from_utf8(&self.buf) {
+                Ok(_) => self.valid = self.buf.len(),
+                Err(e) => {
+                    self.valid = e.valid_up_to();
+                    if e.error_len().is_some() {
+                        self.invalid = Some((self.offset + self.valid as u64, false));
+                    }
+                }
+            }
+        }
+        Ok(&self.buf[self.pos..self.valid])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.valid);
+    }
+}
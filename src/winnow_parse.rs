@@ -0,0 +1,73 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use winnow::error::ErrMode;
+use winnow::stream::{Partial, Stream};
+use winnow::Parser;
+
+use crate::drive::{drive, Outcome};
+use crate::{ParseError, ParseErrorKind};
+
+/// Run a `winnow` parser over a `BufRead`, wrapping its input in `Partial`.
+///
+/// The parser is first run on the reader's buffered bytes. While it returns
+/// `ErrMode::Incomplete`, the buffered bytes are consumed into an
+/// accumulation buffer and the parser is retried with the next chunk
+/// appended, so a value may span any number of `fill_buf` calls. On success
+/// exactly the bytes the parser used are consumed. The accumulation buffer
+/// only lives for a single call, so parsing many values in a loop doesn't
+/// grow it.
+///
+/// `ErrMode::Backtrack` and `ErrMode::Cut` are returned as
+/// `ErrorKind::InvalidData` wrapping a [`ParseError`] of kind
+/// `ParseErrorKind::Mismatch`. Its offset is where the parser stopped,
+/// counting from the reader's position when `parse_partial` was called, and
+/// its source is the `winnow` error's message. Running out of input while the
+/// parser is incomplete returns `ErrorKind::UnexpectedEof`.
+///
+/// A `BufRead` can't take bytes back once they're consumed. So if the parser
+/// fails, or succeeds using fewer bytes than an earlier attempt reported as
+/// incomplete, the bytes moved into the accumulation buffer stay consumed:
+/// the reader is left at the start of the last chunk the parser saw. If the
+/// value fits within a single `fill_buf` call nothing is consumed on failure.
+///
+/// [`ParseError`]: struct.ParseError.html
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufReader, Read};
+/// use winnow::binary::be_u16;
+/// use winnow::error::{ContextError, ErrMode};
+/// use winnow::stream::Partial;
+/// use winnow::Parser;
+///
+/// let mut reader = BufReader::with_capacity(1, &[0, 12, 1][..]);
+/// let parser = |i: &mut Partial<&[u8]>| be_u16::<_, ErrMode<ContextError>>.parse_next(i);
+/// let num = omnom::parse_partial(&mut reader, parser).unwrap();
+/// assert_eq!(num, 12);
+///
+/// let mut rest = vec![];
+/// reader.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, [1]);
+/// ```
+pub fn parse_partial<R, O, E, P>(reader: &mut R, mut parser: P) -> io::Result<O>
+where
+    R: BufRead + ?Sized,
+    P: for<'a> Parser<Partial<&'a [u8]>, O, ErrMode<E>>,
+    E: fmt::Display,
+{
+    drive(reader, |bytes| {
+        let mut input = Partial::new(bytes);
+        match parser.parse_next(&mut input) {
+            Ok(value) => Outcome::Done(value, bytes.len() - input.eof_offset()),
+            Err(ErrMode::Incomplete(_)) => Outcome::Incomplete,
+            Err(ErrMode::Backtrack(e)) | Err(ErrMode::Cut(e)) => {
+                let offset = bytes.len() - input.eof_offset();
+                let err =
+                    ParseError::new(offset, ParseErrorKind::Mismatch).with_source(e.to_string());
+                Outcome::Failed(err)
+            }
+        }
+    })
+}
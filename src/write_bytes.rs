@@ -1,23 +1,103 @@
+use core::mem;
+#[cfg(feature = "std")]
 use std::io::{self, Write};
 
 /// Trait to enable writing bytes to a writer.
 pub trait WriteBytes {
+    /// The byte array this type is encoded as, e.g. `[u8; 4]` for `u32`.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Returns the representation of this value as a big endian byte array.
+    fn to_be_array(&self) -> Self::Bytes;
+
+    /// Returns the representation of this value as a little endian byte array.
+    fn to_le_array(&self) -> Self::Bytes;
+
+    /// Returns the representation of this value as a byte array in native
+    /// endianness.
+    fn to_ne_array(&self) -> Self::Bytes;
+
+    /// Write bytes to the start of a slice as big endian.
+    ///
+    /// Returns the amount of bytes written, or `None` if the slice is too
+    /// short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::WriteBytes;
+    ///
+    /// let mut buf = [0; 3];
+    /// assert_eq!(12_u16.to_be_slice(&mut buf), Some(2));
+    /// assert_eq!(buf, [0, 12, 0]);
+    /// assert_eq!(12_u32.to_be_slice(&mut buf), None);
+    /// ```
+    fn to_be_slice(&self, slice: &mut [u8]) -> Option<usize> {
+        write_array(self.to_be_array(), slice)
+    }
+
+    /// Write bytes to the start of a slice as little endian.
+    ///
+    /// Returns the amount of bytes written, or `None` if the slice is too
+    /// short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::WriteBytes;
+    ///
+    /// let mut buf = [0; 3];
+    /// assert_eq!(12_u16.to_le_slice(&mut buf), Some(2));
+    /// assert_eq!(buf, [12, 0, 0]);
+    /// ```
+    fn to_le_slice(&self, slice: &mut [u8]) -> Option<usize> {
+        write_array(self.to_le_array(), slice)
+    }
+
+    /// Write bytes to the start of a slice using native endianness.
+    ///
+    /// Returns the amount of bytes written, or `None` if the slice is too
+    /// short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::WriteBytes;
+    ///
+    /// let mut buf = [0; 2];
+    /// assert_eq!(12_u16.to_ne_slice(&mut buf), Some(2));
+    /// assert_eq!(buf, 12_u16.to_ne_bytes());
+    /// ```
+    fn to_ne_slice(&self, slice: &mut [u8]) -> Option<usize> {
+        write_array(self.to_ne_array(), slice)
+    }
+
     /// Write bytes to a writer as big endian.
     ///
     /// Returns the amount of bytes written.
+    #[cfg(feature = "std")]
     fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize>;
 
     /// Write bytes to a writer as little endian.
     ///
     /// Returns the amount of bytes written.
+    #[cfg(feature = "std")]
     fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize>;
 
     /// Write bytes to a writer using native endianness.
     ///
     /// Returns the amount of bytes written.
+    #[cfg(feature = "std")]
     fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize>;
 }
 
+/// Copy a byte array to the start of `slice`.
+fn write_array<B: AsRef<[u8]>>(bytes: B, slice: &mut [u8]) -> Option<usize> {
+    let bytes = bytes.as_ref();
+    slice.get_mut(..bytes.len())?.copy_from_slice(bytes);
+    Some(bytes.len())
+}
+
 macro_rules! doc_comment {
     ($x:expr, $($tt:tt)*) => {
         #[doc = $x]
@@ -28,6 +108,20 @@ macro_rules! doc_comment {
 macro_rules! write_bytes_impl {
     ($($SelfT:ty),* $(,)?) => { $(
         impl WriteBytes for $SelfT {
+        type Bytes = [u8; mem::size_of::<$SelfT>()];
+
+        fn to_be_array(&self) -> Self::Bytes {
+            self.to_be_bytes()
+        }
+
+        fn to_le_array(&self) -> Self::Bytes {
+            self.to_le_bytes()
+        }
+
+        fn to_ne_array(&self) -> Self::Bytes {
+            self.to_ne_bytes()
+        }
+
         doc_comment! {
             concat!("Write bytes to a writer as big endian.
 
@@ -42,6 +136,7 @@ let mut buf = Cursor::new(vec![0; 15]);
 let num = 12_", stringify!($SelfT), ";
 buf.write_be(num).unwrap();
 ```"),
+            #[cfg(feature = "std")]
             fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
                 let b = &self.to_be_bytes();
                 let len = b.len();
@@ -64,6 +159,7 @@ let mut buf = Cursor::new(vec![0; 15]);
 let num = 12_", stringify!($SelfT), ";
 buf.write_le(num).unwrap();
 ```"),
+            #[cfg(feature = "std")]
             fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
                 let b = &self.to_le_bytes();
                 let len = b.len();
@@ -93,6 +189,7 @@ let mut buf = Cursor::new(vec![0; 15]);
 let num = 12_", stringify!($SelfT), ";
 buf.write_ne(num).unwrap();
 ```"),
+            #[cfg(feature = "std")]
             fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
                 let b = &self.to_ne_bytes();
                 let len = b.len();
@@ -103,5 +200,40 @@ buf.write_ne(num).unwrap();
     }
 )*}}
 
-write_bytes_impl!(u8, u16, u32, u64, u128, usize);
-write_bytes_impl!(i8, i16, i32, i64, i128, isize);
+int_types!(write_bytes_impl);
+
+/// Byte arrays are written as-is, regardless of endianness.
+impl<const N: usize> WriteBytes for [u8; N]
+where
+    [u8; N]: Default,
+{
+    type Bytes = [u8; N];
+
+    fn to_be_array(&self) -> Self::Bytes {
+        *self
+    }
+
+    fn to_le_array(&self) -> Self::Bytes {
+        *self
+    }
+
+    fn to_ne_array(&self) -> Self::Bytes {
+        *self
+    }
+
+    #[cfg(feature = "std")]
+    fn write_be_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(self)?;
+        Ok(N)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_le_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.write_be_bytes(writer)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_ne_bytes<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        self.write_be_bytes(writer)
+    }
+}
@@ -1,5 +1,8 @@
-use crate::WriteBytes;
-use std::io::{self, Write};
+use crate::buf_read_ext::{MAX_LABELS_LEN, MAX_LABEL_LEN};
+use crate::{leb128, ByteSet, FlushGuard, SwapBytes, WriteBytes};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{self, ErrorKind, Write};
 
 /// Extension trait to `Write` to write bytes using endianness.
 pub trait WriteExt: Write + Sized {
@@ -23,6 +26,673 @@ pub trait WriteExt: Write + Sized {
     fn write_ne<B: WriteBytes>(&mut self, num: B) -> io::Result<usize> {
         num.write_ne_bytes(self)
     }
+
+    /// Write bytes using the opposite of native endianness.
+    ///
+    /// This writes what [`WriteExt::write_ne`] writes on a machine of the
+    /// other endianness, for [`ReadExt::read_ne`] to read there.
+    ///
+    /// Returns the amount of bytes written.
+    ///
+    /// [`WriteExt::write_ne`]: #method.write_ne
+    /// [`ReadExt::read_ne`]: trait.ReadExt.html#method.read_ne
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    /// use omnom::SwapBytes;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_swapped(0x0102_u16).unwrap();
+    /// let num: u16 = buf.as_slice().read_ne().unwrap();
+    /// assert_eq!(num, 0x0102_u16.to_opposite_endian());
+    /// ```
+    fn write_swapped<B: WriteBytes + SwapBytes>(&mut self, num: B) -> io::Result<usize> {
+        if cfg!(target_endian = "little") {
+            num.write_be_bytes(self)
+        } else {
+            num.write_le_bytes(self)
+        }
+    }
+
+    /// Write the length of `items` as a big endian `L`, followed by the items
+    /// as big endian.
+    ///
+    /// The items are converted with [`WriteBytes::to_be_array`] and written
+    /// with a single `write_all`. Returns the amount of bytes written, or
+    /// `ErrorKind::InvalidInput` without writing anything if the length
+    /// doesn't fit in an `L`.
+    ///
+    /// [`WriteBytes::to_be_array`]: trait.WriteBytes.html#tymethod.to_be_array
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_vec_prefixed_be::<u16, u16>(&[1, 2]).unwrap();
+    /// assert_eq!(buf, [0, 2, 0, 1, 0, 2]);
+    ///
+    /// let err = buf.write_vec_prefixed_be::<u8, u8>(&[0; 256]).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    /// ```
+    fn write_vec_prefixed_be<L, T>(&mut self, items: &[T]) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        T: WriteBytes,
+    {
+        let len = prefix::<L>(items.len())?;
+        write_vec(self, len.to_be_array(), items, T::to_be_array)
+    }
+
+    /// Write the length of `items` as a little endian `L`, followed by the
+    /// items as little endian.
+    ///
+    /// See [`write_vec_prefixed_be`] for details.
+    ///
+    /// [`write_vec_prefixed_be`]: #method.write_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_vec_prefixed_le::<u16, u16>(&[1, 2]).unwrap();
+    /// assert_eq!(buf, [2, 0, 1, 0, 2, 0]);
+    /// ```
+    fn write_vec_prefixed_le<L, T>(&mut self, items: &[T]) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        T: WriteBytes,
+    {
+        let len = prefix::<L>(items.len())?;
+        write_vec(self, len.to_le_array(), items, T::to_le_array)
+    }
+
+    /// Write the length of `items` as an `L`, followed by the items, using
+    /// native endianness.
+    ///
+    /// See [`write_vec_prefixed_be`] for details.
+    ///
+    /// [`write_vec_prefixed_be`]: #method.write_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// assert_eq!(buf.write_vec_prefixed_ne::<u32, u8>(&[1, 2]).unwrap(), 6);
+    /// ```
+    fn write_vec_prefixed_ne<L, T>(&mut self, items: &[T]) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        T: WriteBytes,
+    {
+        let len = prefix::<L>(items.len())?;
+        write_vec(self, len.to_ne_array(), items, T::to_ne_array)
+    }
+
+    /// Write the length of `map` as a big endian `L`, followed by its entries
+    /// as pairs of a big endian key and a big endian value.
+    ///
+    /// The entries are written in ascending order of their keys, so the same
+    /// map always produces the same bytes. Like [`write_vec_prefixed_be`],
+    /// they're written with a single `write_all`, and
+    /// `ErrorKind::InvalidInput` is returned without writing anything if the
+    /// length doesn't fit in an `L`.
+    ///
+    /// [`write_vec_prefixed_be`]: #method.write_vec_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use omnom::prelude::*;
+    ///
+    /// let map: HashMap<u8, u16> = [(2, 20), (1, 10)].iter().copied().collect();
+    /// let mut buf = vec![];
+    /// buf.write_map_prefixed_be::<u8, _, _>(&map).unwrap();
+    /// assert_eq!(buf, [2, 1, 0, 10, 2, 0, 20]);
+    /// ```
+    fn write_map_prefixed_be<L, K, V>(&mut self, map: &HashMap<K, V>) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        K: WriteBytes + Ord,
+        V: WriteBytes,
+    {
+        let len = prefix::<L>(map.len())?;
+        write_map(self, len.to_be_array(), map, K::to_be_array, V::to_be_array)
+    }
+
+    /// Write the length of `map` as a little endian `L`, followed by its
+    /// entries as pairs of a little endian key and a little endian value.
+    ///
+    /// See [`write_map_prefixed_be`] for details.
+    ///
+    /// [`write_map_prefixed_be`]: #method.write_map_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use omnom::prelude::*;
+    ///
+    /// let map: HashMap<u8, u16> = [(2, 20), (1, 10)].iter().copied().collect();
+    /// let mut buf = vec![];
+    /// buf.write_map_prefixed_le::<u16, _, _>(&map).unwrap();
+    /// assert_eq!(buf, [2, 0, 1, 10, 0, 2, 20, 0]);
+    /// ```
+    fn write_map_prefixed_le<L, K, V>(&mut self, map: &HashMap<K, V>) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        K: WriteBytes + Ord,
+        V: WriteBytes,
+    {
+        let len = prefix::<L>(map.len())?;
+        write_map(self, len.to_le_array(), map, K::to_le_array, V::to_le_array)
+    }
+
+    /// Write the length of `map` as an `L`, followed by its entries as pairs
+    /// of a key and a value, using native endianness.
+    ///
+    /// See [`write_map_prefixed_be`] for details.
+    ///
+    /// [`write_map_prefixed_be`]: #method.write_map_prefixed_be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use omnom::prelude::*;
+    ///
+    /// let map: HashMap<u8, u8> = [(2, 20), (1, 10)].iter().copied().collect();
+    /// let mut buf = vec![];
+    /// buf.write_map_prefixed_ne::<u8, _, _>(&map).unwrap();
+    /// assert_eq!(buf, [2, 1, 10, 2, 20]);
+    /// ```
+    fn write_map_prefixed_ne<L, K, V>(&mut self, map: &HashMap<K, V>) -> io::Result<usize>
+    where
+        L: WriteBytes + TryFrom<usize>,
+        K: WriteBytes + Ord,
+        V: WriteBytes,
+    {
+        let len = prefix::<L>(map.len())?;
+        write_map(self, len.to_ne_array(), map, K::to_ne_array, V::to_ne_array)
+    }
+
+    /// Write a bitmap of booleans, packed 8 to a byte.
+    ///
+    /// Bits are stored least significant bit first, as in Arrow and Parquet,
+    /// and the padding bits of the last byte are zero. Returns the amount of
+    /// bytes written, which is `bits.len()` rounded up to whole bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// let bits = [true, false, true, false, false, false, false, false, true];
+    /// assert_eq!(buf.write_bitmap(&bits).unwrap(), 2);
+    /// assert_eq!(buf, [0b0000_0101, 0b0000_0001]);
+    /// ```
+    fn write_bitmap(&mut self, bits: &[bool]) -> io::Result<usize> {
+        let mut bytes = [0; 64];
+        let mut written = 0;
+        for chunk in bits.chunks(bytes.len() * 8) {
+            let bytes = &mut bytes[..chunk.len().div_ceil(8)];
+            bytes.iter_mut().for_each(|byte| *byte = 0);
+            for (i, &bit) in chunk.iter().enumerate() {
+                bytes[i / 8] |= u8::from(bit) << (i % 8);
+            }
+            self.write_all(bytes)?;
+            written += bytes.len();
+        }
+        Ok(written)
+    }
+
+    /// Write `data` as a field of exactly `width` bytes, filling the rest of
+    /// the field with `pad`.
+    ///
+    /// This is for fixed-width fields such as the NUL-padded names in a tar
+    /// header. `policy` decides what happens if `data` is longer than
+    /// `width`. The field is written with a single `write_all`, and nothing
+    /// is written if it's rejected. Returns the amount of bytes written,
+    /// which is always `width`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    /// use omnom::OverflowPolicy;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_padded(b"abc", 5, b' ', OverflowPolicy::Error).unwrap();
+    /// buf.write_padded(b"defgh", 3, 0, OverflowPolicy::Truncate).unwrap();
+    /// assert_eq!(buf, b"abc  def");
+    ///
+    /// let err = buf.write_padded(b"defgh", 3, 0, OverflowPolicy::Error).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    /// ```
+    fn write_padded(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        pad: u8,
+        policy: OverflowPolicy,
+    ) -> io::Result<usize> {
+        let data = match policy {
+            _ if data.len() <= width => data,
+            OverflowPolicy::Truncate => &data[..width],
+            OverflowPolicy::Error => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "{} bytes don't fit in a field of {} bytes",
+                        data.len(),
+                        width
+                    ),
+                ))
+            }
+        };
+        let mut stack = [0; 256];
+        let mut heap = Vec::new();
+        let field = if width <= stack.len() {
+            &mut stack[..width]
+        } else {
+            heap.resize(width, 0);
+            &mut heap[..]
+        };
+        field[..data.len()].copy_from_slice(data);
+        field[data.len()..].iter_mut().for_each(|byte| *byte = pad);
+        self.write_all(field)?;
+        Ok(width)
+    }
+
+    /// Write `payload` as a message delimited by its length, as a Protocol
+    /// Buffers varint.
+    ///
+    /// This is the framing of protobuf's `writeDelimitedTo`, which
+    /// [`BufReadExt::read_delimited_message`] reads back. Returns the amount
+    /// of bytes written, including the length.
+    ///
+    /// [`BufReadExt::read_delimited_message`]: trait.BufReadExt.html#method.read_delimited_message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// assert_eq!(buf.write_delimited_message(&[0x08, 0x96, 0x01]).unwrap(), 4);
+    /// assert_eq!(buf.write_delimited_message(&[0; 300]).unwrap(), 302);
+    /// assert_eq!(buf[..6], [0x03, 0x08, 0x96, 0x01, 0xac, 0x02]);
+    /// ```
+    fn write_delimited_message(&mut self, payload: &[u8]) -> io::Result<usize> {
+        let mut len = [0; leb128::MAX_LEN];
+        let len = leb128::encode(payload.len() as u64, &mut len);
+        self.write_all(len)?;
+        self.write_all(payload)?;
+        Ok(len.len() + payload.len())
+    }
+
+    /// Write an MQTT remaining length, a variable byte integer of 1 to 4
+    /// bytes.
+    ///
+    /// The value is written in as few bytes as possible, as
+    /// [`ReadExt::read_mqtt_remaining_len`] requires. Returns the amount of
+    /// bytes written, or `ErrorKind::InvalidInput` if `n` is over
+    /// 268,435,455, the largest value 4 bytes hold.
+    ///
+    /// [`ReadExt::read_mqtt_remaining_len`]: trait.ReadExt.html#method.read_mqtt_remaining_len
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// assert_eq!(buf.write_mqtt_remaining_len(321).unwrap(), 2);
+    /// assert_eq!(buf, [0xc1, 0x02]);
+    /// assert!(buf.write_mqtt_remaining_len(1 << 28).is_err());
+    /// ```
+    fn write_mqtt_remaining_len(&mut self, n: u32) -> io::Result<usize> {
+        if n > MQTT_MAX_REMAINING_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "remaining length {} exceeds the maximum of {}",
+                    n, MQTT_MAX_REMAINING_LEN
+                ),
+            ));
+        }
+        let mut buf = [0; leb128::MAX_LEN];
+        let buf = leb128::encode(u64::from(n), &mut buf);
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    /// Write `name` as a sequence of length-prefixed labels, such as a DNS
+    /// name.
+    ///
+    /// `name` is split into labels at `separator`, and each label is written
+    /// as its length byte followed by its bytes, ending with a zero length.
+    /// An empty `name` or a lone separator is the root name, and a trailing
+    /// separator is ignored. Returns the amount of bytes written.
+    ///
+    /// This is the counterpart of [`BufReadExt::read_label_sequence`]. It
+    /// returns `ErrorKind::InvalidInput` without writing anything if a label
+    /// is empty or longer than 63 bytes, or the sequence is longer than 255
+    /// bytes.
+    ///
+    /// [`BufReadExt::read_label_sequence`]: trait.BufReadExt.html#method.read_label_sequence
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// assert_eq!(buf.write_label_sequence(b"example.com.", b'.').unwrap(), 13);
+    /// assert_eq!(buf, b"\x07example\x03com\x00");
+    /// ```
+    fn write_label_sequence(&mut self, name: &[u8], separator: u8) -> io::Result<usize> {
+        let name = match name.split_last() {
+            Some((&last, rest)) if last == separator => rest,
+            _ => name,
+        };
+        let mut buf = Vec::with_capacity(name.len() + 2);
+        if !name.is_empty() {
+            for label in name.split(|b| *b == separator) {
+                if label.is_empty() || label.len() > MAX_LABEL_LEN {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("label of {} bytes isn't within 1 to 63 bytes", label.len()),
+                    ));
+                }
+                buf.push(label.len() as u8);
+                buf.extend_from_slice(label);
+            }
+        }
+        buf.push(0);
+        if buf.len() > MAX_LABELS_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "label sequence of {} bytes exceeds the maximum of 255",
+                    buf.len()
+                ),
+            ));
+        }
+        self.write_all(&buf)?;
+        Ok(buf.len())
+    }
+
+    /// Write a `char` encoded as UTF-8.
+    ///
+    /// Returns the amount of bytes written, from 1 to 4.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// assert_eq!(buf.write_utf8_char('a').unwrap(), 1);
+    /// assert_eq!(buf.write_utf8_char('€').unwrap(), 3);
+    /// assert_eq!(buf, "a€".as_bytes());
+    /// ```
+    fn write_utf8_char(&mut self, ch: char) -> io::Result<usize> {
+        let mut buf = [0; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+        self.write_all(encoded.as_bytes())?;
+        Ok(encoded.len())
+    }
+
+    /// Write `s`, with each byte in `needs_escape` preceded by `escape`.
+    ///
+    /// The bytes between escaped bytes are written with a single
+    /// `write_all` per run, so a string without bytes to escape takes a
+    /// single write. `needs_escape` should usually hold `escape` itself, so
+    /// that [`BufReadExt::read_record`] reads back the same string. Returns
+    /// the amount of bytes written.
+    ///
+    /// [`BufReadExt::read_record`]: trait.BufReadExt.html#method.read_record
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    /// use omnom::ByteSet;
+    ///
+    /// let mut buf = vec![];
+    /// let special = ByteSet::new(b"\"\\");
+    /// buf.write_str_escaped(r#"say "hi" \o/"#, b'\\', &special).unwrap();
+    /// assert_eq!(buf, br#"say \"hi\" \\o/"#);
+    /// ```
+    fn write_str_escaped(
+        &mut self,
+        s: &str,
+        escape: u8,
+        needs_escape: &ByteSet,
+    ) -> io::Result<usize> {
+        let mut rest = s.as_bytes();
+        let mut written = 0;
+        while let Some(i) = rest.iter().position(|b| needs_escape.contains(*b)) {
+            if i > 0 {
+                self.write_all(&rest[..i])?;
+            }
+            self.write_all(&[escape, rest[i]])?;
+            written += i + 2;
+            rest = &rest[i + 1..];
+        }
+        if !rest.is_empty() {
+            self.write_all(rest)?;
+        }
+        Ok(written + rest.len())
+    }
+
+    /// Write an `f64` as the shortest decimal text that parses back to the
+    /// same value.
+    ///
+    /// The text is formatted with the Ryū algorithm into a stack buffer, and
+    /// written with a single `write_all`. It uses scientific notation for
+    /// very large and very small values, as in `1e300`, and always has a
+    /// fraction or an exponent otherwise, as in `1.0`. Negative zero keeps
+    /// its sign as `-0.0`, and the special values are written as `NaN`, `inf`
+    /// and `-inf`, which `str::parse` accepts as well. Returns the amount of
+    /// bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_ascii_float(0.1).unwrap();
+    /// buf.write_ascii_float(-1e300).unwrap();
+    /// buf.write_ascii_float(f64::NAN).unwrap();
+    /// assert_eq!(buf, b"0.1-1e300NaN");
+    /// ```
+    #[cfg(feature = "ryu")]
+    fn write_ascii_float(&mut self, f: f64) -> io::Result<usize> {
+        write_float(self, f)
+    }
+
+    /// Write an `f32` as the shortest decimal text that parses back to the
+    /// same value.
+    ///
+    /// This formats like [`write_ascii_float`], with the shortest text for the
+    /// `f32` rather than for the `f64` it converts to: `0.1_f32` is written
+    /// as `0.1`.
+    ///
+    /// [`write_ascii_float`]: #method.write_ascii_float
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use omnom::prelude::*;
+    ///
+    /// let mut buf = vec![];
+    /// buf.write_ascii_float_f32(0.1).unwrap();
+    /// buf.write_ascii_float_f32(-0.0).unwrap();
+    /// assert_eq!(buf, b"0.1-0.0");
+    /// ```
+    #[cfg(feature = "ryu")]
+    fn write_ascii_float_f32(&mut self, f: f32) -> io::Result<usize> {
+        write_float(self, f)
+    }
+
+    /// Returns a guard that flushes the writer when it's dropped.
+    ///
+    /// This is for sections written through a `BufWriter`, which must be
+    /// flushed on every path out of the function that writes them. Call
+    /// [`FlushGuard::finish`] at the end to get the error of the flush,
+    /// which is ignored when the guard is dropped.
+    ///
+    /// [`FlushGuard::finish`]: struct.FlushGuard.html#method.finish
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, BufWriter, Write};
+    /// use omnom::prelude::*;
+    ///
+    /// fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    ///     let mut frame = writer.scoped_flush();
+    ///     frame.write_be(payload.len() as u16)?;
+    ///     if payload.is_empty() {
+    ///         return Ok(());
+    ///     }
+    ///     frame.write_all(payload)?;
+    ///     frame.finish()
+    /// }
+    ///
+    /// let mut writer = BufWriter::new(vec![]);
+    /// write_frame(&mut writer, b"").unwrap();
+    /// assert_eq!(writer.get_ref(), &[0, 0]);
+    /// ```
+    fn scoped_flush(&mut self) -> FlushGuard<'_, Self> {
+        FlushGuard::new(self)
+    }
 }
 
 impl<T: Write> WriteExt for T {}
+
+/// The largest value an MQTT variable byte integer holds.
+const MQTT_MAX_REMAINING_LEN: u32 = (1 << 28) - 1;
+
+/// What [`WriteExt::write_padded`] does with data longer than its field.
+///
+/// [`WriteExt::write_padded`]: trait.WriteExt.html#method.write_padded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Reject the data with `ErrorKind::InvalidInput`.
+    Error,
+    /// Write only the first `width` bytes of the data.
+    Truncate,
+}
+
+fn prefix<L: TryFrom<usize>>(len: usize) -> io::Result<L> {
+    L::try_from(len).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} elements don't fit in the length prefix", len),
+        )
+    })
+}
+
+/// Returns the size of the byte array of `T`.
+fn size_of<T: WriteBytes>() -> usize {
+    T::Bytes::default().as_ref().len()
+}
+
+/// Write `prefix` and `records` of `size` bytes in one go, converting each
+/// record with `encode`.
+fn write_records<W, B, I>(
+    writer: &mut W,
+    prefix: B,
+    records: I,
+    size: usize,
+    mut encode: impl FnMut(&mut Vec<u8>, I::Item),
+) -> io::Result<usize>
+where
+    W: Write,
+    B: AsRef<[u8]>,
+    I: ExactSizeIterator,
+{
+    let mut bytes = Vec::with_capacity(prefix.as_ref().len() + records.len() * size);
+    bytes.extend_from_slice(prefix.as_ref());
+    for record in records {
+        encode(&mut bytes, record);
+    }
+    writer.write_all(&bytes)?;
+    Ok(bytes.len())
+}
+
+/// Write `prefix` and `items`, converting each item with `to_array`.
+fn write_vec<W, B, T>(
+    writer: &mut W,
+    prefix: B,
+    items: &[T],
+    to_array: fn(&T) -> T::Bytes,
+) -> io::Result<usize>
+where
+    W: Write,
+    B: AsRef<[u8]>,
+    T: WriteBytes,
+{
+    write_records(
+        writer,
+        prefix,
+        items.iter(),
+        size_of::<T>(),
+        |bytes, item| bytes.extend_from_slice(to_array(item).as_ref()),
+    )
+}
+
+/// Write `prefix` and the entries of `map` sorted by key, converting each key
+/// and value with `key` and `value`.
+fn write_map<W, B, K, V>(
+    writer: &mut W,
+    prefix: B,
+    map: &HashMap<K, V>,
+    key: fn(&K) -> K::Bytes,
+    value: fn(&V) -> V::Bytes,
+) -> io::Result<usize>
+where
+    W: Write,
+    B: AsRef<[u8]>,
+    K: WriteBytes + Ord,
+    V: WriteBytes,
+{
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    let size = size_of::<K>() + size_of::<V>();
+    write_records(
+        writer,
+        prefix,
+        entries.into_iter(),
+        size,
+        |bytes, (k, v)| {
+            bytes.extend_from_slice(key(k).as_ref());
+            bytes.extend_from_slice(value(v).as_ref());
+        },
+    )
+}
+
+#[cfg(feature = "ryu")]
+fn write_float<W, F>(writer: &mut W, f: F) -> io::Result<usize>
+where
+    W: Write,
+    F: ryu::Float,
+{
+    let mut buf = ryu::Buffer::new();
+    let text = buf.format(f);
+    writer.write_all(text.as_bytes())?;
+    Ok(text.len())
+}
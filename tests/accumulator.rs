@@ -0,0 +1,113 @@
+#![cfg(feature = "std")]
+
+use omnom::{Accumulator, FeedStatus};
+use std::collections::VecDeque;
+use std::io::{self, BufReader, ErrorKind, Read};
+
+/// A non-blocking reader, which returns `WouldBlock` wherever `events` has a
+/// `None`.
+struct NonBlocking {
+    events: VecDeque<Option<Vec<u8>>>,
+}
+
+impl NonBlocking {
+    fn new(events: &[Option<&[u8]>]) -> Self {
+        Self {
+            events: events.iter().map(|e| e.map(<[u8]>::to_vec)).collect(),
+        }
+    }
+}
+
+impl Read for NonBlocking {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.events.pop_front() {
+            None => Ok(0),
+            Some(None) => Err(ErrorKind::WouldBlock.into()),
+            Some(Some(mut data)) => {
+                let len = data.len().min(buf.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                if len < data.len() {
+                    self.events.push_front(Some(data.split_off(len)));
+                }
+                Ok(len)
+            }
+        }
+    }
+}
+
+#[test]
+fn token_across_three_feeds() {
+    let reader = NonBlocking::new(&[
+        Some(b"HEL"),
+        None,
+        Some(b"LO W"),
+        None,
+        Some(b"ORLD\n"),
+        None,
+    ]);
+    let mut reader = BufReader::new(reader);
+    let mut acc = Accumulator::new(64);
+
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::WouldBlock);
+    assert_eq!(acc.take_until(b'\n'), None);
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::WouldBlock);
+    assert_eq!(acc.take_until(b'\n'), None);
+    assert_eq!(acc.as_slice(), b"HELLO W");
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::WouldBlock);
+    assert_eq!(acc.take_until(b'\n').unwrap(), b"HELLO WORLD\n");
+    assert!(acc.is_empty());
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::Eof);
+}
+
+#[test]
+fn many_tokens_in_one_feed() {
+    let mut reader = BufReader::new(NonBlocking::new(&[Some(b"a\nbb\nccc\ndd"), None]));
+    let mut acc = Accumulator::new(64);
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::WouldBlock);
+
+    let mut tokens = vec![];
+    while let Some(token) = acc.take_until(b'\n') {
+        tokens.push(token);
+    }
+    assert_eq!(tokens, [&b"a\n"[..], b"bb\n", b"ccc\n"]);
+    assert_eq!(acc.as_slice(), b"dd");
+}
+
+#[test]
+fn size_cap() {
+    let mut reader = BufReader::new(NonBlocking::new(&[Some(b"0123456789")]));
+    let mut acc = Accumulator::new(4);
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::Full);
+    assert!(acc.is_full());
+    assert_eq!(acc.as_slice(), b"0123");
+
+    // Full stays full until tokens are taken out.
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::Full);
+    assert_eq!(acc.take_exact(3).unwrap(), b"012");
+    assert_eq!(acc.feed(&mut reader).unwrap(), FeedStatus::Full);
+    assert_eq!(acc.as_slice(), b"3456");
+}
+
+#[test]
+fn no_data_lost_across_feeds() {
+    let input: Vec<u8> = (0..=255).collect();
+    let events: Vec<_> = input
+        .chunks(7)
+        .flat_map(|chunk| vec![Some(chunk), None])
+        .collect();
+    let mut reader = BufReader::with_capacity(5, NonBlocking::new(&events));
+    let mut acc = Accumulator::new(16);
+
+    let mut out = vec![];
+    loop {
+        let status = acc.feed(&mut reader).unwrap();
+        while let Some(token) = acc.take_exact(4) {
+            out.extend_from_slice(&token);
+        }
+        if status == FeedStatus::Eof {
+            break;
+        }
+    }
+    assert_eq!(out, input);
+    assert!(acc.is_empty());
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::{BufRead, BufReader};
+use std::ops::ControlFlow;
+
+/// An allocator counting the allocations and reallocations of each thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let value = f();
+    (value, ALLOCATIONS.with(Cell::get) - before)
+}
+
+const TOKEN_LEN: usize = 1024 * 1024;
+
+fn input() -> Vec<u8> {
+    let mut input = vec![b'a'; TOKEN_LEN];
+    input.push(b' ');
+    input
+}
+
+#[test]
+fn read_while_grows_by_chunks() {
+    let input = input();
+    let mut reader = BufReader::new(&input[..]);
+    let mut buf = vec![];
+
+    let (read, count) = allocations(|| reader.read_while(&mut buf, |b| b != b' ').unwrap());
+    assert_eq!(read, TOKEN_LEN);
+    assert_eq!(buf.len(), TOKEN_LEN);
+    // Growing one byte at a time took 20+ reallocations for a 1 MiB token;
+    // growing by 8 KiB chunks doubles from 8 KiB instead.
+    assert!(count <= 10, "{} allocations", count);
+}
+
+#[test]
+fn read_while_with_capacity_allocates_once() {
+    let input = input();
+    let mut reader = BufReader::new(&input[..]);
+    let mut buf = vec![];
+
+    let (read, count) = allocations(|| {
+        reader
+            .read_while_with_capacity(&mut buf, TOKEN_LEN, |b| b != b' ')
+            .unwrap()
+    });
+    assert_eq!(read, TOKEN_LEN);
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn for_each_line_borrows_from_the_buffer() {
+    let input = b"short line\n".repeat(1000);
+    let mut reader = BufReader::with_capacity(input.len(), &input[..]);
+    reader.fill_buf().unwrap();
+
+    let mut total = 0;
+    let (lines, count) = allocations(|| {
+        reader
+            .for_each_line(|line| {
+                total += line.len();
+                Ok(ControlFlow::Continue(()))
+            })
+            .unwrap()
+    });
+    assert_eq!(lines, 1000);
+    assert_eq!(total, 10 * 1000);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn skip_bytes_does_not_allocate() {
+    let input = input();
+    let mut reader = &input[..];
+
+    let (skipped, count) = allocations(|| reader.skip_bytes(TOKEN_LEN as u64).unwrap());
+    assert_eq!(skipped, TOKEN_LEN as u64);
+    assert_eq!(count, 0);
+    assert_eq!(reader, b" ");
+}
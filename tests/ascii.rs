@@ -0,0 +1,370 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::BoolStyle;
+use std::io::{BufRead, BufReader, Cursor, ErrorKind, Read};
+use std::net::Ipv4Addr;
+
+#[test]
+fn read_number_radix() {
+    let cases: &[(&[u8], i64, &[u8])] = &[
+        (b"42", 42, b""),
+        (b"+42;", 42, b";"),
+        (b"-42", -42, b""),
+        (b"0x1F", 0x1f, b""),
+        (b"0XfF", 0xff, b""),
+        (b"0o17", 0o17, b""),
+        (b"0b1010", 0b1010, b""),
+        (b"-0b1", -1, b""),
+        (b"0b102", 2, b"2"),
+        (b"0xffg", 0xff, b"g"),
+        (b"0", 0, b""),
+        (b"0)", 0, b")"),
+        (b"0y", 0, b"y"),
+        (b"010", 10, b""),
+        (b"1_000_000", 1_000_000, b""),
+        (b"0x_ff_", 0xff, b""),
+        (b"0_1", 1, b""),
+        (b"9223372036854775807", i64::MAX, b""),
+        (b"-9223372036854775808", i64::MIN, b""),
+        (b"-0x8000000000000000", i64::MIN, b""),
+    ];
+    for (input, value, rest) in cases {
+        let mut reader = BufReader::with_capacity(2, *input);
+        assert_eq!(reader.read_number_radix().unwrap(), *value, "{:?}", input);
+        let mut remaining = vec![];
+        reader.read_while(&mut remaining, |_| true).unwrap();
+        assert_eq!(&remaining, rest, "{:?}", input);
+    }
+}
+
+#[test]
+fn read_number_radix_errors() {
+    let cases: &[&[u8]] = &[
+        b"",
+        b"x",
+        b"-",
+        b"_1",
+        b"0x",
+        b"0xg",
+        b"0b_",
+        b"9223372036854775808",
+        b"-9223372036854775809",
+        b"0x1_0000_0000_0000_0000",
+    ];
+    for input in cases {
+        let err = Cursor::new(input).read_number_radix().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", input);
+    }
+
+    // The whole digit run is consumed on overflow.
+    let mut reader = Cursor::new(b"99999999999999999999,1");
+    assert!(reader.read_number_radix().is_err());
+    assert_eq!(reader.fill_buf().unwrap(), b",1");
+}
+
+#[test]
+fn read_unsigned_radix() {
+    let mut reader = Cursor::new(b"18446744073709551615 0xFFFFFFFFFFFFFFFF -1");
+    assert_eq!(reader.read_unsigned_radix().unwrap(), u64::MAX);
+    assert!(reader.consume_prefix(b" ").unwrap());
+    assert_eq!(reader.read_unsigned_radix().unwrap(), u64::MAX);
+    assert!(reader.consume_prefix(b" ").unwrap());
+    let err = reader.read_unsigned_radix().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.fill_buf().unwrap(), b"-1");
+}
+
+#[test]
+fn read_ascii_int() {
+    let mut reader = Cursor::new(b"-9223372036854775808 007 +42x");
+    assert_eq!(reader.read_ascii_int::<i64>().unwrap(), i64::MIN);
+    assert!(reader.consume_prefix(b" ").unwrap());
+    assert_eq!(reader.read_ascii_int::<u8>().unwrap(), 7);
+    assert!(reader.consume_prefix(b" ").unwrap());
+    assert_eq!(reader.read_ascii_int::<i32>().unwrap(), 42);
+    assert_eq!(reader.fill_buf().unwrap(), b"x");
+
+    let err = Cursor::new(b"9223372036854775808").read_ascii_int::<i64>();
+    assert_eq!(err.unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn read_ascii_int_in() {
+    let mut reader = Cursor::new(b"-9223372036854775808,");
+    assert_eq!(
+        reader.read_ascii_int_in(i64::MIN..=i64::MAX).unwrap(),
+        i64::MIN
+    );
+    assert_eq!(reader.fill_buf().unwrap(), b",");
+
+    let mut reader = Cursor::new(b"0010;");
+    assert_eq!(reader.read_ascii_int_in(10..=20_u8).unwrap(), 10);
+    assert_eq!(reader.fill_buf().unwrap(), b";");
+
+    let mut reader = Cursor::new(b"20");
+    assert_eq!(reader.read_ascii_int_in(10..=20_u8).unwrap(), 20);
+
+    // One past each bound, and a value that doesn't fit the type at all.
+    let cases: &[(&[u8], &str)] = &[
+        (b"9;", "integer 9 is out of range 10..=20"),
+        (b"21;", "integer 21 is out of range 10..=20"),
+        (b"-1;", "integer -1 is out of range 10..=20"),
+        (b"256;", "integer 256 is out of range 10..=20"),
+    ];
+    for (input, msg) in cases {
+        let mut reader = Cursor::new(input);
+        let err = reader.read_ascii_int_in(10..=20_u8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), *msg);
+        assert_eq!(reader.fill_buf().unwrap(), b";");
+    }
+
+    // An empty digit run is an error, and leaves the terminator.
+    for input in [&b";"[..], b"-;", b""] {
+        let mut reader = Cursor::new(input);
+        let err = reader.read_ascii_int_in(0..=9_u8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            reader.fill_buf().unwrap(),
+            input.strip_prefix(b"-").unwrap_or(input)
+        );
+    }
+}
+
+#[test]
+fn read_bool_ascii() {
+    let cases: &[(&[u8], bool)] = &[
+        (b"true", true),
+        (b"false", false),
+        (b"yes", true),
+        (b"no", false),
+        (b"on", true),
+        (b"off", false),
+        (b"1", true),
+        (b"0", false),
+    ];
+    for &(input, value) in cases {
+        // At EOF, and followed by a terminator.
+        assert_eq!(
+            Cursor::new(input).read_bool_ascii(BoolStyle::ALL).unwrap(),
+            value
+        );
+        let mut reader = Cursor::new([input, b";"].concat());
+        assert_eq!(reader.read_bool_ascii(BoolStyle::ALL).unwrap(), value);
+        assert_eq!(reader.fill_buf().unwrap(), b";");
+    }
+
+    // Only the selected sets are accepted.
+    let err = Cursor::new(b"yes").read_bool_ascii(BoolStyle::TRUE_FALSE | BoolStyle::ONE_ZERO);
+    let err = err.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "expected one of true, false, 1, 0");
+}
+
+#[test]
+fn read_bool_ascii_case() {
+    let style = BoolStyle::TRUE_FALSE | BoolStyle::ON_OFF;
+    assert!(Cursor::new(b"TRUE").read_bool_ascii(style).is_err());
+    for input in [&b"TRUE"[..], b"True", b"tRuE"] {
+        let mut reader = Cursor::new(input);
+        assert!(reader.read_bool_ascii(style.ignore_case()).unwrap());
+    }
+    let mut reader = Cursor::new(b"OFF");
+    assert!(!reader.read_bool_ascii(style.ignore_case()).unwrap());
+}
+
+#[test]
+fn read_bool_ascii_boundary() {
+    // A literal followed by an alphanumeric byte doesn't match, and nothing is
+    // consumed.
+    for input in [&b"online"[..], b"10", b"no1", b"falsey", b"of;", b"nope"] {
+        let mut reader = Cursor::new(input);
+        let err = reader.read_bool_ascii(BoolStyle::ALL).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", input);
+        assert_eq!(reader.fill_buf().unwrap(), input);
+    }
+
+    // Punctuation and whitespace end a literal.
+    let mut reader = Cursor::new(b"on-line");
+    assert!(reader.read_bool_ascii(BoolStyle::ALL).unwrap());
+    assert_eq!(reader.fill_buf().unwrap(), b"-line");
+
+    // Literals that span buffer refills still match.
+    let mut reader = BufReader::with_capacity(2, &b"false\n"[..]);
+    assert!(!reader.read_bool_ascii(BoolStyle::ALL).unwrap());
+    assert_eq!(reader.fill_buf().unwrap(), b"\n");
+}
+
+#[test]
+fn read_ipv4_ascii() {
+    let cases: &[(&[u8], [u8; 4], &[u8])] = &[
+        (b"192.168.0.1", [192, 168, 0, 1], b""),
+        (b"0.0.0.0 ", [0, 0, 0, 0], b" "),
+        (b"255.255.255.255/24", [255, 255, 255, 255], b"/24"),
+        (b"10.20.30.40x", [10, 20, 30, 40], b"x"),
+        (b"1.2.3.4:80", [1, 2, 3, 4], b":80"),
+    ];
+    for &(input, octets, rest) in cases {
+        let mut reader = Cursor::new(input);
+        assert_eq!(reader.read_ipv4_ascii().unwrap(), Ipv4Addr::from(octets));
+        assert_eq!(reader.fill_buf().unwrap(), rest);
+    }
+
+    // Across buffer refills.
+    let mut reader = BufReader::with_capacity(3, &b"172.16.254.1 up"[..]);
+    assert_eq!(
+        reader.read_ipv4_ascii().unwrap(),
+        Ipv4Addr::new(172, 16, 254, 1)
+    );
+    assert_eq!(reader.fill_buf().unwrap(), b" up");
+}
+
+#[test]
+fn read_ipv4_ascii_errors() {
+    // Each input, and what's left of it after the error.
+    let cases: &[(&[u8], &[u8])] = &[
+        (b"256.0.0.1", b"6.0.0.1"),
+        (b"1.2.3.300", b"0"),
+        (b"1.2.3.1000", b"0"),
+        (b"01.2.3.4", b"1.2.3.4"),
+        (b"1.2.3.04", b"4"),
+        (b"1.2.3", b""),
+        (b"1.2.3 ", b" "),
+        (b"1.2..3", b".3"),
+        (b"1.2.3.4.5", b".5"),
+        (b"1.2.3.04.5", b"4.5"),
+        (b"", b""),
+        (b"x", b"x"),
+    ];
+    for &(input, rest) in cases {
+        let mut reader = Cursor::new(input);
+        let err = reader.read_ipv4_ascii().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", input);
+        assert_eq!(reader.fill_buf().unwrap(), rest, "{:?}", input);
+    }
+}
+
+#[test]
+fn read_rfc3339() {
+    let cases: &[(&[u8], i64, u32, i16)] = &[
+        (b"1970-01-01T00:00:00Z", 0, 0, 0),
+        (b"2024-05-01T12:34:56Z", 1_714_566_896, 0, 0),
+        (b"2024-05-01T12:34:56.7Z", 1_714_566_896, 700_000_000, 0),
+        (b"2024-05-01T12:34:56.789Z", 1_714_566_896, 789_000_000, 0),
+        (b"2024-05-01T12:34:56.000001z", 1_714_566_896, 1_000, 0),
+        (
+            b"2024-05-01t12:34:56.123456789Z",
+            1_714_566_896,
+            123_456_789,
+            0,
+        ),
+        (b"2024-05-01T14:34:56+02:00", 1_714_566_896, 0, 120),
+        (b"2024-05-01T07:04:56-05:30", 1_714_566_896, 0, -330),
+        (b"2024-02-29T00:00:00-00:00", 1_709_164_800, 0, 0),
+        (b"1969-12-31T23:59:59.5Z", -1, 500_000_000, 0),
+        // A leap second reads as the first second of the next minute.
+        (b"2016-12-31T23:59:60Z", 1_483_228_800, 0, 0),
+    ];
+    for &(input, secs, nanos, offset) in cases {
+        let mut reader = Cursor::new([input, b" rest"].concat());
+        let ts = reader.read_rfc3339().unwrap();
+        assert_eq!(ts.secs_since_epoch, secs, "{:?}", input);
+        assert_eq!(ts.nanos, nanos, "{:?}", input);
+        assert_eq!(ts.offset_minutes, offset, "{:?}", input);
+        assert_eq!(reader.fill_buf().unwrap(), b" rest");
+    }
+
+    // At EOF, and across buffer refills.
+    let mut reader = BufReader::with_capacity(3, &b"2000-01-01T00:00:00.25+00:00"[..]);
+    let ts = reader.read_rfc3339().unwrap();
+    assert_eq!((ts.secs_since_epoch, ts.nanos), (946_684_800, 250_000_000));
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+}
+
+#[test]
+fn read_rfc3339_errors() {
+    // Each input, and what's left of it after the error.
+    let cases: &[(&[u8], &[u8])] = &[
+        (b"2024-13-01T00:00:00Z", b"-01T00:00:00Z"),
+        (b"2024-02-30T00:00:00Z", b"T00:00:00Z"),
+        (b"2023-02-29T00:00:00Z", b"T00:00:00Z"),
+        (b"2024-04-31T00:00:00Z", b"T00:00:00Z"),
+        (b"2024-05-01T24:00:00Z", b":00:00Z"),
+        (b"2024-05-01T12:60:00Z", b":00Z"),
+        (b"2024-05-01T12:00:61Z", b"Z"),
+        (b"2024-05-01 12:00:00Z", b" 12:00:00Z"),
+        (b"2024-5-01T12:00:00Z", b"-01T12:00:00Z"),
+        (b"2024-05-01T12:00:00", b""),
+        (b"2024-05-01T12:00:00 ", b" "),
+        (b"2024-05-01T12:00:00.Z", b"Z"),
+        (b"2024-05-01T12:00:00.1234567891Z", b"1Z"),
+        (b"2024-05-01T12:00:00+0100", b"00"),
+        (b"2024-05-01T12:00:00+24:00", b":00"),
+        (b"24-05-01", b"-05-01"),
+    ];
+    for &(input, rest) in cases {
+        let mut reader = Cursor::new(input);
+        let err = reader.read_rfc3339().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", input);
+        assert_eq!(reader.fill_buf().unwrap(), rest, "{:?}", input);
+    }
+}
+
+#[test]
+fn skip_unicode_whitespace() {
+    let input = "\t \u{a0}\u{2003}\r\n\u{3000}\u{85}word \u{a0}".as_bytes();
+    for capacity in 1..=input.len() {
+        let mut reader = BufReader::with_capacity(capacity, input);
+        assert_eq!(
+            reader.skip_unicode_whitespace().unwrap(),
+            14,
+            "{}",
+            capacity
+        );
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, "word \u{a0}".as_bytes());
+    }
+
+    // Non-whitespace multi-byte characters are left unconsumed.
+    for input in ["é", "\u{2014}", "\u{1680}\u{1681}", "\u{3001}", "😀"] {
+        let mut reader = Cursor::new(input.as_bytes());
+        let skipped = reader.skip_unicode_whitespace().unwrap();
+        let rest = &input.as_bytes()[skipped..];
+        assert_eq!(reader.fill_buf().unwrap(), rest);
+        assert!(!rest.is_empty());
+    }
+
+    assert_eq!(Cursor::new(b"").skip_unicode_whitespace().unwrap(), 0);
+}
+
+#[test]
+fn skip_unicode_whitespace_invalid() {
+    // Invalid UTF-8 stops the skip without being consumed.
+    let cases: &[(&[u8], usize)] = &[
+        (b" \xff", 1),
+        (b" \xc3", 1),
+        (b" \xc2\x20", 1),
+        (b" \xe3\x80\x20", 1),
+        (b" \x80", 1),
+    ];
+    for &(input, skipped) in cases {
+        let mut reader = Cursor::new(input);
+        assert_eq!(reader.skip_unicode_whitespace().unwrap(), skipped);
+        assert_eq!(reader.fill_buf().unwrap(), &input[skipped..]);
+    }
+
+    // A character split across reads that starts like whitespace but isn't.
+    let input = "\u{2014}".as_bytes();
+    let mut reader = BufReader::with_capacity(2, input);
+    let err = reader.skip_unicode_whitespace().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.fill_buf().unwrap(), &input[2..]);
+
+    // The stream ends in the middle of what could be whitespace.
+    for input in [&b" \xc2"[..], b" \xe3\x80"] {
+        let err = Cursor::new(input).skip_unicode_whitespace().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
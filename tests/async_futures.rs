@@ -0,0 +1,218 @@
+#![cfg(feature = "async-futures")]
+
+use futures::executor::block_on;
+use futures::io::{AsyncBufRead, AsyncRead, AsyncReadExt};
+use futures::stream::{StreamExt, TryStreamExt};
+use omnom::{AsyncBufReadExt, AsyncCheckpoint};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A reader that yields at most `chunk` bytes at a time, and returns
+/// `Pending` before every chunk.
+struct Trickle {
+    data: Vec<u8>,
+    pos: usize,
+    chunk: usize,
+    ready: bool,
+}
+
+impl Trickle {
+    fn new(data: &[u8], chunk: usize) -> Self {
+        Self {
+            data: data.to_vec(),
+            pos: 0,
+            chunk,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(available)) => available,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncBufRead for Trickle {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if !this.ready {
+            this.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let end = (this.pos + this.chunk).min(this.data.len());
+        Poll::Ready(Ok(&this.data[this.pos..end]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.pos += amt;
+        if amt > 0 {
+            this.ready = false;
+        }
+    }
+}
+
+async fn expect_token<R: AsyncBufRead + Unpin>(reader: &mut R, token: &[u8]) -> bool {
+    let mut buf = vec![0; token.len()];
+    reader.read_exact(&mut buf).await.is_ok() && buf == token
+}
+
+#[test]
+fn checkpoint_speculative_parse() {
+    block_on(async {
+        let mut reader = AsyncCheckpoint::new(Trickle::new(b"POST /index.html", 2));
+
+        reader.checkpoint();
+        assert!(!expect_token(&mut reader, b"PUT ").await);
+        reader.rollback();
+
+        reader.checkpoint();
+        assert!(expect_token(&mut reader, b"POST ").await);
+        reader.commit();
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"/index.html");
+    });
+}
+
+#[test]
+fn checkpoint_nested() {
+    block_on(async {
+        let mut reader = AsyncCheckpoint::new(Trickle::new(b"abcdef", 1));
+
+        reader.checkpoint();
+        assert!(expect_token(&mut reader, b"ab").await);
+        reader.checkpoint();
+        assert!(expect_token(&mut reader, b"cd").await);
+        assert_eq!(reader.depth(), 2);
+        reader.rollback();
+        assert!(expect_token(&mut reader, b"cd").await);
+        reader.rollback();
+        assert_eq!(reader.depth(), 0);
+
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).await.unwrap();
+        assert_eq!(all, b"abcdef");
+    });
+}
+
+#[test]
+fn checkpoint_commit_releases_memory() {
+    block_on(async {
+        let mut reader = AsyncCheckpoint::new(Trickle::new(b"hello world", 3));
+
+        reader.checkpoint();
+        let mut line = Vec::new();
+        futures::io::AsyncBufReadExt::read_until(&mut reader, b' ', &mut line)
+            .await
+            .unwrap();
+        assert_eq!(line, b"hello ");
+        assert!(reader.replay_len() >= 6);
+        reader.commit();
+        assert!(reader.replay_len() < 6);
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"world");
+        assert_eq!(reader.replay_len(), 0);
+    });
+}
+
+#[test]
+fn records_split_across_small_reads() {
+    block_on(async {
+        let reader = Trickle::new(b"lorem\nipsum\n\ndolor sit\namet", 2);
+        let records: Vec<Vec<u8>> = reader.records(b'\n').try_collect().await.unwrap();
+        let expected: Vec<&[u8]> = vec![b"lorem", b"ipsum", b"", b"dolor sit", b"amet"];
+        assert_eq!(records, expected);
+    });
+}
+
+#[test]
+fn records_max_len_does_not_poison() {
+    block_on(async {
+        let reader = Trickle::new(b"short\nfar too long\nok\n", 3);
+        let mut records = reader.records(b'\n').max_len(5);
+
+        assert_eq!(records.next().await.unwrap().unwrap(), b"short");
+        let err = records.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(records.next().await.unwrap().unwrap(), b"ok");
+        assert!(records.next().await.is_none());
+    });
+}
+
+#[test]
+fn records_terminate_cleanly() {
+    block_on(async {
+        let mut records = Trickle::new(b"", 1).records(b',');
+        assert!(records.next().await.is_none());
+        assert!(records.next().await.is_none());
+
+        let mut records = Trickle::new(b"a,", 1).records(b',');
+        assert_eq!(records.next().await.unwrap().unwrap(), b"a");
+        assert!(records.next().await.is_none());
+    });
+}
+
+#[test]
+fn peek_then_read() {
+    block_on(async {
+        let mut reader = Trickle::new(b"\x16\x03\x01rest", 2);
+        assert_eq!(reader.peek_byte().await.unwrap(), Some(0x16));
+        assert_eq!(reader.peek_byte().await.unwrap(), Some(0x16));
+
+        let mut peeked = [0; 4];
+        let n = reader.peek(&mut peeked).await.unwrap();
+        assert_eq!(n, 2);
+
+        let mut read = [0; 2];
+        reader.read_exact(&mut read).await.unwrap();
+        assert_eq!(&peeked[..n], &read);
+    });
+}
+
+#[test]
+fn peek_stable_across_pending() {
+    block_on(async {
+        let mut reader = Trickle::new(b"abc", 3);
+        for _ in 0..3 {
+            let mut buf = [0; 3];
+            assert_eq!(reader.peek(&mut buf).await.unwrap(), 3);
+            assert_eq!(&buf, b"abc");
+        }
+
+        let mut buf = Vec::new();
+        assert_eq!(reader.fill_until(b'b', &mut buf).await.unwrap(), 2);
+        assert_eq!(buf, b"ab");
+        let mut buf = Vec::new();
+        assert_eq!(reader.fill_until(b'x', &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, b"abc");
+    });
+}
+
+#[test]
+fn peek_at_eof() {
+    block_on(async {
+        let mut reader = Trickle::new(b"", 1);
+        assert_eq!(reader.peek_byte().await.unwrap(), None);
+        assert_eq!(reader.peek(&mut [0; 4]).await.unwrap(), 0);
+        assert_eq!(reader.fill_until(b'\n', &mut Vec::new()).await.unwrap(), 0);
+    });
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "std")]
+
+use omnom::{BitReader, BitWriter};
+use std::io::{BufReader, Cursor, ErrorKind};
+
+/// Pack a string of `0`s and `1`s into bytes, padding with zeros.
+fn bits(s: &str) -> Vec<u8> {
+    let mut writer = BitWriter::new(vec![]);
+    for c in s.chars() {
+        writer.write_bit(c == '1').unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn ue_spec_examples() {
+    // The codewords of H.264 table 9-2.
+    let codes = [
+        "1", "010", "011", "00100", "00101", "00110", "00111", "0001000", "0001001",
+    ];
+    for (value, code) in codes.iter().enumerate() {
+        let mut reader = BitReader::new(Cursor::new(bits(code)));
+        assert_eq!(reader.read_ue().unwrap(), value as u32, "{}", code);
+
+        let mut writer = BitWriter::new(vec![]);
+        writer.write_ue(value as u32).unwrap();
+        assert_eq!(writer.finish().unwrap(), bits(code), "{}", code);
+    }
+}
+
+#[test]
+fn se_mapping() {
+    // The mapping of H.264 table 9-3.
+    let values = [0, 1, -1, 2, -2, 3, -3, 4, -4];
+    for (code, value) in values.iter().enumerate() {
+        let mut writer = BitWriter::new(vec![]);
+        writer.write_ue(code as u32).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert_eq!(BitReader::new(&bytes[..]).read_se().unwrap(), *value);
+
+        let mut writer = BitWriter::new(vec![]);
+        writer.write_se(*value).unwrap();
+        assert_eq!(writer.finish().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn overflow_guard() {
+    let mut max = "0".repeat(31);
+    max.push('1');
+    max.push_str(&"1".repeat(31));
+    let mut reader = BitReader::new(Cursor::new(bits(&max)));
+    assert_eq!(reader.read_ue().unwrap(), u32::MAX - 1);
+
+    let mut reader = BitReader::new(&[0, 0, 0, 0, 0x80][..]);
+    let err = reader.read_ue().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut writer = BitWriter::new(vec![]);
+    assert_eq!(
+        writer.write_ue(u32::MAX).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_se(i32::MIN).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert!(writer.is_aligned());
+    assert!(writer.finish().unwrap().is_empty());
+}
+
+#[test]
+fn round_trip() {
+    let unsigned = [0, 1, 7, 8, 255, 1000, 65_535, 1 << 20, u32::MAX - 1];
+    let signed = [0, 1, -1, 100, -100, i32::MAX, i32::MIN + 1];
+    let mut writer = BitWriter::new(vec![]);
+    for value in &unsigned {
+        writer.write_ue(*value).unwrap();
+    }
+    for value in &signed {
+        writer.write_se(*value).unwrap();
+    }
+    writer.write_bit(true).unwrap();
+    let bytes = writer.finish().unwrap();
+
+    // A capacity of 1 makes every byte a separate `fill_buf` chunk.
+    let mut reader = BitReader::new(BufReader::with_capacity(1, &bytes[..]));
+    for value in &unsigned {
+        assert_eq!(reader.read_ue().unwrap(), *value);
+    }
+    for value in &signed {
+        assert_eq!(reader.read_se().unwrap(), *value);
+    }
+    assert!(reader.read_bit().unwrap());
+    assert!(reader.align() < 8);
+    assert_eq!(
+        reader.read_bit().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn codes_crossing_bytes() {
+    // 6 bits of padding, then 0001001 (8) across the byte boundary.
+    let bytes = bits("1111110001001");
+    assert_eq!(bytes, [0b1111_1100, 0b0100_1000]);
+    let mut reader = BitReader::new(BufReader::with_capacity(1, &bytes[..]));
+    assert_eq!(reader.read_bits(6).unwrap(), 0b11_1111);
+    assert_eq!(reader.read_ue().unwrap(), 8);
+    assert!(!reader.is_aligned());
+    assert_eq!(reader.align(), 3);
+}
+
+#[test]
+fn truncated_code() {
+    let mut reader = BitReader::new(&[0b0000_0001][..]);
+    let err = reader.read_ue().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use std::io::{BufRead, BufReader, Cursor, ErrorKind, Read};
+
+#[test]
+fn exact_consumption() {
+    let mut conn = BufReader::with_capacity(4, &b"0123456789rest"[..]);
+    let mut body = conn.body_exact(10);
+    let mut out = vec![];
+    body.read_to_end(&mut out).unwrap();
+    assert_eq!(body.remaining(), 0);
+    body.finish().unwrap();
+    assert_eq!(out, b"0123456789");
+
+    let mut rest = vec![];
+    conn.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"rest");
+}
+
+#[test]
+fn early_finish_drains() {
+    let mut conn = BufReader::with_capacity(3, &b"0123456789rest"[..]);
+    let mut body = conn.body_exact(10).drain_on_finish(true);
+    let mut head = [0; 2];
+    body.read_exact(&mut head).unwrap();
+    body.finish().unwrap();
+
+    let mut rest = vec![];
+    conn.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"rest");
+}
+
+#[test]
+fn early_finish_errors() {
+    let mut conn = Cursor::new(b"0123456789rest");
+    let mut body = conn.body_exact(10);
+    let mut head = [0; 2];
+    body.read_exact(&mut head).unwrap();
+    let err = body.finish().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(conn.position(), 2);
+}
+
+#[test]
+fn eof_before_body_ends() {
+    let mut conn = Cursor::new(b"0123");
+    let mut body = conn.body_exact(10);
+    let mut out = vec![];
+    let err = body.read_to_end(&mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(out, b"0123");
+    assert_eq!(body.remaining(), 6);
+    // A failed read silences the drop check.
+    drop(body);
+
+    let err = conn
+        .body_exact(1)
+        .drain_on_finish(true)
+        .finish()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn pipelined_requests() {
+    let input = b"POST /a\n5\nhelloPOST /b\n3\nbye";
+    let mut conn = BufReader::with_capacity(4, &input[..]);
+    let mut bodies = vec![];
+    loop {
+        let mut line = String::new();
+        if conn.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let mut len = String::new();
+        conn.read_line(&mut len).unwrap();
+        let len: u64 = len.trim().parse().unwrap();
+
+        let mut body = conn.body_exact(len);
+        let mut text = String::new();
+        body.read_to_string(&mut text).unwrap();
+        body.finish().unwrap();
+        bodies.push((line.trim().to_string(), text));
+    }
+    assert_eq!(
+        bodies,
+        [
+            ("POST /a".to_string(), "hello".to_string()),
+            ("POST /b".to_string(), "bye".to_string())
+        ]
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "call `finish`")]
+fn dropping_unfinished_panics_in_debug() {
+    let mut conn = Cursor::new(b"0123456789");
+    let mut body = conn.body_exact(10);
+    let mut head = [0; 2];
+    body.read_exact(&mut head).unwrap();
+}
+
+#[test]
+fn dropping_fully_read_is_fine() {
+    let mut conn = Cursor::new(b"01");
+    let mut body = conn.body_exact(2);
+    let mut out = [0; 2];
+    body.read_exact(&mut out).unwrap();
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::BoundedRetry;
+use std::io::{self, BufReader, ErrorKind, Read};
+
+/// A reader that's interrupted `interrupts` times before every read of data,
+/// or forever if it's `None`.
+struct Interrupting<'a> {
+    data: &'a [u8],
+    interrupts: Option<usize>,
+    left: usize,
+}
+
+impl<'a> Interrupting<'a> {
+    fn new(data: &'a [u8], interrupts: Option<usize>) -> Self {
+        Self {
+            data,
+            interrupts,
+            left: interrupts.unwrap_or(0),
+        }
+    }
+}
+
+impl Read for Interrupting<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.interrupts {
+            None => return Err(ErrorKind::Interrupted.into()),
+            Some(_) if self.left > 0 => {
+                self.left -= 1;
+                return Err(ErrorKind::Interrupted.into());
+            }
+            Some(n) => self.left = n,
+        }
+        let len = buf.len().min(self.data.len()).min(2);
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn retries_below_the_cap() {
+    let reader = BufReader::new(Interrupting::new(b"lorem ipsum", Some(5)));
+    let mut reader = BoundedRetry::new(reader, 5);
+    let mut buf = vec![];
+    reader.read_while(&mut buf, |b| b != b' ').unwrap();
+    assert_eq!(buf, b"lorem");
+    assert_eq!(reader.skip_while(|_| true).unwrap(), 6);
+
+    // The count resets after each successful read.
+    let mut reader = BoundedRetry::new(Interrupting::new(b"abcdef", Some(2)), 2);
+    assert_eq!(reader.read_be::<u32>().unwrap(), 0x6162_6364);
+}
+
+#[test]
+fn errors_past_the_cap() {
+    let reader = BufReader::new(Interrupting::new(b"lorem", Some(6)));
+    let mut reader = BoundedRetry::new(reader, 5);
+    let err = reader.skip_while(|_| true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(
+        err.to_string(),
+        "read was interrupted more than 5 times in a row"
+    );
+
+    let reader = BufReader::new(Interrupting::new(b"lorem", None));
+    let mut reader = BoundedRetry::new(reader, 100);
+    let mut buf = vec![];
+    let err = reader.read_while(&mut buf, |_| true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+
+    let mut reader = BoundedRetry::new(Interrupting::new(b"lorem", None), 0);
+    assert_eq!(reader.read_be::<u8>().unwrap_err().kind(), ErrorKind::Other);
+}
+
+#[test]
+fn unbounded_without_wrapper() {
+    let mut reader = BufReader::new(Interrupting::new(b"lorem ipsum", Some(1000)));
+    let mut buf = vec![];
+    reader.read_while(&mut buf, |b| b != b' ').unwrap();
+    assert_eq!(buf, b"lorem");
+}
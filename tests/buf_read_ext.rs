@@ -0,0 +1,1300 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{
+    BufferAhead, BufferTooSmall, CopyEnd, LineResult, Lookahead, ParseError, ParseErrorKind,
+    ShortRead, SkipOutcome, Unread,
+};
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind, Read};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+#[test]
+fn skip_while_chunk() {
+    // A closure with `memchr` semantics: accept everything before a newline.
+    let mut reader = BufReader::with_capacity(4, &b"lorem ipsum\ndolor"[..]);
+    let skipped = reader
+        .skip_while_chunk(|chunk| {
+            chunk
+                .iter()
+                .position(|b| *b == b'\n')
+                .unwrap_or(chunk.len())
+        })
+        .unwrap();
+    assert_eq!(skipped, 11);
+    assert_eq!(reader.fill_buf().unwrap(), b"\n");
+
+    // Accepting a chunk up to its boundary continues into the next one.
+    let mut reader = BufReader::with_capacity(4, &b"    lorem"[..]);
+    let mut calls = 0;
+    let skipped = reader
+        .skip_while_chunk(|chunk| {
+            calls += 1;
+            chunk.iter().take_while(|b| **b == b' ').count()
+        })
+        .unwrap();
+    assert_eq!(skipped, 4);
+    assert_eq!(calls, 2);
+    assert_eq!(reader.fill_buf().unwrap(), b"lore");
+
+    // Accepting nothing on the first chunk consumes nothing.
+    let mut reader = Cursor::new(b"lorem");
+    assert_eq!(reader.skip_while_chunk(|_| 0).unwrap(), 0);
+    assert_eq!(reader.position(), 0);
+
+    // Accepting everything runs to EOF.
+    let mut reader = BufReader::with_capacity(3, &b"lorem ipsum"[..]);
+    assert_eq!(
+        reader.skip_while_chunk(|chunk| chunk.len() * 2).unwrap(),
+        11
+    );
+}
+
+#[test]
+fn read_while_chunk() {
+    // Accepting nothing reads nothing.
+    let mut reader = Cursor::new(b"lorem");
+    let mut buf = vec![];
+    assert_eq!(reader.read_while_chunk(&mut buf, |_| 0).unwrap(), 0);
+    assert!(buf.is_empty());
+    assert_eq!(reader.position(), 0);
+
+    // Accepting whole chunks continues across many of them.
+    let input: Vec<u8> = (0..=255).collect();
+    let mut reader = BufReader::with_capacity(7, &input[..]);
+    let mut calls = 0;
+    let read = reader
+        .read_while_chunk(&mut buf, |chunk| {
+            calls += 1;
+            chunk.len()
+        })
+        .unwrap();
+    assert_eq!(read, 256);
+    assert_eq!(buf, input);
+    assert_eq!(calls, 256 / 7 + 1);
+
+    // Accepting more than the chunk holds is clamped to the chunk.
+    let mut reader = BufReader::with_capacity(3, &b"lorem-ipsum"[..]);
+    let mut buf = vec![];
+    let read = reader
+        .read_while_chunk(&mut buf, |chunk| {
+            match chunk.iter().position(|b| *b == b'-') {
+                Some(i) => i,
+                None => usize::MAX,
+            }
+        })
+        .unwrap();
+    assert_eq!(read, 5);
+    assert_eq!(buf, b"lorem");
+    assert_eq!(reader.fill_buf().unwrap(), b"-");
+}
+
+#[test]
+fn read_until_nth() {
+    // Exactly n delimiters.
+    let mut reader = Cursor::new(b"a,b,c,d,e,");
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b',', 5, &mut buf).unwrap(), (10, 5));
+    assert_eq!(buf, b"a,b,c,d,e,");
+
+    // Fewer than n before EOF.
+    let mut reader = Cursor::new(b"a,b,c");
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b',', 5, &mut buf).unwrap(), (5, 2));
+    assert_eq!(buf, b"a,b,c");
+
+    // Adjacent delimiters.
+    let mut reader = Cursor::new(b",,,x");
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b',', 2, &mut buf).unwrap(), (2, 2));
+    assert_eq!(reader.fill_buf().unwrap(), b",x");
+
+    // Occurrences straddling `fill_buf` boundaries.
+    let mut reader = BufReader::with_capacity(3, &b"ab,cd,ef,gh"[..]);
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b',', 3, &mut buf).unwrap(), (9, 3));
+    assert_eq!(buf, b"ab,cd,ef,");
+    assert_eq!(reader.fill_buf().unwrap(), b"gh");
+
+    // n == 0 is a no-op.
+    let mut reader = Cursor::new(b"a,b");
+    assert_eq!(reader.read_until_nth(b',', 0, &mut buf).unwrap(), (0, 0));
+    assert_eq!(reader.position(), 0);
+}
+
+#[test]
+fn skip_until_nth() {
+    // Occurrences spread over several chunks.
+    let mut reader = BufReader::with_capacity(4, &b"a\tbb\tccc\tdddd\te"[..]);
+    assert_eq!(reader.skip_until_nth(b'\t', 3).unwrap(), (9, 3));
+
+    // Fewer than n available.
+    assert_eq!(reader.skip_until_nth(b'\t', 3).unwrap(), (6, 1));
+    assert!(reader.fill_buf().unwrap().is_empty());
+
+    // n == 0 is a no-op.
+    let mut reader = Cursor::new(b"a\tb");
+    assert_eq!(reader.skip_until_nth(b'\t', 0).unwrap(), (0, 0));
+    assert_eq!(reader.position(), 0);
+}
+
+#[test]
+fn skip_until_nth_large_tsv() {
+    let mut input = vec![];
+    for i in 0..100_000 {
+        input.extend(format!("{}\tcolumn\tcolumn\t{}\n", i, i * 2).as_bytes());
+    }
+    assert!(input.len() > 2 * 1024 * 1024);
+
+    let mut reader = BufReader::new(&input[..]);
+    let mut last = vec![];
+    let mut lines = 0;
+    loop {
+        let (_, found) = reader.skip_until_nth(b'\t', 3).unwrap();
+        if found < 3 {
+            break;
+        }
+        last.clear();
+        reader.read_until(b'\n', &mut last).unwrap();
+        lines += 1;
+    }
+    assert_eq!(lines, 100_000);
+    assert_eq!(last, b"199998\n");
+}
+
+#[test]
+fn count_byte() {
+    assert_eq!(Cursor::new(b"").count_byte(b'\n').unwrap(), 0);
+
+    // Occurrences at chunk boundaries.
+    let mut reader = BufReader::with_capacity(3, &b"ab\n\ncd\n\n\n"[..]);
+    assert_eq!(reader.count_byte(b'\n').unwrap(), 5);
+    assert!(reader.fill_buf().unwrap().is_empty());
+
+    // A multi-megabyte stream agrees with a naive count.
+    let input: Vec<u8> = (0..4 * 1024 * 1024_u32).map(|i| (i % 251) as u8).collect();
+    let naive = input.iter().filter(|b| **b == b'\n').count() as u64;
+    let mut reader = BufReader::new(&input[..]);
+    assert_eq!(reader.count_byte(b'\n').unwrap(), naive);
+
+    let naive = input.iter().filter(|b| b.is_ascii_digit()).count() as u64;
+    let mut reader = BufReader::new(&input[..]);
+    assert_eq!(
+        reader.count_matching(|b| b.is_ascii_digit()).unwrap(),
+        naive
+    );
+}
+
+#[test]
+fn read_balanced() {
+    let mut reader = Cursor::new(b"a (b (c)) d) e".to_vec());
+    let mut buf = vec![];
+    assert_eq!(reader.read_balanced(b'(', b')', &mut buf).unwrap(), 11);
+    assert_eq!(buf, b"a (b (c)) d");
+    assert_eq!(reader.fill_buf().unwrap(), b" e");
+
+    // An empty block.
+    let mut reader = Cursor::new(b"]]".to_vec());
+    let mut buf = vec![];
+    assert_eq!(reader.read_balanced(b'[', b']', &mut buf).unwrap(), 0);
+    assert_eq!(reader.fill_buf().unwrap(), b"]");
+
+    // Without string awareness quotes are plain bytes.
+    let mut reader = Cursor::new(br#""(" ) ")""#.to_vec());
+    let mut buf = vec![];
+    reader.read_balanced(b'(', b')', &mut buf).unwrap();
+    assert_eq!(buf, br#""(" ) ""#);
+}
+
+#[test]
+fn read_balanced_quoted() {
+    let input = br#""k": "{\"}", "n": {"m": "}"}} tail"#;
+    let mut reader = Cursor::new(input.to_vec());
+    let mut buf = vec![];
+    reader.read_balanced_quoted(b'{', b'}', &mut buf).unwrap();
+    assert_eq!(buf, &input[..input.len() - 6]);
+    assert_eq!(reader.fill_buf().unwrap(), b" tail");
+}
+
+#[test]
+fn read_balanced_unbalanced() {
+    let mut reader = Cursor::new(b"(a (b)".to_vec());
+    let mut buf = vec![];
+    let err = reader.read_balanced(b'(', b')', &mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "unbalanced delimiters: reached EOF at depth 2"
+    );
+    assert_eq!(buf, b"(a (b)");
+
+    // An unterminated string hides the closing delimiter.
+    let mut reader = Cursor::new(br#""\")"#.to_vec());
+    let mut buf = vec![];
+    let err = reader.read_balanced_quoted(b'(', b')', &mut buf);
+    assert_eq!(err.unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn read_balanced_chunk_boundaries() {
+    // Every chunk boundary position, including between an escape and the
+    // byte it escapes.
+    let input = br#"a("\")"(b))) c"#;
+    for capacity in 1..input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut buf = vec![];
+        reader.read_balanced_quoted(b'(', b')', &mut buf).unwrap();
+        assert_eq!(buf, br#"a("\")"(b))"#, "capacity {}", capacity);
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b" c");
+    }
+}
+
+#[test]
+fn read_percent_decoded_until() {
+    let mut reader = Cursor::new(b"a%20b+c%2fd%2F&e".to_vec());
+    let mut out = vec![];
+    let res = reader.read_percent_decoded_until(b"&", &mut out, false);
+    assert_eq!(res.unwrap(), (8, Some(b'&')));
+    assert_eq!(out, b"a b+c/d/");
+
+    // Form mode decodes `+`, but not an escaped `+`.
+    let mut reader = Cursor::new(b"a+b%2B".to_vec());
+    let mut out = vec![];
+    let res = reader.read_percent_decoded_until(b"&", &mut out, true);
+    assert_eq!(res.unwrap(), (4, None));
+    assert_eq!(out, b"a b+");
+
+    // Escaped delimiters don't end the read.
+    let mut reader = Cursor::new(b"k%3Dv=x".to_vec());
+    let mut out = vec![];
+    let res = reader.read_percent_decoded_until(b"=&", &mut out, true);
+    assert_eq!(res.unwrap(), (3, Some(b'=')));
+    assert_eq!(out, b"k=v");
+    assert_eq!(reader.fill_buf().unwrap(), b"x");
+
+    // An empty component.
+    let mut reader = Cursor::new(b"&".to_vec());
+    let mut out = vec![];
+    let res = reader.read_percent_decoded_until(b"&", &mut out, true);
+    assert_eq!(res.unwrap(), (0, Some(b'&')));
+}
+
+#[test]
+fn read_percent_decoded_until_malformed() {
+    let cases: &[(&[u8], &str, &[u8])] = &[
+        (b"a%G1&", "invalid percent escape `%G`", b"G1&"),
+        (b"a%4G&", "invalid percent escape `%4G`", b"G&"),
+        (b"a%&b", "invalid percent escape `%&`", b"&b"),
+        (b"a%", "invalid percent escape `%`", b""),
+        (b"a%4", "invalid percent escape `%4`", b""),
+    ];
+    for &(input, msg, rest) in cases {
+        let mut reader = Cursor::new(input);
+        let mut out = vec![];
+        let err = reader
+            .read_percent_decoded_until(b"&", &mut out, true)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), msg);
+        assert_eq!(reader.fill_buf().unwrap(), rest);
+    }
+}
+
+#[test]
+fn read_percent_decoded_until_chunk_boundaries() {
+    let input = b"%E2%9C%93+ok%21&next";
+    for capacity in 1..input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut out = vec![];
+        let res = reader.read_percent_decoded_until(b"&", &mut out, true);
+        assert_eq!(res.unwrap(), (7, Some(b'&')), "capacity {}", capacity);
+        assert_eq!(out, "✓ ok!".as_bytes());
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"next");
+    }
+}
+
+#[test]
+fn read_unfolded_line() {
+    let input = b"To: a@example.com,\r\n b@example.com,\r\n\t c@example.com\r\nFrom: d\r\n";
+    let mut reader = Cursor::new(input.to_vec());
+    let mut buf = vec![];
+    assert_eq!(reader.read_unfolded_line(&mut buf).unwrap(), 54);
+    assert_eq!(buf, b"To: a@example.com, b@example.com, c@example.com");
+
+    // No folding.
+    buf.clear();
+    assert_eq!(reader.read_unfolded_line(&mut buf).unwrap(), 9);
+    assert_eq!(buf, b"From: d");
+    buf.clear();
+    assert_eq!(reader.read_unfolded_line(&mut buf).unwrap(), 0);
+    assert_eq!(buf, b"");
+}
+
+#[test]
+fn read_unfolded_line_terminator() {
+    // A fold right before the empty line that ends the headers leaves that
+    // line in the reader, also when it's at a buffer boundary.
+    let input = b"A: 1\n 2\r\n\r\nbody";
+    for capacity in 1..input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut buf = vec![];
+        assert_eq!(reader.read_unfolded_line(&mut buf).unwrap(), 9);
+        assert_eq!(buf, b"A: 1 2", "capacity {}", capacity);
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"\r\nbody");
+    }
+}
+
+#[test]
+fn read_unfolded_line_eof() {
+    for input in [&b"A: 1"[..], b"A: 1\r\n 2", b"A: 1\r\n  "] {
+        let mut reader = Cursor::new(input);
+        let mut buf = vec![];
+        let err = reader.read_unfolded_line(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof, "{:?}", input);
+    }
+}
+
+#[test]
+fn chunks_exact() {
+    // An exact multiple, across buffer refills.
+    let reader = BufReader::with_capacity(5, &b"abcdefghijkl"[..]);
+    let mut chunks = reader.chunks_exact(4);
+    let all: Vec<_> = chunks.by_ref().map(Result::unwrap).collect();
+    assert_eq!(all, [b"abcd", b"efgh", b"ijkl"]);
+    assert_eq!(chunks.remainder(), b"");
+
+    // With a remainder.
+    let mut chunks = Cursor::new(b"abcdefghij").chunks_exact(4);
+    assert_eq!(chunks.by_ref().count(), 2);
+    assert_eq!(chunks.remainder(), b"ij");
+    assert!(chunks.next().is_none());
+    assert_eq!(chunks.remainder(), b"ij");
+
+    // An empty stream.
+    let mut chunks = Cursor::new(b"").chunks_exact(4);
+    assert!(chunks.next().is_none());
+    assert_eq!(chunks.remainder(), b"");
+
+    // A chunk size larger than the stream.
+    let mut chunks = Cursor::new(b"abc").chunks_exact(512);
+    assert!(chunks.next().is_none());
+    assert_eq!(chunks.remainder(), b"abc");
+}
+
+#[test]
+fn for_each_chunk() {
+    // Each input, the bytes of its whole chunks, and its remainder.
+    let cases: &[(&[u8], &[u8], &[u8])] = &[
+        (b"abcdef", b"abcdef", b""),
+        (b"abcdefg", b"abcdef", b"g"),
+        (b"", b"", b""),
+        (b"ab", b"", b"ab"),
+    ];
+    for &(input, expected, remainder) in cases {
+        let mut reader = BufReader::with_capacity(2, input);
+        let mut chunks = vec![];
+        let rest = reader
+            .for_each_chunk(3, |chunk| {
+                chunks.push(chunk.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.len() == 3));
+        assert_eq!(chunks.concat(), expected);
+        assert_eq!(rest, remainder);
+    }
+
+    // An error from the callback stops the iteration.
+    let mut reader = Cursor::new(b"abcdef");
+    let err = reader
+        .for_each_chunk(2, |chunk| match chunk {
+            b"cd" => Err(std::io::Error::other("stop")),
+            _ => Ok(()),
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "stop");
+    assert_eq!(reader.fill_buf().unwrap(), b"ef");
+}
+
+#[test]
+fn count_pattern() {
+    // Non-overlapping occurrences.
+    let cases: &[(&[u8], &[u8], u64)] = &[
+        (b"aaaa", b"aaa", 1),
+        (b"aaaa", b"aa", 2),
+        (b"aaaaa", b"aa", 2),
+        (b"abcabc", b"abc", 2),
+        (b"abcab", b"abc", 1),
+        (b"", b"abc", 0),
+        (b"ab", b"abc", 0),
+        (b"x", b"x", 1),
+    ];
+    for &(input, needle, count) in cases {
+        // Every buffer size, so occurrences fall on every chunk boundary,
+        // including needles longer than the buffer.
+        for capacity in 1..=input.len().max(1) {
+            let mut reader = BufReader::with_capacity(capacity, input);
+            let found = reader.count_pattern(needle).unwrap();
+            assert_eq!(found, count, "{:?} in {:?}, {}", needle, input, capacity);
+        }
+    }
+
+    let err = Cursor::new(b"abc").count_pattern(b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn count_pattern_boundaries() {
+    let needle = b"\r\n--boundary";
+    let input = [&b"preamble"[..], needle, b"part one", needle, needle, b"--"].concat();
+    for capacity in 1..input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        assert_eq!(reader.count_pattern(needle).unwrap(), 3, "{}", capacity);
+    }
+}
+
+#[test]
+fn count_pattern_large() {
+    let record = b"field\tfield\r\n--boundary\r\n";
+    let input = record.repeat(1 << 16);
+    let mut reader = BufReader::with_capacity(8 * 1024 - 3, &input[..]);
+    assert_eq!(reader.count_pattern(b"\r\n--boundary").unwrap(), 1 << 16);
+}
+
+#[test]
+fn copy_replace() {
+    // Each input, needle, replacement, the expected output, and the amount
+    // of replacements.
+    let cases: &[(&str, &str, &str, &str, u64)] = &[
+        ("a\r\nb\r\n", "\r\n", "\n", "a\nb\n", 2),
+        ("x{{a}}y{{a}}", "{{a}}", "omnom", "xomnomyomnom", 2),
+        ("a-b", "-", "<dash>", "a<dash>b", 1),
+        ("----", "--", "=", "==", 2),
+        ("---", "--", "=", "=-", 1),
+        ("abab", "ab", "", "", 2),
+        ("no match here", "xyz", "!", "no match here", 0),
+        ("", "xyz", "!", "", 0),
+    ];
+    for &(input, needle, replacement, output, count) in cases {
+        for capacity in 1..=input.len().max(1) {
+            let mut reader = BufReader::with_capacity(capacity, input.as_bytes());
+            let mut out = vec![];
+            let res = reader.copy_replace(needle.as_bytes(), replacement.as_bytes(), &mut out);
+            let res = res.unwrap();
+            assert_eq!(out, output.as_bytes(), "{:?}, capacity {}", input, capacity);
+            assert_eq!(res, (output.len() as u64, count));
+        }
+    }
+
+    let err = Cursor::new(b"abc").copy_replace(b"", b"x", &mut vec![]);
+    assert_eq!(err.unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn copy_replace_passthrough() {
+    let input: Vec<u8> = (0..=255).cycle().take(100_000).collect();
+    let mut reader = BufReader::with_capacity(1000, &input[..]);
+    let mut out = vec![];
+    let res = reader.copy_replace(&[7, 9], b"x", &mut out).unwrap();
+    assert_eq!(res, (input.len() as u64, 0));
+    assert_eq!(out, input);
+}
+
+#[test]
+fn read_record() {
+    // Each input, and the records read from it.
+    let cases: &[(&[u8], &[&[u8]])] = &[
+        (br"foo\,bar,baz", &[b"foo,bar", b"baz"]),
+        (br"a\\,b", &[br"a\", b"b"]),
+        (br"a\\\,b,", &[br"a\,b"]),
+        (br"\a\b,", &[b"ab"]),
+        (b",,", &[b"", b""]),
+        // A trailing escape byte is kept.
+        (br"a,b\", &[b"a", br"b\"]),
+    ];
+    for &(input, records) in cases {
+        // Every buffer size, so escapes fall on every chunk boundary.
+        for capacity in 1..=input.len() {
+            let mut reader = BufReader::with_capacity(capacity, input);
+            let mut read = vec![];
+            loop {
+                let mut buf = vec![];
+                let (n, found) = reader.read_record(b',', b'\\', &mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                read.push(buf);
+                if !found {
+                    break;
+                }
+            }
+            assert_eq!(read, records, "{:?}, capacity {}", input, capacity);
+        }
+    }
+}
+
+#[test]
+fn read_record_counts() {
+    let mut reader = Cursor::new(br"a\,b,c");
+    let mut buf = vec![];
+    assert_eq!(
+        reader.read_record(b',', b'\\', &mut buf).unwrap(),
+        (5, true)
+    );
+    assert_eq!(
+        reader.read_record(b',', b'\\', &mut buf).unwrap(),
+        (1, false)
+    );
+    assert_eq!(
+        reader.read_record(b',', b'\\', &mut buf).unwrap(),
+        (0, false)
+    );
+    assert_eq!(buf, b"a,bc");
+}
+
+#[test]
+fn read_line_max() {
+    let input = b"12345\n123456\nabc\n\nend";
+    for capacity in 1..=input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut buf = vec![];
+
+        // A line exactly at the limit.
+        let res = reader.read_line_max(&mut buf, 5).unwrap();
+        assert_eq!(res, LineResult::Line(6));
+        assert_eq!(buf, b"12345\n");
+
+        // One byte over leaves the rest of the line, and the next line reads
+        // fine after skipping it.
+        buf.clear();
+        let err = reader.read_line_max(&mut buf, 5).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = ParseError::from(err);
+        assert_eq!(err.kind(), &ParseErrorKind::LimitExceeded);
+        assert_eq!(err.offset(), Some(5));
+        assert_eq!(buf, b"12345");
+        assert_eq!(reader.skip_line().unwrap(), 2);
+
+        buf.clear();
+        assert_eq!(
+            reader.read_line_max(&mut buf, 5).unwrap(),
+            LineResult::Line(4)
+        );
+        assert_eq!(
+            reader.read_line_max(&mut buf, 5).unwrap(),
+            LineResult::Line(1)
+        );
+        assert_eq!(
+            reader.read_line_max(&mut buf, 5).unwrap(),
+            LineResult::Eof(3)
+        );
+        assert_eq!(
+            reader.read_line_max(&mut buf, 5).unwrap(),
+            LineResult::Eof(0)
+        );
+        assert_eq!(buf, b"abc\n\nend");
+    }
+}
+
+#[test]
+fn read_line_max_eof() {
+    // A last line that's exactly at the limit, or over it.
+    let mut buf = vec![];
+    let res = Cursor::new(b"12345").read_line_max(&mut buf, 5);
+    assert_eq!(res.unwrap(), LineResult::Eof(5));
+    let res = Cursor::new(b"123456").read_line_max(&mut buf, 5);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // A limit of zero only allows empty lines.
+    let mut reader = Cursor::new(b"\nx");
+    assert_eq!(
+        reader.read_line_max(&mut buf, 0).unwrap(),
+        LineResult::Line(1)
+    );
+    assert!(reader.read_line_max(&mut buf, 0).is_err());
+}
+
+#[test]
+fn for_each_line() {
+    let input = b"one\r\ntwo\n\n\r\nlonger line\nlast";
+    for capacity in 1..=input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut lines = vec![];
+        let count = reader
+            .for_each_line(|line| {
+                lines.push(line.to_vec());
+                Ok(ControlFlow::Continue(()))
+            })
+            .unwrap();
+        assert_eq!(count, 6);
+        assert_eq!(
+            lines,
+            [&b"one"[..], b"two", b"", b"", b"longer line", b"last"],
+            "capacity {}",
+            capacity
+        );
+    }
+
+    // A final line with a terminator doesn't produce an extra empty line.
+    let mut reader = Cursor::new(b"a\n");
+    let count = reader.for_each_line(|_| Ok(ControlFlow::Continue(())));
+    assert_eq!(count.unwrap(), 1);
+}
+
+#[test]
+fn for_each_line_break() {
+    let input = b"a\nstop\nb\n";
+    for capacity in 1..=input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        let count = reader
+            .for_each_line(|line| match line {
+                b"stop" => Ok(ControlFlow::Break(())),
+                _ => Ok(ControlFlow::Continue(())),
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"b\n");
+    }
+
+    // Errors stop reading too.
+    let mut reader = Cursor::new(b"a\nb\nc\n");
+    let err = reader
+        .for_each_line(|line| match line {
+            b"b" => Err(std::io::Error::other("bad line")),
+            _ => Ok(ControlFlow::Continue(())),
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "bad line");
+    assert_eq!(reader.fill_buf().unwrap(), b"c\n");
+}
+
+/// A reader that returns a few bytes at a time, sleeping before each read.
+struct Throttled<'a>(&'a [u8]);
+
+impl Read for Throttled<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::thread::sleep(Duration::from_millis(1));
+        let n = buf.len().min(self.0.len()).min(16);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn copy_until_cancellable() {
+    let input = vec![b'a'; 64 * 1024];
+    let cancel = AtomicBool::new(false);
+    let mut reader = BufReader::new(Throttled(&input));
+    let mut out = vec![];
+
+    let (copied, end) = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        reader
+            .copy_until_cancellable(b'\n', &mut out, &cancel)
+            .unwrap()
+    });
+    assert_eq!(end, CopyEnd::Cancelled);
+    assert!(copied > 0 && copied < input.len() as u64, "{}", copied);
+    assert_eq!(out.len() as u64, copied);
+
+    // Resuming copies the rest.
+    cancel.store(false, Ordering::Relaxed);
+    let (rest, end) = reader
+        .copy_until_cancellable(b'\n', &mut out, &cancel)
+        .unwrap();
+    assert_eq!(end, CopyEnd::Eof);
+    assert_eq!(copied + rest, input.len() as u64);
+    assert_eq!(out, input);
+}
+
+#[test]
+fn copy_until_cancellable_never_cancelled() {
+    // Without cancellation the results match the plain variants.
+    let input = b"lorem\nipsum\ndolor";
+    let cancel = AtomicBool::new(false);
+    for capacity in 1..=input.len() {
+        let mut a = BufReader::with_capacity(capacity, &input[..]);
+        let mut b = BufReader::with_capacity(capacity, &input[..]);
+        let (mut out_a, mut out_b) = (vec![], vec![]);
+        for _ in 0..3 {
+            let copied = a.copy_until(b'\n', &mut out_a).unwrap();
+            let (copied_b, _) = b
+                .copy_until_cancellable(b'\n', &mut out_b, &cancel)
+                .unwrap();
+            assert_eq!(copied, copied_b);
+        }
+        assert_eq!(out_a, out_b);
+        assert_eq!(out_a, input);
+    }
+
+    let mut reader = Cursor::new(b"lorem\nipsum");
+    let mut out = vec![];
+    let res = reader.copy_until_cancellable(b'\n', &mut out, &cancel);
+    assert_eq!(res.unwrap(), (6, CopyEnd::Delimiter));
+    let res = reader.copy_until_cancellable(b'\n', &mut out, &cancel);
+    assert_eq!(res.unwrap(), (5, CopyEnd::Eof));
+}
+
+#[test]
+fn skip_to_end_cancellable() {
+    let input = vec![0; 64 * 1024];
+    let cancel = AtomicBool::new(false);
+    let mut reader = BufReader::new(Throttled(&input));
+
+    let (skipped, end) = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        reader.skip_to_end_cancellable(&cancel).unwrap()
+    });
+    assert_eq!(end, CopyEnd::Cancelled);
+    assert!(skipped < input.len() as u64);
+
+    cancel.store(false, Ordering::Relaxed);
+    let (rest, end) = reader.skip_to_end_cancellable(&cancel).unwrap();
+    assert_eq!(end, CopyEnd::Eof);
+    assert_eq!(skipped + rest, input.len() as u64);
+    assert_eq!(reader.skip_to_end().unwrap(), 0);
+}
+
+/// A writer that fails once it has been given `limit` bytes.
+struct FailAfter {
+    written: usize,
+    limit: usize,
+}
+
+impl std::io::Write for FailAfter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written + buf.len() > self.limit {
+            return Err(std::io::Error::other("disk full"));
+        }
+        self.written += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn copy_with_progress() {
+    let input: Vec<u8> = (0..100).collect();
+    let mut reader = BufReader::with_capacity(16, &input[..]);
+    let mut out = vec![];
+    let mut reports = vec![];
+    reader
+        .copy_exact_with_progress(90, &mut out, |n| reports.push(n))
+        .unwrap();
+    assert_eq!(out, &input[..90]);
+    // Once per chunk, and once more at the end.
+    assert_eq!(reports, [16, 32, 48, 64, 80, 90, 90]);
+
+    let mut reports = vec![];
+    let mut out = vec![];
+    let copied = reader
+        .copy_until_with_progress(95, &mut out, |n| reports.push(n))
+        .unwrap();
+    assert_eq!(copied, 6);
+    assert_eq!(reports, [6, 6]);
+
+    let mut reports = vec![];
+    let skipped = reader
+        .skip_to_end_with_progress(|n| reports.push(n))
+        .unwrap();
+    assert_eq!(skipped, 4);
+    assert_eq!(reports, [4, 4]);
+}
+
+#[test]
+fn copy_with_progress_monotonic() {
+    let input = vec![b'x'; 100_000];
+    let mut reader = BufReader::with_capacity(1000, &input[..]);
+    let mut reports = vec![];
+    let copied = reader
+        .copy_until_with_progress(b'\n', &mut std::io::sink(), |n| reports.push(n))
+        .unwrap();
+    assert_eq!(copied, 100_000);
+    assert_eq!(reports.len(), 101);
+    assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(reports.last(), Some(&100_000));
+}
+
+#[test]
+fn copy_with_progress_errors() {
+    // A failing writer still gets a final report.
+    let input = [0; 100];
+    let mut reader = BufReader::with_capacity(10, &input[..]);
+    let mut writer = FailAfter {
+        written: 0,
+        limit: 25,
+    };
+    let mut reports = vec![];
+    let err = reader
+        .copy_exact_with_progress(100, &mut writer, |n| reports.push(n))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+    assert_eq!(reports, [10, 20, 20]);
+
+    // So does a stream that ends early.
+    let mut reader = BufReader::with_capacity(10, &input[..15]);
+    let mut reports = vec![];
+    let err = reader
+        .copy_exact_with_progress(20, &mut vec![], |n| reports.push(n))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(reports, [10, 15, 15]);
+}
+
+#[test]
+fn copy_with_progress_zero_bytes() {
+    let mut reports = vec![];
+    let mut reader = Cursor::new(b"abc");
+    reader
+        .copy_exact_with_progress(0, &mut vec![], |n| reports.push(n))
+        .unwrap();
+    assert_eq!(reports, [0]);
+
+    let mut reports = vec![];
+    let mut reader = Cursor::new(b"");
+    reader
+        .copy_until_with_progress(b'\n', &mut vec![], |n| reports.push(n))
+        .unwrap();
+    reader
+        .skip_to_end_with_progress(|n| reports.push(n))
+        .unwrap();
+    assert_eq!(reports, [0, 0]);
+}
+
+#[test]
+fn while_peek_stops_mid_chunk() {
+    let mut reader = BufReader::with_capacity(8, &b"123e5"[..]);
+    let mut buf = vec![];
+    let res = reader
+        .read_while_peek(&mut buf, |b| b.is_ascii_digit())
+        .unwrap();
+    assert_eq!(res, (3, Some(b'e')));
+    assert_eq!(buf, b"123");
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'e');
+
+    let mut reader = BufReader::with_capacity(8, &b"   x"[..]);
+    assert_eq!(
+        reader.skip_while_peek(|b| b == b' ').unwrap(),
+        (3, Some(b'x'))
+    );
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'x');
+}
+
+#[test]
+fn while_peek_stops_at_chunk_boundary() {
+    // The stopping byte is the first byte of the second buffer.
+    let mut reader = BufReader::with_capacity(4, &b"1234.5"[..]);
+    let mut buf = vec![];
+    let res = reader
+        .read_while_peek(&mut buf, |b| b.is_ascii_digit())
+        .unwrap();
+    assert_eq!(res, (4, Some(b'.')));
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'.');
+
+    let mut reader = BufReader::with_capacity(4, &b"    ;"[..]);
+    assert_eq!(
+        reader.skip_while_peek(|b| b == b' ').unwrap(),
+        (4, Some(b';'))
+    );
+    assert_eq!(reader.read_be::<u8>().unwrap(), b';');
+}
+
+#[test]
+fn while_peek_stops_at_eof() {
+    let mut reader = BufReader::with_capacity(2, &b"12345"[..]);
+    let mut buf = vec![];
+    let res = reader
+        .read_while_peek(&mut buf, |b| b.is_ascii_digit())
+        .unwrap();
+    assert_eq!(res, (5, None));
+    assert_eq!(reader.skip_while_peek(|_| false).unwrap(), (0, None));
+}
+
+#[test]
+fn compare_buffered() {
+    use std::cmp::Ordering::*;
+
+    let mut reader = &b"key-0042 rest"[..];
+    assert_eq!(reader.compare_buffered(b"key-0042").unwrap(), Equal);
+    assert_eq!(reader.compare_buffered(b"key-0041").unwrap(), Greater);
+    assert_eq!(reader.compare_buffered(b"key-0043").unwrap(), Less);
+    assert_eq!(reader.compare_buffered(b"jey-0042").unwrap(), Greater);
+    assert_eq!(reader.compare_buffered(b"ley-0042").unwrap(), Less);
+    assert_eq!(reader.compare_buffered(b"").unwrap(), Equal);
+    assert_eq!(reader, b"key-0042 rest");
+}
+
+#[test]
+fn compare_buffered_shorter_stream() {
+    use std::cmp::Ordering::*;
+
+    let mut reader = &b"abc"[..];
+    assert_eq!(reader.compare_buffered(b"abcd").unwrap(), Less);
+    assert_eq!(reader.compare_buffered(b"abbd").unwrap(), Greater);
+    assert_eq!(reader.compare_buffered(b"abd").unwrap(), Less);
+    assert_eq!((&b""[..]).compare_buffered(b"a").unwrap(), Less);
+
+    // `Lookahead` sees past the underlying buffer.
+    let inner = BufReader::with_capacity(2, &b"abcdef"[..]);
+    let mut reader = omnom::Lookahead::<_, 6>::new(inner).unwrap();
+    reader.consume(1);
+    assert_eq!(reader.compare_buffered(b"bcdef").unwrap(), Equal);
+    assert_eq!(reader.compare_buffered(b"bcdeg").unwrap(), Less);
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"bcdef");
+}
+
+#[test]
+fn skip_while_limited_at_chunk_boundary() {
+    let mut reader = BufReader::with_capacity(4, &b"aaaaaaaab"[..]);
+    let res = reader.skip_while_limited(4, |b| b == b'a').unwrap();
+    assert_eq!(res, SkipOutcome::LimitReached(4));
+    assert_eq!(reader.fill_buf().unwrap(), b"aaaa");
+
+    let mut reader = BufReader::with_capacity(4, &b"aaaab"[..]);
+    let res = reader.skip_while_limited(4, |b| b == b'a').unwrap();
+    assert_eq!(res, SkipOutcome::PredicateFailed(4));
+    assert_eq!(reader.fill_buf().unwrap(), b"b");
+
+    let mut reader = BufReader::with_capacity(4, &b"aaaa"[..]);
+    let res = reader.skip_while_limited(4, |b| b == b'a').unwrap();
+    assert_eq!(res, SkipOutcome::Eof(4));
+}
+
+#[test]
+fn skip_while_limited_beyond_stream() {
+    let mut reader = BufReader::with_capacity(3, &b"  x"[..]);
+    let res = reader.skip_while_limited(100, |b| b == b' ').unwrap();
+    assert_eq!(res, SkipOutcome::PredicateFailed(2));
+    assert_eq!(reader.fill_buf().unwrap(), b"x");
+
+    let mut reader = BufReader::with_capacity(3, &b"        "[..]);
+    let res = reader.skip_while_limited(u64::MAX, |b| b == b' ').unwrap();
+    assert_eq!(res, SkipOutcome::Eof(8));
+}
+
+#[test]
+fn skip_while_limited_zero() {
+    let mut reader = Cursor::new(b"ab");
+    let res = reader.skip_while_limited(0, |b| b == b'a').unwrap();
+    assert_eq!(res, SkipOutcome::LimitReached(0));
+    let res = reader.skip_while_limited(0, |b| b == b'b').unwrap();
+    assert_eq!(res, SkipOutcome::PredicateFailed(0));
+    assert_eq!(reader.fill_buf().unwrap(), b"ab");
+
+    let mut reader = Cursor::new(b"");
+    let res = reader.skip_while_limited(0, |_| true).unwrap();
+    assert_eq!(res, SkipOutcome::Eof(0));
+}
+
+#[test]
+fn skip_while_limited_resume() {
+    let mut reader = BufReader::with_capacity(3, &b"0123456789;"[..]);
+    let mut total = 0;
+    loop {
+        match reader
+            .skip_while_limited(4, |b| b.is_ascii_digit())
+            .unwrap()
+        {
+            SkipOutcome::LimitReached(n) => total += n,
+            SkipOutcome::PredicateFailed(n) => break total += n,
+            SkipOutcome::Eof(_) => panic!("unexpected EOF"),
+        }
+    }
+    assert_eq!(total, 10);
+    assert_eq!(reader.fill_buf().unwrap(), b";");
+}
+
+#[test]
+fn read_remaining_matches_read_to_end() {
+    let bytes: Vec<u8> = (0..100_000).map(|i| (i * 7) as u8).collect();
+    let mut expected = vec![];
+    BufReader::with_capacity(333, &bytes[..])
+        .read_to_end(&mut expected)
+        .unwrap();
+
+    let mut out = vec![];
+    let read = BufReader::with_capacity(333, &bytes[..])
+        .read_while(&mut out, |_| true)
+        .unwrap();
+    assert_eq!(read, bytes.len());
+    assert_eq!(out, expected);
+
+    let mut reader = BufReader::with_capacity(333, &bytes[..]);
+    let mut out = b"prefix".to_vec();
+    assert_eq!(reader.read_remaining(&mut out).unwrap(), bytes.len());
+    assert_eq!(out[..6], *b"prefix");
+    assert_eq!(out[6..], expected[..]);
+    assert_eq!(reader.read_remaining(&mut out).unwrap(), 0);
+}
+
+#[test]
+fn peek_byte() {
+    let mut reader = BufReader::with_capacity(2, &b"abc"[..]);
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'a'));
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'a'));
+    reader.consume(1);
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'b'));
+    reader.consume(1);
+    // The next byte is past the first buffer.
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'c'));
+    reader.consume(1);
+    assert_eq!(reader.peek_byte().unwrap(), None);
+    assert_eq!(reader.peek_byte().unwrap(), None);
+}
+
+/// A reader that's interrupted before each read.
+struct Interrupting<'a> {
+    data: &'a [u8],
+    interrupted: bool,
+}
+
+impl Read for Interrupting<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupted = !self.interrupted;
+        if self.interrupted {
+            return Err(ErrorKind::Interrupted.into());
+        }
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn peek_byte_interrupted() {
+    let inner = Interrupting {
+        data: b"x",
+        interrupted: false,
+    };
+    let mut reader = BufReader::new(inner);
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'x'));
+    reader.consume(1);
+    assert_eq!(reader.peek_byte().unwrap(), None);
+}
+
+#[test]
+fn fill_exact_buffered() {
+    let mut reader = BufReader::with_capacity(8, &b"lorem ipsum"[..]);
+    let mut buf = [0; 8];
+    reader.fill_exact_buffered(&mut buf).unwrap();
+    assert_eq!(&buf, b"lorem ip");
+    reader.fill_exact_buffered(&mut buf[..5]).unwrap();
+    assert_eq!(&buf[..5], b"lorem");
+
+    let mut read = [0; 11];
+    reader.read_exact(&mut read).unwrap();
+    assert_eq!(&read, b"lorem ipsum");
+
+    let err = reader.fill_exact_buffered(&mut [0; 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    reader.fill_exact_buffered(&mut []).unwrap();
+}
+
+#[test]
+fn fill_exact_truncated() {
+    fn check<R: BufferAhead>(mut reader: R) {
+        let mut buf = [0; 3];
+        reader.fill_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"abc");
+
+        // The stream ends before the requested bytes.
+        let err = reader.fill_exact(&mut [0; 8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+        assert_eq!((short.needed(), short.available()), (8, 3));
+        assert_eq!(reader.fill_buf().unwrap(), b"abc");
+    }
+
+    check(Cursor::new(b"abc"));
+    check(Lookahead::<_, 8>::new(BufReader::with_capacity(2, &b"abc"[..])).unwrap());
+    check(Unread::new(BufReader::with_capacity(2, &b"abc"[..])));
+}
+
+#[test]
+fn fill_be_then_read() {
+    let mut reader = Unread::new(BufReader::with_capacity(
+        8,
+        &b"\x00\x01\x02\x03\x04\x05"[..],
+    ));
+    let peeked: u32 = reader.fill_be().unwrap();
+    assert_eq!(peeked, 0x0001_0203);
+    assert_eq!(reader.fill_le::<u32>().unwrap(), 0x0302_0100);
+    assert_eq!(
+        reader.fill_ne::<u32>().unwrap(),
+        u32::from_ne_bytes([0, 1, 2, 3])
+    );
+    assert_eq!(reader.read_be::<u32>().unwrap(), peeked);
+
+    // Fewer bytes are left than the value needs.
+    let err = reader.fill_be::<u32>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (4, 2));
+    assert_eq!(reader.fill_be::<u16>().unwrap(), 0x0405);
+    assert_eq!(reader.read_be::<u16>().unwrap(), 0x0405);
+    assert!(reader.fill_le::<u8>().is_err());
+}
+
+#[test]
+fn fill_be_across_refill() {
+    // The value straddles the 3 byte buffer of the inner reader.
+    let input = &b"\x01\x02\x03\x04\x05\x06"[..];
+    let mut inner = BufReader::with_capacity(3, input);
+    assert_eq!(inner.fill_buf().unwrap(), b"\x01\x02\x03");
+    inner.consume(2);
+
+    let mut reader = Unread::new(inner);
+    let peeked: u32 = reader.fill_be().unwrap();
+    assert_eq!(peeked, 0x0304_0506);
+    assert_eq!(reader.fill_le::<u32>().unwrap(), 0x0605_0403);
+    assert_eq!(reader.read_be::<u32>().unwrap(), peeked);
+
+    // `Lookahead` tops up its buffer instead.
+    let inner = BufReader::with_capacity(3, input);
+    let mut reader = Lookahead::<_, 2>::new(inner).unwrap();
+    reader.consume(1);
+    let peeked: u32 = reader.fill_be().unwrap();
+    assert_eq!(peeked, 0x0203_0405);
+    assert_eq!(reader.read_be::<u32>().unwrap(), peeked);
+}
+
+#[test]
+fn fill_exact_small_capacity() {
+    // The buffer of the reader can't hold the requested bytes.
+    let mut reader = BufReader::with_capacity(2, &b"lorem ipsum"[..]);
+    // This isn't the end of the stream, so it's not `UnexpectedEof`.
+    let err = reader.fill_exact_buffered(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    let small = err.get_ref().unwrap().downcast_ref::<BufferTooSmall>();
+    assert_eq!(small, Some(&BufferTooSmall::new(8, 2)));
+    assert_eq!(reader.fill_buf().unwrap(), b"lo");
+
+    let mut reader = Lookahead::<_, 8>::new(reader).unwrap();
+    let mut buf = [0; 8];
+    reader.fill_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"lorem ip");
+    let mut read = [0; 8];
+    reader.read_exact(&mut read).unwrap();
+    assert_eq!(read, buf);
+
+    let err = reader.fill_exact(&mut [0; 4]).unwrap_err();
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (4, 3));
+    reader.consume(3);
+    let err = reader.fill_exact(&mut [0; 4]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (4, 0));
+}
+
+#[test]
+fn peek_across_refills() {
+    let input = &b"RIFF\x04\0\0\0WAVE"[..];
+    let mut reader = Lookahead::<_, 2>::new(BufReader::with_capacity(3, input)).unwrap();
+    assert_eq!(reader.peek(4).unwrap(), b"RIFF");
+    assert_eq!(reader.peek(12).unwrap(), input);
+    reader.consume(8);
+    let err = reader.peek(5).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (5, 4));
+    assert_eq!(reader.peek(4).unwrap(), b"WAVE");
+
+    let mut cursor = Cursor::new(b"abc");
+    assert_eq!(cursor.peek(3).unwrap(), b"abc");
+    assert_eq!(cursor.peek(0).unwrap(), b"");
+    assert!(cursor.peek(4).is_err());
+}
+
+#[test]
+fn fill_while_keyword() {
+    const KEYWORDS: [&[u8]; 3] = [b"GET", b"HEAD", b"POST"];
+
+    let input = &b"HEAD /index.html HTTP/1.1\r\n"[..];
+    let mut reader = Lookahead::<_, 16>::new(BufReader::with_capacity(2, input)).unwrap();
+    let mut token = vec![];
+    let len = reader
+        .fill_while(&mut token, |b| b.is_ascii_uppercase())
+        .unwrap();
+    assert_eq!(token, b"HEAD");
+    assert!(KEYWORDS.contains(&&token[..]));
+    reader.skip(len).unwrap();
+    assert!(reader.consume_prefix(b" ").unwrap());
+
+    // Not a keyword, so it's parsed differently and left in the stream.
+    let mut token = vec![];
+    reader.fill_while(&mut token, |b| b != b' ').unwrap();
+    assert_eq!(token, b"/index.html");
+    let mut path = String::new();
+    reader.read_until(b' ', &mut vec![]).unwrap();
+    reader.read_line(&mut path).unwrap();
+    assert_eq!(path, "HTTP/1.1\r\n");
+}
+
+#[test]
+fn fill_while_across_refills() {
+    // The token is longer than the inner reader's buffer.
+    let inner = BufReader::with_capacity(2, &b"lorem ipsum"[..]);
+    let mut reader = Unread::new(inner);
+    let mut buf = vec![];
+    assert_eq!(reader.fill_while(&mut buf, |b| b != b' ').unwrap(), 5);
+    assert_eq!(buf, b"lorem");
+    let mut read = vec![];
+    reader.read_until(b' ', &mut read).unwrap();
+    assert_eq!(read, b"lorem ");
+
+    // The predicate holds until EOF.
+    let mut buf = vec![];
+    assert_eq!(reader.fill_while(&mut buf, |_| true).unwrap(), 5);
+    assert_eq!(buf, b"ipsum");
+    assert_eq!(reader.skip_to_end().unwrap(), 5);
+    assert_eq!(reader.fill_while(&mut buf, |_| true).unwrap(), 0);
+}
+
+#[test]
+fn fill_until() {
+    let input = &b"GET / HTTP/1.1\r\nHost: example.com\r\n"[..];
+    let mut reader = Unread::new(BufReader::with_capacity(64, input));
+    let mut line = vec![];
+    assert_eq!(reader.fill_until(b'\n', &mut line).unwrap(), (16, true));
+    let mut read = vec![];
+    reader.read_until(b'\n', &mut read).unwrap();
+    assert_eq!(read, line);
+
+    // The delimiter is past the end of the inner reader's buffer.
+    let mut reader = Unread::new(BufReader::with_capacity(4, input));
+    let mut peeked = vec![];
+    assert_eq!(reader.fill_until(b'\n', &mut peeked).unwrap(), (16, true));
+    assert_eq!(peeked, b"GET / HTTP/1.1\r\n");
+    let mut read = vec![];
+    reader.read_until(b'\n', &mut read).unwrap();
+    assert_eq!(read, peeked);
+
+    // Without the delimiter, all bytes up to EOF are copied.
+    let mut peeked = vec![];
+    assert_eq!(reader.fill_until(b'!', &mut peeked).unwrap(), (19, false));
+    assert_eq!(peeked, b"Host: example.com\r\n");
+    assert_eq!(reader.fill_buf().unwrap(), b"Host: example.com\r\n");
+
+    let mut reader = Cursor::new(b"");
+    assert_eq!(reader.fill_until(b'\n', &mut peeked).unwrap(), (0, false));
+}
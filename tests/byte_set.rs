@@ -0,0 +1,89 @@
+#![cfg(feature = "std")]
+
+use omnom::predicates::any_of;
+use omnom::prelude::*;
+use omnom::ByteSet;
+use std::io::{BufRead, BufReader};
+
+static WORD: ByteSet = ByteSet::range(b'a'..=b'z')
+    .union(ByteSet::range(b'A'..=b'Z'))
+    .union(ByteSet::new(b"_"));
+
+#[test]
+fn set_algebra() {
+    let digits = ByteSet::range(b'0'..=b'9');
+    let odd = ByteSet::new(b"13579");
+    assert_eq!(digits | odd, digits);
+    assert_eq!(digits & odd, odd);
+    assert_eq!((digits & !odd).iter().collect::<Vec<_>>(), b"02468");
+    assert_eq!(!!digits, digits);
+    assert_eq!(!ByteSet::EMPTY, ByteSet::ALL);
+    assert_eq!(digits | !digits, ByteSet::ALL);
+    assert_eq!(digits & !digits, ByteSet::EMPTY);
+    assert_eq!(ByteSet::from(b'0'..=b'9'), digits);
+    assert_eq!(ByteSet::from(&b"9876543210"[..]), digits);
+    assert_eq!(ByteSet::from(*b"13579"), odd);
+    assert_eq!(
+        format!("{:?}", ByteSet::new(b"a\n'")),
+        r"{b'\n', b'\'', b'a'}"
+    );
+}
+
+#[test]
+fn const_construction() {
+    assert_eq!(WORD.len(), 53);
+    assert!(WORD.contains(b'_'));
+    assert!(!WORD.contains(b'-'));
+    const EMPTY: ByteSet = ByteSet::new(b"");
+    assert!(EMPTY.is_empty());
+}
+
+#[test]
+fn boundary_bytes() {
+    let set = ByteSet::new(&[0, 255]);
+    assert_eq!(set.iter().collect::<Vec<_>>(), [0, 255]);
+    assert_eq!((!set).len(), 254);
+    assert!(!set.contains(1) && !set.contains(254));
+    assert_eq!(ByteSet::range(0..=255), ByteSet::ALL);
+    assert_eq!(ByteSet::range(255..=255), ByteSet::new(&[255]));
+    assert_eq!(ByteSet::ALL.len(), 256);
+}
+
+#[test]
+fn reuse_across_calls() {
+    let input = "alpha, beta;gamma  delta,\tepsilon ".repeat(50);
+    let separators = ByteSet::new(b" ,;\t");
+    let mut reader = BufReader::with_capacity(5, input.as_bytes());
+
+    let mut words = vec![];
+    loop {
+        reader.skip_any_of(separators).unwrap();
+        let mut word = vec![];
+        if reader.read_any_of(WORD, &mut word).unwrap() == 0 {
+            break;
+        }
+        words.push(String::from_utf8(word).unwrap());
+    }
+    assert_eq!(words.len(), 250);
+    assert!(words
+        .chunks(5)
+        .all(|w| w == ["alpha", "beta", "gamma", "delta", "epsilon"]));
+
+    let mut p = any_of(separators);
+    assert_eq!((0..=255).filter(|b| p(*b)).count(), 4);
+}
+
+#[test]
+fn until_any() {
+    let mut reader = BufReader::with_capacity(3, &b"key=value;next"[..]);
+    let mut buf = vec![];
+    assert_eq!(
+        reader.read_until_any(b"=;", &mut buf).unwrap(),
+        (4, Some(b'='))
+    );
+    assert_eq!(reader.skip_until_any(b";").unwrap(), (6, Some(b';')));
+    assert_eq!(reader.read_until_any(b"=;", &mut buf).unwrap(), (4, None));
+    assert_eq!(buf, b"key=next");
+    assert_eq!(reader.skip_until_any(b";").unwrap(), (0, None));
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+}
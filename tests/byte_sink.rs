@@ -0,0 +1,207 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{StopReason, Utf8Sink};
+use std::io::{BufRead, BufReader, Cursor, ErrorKind};
+
+#[test]
+fn utf8_sink_across_chunks() {
+    let text = "ünïcödé → 世界 🦀!";
+    // A tiny buffer splits most characters across `fill_buf` calls.
+    for capacity in 1..6 {
+        let mut reader = BufReader::with_capacity(capacity, text.as_bytes());
+        let mut s = String::new();
+        let mut sink = Utf8Sink::new(&mut s);
+        let read = reader.read_while(&mut sink, |b| b != b'!').unwrap();
+        sink.finish().unwrap();
+        assert_eq!(read, text.len() - 1);
+        assert_eq!(s, &text[..text.len() - 1]);
+    }
+}
+
+#[test]
+fn utf8_sink_rejects_invalid() {
+    for input in [&b"\xffabc"[..], b"ab\xc3(", b"abc\xe4\xb8\xe4"].iter() {
+        let mut reader = BufReader::with_capacity(2, *input);
+        let mut s = String::new();
+        let mut sink = Utf8Sink::new(&mut s);
+        let err = reader.read_while(&mut sink, |_| true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(input.starts_with(s.as_bytes()));
+    }
+
+    // The chunk with the invalid byte isn't consumed.
+    let mut cursor = Cursor::new(b"ab\xff");
+    let mut s = String::new();
+    assert!(cursor
+        .read_while(&mut Utf8Sink::new(&mut s), |_| true)
+        .is_err());
+    assert_eq!(cursor.fill_buf().unwrap(), b"ab\xff");
+    assert!(s.is_empty());
+}
+
+#[test]
+fn utf8_sink_incomplete() {
+    let mut cursor = Cursor::new(&b"ab\xe4\xb8"[..]);
+    let mut s = String::new();
+    let mut sink = Utf8Sink::new(&mut s);
+    assert_eq!(cursor.read_while(&mut sink, |_| true).unwrap(), 4);
+    assert_eq!(sink.finish().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(s, "ab");
+}
+
+#[test]
+fn same_results_for_every_sink() {
+    let input = b"(a (b) c) rest";
+    let mut vec = vec![];
+    let mut s = String::new();
+    let mut reader = Cursor::new(&input[1..]);
+    reader.read_balanced(b'(', b')', &mut vec).unwrap();
+    let mut reader = Cursor::new(&input[1..]);
+    let mut sink = Utf8Sink::new(&mut s);
+    reader.read_balanced(b'(', b')', &mut sink).unwrap();
+    sink.finish().unwrap();
+    assert_eq!(vec, s.as_bytes());
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn smallvec_sink() {
+    use smallvec::SmallVec;
+
+    let input = b"a,b,c,d";
+    let mut vec = vec![];
+    let mut small = SmallVec::<[u8; 4]>::new();
+    Cursor::new(input)
+        .read_until_nth(b',', 3, &mut vec)
+        .unwrap();
+    Cursor::new(input)
+        .read_until_nth(b',', 3, &mut small)
+        .unwrap();
+    assert_eq!(&vec[..], &small[..]);
+    assert!(small.spilled());
+
+    let mut small = SmallVec::<[u8; 16]>::new();
+    let mut reader = BufReader::with_capacity(2, &input[..]);
+    reader.read_while(&mut small, |b| b != b'd').unwrap();
+    assert_eq!(&small[..], b"a,b,c,");
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_mut_sink() {
+    let mut buf = bytes::BytesMut::new();
+    Cursor::new(b"lorem ipsum")
+        .read_while(&mut buf, |b| b != b' ')
+        .unwrap();
+    assert_eq!(&buf[..], b"lorem");
+}
+
+#[test]
+fn capped_reads_into_vec() {
+    let mut reader = BufReader::with_capacity(3, &b"lorem ipsum\ndolor"[..]);
+    let mut buf = vec![];
+    let res = reader.read_while_capped(&mut buf, |b| b != b' ').unwrap();
+    assert_eq!(res, (5, StopReason::Rejected(b' ')));
+    let res = reader.read_until_capped(b'\n', &mut buf).unwrap();
+    assert_eq!(res, (7, StopReason::Delimiter));
+    let res = reader.read_until_capped(b'\n', &mut buf).unwrap();
+    assert_eq!(res, (5, StopReason::Eof));
+    assert_eq!(buf, b"lorem ipsum\ndolor");
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_sink() {
+    // Filled exactly: the real reason is reported.
+    let mut reader = BufReader::with_capacity(2, &b"abcd-e"[..]);
+    let mut buf = heapless::Vec::<u8, 4>::new();
+    let res = reader.read_while_capped(&mut buf, |b| b != b'-').unwrap();
+    assert_eq!(res, (4, StopReason::Rejected(b'-')));
+    assert_eq!(&buf[..], b"abcd");
+
+    // Overflow: the byte that didn't fit is still readable.
+    let mut reader = BufReader::with_capacity(3, &b"abcdef\n"[..]);
+    let mut buf = heapless::Vec::<u8, 4>::new();
+    let res = reader.read_until_capped(b'\n', &mut buf).unwrap();
+    assert_eq!(res, (4, StopReason::SinkFull));
+    assert_eq!(&buf[..], b"abcd");
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'e');
+
+    // A full sink makes an unbounded read fail, without appending.
+    let err = reader.read_while(&mut buf, |_| true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(&buf[..], b"abcd");
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn arrayvec_sink() {
+    use arrayvec::ArrayVec;
+
+    // The delimiter doesn't fit once the rest of the line fills the sink.
+    let mut reader = BufReader::with_capacity(2, &b"abcd\n"[..]);
+    let mut buf = ArrayVec::<u8, 4>::new();
+    let res = reader.read_until_capped(b'\n', &mut buf).unwrap();
+    assert_eq!(res, (4, StopReason::SinkFull));
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'\n');
+
+    let mut reader = Cursor::new(b"abcdef");
+    let mut buf = ArrayVec::<u8, 4>::new();
+    let res = reader.read_while_capped(&mut buf, |_| true).unwrap();
+    assert_eq!(res, (4, StopReason::SinkFull));
+    assert_eq!(&buf[..], b"abcd");
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'e');
+
+    // Filled exactly at EOF.
+    let mut reader = Cursor::new(b"wxyz");
+    let mut buf = ArrayVec::<u8, 4>::new();
+    let res = reader.read_while_capped(&mut buf, |_| true).unwrap();
+    assert_eq!(res, (4, StopReason::Eof));
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn percent_decoded_into_arrayvec() {
+    use arrayvec::ArrayVec;
+
+    let mut reader = Cursor::new(b"a%20b&c");
+    let mut buf = ArrayVec::<u8, 4>::new();
+    let res = reader.read_percent_decoded_until(b"&", &mut buf, false);
+    assert_eq!(res.unwrap(), (3, Some(b'&')));
+    assert_eq!(&buf[..], b"a b");
+
+    // The decoded `!` doesn't fit, so its last hex digit isn't consumed.
+    let mut reader = Cursor::new(b"ab%21c");
+    let mut buf = ArrayVec::<u8, 2>::new();
+    assert!(reader
+        .read_percent_decoded_until(b"&", &mut buf, false)
+        .is_err());
+    assert_eq!(&buf[..], b"ab");
+    assert_eq!(reader.position(), 4);
+}
+
+#[cfg(all(feature = "heapless", feature = "arrayvec"))]
+#[test]
+fn fixed_sinks_match_vec() {
+    let input = b"key=value;rest";
+    for capacity in 1..5 {
+        let mut vec = vec![];
+        let mut heapless = heapless::Vec::<u8, 16>::new();
+        let mut array = arrayvec::ArrayVec::<u8, 16>::new();
+        let vec_res = BufReader::with_capacity(capacity, &input[..])
+            .read_until_capped(b';', &mut vec)
+            .unwrap();
+        let heapless_res = BufReader::with_capacity(capacity, &input[..])
+            .read_until_capped(b';', &mut heapless)
+            .unwrap();
+        let array_res = BufReader::with_capacity(capacity, &input[..])
+            .read_until_capped(b';', &mut array)
+            .unwrap();
+        assert_eq!(vec_res, (10, StopReason::Delimiter));
+        assert_eq!(heapless_res, vec_res);
+        assert_eq!(array_res, vec_res);
+        assert_eq!(&heapless[..], &vec[..]);
+        assert_eq!(&array[..], &vec[..]);
+    }
+}
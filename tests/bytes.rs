@@ -0,0 +1,128 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use omnom::prelude::*;
+use omnom::{BufExt, BufMutExt, BufScanExt};
+use std::io::Cursor;
+
+#[test]
+fn values_straddling_chunks() {
+    let mut buf = Bytes::from_static(&[0x01, 0x02, 0x03]).chain(Bytes::from_static(&[0x04, 0x05]));
+    assert_eq!(buf.get_be::<u32>(), 0x0102_0304);
+    assert_eq!(buf.try_get_be::<u16>(), None);
+    assert_eq!(buf.remaining(), 1);
+    assert_eq!(buf.get_le::<u8>(), 0x05);
+
+    let mut buf = (&[0xff][..]).chain(&[0xff, 0xff, 0xff][..]);
+    assert_eq!(buf.try_get_le::<i32>(), Some(-1));
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+#[should_panic]
+fn get_panics_when_short() {
+    let mut buf = &[0, 1, 2][..];
+    buf.get_be::<u32>();
+}
+
+#[test]
+fn bytes_mut_writing() {
+    let mut buf = BytesMut::new();
+    buf.put_be(0x0102_u16);
+    buf.put_le(0x0304_u16);
+    buf.put_ne(-1_i8);
+    buf.put_u8(9);
+    assert_eq!(&buf[..], &[0x01, 0x02, 0x04, 0x03, 0xff, 9]);
+}
+
+#[test]
+fn equivalent_to_io_reads() {
+    let data: Vec<u8> = (0..32).collect();
+    let mut buf = &data[..];
+    let mut cursor = Cursor::new(&data[..]);
+
+    assert_eq!(buf.get_be::<u64>(), cursor.read_be::<u64>().unwrap());
+    assert_eq!(buf.get_le::<i128>(), cursor.read_le::<i128>().unwrap());
+    assert_eq!(buf.get_ne::<u32>(), cursor.read_ne::<u32>().unwrap());
+    assert_eq!(buf.get_be::<i16>(), cursor.read_be::<i16>().unwrap());
+}
+
+#[test]
+fn scanning_chained_buffers() {
+    let mut buf = Bytes::from_static(b"  lo").chain(Bytes::from_static(b"rem-ip"));
+    assert_eq!(BufScanExt::read_while(&mut buf, |b| b == b' '), "  ");
+    assert_eq!(BufScanExt::read_while(&mut buf, |b| b != b'-'), "lorem");
+    assert!(BufScanExt::consume_prefix(&mut buf, b"-"));
+    assert!(!BufScanExt::consume_prefix(&mut buf, b"ipsum"));
+    assert_eq!(BufScanExt::skip_until(&mut buf, b'-'), (2, false));
+    assert!(!buf.has_remaining());
+
+    let mut buf = (&b"HT"[..]).chain(&b"TP/1.1"[..]);
+    assert!(BufScanExt::consume_prefix(&mut buf, b"HTTP/"));
+    assert_eq!(buf.remaining(), 3);
+
+    let mut buf = (&b"a,b"[..]).chain(&b"b,c"[..]);
+    assert_eq!(BufScanExt::skip_until(&mut buf, b','), (2, true));
+    assert_eq!(BufScanExt::skip_until(&mut buf, b','), (3, true));
+    assert_eq!(BufScanExt::skip_until(&mut buf, b','), (1, false));
+}
+
+#[test]
+fn scanning_zero_copy() {
+    let input = Bytes::from_static(b"12345abc");
+    let mut buf = input.clone();
+    let digits = BufScanExt::read_while(&mut buf, |b| b.is_ascii_digit());
+    assert_eq!(digits, "12345");
+    assert!(std::ptr::eq(digits.as_ptr(), input.as_ptr()));
+
+    let mut buf = BytesMut::from(&b"key=value"[..]);
+    let start = buf.as_ptr();
+    let key = BufScanExt::read_while(&mut buf, |b| b != b'=');
+    assert_eq!(key, "key");
+    assert!(std::ptr::eq(key.as_ptr(), start));
+}
+
+#[test]
+fn scanning_parity_with_io() {
+    use std::io::BufRead;
+
+    let data = b"  GET /index.html HTTP/1.1\r\nHost: example.com\r\n";
+    let mut buf = Bytes::from_static(data);
+    let mut reader = std::io::BufReader::with_capacity(4, &data[..]);
+
+    assert_eq!(
+        BufScanExt::read_while(&mut buf, |b| b == b' ').len(),
+        BufReadExt::read_while(&mut reader, &mut vec![], |b| b == b' ').unwrap()
+    );
+    assert_eq!(
+        BufScanExt::consume_prefix(&mut buf, b"GET "),
+        BufReadExt::consume_prefix(&mut reader, b"GET ").unwrap()
+    );
+    let mut path = vec![];
+    BufReadExt::read_while(&mut reader, &mut path, |b| b != b' ').unwrap();
+    assert_eq!(BufScanExt::read_while(&mut buf, |b| b != b' '), path);
+    for _ in 0..3 {
+        let (n, found) = BufScanExt::skip_until(&mut buf, b'\n');
+        assert_eq!(n, BufReadExt::skip_until(&mut reader, b'\n').unwrap());
+        assert_eq!(found, n > 0);
+    }
+    assert_eq!(buf.remaining(), 0);
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+fn consume_prefix_mismatch_across_chunks() {
+    let mut buf = (&b"HT"[..]).chain(&b"TX/1.1"[..]);
+    assert!(!BufScanExt::consume_prefix(&mut buf, b"HTTP/"));
+    assert_eq!(buf.remaining(), 8);
+    assert_eq!(buf.chunk(), b"HT");
+
+    // The same input over a `BufRead` is an error, as the matching part of
+    // the first buffer was consumed.
+    let mut reader = std::io::BufReader::with_capacity(2, &b"HTTX/1.1"[..]);
+    assert!(BufReadExt::consume_prefix(&mut reader, b"HTTP/").is_err());
+
+    let mut buf = (&b"H"[..]).chain((&b"T"[..]).chain(&b"TP/"[..]));
+    assert!(BufScanExt::consume_prefix(&mut buf, b"HTTP/"));
+    assert!(!buf.has_remaining());
+}
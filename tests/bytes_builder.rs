@@ -0,0 +1,87 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::BytesBuilder;
+use std::io::Write;
+
+/// Counts the calls to `write`.
+#[derive(Default)]
+struct CountingWriter {
+    bytes: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn matches_write_ext() {
+    let mut expected = vec![];
+    expected.write_be(0xcafe_babe_u32).unwrap();
+    expected.write_le(52_u16).unwrap();
+    expected.write_ne(-1_i64).unwrap();
+    expected.write_all(b"body").unwrap();
+    expected.write_be(1_u8).unwrap();
+
+    let mut builder = BytesBuilder::new();
+    builder
+        .be(0xcafe_babe_u32)
+        .le(52_u16)
+        .ne(-1_i64)
+        .bytes(b"body")
+        .be(1_u8);
+    assert_eq!(builder.as_slice(), &expected[..]);
+    assert_eq!(builder.len(), expected.len());
+
+    let mut writer = CountingWriter::default();
+    assert_eq!(builder.write_to(&mut writer).unwrap(), expected.len());
+    assert_eq!(writer.bytes, expected);
+    assert_eq!(writer.writes, 1);
+}
+
+#[test]
+fn write_ext_on_builder() {
+    let mut builder = BytesBuilder::new();
+    builder.write_be(7_u16).unwrap();
+    builder.write_vec_prefixed_le::<u8, u8>(&[1, 2]).unwrap();
+    assert_eq!(builder.as_slice(), [0, 7, 2, 1, 2]);
+}
+
+#[test]
+fn reuse_after_clear() {
+    let mut builder = BytesBuilder::with_capacity(16);
+    builder.be(1_u64).be(2_u64);
+    builder.clear();
+    assert!(builder.is_empty());
+    builder.le(3_u16);
+    assert_eq!(builder.as_slice(), [3, 0]);
+    assert_eq!(builder.into_vec(), vec![3, 0]);
+}
+
+#[test]
+fn patch_own_length() {
+    let mut builder = BytesBuilder::new();
+    builder.bytes(b"HDR").be(0_u32).le(0_u16).bytes(b"payload");
+    let len = builder.len();
+    builder
+        .patch_be(3, len as u32)
+        .patch_le(7, (len - 9) as u16);
+    assert_eq!(builder.as_slice(), b"HDR\x00\x00\x00\x10\x07\x00payload");
+}
+
+#[test]
+#[should_panic]
+fn patch_out_of_bounds() {
+    let mut builder = BytesBuilder::new();
+    builder.be(0_u16);
+    builder.patch_be(1, 0_u16);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "std")]
+
+use omnom::{ChecksumMismatch, ChecksummedFrameReader, FrameWriter, ShortRead};
+use std::io::{BufReader, ErrorKind};
+
+fn frames(payloads: &[&[u8]]) -> Vec<u8> {
+    let mut writer = FrameWriter::new(vec![]);
+    for payload in payloads {
+        let written = writer.write_frame(payload).unwrap();
+        assert_eq!(written, payload.len() + 8);
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn round_trip() {
+    let long = vec![0xa5; 1000];
+    let bytes = frames(&[b"lorem", &long, b"ipsum"]);
+    let mut reader = ChecksummedFrameReader::new(BufReader::with_capacity(7, &bytes[..]), 1024);
+    let mut buf = vec![];
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(5));
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(1000));
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(5));
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), None);
+    assert_eq!(&buf[..5], b"lorem");
+    assert_eq!(&buf[5..1005], &long[..]);
+    assert_eq!(&buf[1005..], b"ipsum");
+}
+
+#[test]
+fn known_checksum() {
+    // The check value of CRC-32 (IEEE).
+    let bytes = frames(&[b"123456789"]);
+    assert_eq!(bytes[..4], [0, 0, 0, 9]);
+    assert_eq!(bytes[13..], 0xcbf4_3926_u32.to_be_bytes());
+}
+
+#[test]
+fn empty_payload() {
+    let bytes = frames(&[b"", b"x"]);
+    assert_eq!(bytes[..8], [0; 8]);
+    let mut reader = ChecksummedFrameReader::new(&bytes[..], 0);
+    let mut buf = vec![];
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(0));
+    let err = reader.read_frame(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn flipped_bit() {
+    let original = frames(&[b"lorem ipsum"]);
+    for bit in 0..11 * 8 {
+        let mut bytes = original.clone();
+        bytes[4 + bit / 8] ^= 1 << (bit % 8);
+        let mut reader = ChecksummedFrameReader::new(&bytes[..], 1024);
+        let mut buf = b"kept".to_vec();
+        let err = reader.read_frame(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let mismatch = err
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<ChecksumMismatch>()
+            .unwrap();
+        assert_eq!(mismatch.expected().to_be_bytes(), original[15..]);
+        assert_ne!(mismatch.actual(), mismatch.expected());
+        assert_eq!(buf, b"kept");
+    }
+}
+
+#[test]
+fn truncated_trailer() {
+    let bytes = frames(&[b"lorem"]);
+    for end in 9..bytes.len() {
+        let mut reader = ChecksummedFrameReader::new(&bytes[..end], 1024);
+        let mut buf = vec![];
+        let err = reader.read_frame(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+        assert_eq!((short.needed(), short.available()), (9, end - 4));
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn truncated_length() {
+    let mut reader = ChecksummedFrameReader::new(&b"\x00\x00"[..], 1024);
+    let err = reader.read_frame(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn recovers_after_corrupt_record() {
+    let mut bytes = frames(&[b"first", b"second", b"third"]);
+    bytes[4 + 2] ^= 0x40;
+    let mut reader = ChecksummedFrameReader::new(BufReader::with_capacity(3, &bytes[..]), 1024);
+    let mut records = vec![];
+    let mut corrupt = 0;
+    loop {
+        let mut buf = vec![];
+        match reader.read_frame(&mut buf) {
+            Ok(Some(_)) => records.push(buf),
+            Ok(None) => break,
+            Err(e) if e.kind() == ErrorKind::InvalidData => corrupt += 1,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    assert_eq!(corrupt, 1);
+    assert_eq!(records, [&b"second"[..], b"third"]);
+}
+
+#[test]
+fn oversized_frame() {
+    let bytes = frames(&[b"lorem ipsum"]);
+    let mut reader = ChecksummedFrameReader::new(&bytes[..], 10);
+    let err = reader.read_frame(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ChecksumMismatch>()
+        .is_none());
+}
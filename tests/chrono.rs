@@ -0,0 +1,104 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, TimeZone, Utc};
+use omnom::prelude::*;
+use omnom::{EpochMillis, EpochNanos, EpochSeconds};
+use std::io::{Cursor, ErrorKind};
+
+fn time(rfc3339: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+}
+
+#[test]
+fn round_trips() {
+    let t = time("2024-05-01T12:34:56.789123456Z");
+    let mut cursor = Cursor::new(vec![]);
+    cursor.write_be(EpochSeconds(t)).unwrap();
+    cursor.write_be(EpochMillis(t)).unwrap();
+    cursor.write_be(EpochNanos(t)).unwrap();
+
+    cursor.set_position(0);
+    assert_eq!(cursor.read_be::<i64>().unwrap(), 1_714_566_896);
+    assert_eq!(cursor.read_be::<i64>().unwrap(), 1_714_566_896_789);
+    assert_eq!(cursor.read_be::<i64>().unwrap(), 1_714_566_896_789_123_456);
+
+    cursor.set_position(0);
+    let secs: EpochSeconds = cursor.read_be().unwrap();
+    let millis: EpochMillis = cursor.read_be().unwrap();
+    let nanos: EpochNanos = cursor.read_be().unwrap();
+    assert_eq!(secs.0, time("2024-05-01T12:34:56Z"));
+    assert_eq!(millis.0, time("2024-05-01T12:34:56.789Z"));
+    assert_eq!(nanos.0, t);
+}
+
+#[test]
+fn unix_epoch() {
+    let epoch = Utc.timestamp_opt(0, 0).unwrap();
+    assert_eq!(EpochSeconds(epoch).to_be_array(), [0; 8]);
+    assert_eq!(EpochMillis(epoch).to_be_array(), [0; 8]);
+    assert_eq!(EpochNanos(epoch).to_be_array(), [0; 8]);
+    assert_eq!(EpochSeconds::from_be_array([0; 8]).0, epoch);
+    assert_eq!(EpochMillis::from_be_array([0; 8]).0, epoch);
+    assert_eq!(EpochNanos::from_be_array([0; 8]).0, epoch);
+}
+
+#[test]
+fn before_the_epoch() {
+    let t = time("1969-07-20T20:17:40.5Z");
+    assert_eq!(EpochSeconds(t).to_i64(), Some(-14_182_940));
+    assert_eq!(EpochMillis(t).to_i64(), Some(-14_182_939_500));
+    assert_eq!(EpochNanos(t).to_i64(), Some(-14_182_939_500_000_000));
+
+    // Dropping the fraction rounds towards the past.
+    let mut cursor = Cursor::new(vec![]);
+    cursor.write_le(EpochSeconds(t)).unwrap();
+    cursor.set_position(0);
+    let secs: EpochSeconds = cursor.read_le().unwrap();
+    assert_eq!(secs.0, time("1969-07-20T20:17:40Z"));
+}
+
+#[test]
+fn out_of_range() {
+    for value in [i64::MIN, i64::MAX] {
+        let mut cursor = Cursor::new(value.to_be_bytes());
+        let err = cursor.read_be::<EpochSeconds>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let mut cursor = Cursor::new(value.to_be_bytes());
+        let err = cursor.read_be::<EpochMillis>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // Every `i64` of nanoseconds fits.
+        let mut cursor = Cursor::new(value.to_be_bytes());
+        assert!(cursor.read_be::<EpochNanos>().is_ok());
+    }
+    assert_eq!(
+        EpochSeconds::from_be_array(i64::MAX.to_be_bytes()).0,
+        DateTime::<Utc>::MAX_UTC
+    );
+    assert_eq!(
+        EpochMillis::from_be_array(i64::MIN.to_be_bytes()).0,
+        DateTime::<Utc>::MIN_UTC
+    );
+
+    let far = time("3000-01-01T00:00:00Z");
+    let mut cursor = Cursor::new(vec![]);
+    let err = cursor.write_be(EpochNanos(far)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(cursor.get_ref().is_empty());
+    assert_eq!(EpochNanos(far).to_be_array(), i64::MAX.to_be_bytes());
+}
+
+#[test]
+fn both_endiannesses() {
+    let t = EpochMillis(time("2001-09-09T01:46:40Z"));
+    let mut be = Cursor::new(vec![]);
+    be.write_be(t).unwrap();
+    let mut le = Cursor::new(vec![]);
+    le.write_le(t).unwrap();
+    assert_eq!(be.get_ref(), &1_000_000_000_000_i64.to_be_bytes());
+    assert_eq!(le.get_ref(), &1_000_000_000_000_i64.to_le_bytes());
+
+    be.set_position(0);
+    le.set_position(0);
+    assert_eq!(be.read_be::<EpochMillis>().unwrap(), t);
+    assert_eq!(le.read_le::<EpochMillis>().unwrap(), t);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "std")]
+
+use omnom::combinators::{delimited, preceded, terminated};
+use omnom::prelude::*;
+use omnom::{ParseError, ParseErrorKind};
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind};
+
+/// Parse `key="value"` pairs separated by spaces.
+fn parse_pairs<R: BufRead>(reader: &mut R) -> io::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut pairs = vec![];
+    while !reader.fill_buf()?.is_empty() {
+        let mut key = vec![];
+        terminated(reader, |r| r.read_while(&mut key, |b| b != b'='), b"=")?;
+        let mut value = vec![];
+        delimited(reader, b"\"", b'"', &mut value)?;
+        reader.skip_while(|b| b == b' ')?;
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
+/// Parse a bracketed list of comma separated items, like `[a,b,c]`.
+fn parse_list<R: BufRead>(reader: &mut R) -> io::Result<Vec<Vec<u8>>> {
+    let mut inner = vec![];
+    delimited(reader, b"[", b']', &mut inner)?;
+    Ok(inner.split(|b| *b == b',').map(|s| s.to_vec()).collect())
+}
+
+fn parse_error(err: &io::Error) -> &ParseError {
+    err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap()
+}
+
+#[test]
+fn key_value_pairs() {
+    let input = b"name=\"omnom\" kind=\"parser\"";
+    let mut reader = BufReader::with_capacity(3, &input[..]);
+    let pairs = parse_pairs(&mut reader).unwrap();
+    assert_eq!(
+        pairs,
+        vec![
+            (b"name".to_vec(), b"omnom".to_vec()),
+            (b"kind".to_vec(), b"parser".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn bracketed_lists() {
+    let mut reader = Cursor::new(b"[a,bb,ccc][]");
+    assert_eq!(
+        parse_list(&mut reader).unwrap(),
+        vec![b"a".to_vec(), b"bb".to_vec(), b"ccc".to_vec()]
+    );
+    assert_eq!(parse_list(&mut reader).unwrap(), vec![b"".to_vec()]);
+}
+
+#[test]
+fn mismatched_delimiters() {
+    let mut reader = Cursor::new(b"(a,b]");
+    let err = parse_list(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(parse_error(&err).context(), Some("opening delimiter"));
+    assert_eq!(parse_error(&err).kind(), &ParseErrorKind::Mismatch);
+    assert_eq!(reader.position(), 0);
+
+    let mut reader = Cursor::new(b"[a,b");
+    let err = parse_list(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(parse_error(&err).context(), Some("closing delimiter"));
+    assert_eq!(err.to_string(), "closing delimiter: unexpected end of file");
+
+    let mut reader = Cursor::new(b"key=\"value");
+    let err = parse_pairs(&mut reader).unwrap_err();
+    assert_eq!(parse_error(&err).context(), Some("closing delimiter"));
+
+    let mut reader = Cursor::new(b"\x00\x01;");
+    let err = terminated(&mut reader, |r| r.read_be::<u16>(), b"\r\n").unwrap_err();
+    assert_eq!(parse_error(&err).context(), Some("terminator"));
+    assert_eq!(parse_error(&err).kind(), &ParseErrorKind::Mismatch);
+
+    let mut reader = Cursor::new(b"");
+    let err = preceded(&mut reader, b"v", |r| r.read_be::<u16>()).unwrap_err();
+    assert_eq!(parse_error(&err).context(), Some("prefix"));
+    assert_eq!(parse_error(&err).kind(), &ParseErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn nesting() {
+    let mut reader = Cursor::new(b"list=[a,b];");
+    let list = preceded(&mut reader, b"list=", |r| terminated(r, parse_list, b";")).unwrap();
+    assert_eq!(list, vec![b"a".to_vec(), b"b".to_vec()]);
+
+    // Errors from inner parsers are returned unchanged.
+    let mut reader = Cursor::new(b"list=(a);");
+    let err = preceded(&mut reader, b"list=", |r| terminated(r, parse_list, b";")).unwrap_err();
+    assert_eq!(parse_error(&err).context(), Some("opening delimiter"));
+}
+
+#[test]
+fn consume_prefix_across_chunks() {
+    let mut reader = BufReader::with_capacity(2, &b"HTTP/1.1"[..]);
+    assert!(reader.consume_prefix(b"HTTP/").unwrap());
+    assert_eq!(reader.fill_buf().unwrap(), b"1");
+
+    let mut reader = BufReader::with_capacity(2, &b"HTTP/1.1"[..]);
+    assert!(!reader.consume_prefix(b"FTP").unwrap());
+    let err = reader.consume_prefix(b"HTTPS").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut reader = BufReader::with_capacity(2, &b"HTT"[..]);
+    let err = reader.consume_prefix(b"HTTP").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
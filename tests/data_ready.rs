@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{DataReady, Lookahead};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+/// A reader that returns its data in one read, and panics on any read after
+/// that.
+struct Once(Option<&'static [u8]>);
+
+impl Read for Once {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.0.take().expect("read from the underlying reader");
+        buf[..data.len()].copy_from_slice(data);
+        Ok(data.len())
+    }
+}
+
+#[test]
+fn buf_reader() {
+    let mut reader = BufReader::new(Once(Some(b"abcd")));
+    assert!(!reader.has_data_ready());
+    reader.fill_buf().unwrap();
+    assert!(reader.has_data_ready());
+
+    reader.consume(3);
+    assert!(reader.has_data_ready());
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'd');
+    assert!(!reader.has_data_ready());
+}
+
+#[test]
+fn cursor() {
+    let mut cursor = Cursor::new(vec![1, 2]);
+    assert!(cursor.has_data_ready());
+    cursor.consume(1);
+    assert!(cursor.has_data_ready());
+    cursor.consume(1);
+    assert!(!cursor.has_data_ready());
+    cursor.set_position(10);
+    assert!(!cursor.has_data_ready());
+
+    let mut slice = &b"x"[..];
+    assert!(slice.has_data_ready());
+    slice.consume(1);
+    assert!(!slice.has_data_ready());
+}
+
+#[test]
+fn lookahead() {
+    let mut reader = Lookahead::<_, 2>::new(&b"abc"[..]).unwrap();
+    assert!(reader.has_data_ready());
+    reader.consume(3);
+    assert!(!reader.has_data_ready());
+}
+
+#[test]
+fn drain_without_reading() {
+    let mut reader = BufReader::new(Once(Some(b"a\nb\n")));
+    reader.fill_buf().unwrap();
+    let mut lines = 0;
+    // Draining never reads from `Once` again, which would panic.
+    while reader.has_data_ready() {
+        reader.skip_line().unwrap();
+        lines += 1;
+    }
+    assert_eq!(lines, 2);
+}
+
+#[test]
+fn drain_buffered_after_partial_consume() {
+    let mut reader = BufReader::new(Once(Some(b"upgrade\nframes")));
+    reader.fill_buf().unwrap();
+    reader.consume(8);
+
+    let mut out = b"x".to_vec();
+    assert_eq!(reader.drain_buffered(&mut out), 6);
+    assert_eq!(out, b"xframes");
+    assert!(reader.buffer().is_empty());
+}
+
+#[test]
+fn drain_buffered_empty_doesnt_read() {
+    let mut reader = BufReader::new(Once(None));
+    let mut out = vec![];
+    assert_eq!(reader.drain_buffered(&mut out), 0);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn read_after_drain_buffered() {
+    let source = Cursor::new(b"abcdefgh".to_vec());
+    let mut reader = BufReader::with_capacity(3, source);
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'a');
+
+    let mut out = vec![];
+    assert_eq!(reader.drain_buffered(&mut out), 2);
+    assert_eq!(out, b"bc");
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"defgh");
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::ShortRead;
+use std::io::{BufReader, ErrorKind};
+
+/// Delimited messages as written by protobuf's `writeDelimitedTo`.
+const VECTORS: &[(&[u8], &[u8])] = &[
+    // `Test1 { a: 150 }` from the encoding guide.
+    (&[0x08, 0x96, 0x01], &[0x03, 0x08, 0x96, 0x01]),
+    // `Test2 { b: "testing" }`.
+    (b"\x12\x07testing", b"\x09\x12\x07testing"),
+    // An empty message.
+    (&[], &[0x00]),
+];
+
+#[test]
+fn interop_vectors() {
+    for (payload, framed) in VECTORS {
+        let mut out = vec![];
+        assert_eq!(out.write_delimited_message(payload).unwrap(), framed.len());
+        assert_eq!(&out, framed);
+
+        let mut reader = *framed;
+        let mut buf = vec![];
+        let len = reader.read_delimited_message(&mut buf, 1024).unwrap();
+        assert_eq!(len, Some(payload.len()));
+        assert_eq!(&buf, payload);
+        assert!(reader.is_empty());
+    }
+
+    let mut out = vec![];
+    out.write_delimited_message(&[7; 300]).unwrap();
+    assert_eq!(out[..2], [0xac, 0x02]);
+    assert_eq!(out.len(), 302);
+}
+
+#[test]
+fn stream_of_messages() {
+    let mut stream = vec![];
+    for len in [0, 1, 127, 128, 0, 16_384] {
+        stream
+            .write_delimited_message(&vec![len as u8; len])
+            .unwrap();
+    }
+
+    // A capacity of 1 splits every varint across `fill_buf` chunks.
+    let mut reader = BufReader::with_capacity(1, &stream[..]);
+    let mut lens = vec![];
+    let mut buf = vec![];
+    while let Some(len) = reader.read_delimited_message(&mut buf, 1 << 20).unwrap() {
+        lens.push(len);
+    }
+    assert_eq!(lens, [0, 1, 127, 128, 0, 16_384]);
+    assert_eq!(buf.len(), 1 + 127 + 128 + 16_384);
+    assert_eq!(reader.read_delimited_message(&mut buf, 0).unwrap(), None);
+}
+
+#[test]
+fn max_length() {
+    let mut reader = &b"\x05hello\x06hello!"[..];
+    let mut buf = vec![];
+    assert_eq!(reader.read_delimited_message(&mut buf, 5).unwrap(), Some(5));
+    let err = reader.read_delimited_message(&mut buf, 5).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "message of 6 bytes exceeds the maximum of 5 bytes"
+    );
+    assert_eq!(buf, b"hello");
+
+    // A huge length is rejected before anything is allocated.
+    let mut reader = &b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"[..];
+    let err = reader
+        .read_delimited_message(&mut buf, 1 << 20)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(buf.capacity() < 1 << 20);
+
+    // So is a varint that doesn't fit in 64 bits.
+    let mut reader = &b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02"[..];
+    let err = reader
+        .read_delimited_message(&mut buf, usize::MAX)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn truncated() {
+    let mut buf = b"kept".to_vec();
+
+    // In the length.
+    let mut reader = BufReader::with_capacity(1, &b"\xac"[..]);
+    let err = reader.read_delimited_message(&mut buf, 1024).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // In the payload.
+    let mut reader = BufReader::with_capacity(2, &b"\x05hel"[..]);
+    let err = reader.read_delimited_message(&mut buf, 1024).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!(short, &ShortRead::new(5, 3));
+    assert_eq!(buf, b"kept");
+}
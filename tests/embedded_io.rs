@@ -0,0 +1,106 @@
+#![cfg(feature = "embedded-io")]
+
+use embedded_io::{BufRead, ErrorKind, ErrorType, Read, ReadExactError};
+use omnom::embedded::{BufReadExt, ReadExt, WriteExt};
+
+#[derive(Debug, PartialEq)]
+struct MockError;
+
+impl core::fmt::Display for MockError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("mock error")
+    }
+}
+
+impl core::error::Error for MockError {}
+
+impl embedded_io::Error for MockError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// A reader yielding `chunk` bytes at a time, then failing.
+struct Mock<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl ErrorType for Mock<'_> {
+    type Error = MockError;
+}
+
+impl Read for Mock<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, MockError> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for Mock<'_> {
+    fn fill_buf(&mut self) -> Result<&[u8], MockError> {
+        if self.data.is_empty() {
+            return Err(MockError);
+        }
+        Ok(&self.data[..self.chunk.min(self.data.len())])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.data = &self.data[amt..];
+    }
+}
+
+#[test]
+fn endianness_round_trip() {
+    let mut buf = [0; 14];
+    let mut writer: &mut [u8] = &mut buf;
+    assert_eq!(writer.write_be(0x0102_u16).unwrap(), 2);
+    assert_eq!(writer.write_le(0x0304_u32).unwrap(), 4);
+    assert_eq!(writer.write_ne(-5_i64).unwrap(), 8);
+    assert!(writer.write_be(1_u8).is_err());
+
+    let mut reader: &[u8] = &buf;
+    assert_eq!(reader.read_be::<u16>().unwrap(), 0x0102);
+    assert_eq!(reader.read_le::<u32>().unwrap(), 0x0304);
+    assert_eq!(reader.read_ne::<i64>().unwrap(), -5);
+    assert_eq!(reader.read_be::<u8>(), Err(ReadExactError::UnexpectedEof));
+}
+
+#[test]
+fn scanning_across_chunks() {
+    let mut reader = Mock {
+        data: b"   lorem-ipsum;dolor",
+        chunk: 2,
+    };
+    assert_eq!(reader.skip_while(|b| b == b' ').unwrap(), 3);
+
+    let mut buf = [0; 16];
+    let n = reader.read_while(&mut buf, |b| b != b'-').unwrap();
+    assert_eq!(&buf[..n], b"lorem");
+
+    assert_eq!(reader.skip_until(b';').unwrap(), 7);
+    let n = reader.read_while(&mut buf[..3], |_| true).unwrap();
+    assert_eq!(&buf[..n], b"dol");
+    assert_eq!(reader.data, b"or");
+}
+
+#[test]
+fn custom_errors_propagate() {
+    let mut reader = Mock {
+        data: b"ab",
+        chunk: 1,
+    };
+    assert_eq!(reader.skip_until(b'-'), Err(MockError));
+
+    let mut reader = Mock {
+        data: b"a",
+        chunk: 1,
+    };
+    assert_eq!(
+        reader.read_be::<u16>(),
+        Err(ReadExactError::Other(MockError))
+    );
+}
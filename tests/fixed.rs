@@ -0,0 +1,80 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{Q16_16, Q2_14, Q8_8};
+use std::io::Cursor;
+
+#[test]
+fn truetype_values() {
+    // The `version` field of a `post` table.
+    let mut cursor = Cursor::new([0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x50, 0x00]);
+    let one: Q16_16 = cursor.read_be().unwrap();
+    let two_and_a_bit: Q16_16 = cursor.read_be().unwrap();
+    assert_eq!(one, Q16_16::ONE);
+    assert_eq!(one.to_f64(), 1.0);
+    assert_eq!(two_and_a_bit.to_f64(), 2.3125);
+
+    // The F2Dot14 examples from the OpenType spec.
+    let cases = [
+        (0x7fff, 1.999939),
+        (0x7000, 1.75),
+        (0x0001, 0.000061),
+        (0x0000, 0.0),
+        (-0x0001, -0.000061),
+        (-0x8000, -2.0),
+    ];
+    for &(raw, value) in &cases {
+        let fixed = Q2_14(raw);
+        assert!((fixed.to_f64() - value).abs() < 0.000001, "{:?}", fixed);
+        assert_eq!(Q2_14::from_f64(fixed.to_f64()), fixed);
+    }
+}
+
+#[test]
+fn negative_values() {
+    assert_eq!(Q16_16(-0x18000).to_f32(), -1.5);
+    assert_eq!(Q16_16::from_f32(-1.5), Q16_16(-0x18000));
+    assert_eq!(Q8_8(-1).to_f64(), -1.0 / 256.0);
+    assert_eq!(Q8_8::from_f64(-128.0), Q8_8(i16::MIN));
+    assert_eq!(Q8_8(-0x0180).to_string(), "-1.5");
+}
+
+#[test]
+fn from_float_rounding() {
+    let step = 1.0 / 256.0;
+    // Ties round away from zero.
+    assert_eq!(Q8_8::from_f64(step / 2.0), Q8_8(1));
+    assert_eq!(Q8_8::from_f64(-step / 2.0), Q8_8(-1));
+    assert_eq!(Q8_8::from_f64(step * 0.49), Q8_8(0));
+    assert_eq!(Q8_8::from_f64(step * 1.51), Q8_8(2));
+
+    // The boundaries of the representable range, and beyond.
+    assert_eq!(Q8_8::from_f64(127.0 + 255.0 * step), Q8_8(i16::MAX));
+    assert_eq!(Q8_8::from_f64(127.999), Q8_8(i16::MAX));
+    assert_eq!(Q8_8::from_f64(1000.0), Q8_8(i16::MAX));
+    assert_eq!(Q8_8::from_f64(-128.0), Q8_8(i16::MIN));
+    assert_eq!(Q8_8::from_f64(-1000.0), Q8_8(i16::MIN));
+    assert_eq!(Q16_16::from_f32(f32::INFINITY), Q16_16(i32::MAX));
+    assert_eq!(Q16_16::from_f32(f32::NAN), Q16_16(0));
+}
+
+#[test]
+fn both_endiannesses() {
+    let value = Q16_16::from_f64(-3.25);
+
+    let mut be = Cursor::new(vec![]);
+    be.write_be(value).unwrap();
+    assert_eq!(be.get_ref(), &[0xff, 0xfc, 0xc0, 0x00]);
+
+    let mut le = Cursor::new(vec![]);
+    le.write_le(value).unwrap();
+    assert_eq!(le.get_ref(), &[0x00, 0xc0, 0xfc, 0xff]);
+
+    be.set_position(0);
+    le.set_position(0);
+    assert_eq!(be.read_be::<Q16_16>().unwrap(), value);
+    assert_eq!(le.read_le::<Q16_16>().unwrap(), value);
+
+    assert_eq!(Q8_8::from_le_array([0x40, 0x01]), Q8_8(0x0140));
+    assert_eq!(Q8_8(0x0140).to_be_array(), [0x01, 0x40]);
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use std::io::{self, BufWriter, ErrorKind, Write};
+
+/// A writer that counts flushes, and fails them if `fail` is set.
+#[derive(Default)]
+struct Flushes {
+    written: Vec<u8>,
+    flushes: usize,
+    fail: bool,
+}
+
+impl Write for Flushes {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        if self.fail {
+            return Err(io::Error::new(ErrorKind::BrokenPipe, "peer went away"));
+        }
+        Ok(())
+    }
+}
+
+/// Write a frame, returning early with an error for empty payloads.
+fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    let mut frame = writer.scoped_flush();
+    frame.write_be(payload.len() as u32)?;
+    if payload.is_empty() {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "empty payload"));
+    }
+    frame.write_all(payload)?;
+    frame.finish()
+}
+
+#[test]
+fn flushes_on_early_return() {
+    let mut writer = BufWriter::new(Flushes::default());
+    assert!(write_frame(&mut writer, b"").is_err());
+    assert_eq!(writer.get_ref().written, [0, 0, 0, 0]);
+    assert_eq!(writer.get_ref().flushes, 1);
+
+    write_frame(&mut writer, b"ok").unwrap();
+    assert_eq!(writer.get_ref().written, b"\0\0\0\0\0\0\0\x02ok");
+    assert_eq!(writer.get_ref().flushes, 2);
+}
+
+#[test]
+fn finish_returns_flush_error() {
+    let mut writer = BufWriter::new(Flushes {
+        fail: true,
+        ..Flushes::default()
+    });
+    let err = write_frame(&mut writer, b"ok").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    // `finish` doesn't leave the flush to be repeated on drop.
+    assert_eq!(writer.get_ref().flushes, 1);
+
+    // The error of the flush on drop is ignored.
+    let mut writer = Flushes {
+        fail: true,
+        ..Flushes::default()
+    };
+    writer.scoped_flush().write_all(b"x").unwrap();
+    assert_eq!(writer.flushes, 1);
+}
+
+#[test]
+fn flushes_once() {
+    let mut writer = Flushes::default();
+    {
+        let mut guard = writer.scoped_flush();
+        guard.write_all(b"abc").unwrap();
+        guard.flush().unwrap();
+        assert_eq!(guard.flushes, 1);
+    }
+    assert_eq!(writer.flushes, 2);
+
+    writer.scoped_flush().finish().unwrap();
+    assert_eq!(writer.flushes, 3);
+    assert_eq!(writer.written, b"abc");
+}
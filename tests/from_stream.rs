@@ -0,0 +1,82 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{FromStream, MacAddr, UInt};
+use std::io::{self, BufRead, BufReader, Cursor, ErrorKind};
+
+#[derive(Debug, PartialEq)]
+struct Header {
+    version: u16,
+    flags: Option<u32>,
+}
+
+impl FromStream for Header {
+    fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        Ok(Header {
+            version: reader.read_struct()?,
+            flags: reader.read_struct()?,
+        })
+    }
+}
+
+#[test]
+fn hand_implemented_header() {
+    let mut reader = Cursor::new(b"\x00\x02\x01\x00\x00\x00\x07\x00\x03\x00");
+    let first: Header = reader.read_struct().unwrap();
+    assert_eq!(
+        first,
+        Header {
+            version: 2,
+            flags: Some(7)
+        }
+    );
+    let second: Header = reader.read_struct().unwrap();
+    assert_eq!(
+        second,
+        Header {
+            version: 3,
+            flags: None
+        }
+    );
+}
+
+type Record = ([u8; 4], (u8, Option<(u8, u16)>), Option<i8>, MacAddr);
+
+#[test]
+fn composites() {
+    let bytes = b"ABCD\x00\x01\x01\x00\x02\x00\x01\x02\x03\x04\x05\x06";
+    let mut reader = &bytes[..];
+    let value: Record = reader.read_struct().unwrap();
+    assert_eq!(value.0, *b"ABCD");
+    assert_eq!(value.1, (0, Some((1, 2))));
+    assert_eq!(value.2, None);
+    assert_eq!(value.3, MacAddr([1, 2, 3, 4, 5, 6]));
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn blanket_impl_matches_read_be() {
+    let bytes: Vec<u8> = (1..=32).collect();
+    macro_rules! check {
+        ($($T:ty),*) => { $(
+            let expected: $T = Cursor::new(&bytes).read_be().unwrap();
+            let actual: $T = Cursor::new(&bytes).read_struct().unwrap();
+            assert_eq!(actual, expected);
+        )* };
+    }
+    check!(u8, u16, u32, u64, u128, i16, i32, i64, [u8; 3], UInt<5>);
+}
+
+#[test]
+fn errors_mid_struct() {
+    // The stream ends in the middle of `flags`.
+    let mut reader = BufReader::with_capacity(2, &b"\x00\x02\x01\x00\x00"[..]);
+    let err = reader.read_struct::<Header>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // An invalid tag is rejected after the fields before it were consumed.
+    let mut reader = Cursor::new(b"\x00\x02\x05rest");
+    let err = reader.read_struct::<Header>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.position(), 3);
+}
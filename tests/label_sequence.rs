@@ -0,0 +1,118 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use std::io::{BufReader, ErrorKind};
+
+fn read(input: &[u8]) -> std::io::Result<(Vec<u8>, usize)> {
+    let mut reader = BufReader::with_capacity(3, input);
+    let mut name = b"prev ".to_vec();
+    let read = reader.read_label_sequence(&mut name, b'.');
+    assert!(read.is_ok() || name == b"prev ");
+    read.map(|read| (name[5..].to_vec(), read))
+}
+
+#[test]
+fn multiple_labels() {
+    let (name, len) = read(b"\x04mail\x07example\x03org\x00rest").unwrap();
+    assert_eq!(name, b"mail.example.org");
+    assert_eq!(len, 18);
+
+    let mut buf = vec![];
+    assert_eq!(
+        buf.write_label_sequence(b"mail.example.org", b'.').unwrap(),
+        18
+    );
+    assert_eq!(buf, b"\x04mail\x07example\x03org\x00");
+
+    // Any separator works.
+    let mut buf = vec![];
+    buf.write_label_sequence(b"a/bc", b'/').unwrap();
+    let mut reader = &buf[..];
+    let mut name = vec![];
+    reader.read_label_sequence(&mut name, b'/').unwrap();
+    assert_eq!(name, b"a/bc");
+}
+
+#[test]
+fn root_name() {
+    assert_eq!(read(b"\x00").unwrap(), (vec![], 1));
+    for root in [&b""[..], b"."] {
+        let mut buf = vec![];
+        assert_eq!(buf.write_label_sequence(root, b'.').unwrap(), 1);
+        assert_eq!(buf, b"\x00");
+    }
+}
+
+#[test]
+fn label_limits() {
+    let mut input = vec![63];
+    input.extend_from_slice(&[b'a'; 63]);
+    input.push(0);
+    assert_eq!(read(&input).unwrap().1, 65);
+
+    let err = read(b"\x40aaaa").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "label of 64 bytes exceeds the maximum of 63"
+    );
+
+    let mut buf = vec![];
+    let err = buf.write_label_sequence(&[b'a'; 64], b'.').unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = buf.write_label_sequence(b"a..b", b'.').unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn total_limit() {
+    // 4 labels of 62 bytes and a 1-byte label take 4 * 63 + 2 + 1 = 255
+    // bytes with the terminator.
+    let label = [b'x'; 62];
+    let mut name = vec![];
+    for _ in 0..4 {
+        name.extend_from_slice(&label);
+        name.push(b'.');
+    }
+    name.push(b'y');
+
+    let mut buf = vec![];
+    assert_eq!(buf.write_label_sequence(&name, b'.').unwrap(), 255);
+    assert_eq!(read(&buf).unwrap(), (name.clone(), 255));
+
+    name.push(b'z');
+    let err = vec![].write_label_sequence(&name, b'.').unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let mut input = buf[..252].to_vec();
+    input.extend_from_slice(b"\x02yz\x00");
+    let err = read(&input).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "label sequence exceeds the maximum of 255 bytes"
+    );
+}
+
+#[test]
+fn compression_pointer() {
+    let err = read(b"\x03www\xc0\x0c").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert_eq!(
+        err.to_string(),
+        "label compression pointers aren't supported"
+    );
+}
+
+#[test]
+fn truncated() {
+    assert_eq!(
+        read(b"\x03ww").unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        read(b"\x03www").unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
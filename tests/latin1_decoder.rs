@@ -0,0 +1,53 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::Latin1Decoder;
+use std::io::{BufRead, BufReader, Read};
+
+fn decode(input: &[u8], capacity: usize) -> Vec<u8> {
+    let mut reader = Latin1Decoder::new(BufReader::with_capacity(capacity, input));
+    let mut out = vec![];
+    reader.read_to_end(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn ascii_passthrough() {
+    let input: Vec<u8> = (0..0x80).collect();
+    assert_eq!(decode(&input, 7), input);
+    assert_eq!(decode(b"", 7), b"");
+}
+
+#[test]
+fn high_bytes() {
+    assert_eq!(decode(b"\x80\xa9\xff", 2), "\u{80}©ÿ".as_bytes());
+    let all: Vec<u8> = (0..=255).collect();
+    let expected: String = all.iter().map(|b| char::from(*b)).collect();
+    assert_eq!(decode(&all, 5), expected.as_bytes());
+}
+
+#[test]
+fn consume_mid_character() {
+    let mut reader = Latin1Decoder::new(&b"\xe9t\xe9"[..]);
+    assert_eq!(reader.fill_buf().unwrap(), "été".as_bytes());
+    reader.consume(1);
+    assert_eq!(reader.fill_buf().unwrap(), b"\xa9t\xc3\xa9");
+    reader.consume(3);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0xa9);
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+}
+
+#[test]
+fn matches_encoding_rs() {
+    let sample: Vec<u8> = b"ID3v1 \xa9 1999 Bj\xf6rk \xe0 la carte \xd7\xf7\x00\x7f\x80\x9f"
+        .iter()
+        .copied()
+        .chain(0..=255)
+        .collect();
+    let mut expected = vec![0; sample.len() * 2];
+    let len = encoding_rs::mem::convert_latin1_to_utf8(&sample, &mut expected);
+    expected.truncate(len);
+    for capacity in [1, 3, 64] {
+        assert_eq!(decode(&sample, capacity), expected);
+    }
+}
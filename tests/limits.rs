@@ -0,0 +1,126 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{LimitExceeded, LimitKind, Limits};
+use std::io::{self, Cursor, ErrorKind};
+
+fn limit_exceeded(err: &io::Error) -> LimitExceeded {
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    *err.get_ref()
+        .unwrap()
+        .downcast_ref::<LimitExceeded>()
+        .unwrap()
+}
+
+#[test]
+fn defaults() {
+    let limits = Limits::default();
+    assert_eq!(limits, Limits::new());
+    assert_eq!(limits.alloc_limit(), 1 << 30);
+    assert_eq!(limits.element_limit(), 64 << 20);
+}
+
+#[test]
+fn every_method_respects_limits() {
+    let limits = Limits::new().max_elements(3).max_alloc(6);
+
+    // 4 elements of 1 byte each exceed the element limit.
+    let input = [4, 1, 2, 3, 4, 5, 6, 7, 8];
+    let errs = vec![
+        Cursor::new(input)
+            .read_vec_prefixed_be_with_limits::<u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_vec_prefixed_le_with_limits::<u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_vec_prefixed_ne_with_limits::<u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_map_prefixed_be_with_limits::<u8, u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_map_prefixed_le_with_limits::<u8, u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_map_prefixed_ne_with_limits::<u8, u8, u8>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_bitmap_with_limits(4, limits)
+            .unwrap_err(),
+    ];
+    for err in &errs {
+        let err = limit_exceeded(err);
+        assert_eq!(err.kind(), LimitKind::Elements);
+        assert_eq!((err.requested(), err.limit()), (4, 3));
+    }
+
+    // 2 elements of 4 bytes each exceed the allocation limit.
+    let input = [2, 0, 0, 0, 1, 0, 0, 0, 2];
+    let errs = vec![
+        Cursor::new(input)
+            .read_vec_prefixed_be_with_limits::<u8, u32>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_map_prefixed_be_with_limits::<u8, u16, u16>(limits)
+            .unwrap_err(),
+        Cursor::new(input)
+            .read_to_string_exact_with_limits(8, limits)
+            .unwrap_err(),
+    ];
+    for err in &errs {
+        let err = limit_exceeded(err);
+        assert_eq!(err.kind(), LimitKind::Alloc);
+        assert_eq!((err.requested(), err.limit()), (8, 6));
+    }
+}
+
+#[test]
+fn within_limits() {
+    let limits = Limits::new().max_elements(3).max_alloc(6);
+    let mut cursor = Cursor::new([3, 0, 1, 0, 2, 0, 3]);
+    let values = cursor
+        .read_vec_prefixed_be_with_limits::<u8, u16>(limits)
+        .unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let mut cursor = Cursor::new("abcdef");
+    let s = cursor.read_to_string_exact_with_limits(6, limits).unwrap();
+    assert_eq!(s, "abcdef");
+}
+
+#[test]
+fn error_message() {
+    let err = Cursor::new([5])
+        .read_vec_prefixed_be_with_limits::<u8, u8>(Limits::new().max_elements(2))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "5 elements exceed the maximum of 2 elements"
+    );
+
+    // The `max` of the plain methods is an element limit too.
+    let err = Cursor::new([5])
+        .read_vec_prefixed_be::<u8, u8>(2)
+        .unwrap_err();
+    assert_eq!(limit_exceeded(&err).limit(), 2);
+}
+
+#[test]
+fn unlimited() {
+    let limits = Limits::unlimited();
+    assert_eq!(limits.alloc_limit(), usize::MAX);
+    assert_eq!(limits.element_limit(), usize::MAX);
+
+    // A count above the default element limit is let through, and then fails
+    // on the input being too short.
+    let input = [0x04, 0, 0, 1];
+    let err = Cursor::new(input)
+        .read_vec_prefixed_be_with_limits::<u32, u8>(limits)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let err = Cursor::new(input)
+        .read_vec_prefixed_be_with_limits::<u32, u8>(Limits::new())
+        .unwrap_err();
+    assert_eq!(limit_exceeded(&err).kind(), LimitKind::Elements);
+}
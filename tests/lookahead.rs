@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{BufferAhead, Lookahead};
+use std::io::{BufRead, BufReader, Cursor, Read};
+
+#[test]
+fn window_while_consuming() {
+    let input: Vec<u8> = (0..100).collect();
+    for capacity in 1..=8 {
+        let reader = BufReader::with_capacity(capacity, &input[..]);
+        let mut reader = Lookahead::<_, 4>::new(reader).unwrap();
+        for i in 0..input.len() {
+            let expected = &input[i..(i + 4).min(input.len())];
+            assert_eq!(reader.lookahead(), expected, "{} at {}", capacity, i);
+            reader.consume(1);
+        }
+        assert_eq!(reader.lookahead(), b"");
+        assert_eq!(reader.fill_buf().unwrap(), b"");
+    }
+}
+
+#[test]
+fn end_of_stream() {
+    let mut reader = Lookahead::<_, 8>::new(Cursor::new(b"abc")).unwrap();
+    assert_eq!(reader.lookahead(), b"abc");
+    reader.consume(2);
+    assert_eq!(reader.lookahead(), b"c");
+    reader.consume(5);
+    assert_eq!(reader.lookahead(), b"");
+
+    let reader = Lookahead::<_, 8>::new(Cursor::new(b"")).unwrap();
+    assert_eq!(reader.lookahead(), b"");
+}
+
+#[test]
+fn read_through_wrapper() {
+    let input = [0, 1, 0, 0, 0, 2, 0xff, 3];
+    let reader = BufReader::with_capacity(3, &input[..]);
+    let mut reader = Lookahead::<_, 2>::new(reader).unwrap();
+    assert_eq!(reader.lookahead(), [0, 1]);
+    assert_eq!(reader.read_be::<u16>().unwrap(), 1);
+    assert_eq!(reader.lookahead(), [0, 0]);
+    assert_eq!(reader.read_be::<u32>().unwrap(), 2);
+    assert_eq!(reader.lookahead(), [0xff, 3]);
+
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [0xff, 3]);
+}
+
+#[test]
+fn larger_than_default_capacity() {
+    let input: Vec<u8> = (0..=255).cycle().take(40_000).collect();
+    let reader = BufReader::with_capacity(7, &input[..]);
+    let mut reader = Lookahead::<_, 10_000>::new(reader).unwrap();
+    let mut pos = 0;
+    while pos < input.len() {
+        let end = (pos + 10_000).min(input.len());
+        assert_eq!(reader.lookahead(), &input[pos..end]);
+        let n = 1234.min(input.len() - pos);
+        reader.consume(n);
+        pos += n;
+    }
+}
+
+#[test]
+fn buffer_ahead_past_capacity() {
+    let input: Vec<u8> = (0..=255).cycle().take(20_000).collect();
+    let reader = BufReader::with_capacity(7, &input[..]);
+    let mut reader = Lookahead::<_, 4>::new(reader).unwrap();
+    reader.consume(3);
+    assert_eq!(
+        &reader.buffer_ahead(12_000).unwrap()[..12_000],
+        &input[3..12_003]
+    );
+    assert_eq!(reader.lookahead(), &input[3..7]);
+    assert_eq!(reader.buffer_ahead(30_000).unwrap(), &input[3..]);
+
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, &input[3..]);
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{MacAddr, ParseErrorKind};
+use std::io::{BufReader, Cursor, ErrorKind};
+
+const MAC: MacAddr = MacAddr([0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7]);
+
+#[test]
+fn binary_round_trip() {
+    let mut cursor = Cursor::new(vec![]);
+    cursor.write_be(MAC).unwrap();
+    cursor.write_le(MAC).unwrap();
+    cursor.write_ne(MAC).unwrap();
+    assert_eq!(cursor.get_ref(), &MAC.0.repeat(3));
+
+    cursor.set_position(0);
+    assert_eq!(cursor.read_be::<MacAddr>().unwrap(), MAC);
+    assert_eq!(cursor.read_le::<MacAddr>().unwrap(), MAC);
+    assert_eq!(cursor.read_ne::<MacAddr>().unwrap(), MAC);
+    assert_eq!(
+        cursor.read_be::<MacAddr>().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn text_round_trip() {
+    let colons = MAC.to_string();
+    let hyphens = format!("{:#}", MAC);
+    assert_eq!(colons, "00:1b:44:11:3a:b7");
+    assert_eq!(hyphens, "00-1b-44-11-3a-b7");
+    assert_eq!(colons.parse::<MacAddr>().unwrap(), MAC);
+    assert_eq!(hyphens.parse::<MacAddr>().unwrap(), MAC);
+    assert_eq!("00:1B:44:11:3A:B7".parse::<MacAddr>().unwrap(), MAC);
+    assert_eq!(
+        "FF-FF-FF-FF-FF-FF".parse::<MacAddr>().unwrap(),
+        MacAddr::BROADCAST
+    );
+}
+
+#[test]
+fn text_rejects_malformed() {
+    let cases = [
+        ("", 0, ParseErrorKind::UnexpectedEof),
+        ("00:1b:44:11:3a", 14, ParseErrorKind::UnexpectedEof),
+        ("00:1b:44:11:3a:b", 16, ParseErrorKind::UnexpectedEof),
+        ("00:1b:44:11:3a:b7:", 17, ParseErrorKind::Mismatch),
+        ("00:1b:44:11:3a:b7c", 17, ParseErrorKind::Mismatch),
+        ("00:1b-44:11:3a:b7", 5, ParseErrorKind::Mismatch),
+        ("00.1b.44.11.3a.b7", 2, ParseErrorKind::Mismatch),
+        ("0:1b:44:11:3a:b7", 1, ParseErrorKind::Mismatch),
+        ("00:1g:44:11:3a:b7", 4, ParseErrorKind::Mismatch),
+        ("001b44113ab7", 2, ParseErrorKind::Mismatch),
+    ];
+    for (text, offset, kind) in cases.iter() {
+        let err = text.parse::<MacAddr>().unwrap_err();
+        assert_eq!(err.offset(), Some(*offset), "{}", text);
+        assert_eq!(err.kind(), kind, "{}", text);
+        assert_eq!(err.context(), Some("invalid MAC address"));
+    }
+}
+
+#[test]
+fn classification() {
+    assert!(MacAddr::BROADCAST.is_broadcast());
+    assert!(MacAddr::BROADCAST.is_multicast());
+    assert!(!MAC.is_broadcast());
+    assert!(!MAC.is_multicast());
+    assert!(MAC.is_unicast());
+    assert!(!MAC.is_local());
+
+    let mdns = MacAddr([0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb]);
+    assert!(mdns.is_multicast());
+    assert!(!mdns.is_broadcast());
+    assert!(!mdns.is_local());
+
+    let docker = MacAddr([0x02, 0x42, 0xac, 0x11, 0x00, 0x02]);
+    assert!(docker.is_unicast());
+    assert!(docker.is_local());
+}
+
+#[test]
+fn read_mac_ascii() {
+    let input = b"00:1b:44:11:3a:b7 00-1B-44-11-3A-B7;";
+    for capacity in 1..=input.len() {
+        let mut reader = BufReader::with_capacity(capacity, &input[..]);
+        assert_eq!(reader.read_mac_ascii().unwrap(), MAC);
+        assert!(reader.consume_prefix(b" ").unwrap());
+        assert_eq!(reader.read_mac_ascii().unwrap(), MAC);
+        assert!(reader.consume_prefix(b";").unwrap());
+    }
+}
+
+#[test]
+fn read_mac_ascii_errors() {
+    let cases = [
+        ("00:1b:44:11:3a", ""),
+        ("00:1b:44:11:3a:b", ""),
+        ("00:1b:44:11:3a:b7:", ":"),
+        ("00:1b:44:11:3a:b7c", "c"),
+        ("00:1b-44:11:3a:b7", "-44:11:3a:b7"),
+        ("0:1b:44:11:3a:b7", ":1b:44:11:3a:b7"),
+        ("00:1g:44:11:3a:b7", "g:44:11:3a:b7"),
+    ];
+    for (text, rest) in cases.iter() {
+        let mut cursor = Cursor::new(text.as_bytes());
+        let err = cursor.read_mac_ascii().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", text);
+        assert!(err.to_string().starts_with("invalid MAC address: "));
+        let pos = cursor.position() as usize;
+        assert_eq!(&text[pos..], *rest, "{}", text);
+    }
+}
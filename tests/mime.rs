@@ -0,0 +1,127 @@
+#![cfg(feature = "formats-mime")]
+
+use omnom::formats::mime::{self, Mime, MimeParseError};
+use std::io::{self, BufReader, ErrorKind, Read};
+
+fn parse(s: &str) -> Result<Mime, MimeParseError> {
+    s.parse()
+}
+
+fn params(mime: &Mime) -> Vec<(&str, &str)> {
+    mime.parameters
+        .iter()
+        .map(|(n, v)| (n.as_str(), v.as_str()))
+        .collect()
+}
+
+#[test]
+fn example_cases() {
+    let mime = parse("text/html").unwrap();
+    assert_eq!(mime.base_type, "text");
+    assert_eq!(mime.sub_type, "html");
+    assert!(mime.parameters.is_empty());
+
+    let mime = parse("text/html; charset=utf-8;").unwrap();
+    assert_eq!(params(&mime), [("charset", "utf-8")]);
+
+    // A parameter without a value is skipped, without eating the next one.
+    let mime = parse("text/html;novalue;charset=utf-8").unwrap();
+    assert_eq!(params(&mime), [("charset", "utf-8")]);
+
+    // Parameter values keep their case.
+    let mime = parse("TEXT/Plain; FORMAT=Flowed").unwrap();
+    assert_eq!(mime.essence(), "text/plain");
+    assert_eq!(params(&mime), [("format", "Flowed")]);
+}
+
+#[test]
+fn quoted_values() {
+    let mime = parse(r#"multipart/mixed; boundary="a;b=c"; x=y"#).unwrap();
+    assert_eq!(params(&mime), [("boundary", "a;b=c"), ("x", "y")]);
+
+    let mime = parse(r#"a/b; q="say \"hi\" \\ bye"ignored; r="unterminated"#).unwrap();
+    assert_eq!(
+        params(&mime),
+        [("q", r#"say "hi" \ bye"#), ("r", "unterminated")]
+    );
+
+    let mime = parse(r#"a/b; q="trailing\"#).unwrap();
+    assert_eq!(mime.param("q"), Some("trailing\\"));
+
+    // An empty quoted value is kept, unlike an empty unquoted one.
+    let mime = parse(r#"a/b; q=""; r="#).unwrap();
+    assert_eq!(params(&mime), [("q", "")]);
+}
+
+#[test]
+fn duplicates_and_whitespace() {
+    let mime = parse(" \t text/html ; charset=utf-8 ; CHARSET=latin1 \r\n").unwrap();
+    assert_eq!(mime.essence(), "text/html");
+    assert_eq!(params(&mime), [("charset", "utf-8")]);
+
+    // Whitespace before `=` makes the name invalid.
+    let mime = parse("text/html; charset =utf-8").unwrap();
+    assert!(mime.parameters.is_empty());
+}
+
+#[test]
+fn invalid_code_points() {
+    assert!(matches!(parse(""), Err(MimeParseError::InvalidType)));
+    assert!(matches!(
+        parse("te xt/html"),
+        Err(MimeParseError::InvalidType)
+    ));
+    assert!(matches!(parse("/html"), Err(MimeParseError::InvalidType)));
+    assert!(matches!(parse("text"), Err(MimeParseError::MissingSlash)));
+    assert!(matches!(
+        parse("text/"),
+        Err(MimeParseError::InvalidSubtype)
+    ));
+    assert!(matches!(
+        parse("text/ht(ml"),
+        Err(MimeParseError::InvalidSubtype)
+    ));
+
+    // Invalid parameters are skipped rather than rejected.
+    let mime = parse("text/html; ch@rset=utf-8; a=b\u{7f}; c=d").unwrap();
+    assert_eq!(params(&mime), [("c", "d")]);
+
+    // Bytes 0x80..=0xff are allowed in values, as U+0080..=U+00FF.
+    let mime = mime::parse(&mut &b"a/b; q=\"caf\xe9\""[..]).unwrap();
+    assert_eq!(mime.param("q"), Some("caf\u{e9}"));
+
+    let err = io::Error::from(parse("text").unwrap_err());
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn display_round_trips() {
+    let inputs = [
+        "text/html",
+        "text/html;charset=utf-8",
+        r#"multipart/mixed;boundary="a;b";x=y"#,
+        r#"a/b;q="say \"hi\" \\ bye";e="""#,
+        "Application/JSON ; Q=1 ; q=2 ; r=\"spaced out\"",
+    ];
+    for input in inputs.iter() {
+        let mime = parse(input).unwrap();
+        let serialized = mime.to_string();
+        assert_eq!(parse(&serialized).unwrap(), mime, "{}", input);
+        assert_eq!(parse(&serialized).unwrap().to_string(), serialized);
+    }
+    assert_eq!(
+        parse(r#"A/B; x="token"; y="not a token""#)
+            .unwrap()
+            .to_string(),
+        r#"a/b;x=token;y="not a token""#
+    );
+}
+
+#[test]
+fn reads_until_eof() {
+    let reader = BufReader::with_capacity(3, &b"text/plain; charset=us-ascii"[..]);
+    let mut reader = reader.chain(&b"; x=y"[..]);
+    let mut reader = BufReader::new(&mut reader);
+    let mime = mime::parse(&mut reader).unwrap();
+    assert_eq!(params(&mime), [("charset", "us-ascii"), ("x", "y")]);
+}
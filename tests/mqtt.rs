@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use std::io::{Cursor, ErrorKind};
+
+/// The boundaries of each encoded length, from MQTT 5.0 section 1.5.5.
+const BOUNDARIES: &[(u32, &[u8])] = &[
+    (0, &[0x00]),
+    (127, &[0x7f]),
+    (128, &[0x80, 0x01]),
+    (16_383, &[0xff, 0x7f]),
+    (16_384, &[0x80, 0x80, 0x01]),
+    (2_097_151, &[0xff, 0xff, 0x7f]),
+    (2_097_152, &[0x80, 0x80, 0x80, 0x01]),
+    (268_435_455, &[0xff, 0xff, 0xff, 0x7f]),
+];
+
+#[test]
+fn boundaries() {
+    for (value, encoded) in BOUNDARIES {
+        let mut buf = vec![];
+        assert_eq!(buf.write_mqtt_remaining_len(*value).unwrap(), encoded.len());
+        assert_eq!(&buf, encoded);
+        let mut cursor = Cursor::new(encoded);
+        assert_eq!(cursor.read_mqtt_remaining_len().unwrap(), *value);
+        assert_eq!(cursor.position() as usize, encoded.len());
+    }
+}
+
+#[test]
+fn too_long() {
+    let mut buf = vec![];
+    let err = buf.write_mqtt_remaining_len(268_435_456).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+
+    let mut cursor = Cursor::new([0xff, 0xff, 0xff, 0xff, 0x7f]);
+    let err = cursor.read_mqtt_remaining_len().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "remaining length is longer than 4 bytes");
+}
+
+#[test]
+fn non_minimal() {
+    for encoded in [
+        &[0x80, 0x00][..],
+        &[0xff, 0x80, 0x00],
+        &[0x81, 0x80, 0x80, 0x00],
+    ] {
+        let err = Cursor::new(encoded).read_mqtt_remaining_len().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn truncated() {
+    for encoded in [&[][..], &[0x80], &[0xff, 0xff, 0xff]] {
+        let err = Cursor::new(encoded).read_mqtt_remaining_len().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::MsGuid;
+use std::io::Cursor;
+
+/// The serialized GUID from the `GUID` packet representation in [MS-DTYP].
+const MS_DTYP_BYTES: [u8; 16] = [
+    0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+];
+
+#[test]
+fn published_layout() {
+    let mut cursor = Cursor::new(MS_DTYP_BYTES);
+    let guid: MsGuid = cursor.read_le().unwrap();
+    assert_eq!(guid.to_string(), "{00112233-4455-6677-8899-AABBCCDDEEFF}");
+    assert_eq!(
+        guid,
+        MsGuid::from_fields(
+            0x00112233,
+            0x4455,
+            0x6677,
+            [0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]
+        )
+    );
+
+    // CLSID_MyComputer, as stored in the registry.
+    let stored = [
+        0x20, 0x20, 0xec, 0x21, 0xea, 0x3a, 0x69, 0x10, 0xa2, 0xdd, 0x08, 0x00, 0x2b, 0x30, 0x30,
+        0x9d,
+    ];
+    let guid = MsGuid::from_le_array(stored);
+    assert_eq!(guid.to_string(), "{21EC2020-3AEA-1069-A2DD-08002B30309D}");
+}
+
+#[test]
+fn big_endian_reads_differ() {
+    let mut cursor = Cursor::new(MS_DTYP_BYTES);
+    let canonical: MsGuid = cursor.read_be().unwrap();
+    assert_eq!(canonical.to_bytes(), MS_DTYP_BYTES);
+    assert_eq!(
+        canonical.to_string(),
+        "{33221100-5544-7766-8899-AABBCCDDEEFF}"
+    );
+    assert_ne!(canonical, MsGuid::from_le_array(MS_DTYP_BYTES));
+}
+
+#[test]
+fn round_trip() {
+    let guid = MsGuid::from_le_array(MS_DTYP_BYTES);
+    let mut cursor = Cursor::new(vec![]);
+    cursor.write_le(guid).unwrap();
+    cursor.write_be(guid).unwrap();
+    cursor.write_ne(guid).unwrap();
+    assert_eq!(&cursor.get_ref()[..16], &MS_DTYP_BYTES);
+    assert_eq!(&cursor.get_ref()[16..32], &guid.to_bytes());
+
+    cursor.set_position(0);
+    assert_eq!(cursor.read_le::<MsGuid>().unwrap(), guid);
+    assert_eq!(cursor.read_be::<MsGuid>().unwrap(), guid);
+    assert_eq!(cursor.read_ne::<MsGuid>().unwrap(), guid);
+}
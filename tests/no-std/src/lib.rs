@@ -0,0 +1,52 @@
+//! Checks that `omnom` builds without the standard library.
+//!
+//! Build this crate on its own so `omnom`'s default features aren't enabled
+//! by other workspace members:
+//!
+//! ```sh
+//! $ cargo build -p omnom-no-std
+//! ```
+
+#![no_std]
+#![forbid(unsafe_code, future_incompatible, rust_2018_idioms)]
+#![deny(missing_debug_implementations, nonstandard_style)]
+#![warn(missing_docs, unreachable_pub)]
+
+use omnom::{ReadBytes, WriteBytes};
+
+/// Write `value` as big endian into `buf`, then read it back.
+pub fn round_trip_be<T: ReadBytes + WriteBytes>(value: &T, buf: &mut [u8]) -> Option<T> {
+    let written = value.to_be_slice(buf)?;
+    let (value, read) = T::from_be_slice(&buf[..written])?;
+    if read == written {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Write `value` as little endian into `buf`, then read it back.
+pub fn round_trip_le<T: ReadBytes + WriteBytes>(value: &T, buf: &mut [u8]) -> Option<T> {
+    let written = value.to_le_slice(buf)?;
+    let (value, read) = T::from_le_slice(&buf[..written])?;
+    if read == written {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let mut buf = [0; 16];
+        assert_eq!(round_trip_be(&0xdead_beef_u32, &mut buf), Some(0xdead_beef));
+        assert_eq!(buf[..4], [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(round_trip_le(&-12_i16, &mut buf), Some(-12));
+        assert_eq!(round_trip_be(&u128::MAX, &mut buf), Some(u128::MAX));
+        assert_eq!(round_trip_le(&u64::MAX, &mut buf[..7]), None);
+    }
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "nom")]
+
+use nom::bytes::streaming::{tag, take};
+use nom::number::streaming::be_u16;
+use nom::{IResult, Parser};
+use omnom::{parse_with, ParseError};
+use std::error::Error;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+/// A record: the tag `R`, a big endian length and that many bytes of data.
+fn record(input: &[u8]) -> IResult<&[u8], Vec<u8>> {
+    let (input, _) = tag(&b"R"[..]).parse(input)?;
+    let (input, len) = be_u16(input)?;
+    let (input, data) = take(len).parse(input)?;
+    Ok((input, data.to_vec()))
+}
+
+/// A two-part magic header.
+fn header(input: &[u8]) -> IResult<&[u8], ()> {
+    let (input, _) = (tag(&b"R"[..]), tag(&b"Ab"[..])).parse(input)?;
+    Ok((input, ()))
+}
+
+#[test]
+fn spans_many_chunks() {
+    let mut input = vec![b'R', 0, 20];
+    input.extend(0..20);
+    input.push(b'!');
+    let mut reader = BufReader::with_capacity(4, &input[..]);
+
+    let data = parse_with(&mut reader, record).unwrap();
+    assert_eq!(data, (0..20).collect::<Vec<u8>>());
+    assert_eq!(reader.fill_buf().unwrap(), b"!");
+}
+
+#[test]
+fn failure_position() {
+    // Failing within the first chunk consumes nothing.
+    let mut reader = BufReader::with_capacity(8, &b"X\x00\x01a"[..]);
+    let err = parse_with(&mut reader, record).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let inner = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    assert_eq!(inner.offset(), Some(0));
+    let source = inner.source().unwrap();
+    let source = source.downcast_ref::<nom::error::Error<usize>>().unwrap();
+    assert_eq!(source.code, nom::error::ErrorKind::Tag);
+    assert_eq!(reader.fill_buf().unwrap(), b"X\x00\x01a");
+
+    // Failing after accumulating leaves the reader at the last chunk seen.
+    let mut reader = BufReader::with_capacity(2, &b"RAxz"[..]);
+    let err = parse_with(&mut reader, header).unwrap_err();
+    let inner = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    assert_eq!(inner.offset(), Some(1));
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"xz");
+
+    // Running out of input is an unexpected EOF.
+    let mut reader = BufReader::with_capacity(2, &b"R\x00\x05abc"[..]);
+    let err = parse_with(&mut reader, record).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn sequence_of_records() {
+    let mut input = vec![];
+    for i in 0..10_u8 {
+        input.extend(&[b'R', 0, i]);
+        input.extend(vec![i; i as usize]);
+    }
+    let mut reader = BufReader::with_capacity(3, &input[..]);
+
+    for i in 0..10_u8 {
+        assert_eq!(
+            parse_with(&mut reader, record).unwrap(),
+            vec![i; i as usize]
+        );
+    }
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+/// A reader that's interrupted before each read.
+struct Interrupting<'a> {
+    data: &'a [u8],
+    interrupted: bool,
+}
+
+impl Read for Interrupting<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupted = !self.interrupted;
+        if self.interrupted {
+            return Err(ErrorKind::Interrupted.into());
+        }
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn retries_interrupted_reads() {
+    let inner = Interrupting {
+        data: b"R\x00\x03abc",
+        interrupted: false,
+    };
+    let mut reader = BufReader::with_capacity(2, inner);
+    assert_eq!(parse_with(&mut reader, record).unwrap(), b"abc");
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "std")]
+
+use omnom::NormalizeNewlines;
+use std::io::{BufRead, BufReader, Read};
+
+fn normalize(input: &[u8], capacity: usize) -> Vec<u8> {
+    let mut reader = NormalizeNewlines::new(BufReader::with_capacity(capacity, input));
+    let mut out = vec![];
+    reader.read_to_end(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn each_convention() {
+    assert_eq!(normalize(b"a\r\nb\r\n", 64), b"a\nb\n");
+    assert_eq!(normalize(b"a\rb\r", 64), b"a\nb\n");
+    assert_eq!(normalize(b"a\nb\n", 64), b"a\nb\n");
+    assert_eq!(normalize(b"a\r\r\nb\n\rc\n\n", 64), b"a\n\nb\n\nc\n\n");
+}
+
+#[test]
+fn cr_at_chunk_boundary() {
+    let input = b"ab\r\ncd\r\n\r\nef\rgh";
+    for capacity in 1..=input.len() {
+        assert_eq!(
+            normalize(input, capacity),
+            b"ab\ncd\n\nef\ngh",
+            "{}",
+            capacity
+        );
+    }
+
+    // The `\n` of a `\r\n` alone in a chunk doesn't end the stream early.
+    let mut reader = NormalizeNewlines::new(BufReader::with_capacity(3, &b"ab\r\ncd"[..]));
+    assert_eq!(reader.fill_buf().unwrap(), b"ab\n");
+    reader.consume(3);
+    assert_eq!(reader.fill_buf().unwrap(), b"cd");
+}
+
+#[test]
+fn final_cr() {
+    assert_eq!(normalize(b"last\r", 64), b"last\n");
+    assert_eq!(normalize(b"last\r", 5), b"last\n");
+    assert_eq!(normalize(b"\r", 1), b"\n");
+}
+
+#[test]
+fn passthrough() {
+    let input: Vec<u8> = (0..=255)
+        .filter(|b| *b != b'\r')
+        .cycle()
+        .take(5000)
+        .collect();
+    assert_eq!(normalize(&input, 7), input);
+    assert_eq!(normalize(b"", 7), b"");
+}
@@ -0,0 +1,72 @@
+use omnom::{ParseBuf, ParseError, ParseErrorKind};
+
+#[derive(Debug, PartialEq)]
+enum Record<'a> {
+    Text(&'a [u8]),
+    Number(u32),
+}
+
+/// Parse a sequence of type-length-value records.
+fn parse_tlv(input: &[u8]) -> Result<Vec<Record<'_>>, ParseError> {
+    let mut buf = ParseBuf::new(input);
+    let mut records = vec![];
+    while !buf.is_empty() {
+        let tag: u8 = buf.read_be()?;
+        let len: u16 = buf.read_be()?;
+        let record = match tag {
+            1 => Record::Text(buf.take(len as usize)?),
+            2 if len == 4 => Record::Number(buf.read_be()?),
+            2 => return Err(buf.error("number record must be 4 bytes")),
+            _ => return Err(buf.error("unknown record type")),
+        };
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[test]
+fn tlv() {
+    let input = b"\x01\x00\x05hello\x02\x00\x04\x00\x00\x01\x00";
+    let records = parse_tlv(input).unwrap();
+    assert_eq!(records, vec![Record::Text(b"hello"), Record::Number(256)]);
+}
+
+#[test]
+fn error_offsets() {
+    let err = parse_tlv(b"\x01\x00\x05hel").unwrap_err();
+    assert_eq!(err.offset(), Some(3));
+    assert_eq!(err.kind(), &ParseErrorKind::UnexpectedEof);
+
+    let err = parse_tlv(b"\x01\x00\x00\x07\x00\x00").unwrap_err();
+    assert_eq!(err.offset(), Some(6));
+    assert_eq!(err.kind(), &ParseErrorKind::Message("unknown record type"));
+
+    let err = parse_tlv(b"\x02\x00\x02\x00\x01").unwrap_err();
+    assert_eq!(err.offset(), Some(3));
+    assert_eq!(err.to_string(), "number record must be 4 bytes at byte 3");
+
+    let mut buf = ParseBuf::new(b"GET");
+    let err = buf.expect(b"GETS").unwrap_err();
+    assert_eq!(err.kind(), &ParseErrorKind::UnexpectedEof);
+    let err = buf.expect(b"PUT").unwrap_err();
+    assert_eq!(err.kind(), &ParseErrorKind::Mismatch);
+    assert_eq!(buf.pos(), 0);
+}
+
+#[test]
+fn zero_copy() {
+    let input = b"\x01\x00\x05hello\x01\x00\x05world";
+    let records = parse_tlv(input).unwrap();
+    match records[..] {
+        [Record::Text(a), Record::Text(b)] => {
+            assert!(std::ptr::eq(a.as_ptr(), input[3..].as_ptr()));
+            assert!(std::ptr::eq(b.as_ptr(), input[11..].as_ptr()));
+        }
+        _ => panic!("unexpected records: {:?}", records),
+    }
+
+    let mut buf = ParseBuf::new(input);
+    let head = buf.read_until(b'h');
+    assert!(std::ptr::eq(head.as_ptr(), input.as_ptr()));
+    assert!(std::ptr::eq(buf.remaining().as_ptr(), input[4..].as_ptr()));
+}
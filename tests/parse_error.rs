@@ -0,0 +1,73 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{ParseError, ParseErrorKind, ParseResultExt};
+use std::error::Error;
+use std::io::{self, Cursor, ErrorKind, Read};
+
+/// Read a chunk of a length-prefixed format, annotating failures.
+fn read_chunk(reader: &mut Cursor<&[u8]>) -> io::Result<Vec<u8>> {
+    let offset = reader.position() as usize;
+    let len: u32 = reader
+        .read_be()
+        .with_context(offset, "reading chunk length")?;
+    let mut chunk = vec![0; len as usize];
+    reader
+        .read_exact(&mut chunk)
+        .with_context(offset + 4, "reading chunk data")?;
+    Ok(chunk)
+}
+
+#[test]
+fn annotate_failing_read() {
+    let mut input = vec![0, 0, 0, 128];
+    input.extend(vec![0; 128]);
+    input.extend(&[0, 0]);
+    let mut reader = Cursor::new(&input[..]);
+
+    assert_eq!(read_chunk(&mut reader).unwrap().len(), 128);
+    let err = read_chunk(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let inner = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    assert_eq!(inner.offset(), Some(132));
+    assert_eq!(inner.context(), Some("reading chunk length"));
+    assert_eq!(inner.kind(), &ParseErrorKind::Io(ErrorKind::UnexpectedEof));
+    assert!(inner.source().is_some());
+    assert_eq!(
+        inner.to_string(),
+        "reading chunk length at byte 132: unexpected end of file"
+    );
+}
+
+#[test]
+fn io_round_trip() {
+    let err =
+        ParseError::new(7, ParseErrorKind::Message("bad magic")).with_context("reading header");
+    let err = io::Error::from(err);
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let err = ParseError::from(err);
+    assert_eq!(err.offset(), Some(7));
+    assert_eq!(err.context(), Some("reading header"));
+    assert_eq!(err.kind(), &ParseErrorKind::Message("bad magic"));
+    assert_eq!(err.to_string(), "reading header at byte 7: bad magic");
+
+    let err = ParseError::from(io::Error::other("disk on fire"));
+    assert_eq!(err.offset(), None);
+    assert_eq!(err.kind(), &ParseErrorKind::Io(ErrorKind::Other));
+    assert_eq!(err.source().unwrap().to_string(), "disk on fire");
+}
+
+#[test]
+fn context_keeps_offset() {
+    let err = omnom::ParseBuf::new(&[1])
+        .read_be::<u16>()
+        .context("reading version")
+        .unwrap_err();
+    assert_eq!(err.offset(), Some(0));
+    assert_eq!(
+        err.to_string(),
+        "reading version at byte 0: unexpected end of file"
+    );
+}
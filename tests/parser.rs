@@ -0,0 +1,139 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{LimitExceeded, LimitKind, Limits, Parser, ParserError};
+use std::io::{self, BufRead, BufReader, ErrorKind};
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, PartialEq)]
+struct Chunk {
+    kind: [u8; 4],
+    data: Vec<u8>,
+}
+
+fn png(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut out = SIGNATURE.to_vec();
+    for (kind, data) in chunks {
+        out.write_be(data.len() as u32).unwrap();
+        out.extend_from_slice(*kind);
+        out.extend_from_slice(data);
+        out.write_be(0xdead_beef_u32).unwrap();
+    }
+    out
+}
+
+fn parse_png<R: BufRead>(p: &mut Parser<R>) -> Result<Vec<Chunk>, ParserError> {
+    p.context("signature", |p| {
+        if p.consume_prefix(SIGNATURE)? {
+            Ok(())
+        } else {
+            Err(io::Error::new(ErrorKind::InvalidData, "not a PNG file"))
+        }
+    })?;
+    let mut chunks = vec![];
+    while p.context("chunk", |p| p.fill_buf().map(|b| !b.is_empty()))? {
+        let chunk = p.context("chunk", |p| -> io::Result<Chunk> {
+            let len: u32 = p.context("length", |p| p.read_be())?;
+            let kind: [u8; 4] = p.context("type", |p| p.read_be())?;
+            let data = p.context("data", |p| p.read_bytes(len as usize))?;
+            p.context("crc", |p| p.read_be::<u32>())?;
+            Ok(Chunk { kind, data })
+        })?;
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+#[test]
+fn end_to_end() {
+    let input = png(&[(b"IHDR", &[0; 13]), (b"tEXt", b"a=b"), (b"IEND", b"")]);
+    let mut p = Parser::new(BufReader::with_capacity(5, &input[..]));
+    let chunks = parse_png(&mut p).unwrap();
+    let kinds: Vec<_> = chunks.iter().map(|c| &c.kind).collect();
+    assert_eq!(kinds, [b"IHDR", b"tEXt", b"IEND"]);
+    assert_eq!(chunks[1].data, b"a=b");
+    assert_eq!(p.offset(), input.len() as u64);
+}
+
+#[test]
+fn errors_report_context_and_offset() {
+    let mut input = png(&[(b"IHDR", &[0; 13])]);
+    input.extend_from_slice(b"\x00\x00\x00\x10tE");
+    let mut p = Parser::new(&input[..]);
+    let err = parse_png(&mut p).unwrap_err();
+    assert_eq!(err.contexts(), ["chunk", "type"]);
+    assert_eq!(err.offset(), 39);
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(
+        err.to_string(),
+        "in chunk > type at byte 39: failed to fill whole buffer"
+    );
+
+    // The details survive a round trip through `io::Error`.
+    let err = io::Error::from(err);
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let err = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ParserError>()
+        .unwrap();
+    assert_eq!(err.contexts(), ["chunk", "type"]);
+
+    let mut p = Parser::new(&b"GIF89a"[..]);
+    let err = parse_png(&mut p).unwrap_err();
+    assert_eq!(err.to_string(), "in signature at byte 0: not a PNG file");
+}
+
+#[test]
+fn line_and_column() {
+    let mut p = Parser::new(&b"a = 1\nb = x\n"[..]).track_lines();
+    let err = p
+        .context("assignment", |p| -> io::Result<()> {
+            p.read_until(b'\n', &mut vec![])?;
+            p.read_token(|b| b != b'=')?;
+            p.read_be::<u16>()?;
+            p.context("value", |p| -> io::Result<()> {
+                let value = p.read_token(|b| b.is_ascii_digit())?;
+                if value.is_empty() {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "expected a number"));
+                }
+                Ok(())
+            })?;
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(err.line_column(), Some((2, 5)));
+    assert_eq!(
+        err.to_string(),
+        "in assignment > value at byte 10 (line 2, column 5): expected a number"
+    );
+}
+
+#[test]
+fn limits() {
+    let limits = Limits::new().max_alloc(8).max_token(3);
+
+    let input = png(&[(b"IHDR", &[0; 13])]);
+    let mut p = Parser::new(&input[..]).with_limits(limits);
+    let err = parse_png(&mut p).unwrap_err();
+    assert_eq!(err.contexts(), ["chunk", "data"]);
+    let limit = err.get_ref().get_ref().unwrap();
+    let limit = limit.downcast_ref::<LimitExceeded>().unwrap();
+    assert_eq!(limit.kind(), LimitKind::Alloc);
+    assert_eq!((limit.requested(), limit.limit()), (13, 8));
+    // Nothing was read for the rejected length.
+    assert_eq!(p.offset(), 16);
+
+    let mut p = Parser::new(BufReader::with_capacity(2, &b"abcdef"[..])).with_limits(limits);
+    let err = p.read_token(|_| true).unwrap_err();
+    let limit = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<LimitExceeded>()
+        .unwrap();
+    assert_eq!(limit.kind(), LimitKind::Token);
+    assert_eq!(err.to_string(), "4 bytes exceed the maximum of 3 bytes");
+    assert_eq!(p.offset(), 2);
+    assert_eq!(p.limits().token_limit(), 3);
+}
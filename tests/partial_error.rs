@@ -0,0 +1,122 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::PartialError;
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+
+fn partial(err: &io::Error) -> &PartialError {
+    err.get_ref()
+        .unwrap()
+        .downcast_ref::<PartialError>()
+        .unwrap()
+}
+
+/// A writer that accepts `limit` bytes and fails after that.
+struct FailAfter {
+    written: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for FailAfter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written.len() == self.limit {
+            return Err(io::Error::other("disk full"));
+        }
+        let len = buf.len().min(self.limit - self.written.len());
+        self.written.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A reader that yields `data` and then fails.
+struct BrokenPipe<'a> {
+    data: &'a [u8],
+}
+
+impl Read for BrokenPipe<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.data.is_empty() {
+            return Err(io::Error::new(ErrorKind::BrokenPipe, "connection lost"));
+        }
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn copy_exact_reports_bytes_written() {
+    let data = [7; 20];
+    let mut reader = BufReader::with_capacity(8, &data[..]);
+    let mut writer = FailAfter {
+        written: vec![],
+        limit: 10,
+    };
+    let err = reader.copy_exact(20, &mut writer).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(partial(&err).completed(), 8);
+    assert_eq!(writer.written.len(), 10);
+}
+
+#[test]
+fn copy_until_reports_bytes_written() {
+    let data = [1; 10];
+    let reader = BrokenPipe { data: &data };
+    let mut reader = BufReader::with_capacity(4, reader);
+    let mut out = vec![];
+    let err = reader.copy_until(b'\n', &mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(partial(&err).completed(), 10);
+    assert_eq!(out.len(), 10);
+}
+
+#[test]
+fn read_floats_reports_elements() {
+    let bytes = vec![0; 100 * 4];
+    let mut reader = BrokenPipe { data: &bytes };
+    let mut out = [0.0_f32; 200];
+    let err = reader.read_f32_into_be(&mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(partial(&err).completed(), 64);
+
+    let mut reader = BufReader::with_capacity(16, BrokenPipe { data: &bytes });
+    let err = reader.read_f32_into_be_buffered(&mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(partial(&err).completed(), 100);
+}
+
+#[test]
+fn read_while_reports_bytes_read() {
+    let reader = BrokenPipe { data: b"abcdef" };
+    let mut reader = BufReader::with_capacity(4, reader);
+    let mut buf = vec![];
+    let err = reader.read_while(&mut buf, |_| true).unwrap_err();
+    assert_eq!(partial(&err).completed(), 6);
+    assert_eq!(buf, b"abcdef");
+}
+
+#[test]
+fn downcast_ergonomics() {
+    let reader = BrokenPipe { data: b"abc" };
+    let mut reader = BufReader::new(reader);
+    let err = reader.skip_to_end().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(err.to_string(), "connection lost");
+
+    let partial = err
+        .into_inner()
+        .unwrap()
+        .downcast::<PartialError>()
+        .unwrap();
+    assert_eq!(partial.completed(), 3);
+    assert_eq!(partial.get_ref().kind(), ErrorKind::BrokenPipe);
+    assert_eq!(partial.to_string(), "connection lost");
+
+    let source = partial.into_inner();
+    assert_eq!(source.to_string(), "connection lost");
+
+    let err: io::Error = PartialError::new(5, source).into();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+}
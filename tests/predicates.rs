@@ -0,0 +1,62 @@
+#![cfg(feature = "std")]
+
+use omnom::predicates::*;
+use omnom::prelude::*;
+use std::io::Cursor;
+
+/// Collect the bytes a predicate accepts.
+fn accepted(mut predicate: impl FnMut(u8) -> bool) -> Vec<u8> {
+    (0..=255).filter(|b| predicate(*b)).collect()
+}
+
+#[test]
+fn byte_classes() {
+    // RFC 5234 appendix B.1 core rules.
+    assert_eq!(accepted(ascii_digit), b"0123456789");
+    assert_eq!(accepted(ascii_hexdigit), b"0123456789ABCDEFabcdef");
+    assert_eq!(
+        accepted(ascii_alpha),
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+    );
+    assert_eq!(
+        accepted(ascii_alphanumeric),
+        &b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"[..]
+    );
+
+    // WHATWG Infra "ASCII whitespace" and Fetch "HTTP whitespace".
+    assert_eq!(accepted(ascii_whitespace), b"\t\n\x0C\r ");
+    assert_eq!(accepted(http_whitespace), b"\t\n\r ");
+
+    // RFC 9110 section 5.6.2 `tchar`.
+    let mut tchar = b"!#$%&'*+-.^_`|~".to_vec();
+    tchar.extend(accepted(ascii_alphanumeric));
+    tchar.sort_unstable();
+    assert_eq!(accepted(http_token), tchar);
+}
+
+#[test]
+fn combinators() {
+    assert_eq!(accepted(any_of(b"")), b"");
+    assert_eq!(accepted(any_of(b"\xff;,\x00")), b"\x00,;\xff");
+    assert_eq!(accepted(not(any_of(b"\x00"))).len(), 255);
+    assert_eq!(accepted(or(ascii_digit, any_of(b"+-"))), b"+-0123456789");
+}
+
+#[test]
+fn header_parser() {
+    // A `Name: value` header, with an RFC 9110 OWS around the value.
+    let mut reader = Cursor::new(b"Content-Length: \t 42 \r\n");
+    let mut name = vec![];
+    reader.read_while(&mut name, http_token).unwrap();
+    assert!(reader.consume_prefix(b":").unwrap());
+    reader.skip_while(any_of(b" \t")).unwrap();
+    let mut value = vec![];
+    reader
+        .read_while(&mut value, not(or(any_of(b" \t"), any_of(b"\r\n"))))
+        .unwrap();
+    reader.skip_while(http_whitespace).unwrap();
+
+    assert_eq!(name, b"Content-Length");
+    assert_eq!(value, b"42");
+    assert_eq!(reader.position(), 23);
+}
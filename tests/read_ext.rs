@@ -0,0 +1,575 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{OverflowPolicy, ShortRead};
+use std::collections::HashMap;
+use std::io::{BufReader, Cursor, ErrorKind, Read};
+
+#[test]
+fn read_vec_prefixed_empty() {
+    let mut cursor = Cursor::new([0, 0, 0xff]);
+    let values = cursor.read_vec_prefixed_be::<u16, u32>(0).unwrap();
+    assert!(values.is_empty());
+    assert_eq!(cursor.position(), 2);
+}
+
+#[test]
+fn read_vec_prefixed_cap() {
+    let mut buf = vec![];
+    buf.write_vec_prefixed_le::<u16, u32>(&[1, 2, 3]).unwrap();
+
+    let values = Cursor::new(&buf)
+        .read_vec_prefixed_le::<u16, u32>(3)
+        .unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let err = Cursor::new(&buf)
+        .read_vec_prefixed_le::<u16, u32>(2)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "3 elements exceed the maximum of 2 elements"
+    );
+
+    // The cap applies before anything is allocated.
+    let err = Cursor::new(u64::MAX.to_be_bytes())
+        .read_vec_prefixed_be::<u64, u8>(1 << 20)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn read_vec_prefixed_truncated() {
+    let mut cursor = Cursor::new([3, 0, 1, 0, 2, 0]);
+    let err = cursor.read_vec_prefixed_le::<u8, u16>(8).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn vec_prefixed_round_trip() {
+    let numbers = [0, 1, 0xdead_beef, u32::MAX];
+    let tags = [*b"RIFF", *b"WAVE", *b"fmt "];
+
+    let mut cursor = Cursor::new(vec![]);
+    assert_eq!(
+        cursor.write_vec_prefixed_be::<u16, u32>(&numbers).unwrap(),
+        2 + 16
+    );
+    assert_eq!(
+        cursor.write_vec_prefixed_le::<u32, [u8; 4]>(&tags).unwrap(),
+        4 + 12
+    );
+    assert_eq!(
+        cursor.write_vec_prefixed_ne::<u8, u32>(&numbers).unwrap(),
+        1 + 16
+    );
+    assert_eq!(&cursor.get_ref()[..6], &[0, 4, 0, 0, 0, 0]);
+    assert_eq!(&cursor.get_ref()[18..26], b"\x03\x00\x00\x00RIFF");
+
+    cursor.set_position(0);
+    assert_eq!(cursor.read_vec_prefixed_be::<u16, u32>(4).unwrap(), numbers);
+    assert_eq!(
+        cursor.read_vec_prefixed_le::<u32, [u8; 4]>(4).unwrap(),
+        tags
+    );
+    assert_eq!(cursor.read_vec_prefixed_ne::<u8, u32>(4).unwrap(), numbers);
+}
+
+#[test]
+fn write_vec_prefixed_overflow() {
+    let mut buf = vec![];
+    let err = buf.write_vec_prefixed_be::<u8, u16>(&[0; 256]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+    assert_eq!(
+        buf.write_vec_prefixed_be::<u8, u16>(&[0; 255]).unwrap(),
+        511
+    );
+}
+
+#[test]
+fn read_map_prefixed_empty() {
+    let mut cursor = Cursor::new([0, 0xff]);
+    let map = cursor.read_map_prefixed_be::<u8, u32, u32>(0).unwrap();
+    assert!(map.is_empty());
+    assert_eq!(cursor.position(), 1);
+}
+
+#[test]
+fn read_map_prefixed_duplicates() {
+    let mut cursor = Cursor::new([3, 1, 10, 2, 20, 1, 30]);
+    let err = cursor.read_map_prefixed_le::<u8, u8, u8>(3).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "entry 2 repeats an earlier key");
+}
+
+#[test]
+fn read_map_prefixed_cap() {
+    let input = [2, 1, 10, 2, 20];
+    let map = Cursor::new(input)
+        .read_map_prefixed_be::<u8, u8, u8>(2)
+        .unwrap();
+    assert_eq!(map.len(), 2);
+
+    let err = Cursor::new(input)
+        .read_map_prefixed_be::<u8, u8, u8>(1)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let err = Cursor::new(&input[..4])
+        .read_map_prefixed_be::<u8, u8, u8>(2)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn write_map_prefixed_is_deterministic() {
+    let expected: Vec<u8> = (0..100_u8).flat_map(|k| [0, k, k, k]).collect();
+    for seed in 0..10 {
+        // Insertion order and the hasher's seed don't affect the output.
+        let mut map = HashMap::new();
+        for k in (0..100_u16).map(|k| (k * 7 + seed) % 100) {
+            map.insert(k, [k as u8; 2]);
+        }
+        let mut buf = vec![];
+        buf.write_map_prefixed_be::<u8, _, _>(&map).unwrap();
+        assert_eq!(buf[0], 100);
+        assert_eq!(&buf[1..], &expected[..]);
+    }
+
+    let map: HashMap<u8, u8> = (0..=255).map(|k| (k, k)).chain(Some((0, 0))).collect();
+    let mut buf = vec![];
+    let err = buf.write_map_prefixed_be::<u8, _, _>(&map).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn map_prefixed_round_trip() {
+    let map: HashMap<u32, [u8; 8]> = [
+        (1, *b"first..."),
+        (0xdead_beef, *b"deadbeef"),
+        (u32::MAX, [0xff; 8]),
+    ]
+    .iter()
+    .copied()
+    .collect();
+
+    let mut cursor = Cursor::new(vec![]);
+    assert_eq!(
+        cursor.write_map_prefixed_be::<u16, _, _>(&map).unwrap(),
+        2 + 3 * 12
+    );
+    assert_eq!(
+        cursor.write_map_prefixed_le::<u32, _, _>(&map).unwrap(),
+        4 + 3 * 12
+    );
+    assert_eq!(&cursor.get_ref()[..8], &[0, 3, 0, 0, 0, 1, b'f', b'i']);
+
+    cursor.set_position(0);
+    assert_eq!(
+        cursor.read_map_prefixed_be::<u16, u32, [u8; 8]>(3).unwrap(),
+        map
+    );
+    assert_eq!(
+        cursor.read_map_prefixed_le::<u32, u32, [u8; 8]>(3).unwrap(),
+        map
+    );
+}
+
+/// Values whose bytes are all different, plus NaNs with distinct payloads.
+fn f32_values(len: usize) -> Vec<f32> {
+    (0..len as u32)
+        .map(|i| match i % 4 {
+            0 => f32::from_bits(0x7fc0_0000 | i),
+            1 => f32::from_bits(0xff80_0001 + i),
+            _ => f32::from_bits(i.wrapping_mul(0x9e37_79b9)),
+        })
+        .collect()
+}
+
+fn f64_values(len: usize) -> Vec<f64> {
+    (0..len as u64)
+        .map(|i| match i % 3 {
+            0 => f64::from_bits(0x7ff8_0000_0000_0000 | i),
+            _ => f64::from_bits(i.wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        })
+        .collect()
+}
+
+#[test]
+fn read_floats_match_single_reads() {
+    // 150 values are 2 full chunks and a partial one.
+    let values = f32_values(150);
+    let bytes: Vec<u8> = values
+        .iter()
+        .flat_map(|v| v.to_be_bytes().to_vec())
+        .collect();
+
+    let mut expected = vec![];
+    let mut cursor = Cursor::new(&bytes);
+    for _ in 0..values.len() {
+        expected.push(cursor.read_be::<u32>().unwrap());
+    }
+
+    let mut out = vec![0.0; values.len()];
+    Cursor::new(&bytes).read_f32_into_be(&mut out).unwrap();
+    assert_eq!(
+        out.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+        expected
+    );
+
+    for capacity in [1, 3, 4, 5, 7, 64, 1000] {
+        let mut out = vec![0.0; values.len()];
+        let mut reader = BufReader::with_capacity(capacity, &bytes[..]);
+        reader.read_f32_into_be_buffered(&mut out).unwrap();
+        assert_eq!(
+            out.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+            expected,
+            "capacity {}",
+            capacity
+        );
+    }
+}
+
+#[test]
+fn read_floats_endiannesses() {
+    let values = f64_values(70);
+    let le: Vec<u8> = values
+        .iter()
+        .flat_map(|v| v.to_le_bytes().to_vec())
+        .collect();
+    let ne: Vec<u8> = values
+        .iter()
+        .flat_map(|v| v.to_ne_bytes().to_vec())
+        .collect();
+    let bits: Vec<u64> = values.iter().map(|v| v.to_bits()).collect();
+
+    let mut out = vec![0.0; values.len()];
+    Cursor::new(&le).read_f64_into_le(&mut out).unwrap();
+    assert_eq!(out.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), bits);
+    Cursor::new(&ne).read_f64_into_ne(&mut out).unwrap();
+    assert_eq!(out.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), bits);
+    BufReader::with_capacity(13, &le[..])
+        .read_f64_into_le_buffered(&mut out)
+        .unwrap();
+    assert_eq!(out.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), bits);
+
+    // Only as much as requested is read.
+    let mut reader = BufReader::with_capacity(13, &le[..]);
+    let mut out = [0.0; 3];
+    reader.read_f64_into_le_buffered(&mut out).unwrap();
+    assert_eq!(reader.read_le::<u64>().unwrap(), bits[3]);
+}
+
+#[test]
+fn read_floats_truncated() {
+    let bytes = vec![0; 4 * 100 - 1];
+    let mut out = vec![0.0_f32; 100];
+    let err = Cursor::new(&bytes).read_f32_into_le(&mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let mut reader = BufReader::with_capacity(6, &bytes[..]);
+    let err = reader.read_f32_into_le_buffered(&mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let mut out: [f64; 0] = [];
+    Cursor::new(&bytes).read_f64_into_be(&mut out).unwrap();
+}
+
+/// A pattern that isn't periodic in 8 bits.
+fn bit_pattern(len: usize) -> Vec<bool> {
+    (0..len).map(|i| (i * i + i / 3) % 5 < 2).collect()
+}
+
+#[test]
+fn bitmap_round_trip() {
+    for &len in &[0, 1, 7, 8, 9, 64, 513, 1000] {
+        let bits = bit_pattern(len);
+        let mut buf = vec![];
+        assert_eq!(buf.write_bitmap(&bits).unwrap(), len.div_ceil(8));
+        assert_eq!(buf.len(), len.div_ceil(8));
+        if len % 8 != 0 {
+            // Padding bits are zero.
+            assert_eq!(buf[buf.len() - 1] >> (len % 8), 0, "{} bits", len);
+        }
+
+        let mut cursor = Cursor::new(&buf);
+        assert_eq!(cursor.read_bitmap(len).unwrap(), bits, "{} bits", len);
+        assert_eq!(cursor.position() as usize, buf.len());
+
+        let mut out = vec![false; len];
+        Cursor::new(&buf).read_bitmap_into(&mut out).unwrap();
+        assert_eq!(out, bits);
+    }
+}
+
+#[test]
+fn bitmap_layout() {
+    let mut buf = vec![];
+    buf.write_bitmap(&[true]).unwrap();
+    buf.write_bitmap(&[false, false, false, false, false, false, false, true])
+        .unwrap();
+    buf.write_bitmap(&[true; 9]).unwrap();
+    assert_eq!(buf, [0x01, 0x80, 0xff, 0x01]);
+}
+
+#[test]
+fn bitmap_ignores_padding() {
+    let mut cursor = Cursor::new([0b1111_1010, 0xff]);
+    let bits = cursor.read_bitmap(3).unwrap();
+    assert_eq!(bits, [false, true, false]);
+    assert_eq!(cursor.position(), 1);
+}
+
+#[test]
+fn bitmap_truncated() {
+    let err = Cursor::new([0xff]).read_bitmap(9).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn string_exact_multi_byte() {
+    let text = "grüße, 世界 🦀";
+    let mut cursor = Cursor::new(format!("{}rest", text));
+    assert_eq!(cursor.read_to_string_exact(text.len()).unwrap(), text);
+    assert_eq!(cursor.read_to_string_exact(4).unwrap(), "rest");
+}
+
+#[test]
+fn string_exact_invalid() {
+    let cases: &[(&[u8], usize)] = &[(b"\xffabc", 0), (b"ab\xc3(cd", 2), (b"abc\xe4\xb8", 3)];
+    for (bytes, offset) in cases {
+        let err = Cursor::new(bytes)
+            .read_to_string_exact(bytes.len())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), format!("invalid UTF-8 at byte {}", offset));
+    }
+}
+
+#[test]
+fn string_exact_empty() {
+    let mut cursor = Cursor::new(b"abc");
+    assert_eq!(cursor.read_to_string_exact(0).unwrap(), "");
+    assert_eq!(cursor.position(), 0);
+}
+
+#[test]
+fn string_exact_truncated() {
+    let err = Cursor::new(b"abc").read_to_string_exact(5).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "expected 5 bytes, found 3");
+
+    // A corrupt length doesn't allocate up front.
+    let err = Cursor::new(b"abc")
+        .read_to_string_exact(usize::MAX)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn padded_fits() {
+    for policy in [OverflowPolicy::Error, OverflowPolicy::Truncate]
+        .iter()
+        .copied()
+    {
+        let mut buf = vec![];
+        assert_eq!(buf.write_padded(b"ab", 4, 0, policy).unwrap(), 4);
+        assert_eq!(buf.write_padded(b"cdef", 4, 0, policy).unwrap(), 4);
+        assert_eq!(buf.write_padded(b"", 0, 0, policy).unwrap(), 0);
+        assert_eq!(buf.write_padded(b"", 2, b' ', policy).unwrap(), 2);
+        assert_eq!(buf, b"ab\0\0cdef  ");
+    }
+}
+
+#[test]
+fn padded_overflow() {
+    let mut buf = vec![];
+    let err = buf
+        .write_padded(b"abcde", 4, 0, OverflowPolicy::Error)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "5 bytes don't fit in a field of 4 bytes");
+    assert!(buf.write_padded(b"a", 0, 0, OverflowPolicy::Error).is_err());
+    assert!(buf.is_empty());
+
+    assert_eq!(
+        buf.write_padded(b"abcde", 4, 0, OverflowPolicy::Truncate)
+            .unwrap(),
+        4
+    );
+    assert_eq!(
+        buf.write_padded(b"a", 0, 0, OverflowPolicy::Truncate)
+            .unwrap(),
+        0
+    );
+    assert_eq!(buf, b"abcd");
+}
+
+#[test]
+fn padded_round_trip() {
+    let name = vec![b'x'; 300];
+    let mut buf = vec![];
+    buf.write_padded(b"hello.txt", 100, 0, OverflowPolicy::Error)
+        .unwrap();
+    buf.write_padded(&name, 512, 0, OverflowPolicy::Error)
+        .unwrap();
+    assert_eq!(buf.len(), 612);
+
+    let mut cursor = Cursor::new(buf);
+    let mut field = [0; 100];
+    cursor.read_exact(&mut field).unwrap();
+    let end = field.iter().position(|&b| b == 0).unwrap();
+    assert_eq!(&field[..end], b"hello.txt");
+    let mut field = [0; 512];
+    cursor.read_exact(&mut field).unwrap();
+    assert_eq!(&field[..300], &name[..]);
+    assert!(field[300..].iter().all(|&b| b == 0));
+}
+
+/// A reader that returns at most 3 bytes per read, and is interrupted before
+/// every other read.
+struct Trickle<'a> {
+    data: &'a [u8],
+    interrupt: bool,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(ErrorKind::Interrupted.into());
+        }
+        let n = buf.len().min(self.data.len()).min(3);
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn skip_bytes_zero() {
+    let mut cursor = Cursor::new(b"abc");
+    assert_eq!(cursor.skip_bytes(0).unwrap(), 0);
+    cursor.skip_bytes_exact(0).unwrap();
+    assert_eq!(cursor.position(), 0);
+}
+
+#[test]
+fn skip_bytes_many_chunks() {
+    let input: Vec<u8> = (0..100_000_u32).map(|i| i as u8).collect();
+    let mut cursor = Cursor::new(&input);
+    assert_eq!(cursor.skip_bytes(70_000).unwrap(), 70_000);
+    assert_eq!(cursor.read_be::<u8>().unwrap(), 70_000_u32 as u8);
+    cursor.skip_bytes_exact(29_998).unwrap();
+    assert_eq!(cursor.read_be::<u8>().unwrap(), 99_999_u32 as u8);
+}
+
+#[test]
+fn skip_bytes_eof() {
+    let mut cursor = Cursor::new(b"abcde");
+    assert_eq!(cursor.skip_bytes(u64::MAX).unwrap(), 5);
+    assert_eq!(cursor.skip_bytes(1).unwrap(), 0);
+
+    let mut cursor = Cursor::new(b"abcde");
+    let err = cursor.skip_bytes_exact(8).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "expected 8 bytes, found 5");
+}
+
+#[test]
+fn skip_bytes_partial_reads() {
+    let data: Vec<u8> = (0..20).collect();
+    let mut reader = Trickle {
+        data: &data,
+        interrupt: false,
+    };
+    assert_eq!(reader.skip_bytes(10).unwrap(), 10);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 10);
+    reader.skip_bytes_exact(8).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 19);
+}
+
+#[test]
+fn read_padded_full_value() {
+    let mut cursor = Cursor::new([1, 2, 3, 4, 1, 2, 3, 4]);
+    assert_eq!(cursor.read_le_padded::<u32>().unwrap(), (0x0403_0201, 4));
+    assert_eq!(cursor.read_be_padded::<u32>().unwrap(), (0x0102_0304, 4));
+}
+
+#[test]
+fn read_padded_one_byte_short() {
+    // Little endian loses the high byte, big endian the low byte.
+    let mut cursor = Cursor::new([0x11, 0x22, 0x33]);
+    assert_eq!(cursor.read_le_padded::<u32>().unwrap(), (0x0033_2211, 3));
+    let mut cursor = Cursor::new([0x11, 0x22, 0x33]);
+    assert_eq!(cursor.read_be_padded::<u32>().unwrap(), (0x1122_3300, 3));
+
+    let mut cursor = Cursor::new([0xff]);
+    assert_eq!(cursor.read_le_padded::<i16>().unwrap(), (0x00ff, 1));
+}
+
+#[test]
+fn read_padded_clean_eof() {
+    let mut cursor = Cursor::new([]);
+    assert_eq!(cursor.read_le_padded::<u64>().unwrap(), (0, 0));
+    assert_eq!(cursor.read_be_padded::<u64>().unwrap(), (0, 0));
+}
+
+#[test]
+fn read_padded_partial_reads() {
+    let data = [1, 2, 3, 4, 5, 6, 7];
+    let mut reader = Trickle {
+        data: &data,
+        interrupt: false,
+    };
+    assert_eq!(
+        reader.read_be_padded::<u64>().unwrap(),
+        (0x0102_0304_0506_0700, 7)
+    );
+}
+
+#[test]
+fn read_exact_vec_matches_read_exact() {
+    let bytes: Vec<u8> = (0..100_000).map(|i| (i * 31) as u8).collect();
+    let mut naive = vec![0; 70_000];
+    Cursor::new(&bytes).read_exact(&mut naive).unwrap();
+
+    let mut reader = BufReader::with_capacity(1000, &bytes[..]);
+    let vec = reader.read_exact_vec(70_000).unwrap();
+    assert_eq!(vec, naive);
+    assert_eq!(reader.read_exact_vec(30_000).unwrap(), bytes[70_000..]);
+}
+
+#[test]
+fn read_exact_vec_truncated() {
+    let mut reader = BufReader::with_capacity(3, &b"lorem"[..]);
+    let err = reader.read_exact_vec(8).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (8, 5));
+
+    // A corrupt length doesn't reserve more than the reader holds.
+    let err = Cursor::new(b"abc").read_exact_vec(usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let mut buf = vec![];
+    buf.write_vec_prefixed_be::<u16, u32>(&[1, 2, 3]).unwrap();
+    buf.truncate(buf.len() - 1);
+    let err = Cursor::new(&buf)
+        .read_vec_prefixed_be::<u16, u32>(3)
+        .unwrap_err();
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (12, 11));
+}
+
+#[test]
+fn read_exact_vec_empty() {
+    let mut cursor = Cursor::new(b"");
+    assert!(cursor.read_exact_vec(0).unwrap().is_empty());
+    let mut cursor = Cursor::new(b"x");
+    assert!(cursor.read_exact_vec(0).unwrap().is_empty());
+    assert_eq!(cursor.position(), 0);
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "ryu")]
+
+use omnom::prelude::*;
+
+fn f64_text(f: f64) -> String {
+    let mut buf = vec![];
+    let written = buf.write_ascii_float(f).unwrap();
+    assert_eq!(written, buf.len());
+    String::from_utf8(buf).unwrap()
+}
+
+fn f32_text(f: f32) -> String {
+    let mut buf = vec![];
+    let written = buf.write_ascii_float_f32(f).unwrap();
+    assert_eq!(written, buf.len());
+    String::from_utf8(buf).unwrap()
+}
+
+/// A xorshift generator, to sample bit patterns without a dependency.
+fn bit_patterns(mut state: u64) -> impl Iterator<Item = u64> {
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    })
+}
+
+#[test]
+fn random_values_round_trip() {
+    for bits in bit_patterns(0x9e37_79b9_7f4a_7c15).take(100_000) {
+        let f = f64::from_bits(bits);
+        if f.is_nan() {
+            continue;
+        }
+        let text = f64_text(f);
+        assert_eq!(text.parse::<f64>().unwrap().to_bits(), bits, "{}", text);
+
+        let f = f32::from_bits(bits as u32);
+        if f.is_nan() {
+            continue;
+        }
+        let text = f32_text(f);
+        assert_eq!(
+            text.parse::<f32>().unwrap().to_bits(),
+            bits as u32,
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn special_values() {
+    assert_eq!(f64_text(f64::NAN), "NaN");
+    assert_eq!(f64_text(f64::INFINITY), "inf");
+    assert_eq!(f64_text(f64::NEG_INFINITY), "-inf");
+    assert_eq!(f64_text(0.0), "0.0");
+    assert_eq!(f64_text(-0.0), "-0.0");
+    assert!(f64_text(-0.0).parse::<f64>().unwrap().is_sign_negative());
+    assert_eq!(f32_text(f32::NAN), "NaN");
+    assert_eq!(f32_text(f32::NEG_INFINITY), "-inf");
+    assert_eq!(f32_text(-0.0), "-0.0");
+
+    for text in ["NaN", "inf", "-inf"].iter() {
+        assert_eq!(f64_text(text.parse().unwrap()), *text);
+    }
+}
+
+#[test]
+fn shortest_text() {
+    assert_eq!(f64_text(0.1), "0.1");
+    assert_eq!(f64_text(0.1 + 0.2), "0.30000000000000004");
+    assert_eq!(f64_text(1.0), "1.0");
+    assert_eq!(f64_text(1e300), "1e300");
+    assert_eq!(f32_text(0.1), "0.1");
+    assert_eq!(f32_text(16777216.0), "16777216.0");
+}
+
+#[test]
+fn subnormals() {
+    let smallest = f64::from_bits(1);
+    assert_eq!(f64_text(smallest), "5e-324");
+    assert_eq!(f32_text(f32::from_bits(1)), "1e-45");
+
+    for bits in [1_u64, 2, 0x000f_ffff_ffff_ffff, 0x0008_0000_0000_0000].iter() {
+        let f = f64::from_bits(*bits);
+        assert!(f.is_subnormal());
+        assert_eq!(f64_text(f).parse::<f64>().unwrap(), f);
+        assert_eq!(f64_text(-f).parse::<f64>().unwrap(), -f);
+    }
+}
@@ -0,0 +1,358 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::SeekExt;
+use std::io::{self, BufRead, BufReader, Cursor, Seek, SeekFrom, Write};
+
+#[test]
+fn patch_length_after_body() {
+    let mut cursor = Cursor::new(vec![]);
+    cursor.write_all(b"TAG").unwrap();
+    let len = cursor.write_placeholder_be::<u32>().unwrap();
+    assert_eq!(len.position(), 3);
+    let start = cursor.position();
+    for i in 0..10_u8 {
+        cursor.write_all(&vec![i; usize::from(i)]).unwrap();
+    }
+    let body_len = cursor.position() - start;
+    cursor.patch(len, body_len as u32).unwrap();
+    cursor.write_all(b"END").unwrap();
+
+    let bytes = cursor.into_inner();
+    assert_eq!(&bytes[..7], b"TAG\x00\x00\x00\x2d");
+    assert_eq!(bytes.len(), 3 + 4 + 45 + 3);
+    assert_eq!(&bytes[bytes.len() - 3..], b"END");
+}
+
+#[test]
+fn nested_placeholders() {
+    // A chunk containing two sub-chunks, each with a little endian length.
+    let mut cursor = Cursor::new(vec![]);
+    let outer = cursor.write_placeholder_le::<u16>().unwrap();
+    let first = cursor.write_placeholder_le::<u16>().unwrap();
+    cursor.write_all(b"abc").unwrap();
+    cursor.patch(first, 3).unwrap();
+    let second = cursor.write_placeholder_le::<u16>().unwrap();
+    cursor.write_all(b"de").unwrap();
+    cursor.patch(second, 2).unwrap();
+    cursor.patch(outer, 9).unwrap();
+    assert_eq!(cursor.get_ref(), b"\x09\x00\x03\x00abc\x02\x00de");
+    assert_eq!(cursor.position(), 11);
+}
+
+#[test]
+fn patch_out_of_order() {
+    let mut cursor = Cursor::new(vec![]);
+    let a = cursor.write_placeholder_be::<u16>().unwrap();
+    let b = cursor.write_placeholder_le::<u32>().unwrap();
+    let c = cursor.write_placeholder_ne::<u8>().unwrap();
+    cursor.write_be(0xffff_u16).unwrap();
+    cursor.patch(b, 0x0102_0304).unwrap();
+    cursor.patch(c, 5).unwrap();
+    cursor.patch(a, 0x0607).unwrap();
+    assert_eq!(
+        cursor.get_ref(),
+        &[0x06, 0x07, 0x04, 0x03, 0x02, 0x01, 0x05, 0xff, 0xff]
+    );
+    assert_eq!(cursor.position(), 9);
+}
+
+/// A writer whose writes fail once `fail` is set.
+struct Flaky {
+    inner: Cursor<Vec<u8>>,
+    fail: bool,
+}
+
+impl Write for Flaky {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.fail {
+            return Err(io::Error::other("disk full"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Flaky {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn patch_restores_position_on_error() {
+    let mut writer = Flaky {
+        inner: Cursor::new(vec![]),
+        fail: false,
+    };
+    let placeholder = writer.write_placeholder_be::<u32>().unwrap();
+    writer.write_all(b"body").unwrap();
+
+    writer.fail = true;
+    let err = writer.patch(placeholder, 4).unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+    assert_eq!(writer.stream_position().unwrap(), 8);
+}
+
+fn numbers() -> BufReader<Cursor<Vec<u8>>> {
+    BufReader::with_capacity(4, Cursor::new((0..100).collect()))
+}
+
+#[test]
+fn skip_seek_within_buffer() {
+    let mut reader = numbers();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+    reader.skip_seek(2).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 3);
+    assert_eq!(reader.stream_position().unwrap(), 4);
+}
+
+#[test]
+fn skip_seek_past_buffer() {
+    let mut reader = numbers();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+    reader.skip_seek(50).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 51);
+    reader.skip_seek(47).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 99);
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+fn skip_seek_to_end() {
+    let mut reader = numbers();
+    reader.skip_seek(100).unwrap();
+    assert!(reader.fill_buf().unwrap().is_empty());
+    reader.skip_seek(0).unwrap();
+}
+
+#[test]
+fn skip_seek_past_end() {
+    let mut reader = numbers();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+    let err = reader.skip_seek(100).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "can't skip 100 bytes, only 99 are left");
+    assert_eq!(reader.stream_position().unwrap(), 100);
+
+    let mut reader = numbers();
+    assert!(reader.skip_seek(u64::MAX).is_err());
+}
+
+#[test]
+fn peek_past_buffer_capacity() {
+    let mut reader = numbers();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+    let sum = reader
+        .peek_with(|r| {
+            let mut sum = 0_u32;
+            for _ in 0..20 {
+                sum += u32::from(r.read_be::<u8>()?);
+            }
+            Ok(sum)
+        })
+        .unwrap();
+    assert_eq!(sum, (1..=20).sum());
+    assert_eq!(reader.read_be::<u8>().unwrap(), 1);
+}
+
+#[test]
+fn nested_peeks() {
+    let mut reader = numbers();
+    let (outer, inner) = reader
+        .peek_with(|r| {
+            r.skip_seek(10)?;
+            let inner = r.peek_with(|r| {
+                r.skip_seek(30)?;
+                r.read_be::<u8>()
+            })?;
+            Ok((r.read_be::<u8>()?, inner))
+        })
+        .unwrap();
+    assert_eq!((outer, inner), (10, 40));
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+}
+
+#[test]
+fn peek_restores_position_on_error() {
+    let mut reader = numbers();
+    reader.skip_seek(5).unwrap();
+    let err = reader
+        .peek_with(|r| {
+            r.skip_seek(10)?;
+            r.read_be::<u128>()?;
+            Err::<(), _>(io::Error::new(io::ErrorKind::InvalidData, "bad magic"))
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "bad magic");
+    assert_eq!(reader.read_be::<u8>().unwrap(), 5);
+}
+
+#[test]
+fn mark_and_rewind() {
+    let mut reader = numbers();
+    reader.skip_seek(3).unwrap();
+    let mark = reader.mark().unwrap();
+    assert_eq!(mark.position(), 3);
+
+    reader.rewind_to(mark).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 3);
+    reader.skip_seek(60).unwrap();
+    reader.rewind_to(mark).unwrap();
+    assert_eq!(reader.read_be::<u8>().unwrap(), 3);
+    assert_eq!(reader.stream_position().unwrap(), 4);
+}
+
+#[test]
+fn peek_at_ahead_and_behind() {
+    let mut reader = numbers();
+    reader.skip_seek(50).unwrap();
+
+    let mut buf = [0; 3];
+    assert_eq!(reader.peek_at(SeekFrom::Current(20), &mut buf).unwrap(), 3);
+    assert_eq!(buf, [70, 71, 72]);
+    assert_eq!(reader.peek_at(SeekFrom::Current(-10), &mut buf).unwrap(), 3);
+    assert_eq!(buf, [40, 41, 42]);
+    assert_eq!(reader.peek_at(SeekFrom::Start(1), &mut buf).unwrap(), 3);
+    assert_eq!(buf, [1, 2, 3]);
+    assert_eq!(reader.peek_at(SeekFrom::End(-2), &mut buf).unwrap(), 2);
+    assert_eq!(buf[..2], [98, 99]);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 50);
+}
+
+#[test]
+fn peek_at_eof() {
+    let mut cursor = Cursor::new([1, 2, 3]);
+    let mut buf = [0; 4];
+    assert_eq!(cursor.peek_at(SeekFrom::End(0), &mut buf).unwrap(), 0);
+    assert_eq!(cursor.peek_at(SeekFrom::Start(10), &mut buf).unwrap(), 0);
+    assert_eq!(cursor.peek_at(SeekFrom::Current(1), &mut buf).unwrap(), 2);
+    assert_eq!(buf[..2], [2, 3]);
+    assert!(cursor.peek_at(SeekFrom::Current(-1), &mut buf).is_err());
+    assert_eq!(cursor.position(), 0);
+}
+
+#[test]
+fn peek_at_doesnt_change_sequential_reads() {
+    let read_all = |peek: bool| {
+        let mut reader = numbers();
+        let mut out = vec![];
+        let mut buf = [0; 5];
+        while let Ok(byte) = reader.read_be::<u8>() {
+            out.push(byte);
+            if peek {
+                reader
+                    .peek_at(SeekFrom::Current(i64::from(byte % 7) - 3), &mut buf)
+                    .ok();
+            }
+        }
+        out
+    };
+    assert_eq!(read_all(true), read_all(false));
+}
+
+/// A file-like reader that counts its seeks.
+struct CountingFile {
+    inner: Cursor<Vec<u8>>,
+    seeks: usize,
+}
+
+impl io::Read for CountingFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for CountingFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if pos != SeekFrom::Current(0) {
+            self.seeks += 1;
+        }
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn peek_at_within_buffer_doesnt_seek() {
+    let file = CountingFile {
+        inner: Cursor::new((0..100).collect()),
+        seeks: 0,
+    };
+    let mut reader = BufReader::with_capacity(16, file);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0);
+
+    let mut buf = [0; 4];
+    reader.peek_at(SeekFrom::Current(8), &mut buf).unwrap();
+    assert_eq!(buf, [9, 10, 11, 12]);
+    reader.peek_at(SeekFrom::Start(12), &mut buf).unwrap();
+    assert_eq!(buf, [12, 13, 14, 15]);
+    assert_eq!(reader.get_ref().seeks, 0);
+    assert_eq!(reader.buffer().len(), 15);
+
+    reader.peek_at(SeekFrom::Start(13), &mut buf).unwrap();
+    assert_eq!(buf, [13, 14, 15, 16]);
+    assert_eq!(reader.get_ref().seeks, 2);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 1);
+}
+
+#[test]
+fn write_at_middle_of_slice() {
+    let mut image = [0xaa; 8];
+    let mut cursor = Cursor::new(&mut image[..]);
+    cursor.set_position(7);
+    assert_eq!(cursor.write_be_at(3, 0x0102_0304_u32).unwrap(), 4);
+    assert_eq!(cursor.position(), 7);
+    assert_eq!(image, [0xaa, 0xaa, 0xaa, 1, 2, 3, 4, 0xaa]);
+
+    let mut cursor = Cursor::new(&mut image[..]);
+    cursor.write_le_at(1, 0x0506_u16).unwrap();
+    assert_eq!(cursor.position(), 0);
+    assert_eq!(image, [0xaa, 6, 5, 1, 2, 3, 4, 0xaa]);
+}
+
+#[test]
+fn write_at_past_end_of_slice() {
+    let mut image = [0; 4];
+    let mut cursor = Cursor::new(&mut image[..]);
+    cursor.set_position(1);
+    let err = cursor.write_be_at(3, 0x0102_u16).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(cursor.position(), 1);
+    assert!(cursor.write_le_at(u64::MAX, 0x01_u8).is_err());
+    assert_eq!(image, [0; 4]);
+
+    // A `Vec` isn't grown to fit the value.
+    let mut cursor = Cursor::new(vec![0; 4]);
+    assert!(cursor.write_le_at(2, 0x0102_0304_u32).is_err());
+    assert_eq!(cursor.get_ref(), &[0; 4]);
+    assert_eq!(cursor.write_le_at(4, [0_u8; 0]).unwrap(), 0);
+}
+
+#[test]
+fn read_at_restores_position_on_error() {
+    let mut reader = numbers();
+    reader.skip_seek(2).unwrap();
+    let err = reader.read_be_at::<u32>(98).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 2);
+
+    let mut cursor = Cursor::new([1, 2, 3]);
+    assert!(cursor.read_le_at::<u16>(10).is_err());
+    assert_eq!(cursor.position(), 0);
+}
+
+#[test]
+fn round_trip_at_start_and_tail() {
+    let mut cursor = Cursor::new(vec![0; 12]);
+    cursor.set_position(5);
+    cursor.write_be_at(0, 0xdead_beef_u32).unwrap();
+    cursor.write_le_at(8, -2_i32).unwrap();
+    assert_eq!(cursor.read_be_at::<u32>(0).unwrap(), 0xdead_beef);
+    assert_eq!(cursor.read_le_at::<i32>(8).unwrap(), -2);
+    assert_eq!(cursor.read_be_at::<u8>(11).unwrap(), 0xff);
+    assert_eq!(cursor.position(), 5);
+    assert_eq!(cursor.get_ref().len(), 12);
+}
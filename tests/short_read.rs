@@ -0,0 +1,50 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::ShortRead;
+use std::io::{self, BufReader, Cursor, ErrorKind};
+
+fn short_read(err: &io::Error) -> ShortRead {
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    *err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap()
+}
+
+#[test]
+fn consume_prefix_reports_available() {
+    let mut reader = BufReader::with_capacity(2, &b"HTT"[..]);
+    let err = reader.consume_prefix(b"HTTP/").unwrap_err();
+    assert_eq!(short_read(&err), ShortRead::new(5, 3));
+    assert_eq!(err.to_string(), "expected 5 bytes, found 3");
+}
+
+#[test]
+fn read_floats_reports_available() {
+    let mut bytes = vec![0; 8];
+    bytes.extend_from_slice(&[1, 2, 3]);
+    let mut reader = BufReader::with_capacity(4, &bytes[..]);
+    let mut out = [0.0_f32; 4];
+    let err = reader.read_f32_into_be_buffered(&mut out).unwrap_err();
+    let short = short_read(&err);
+    assert_eq!((short.needed(), short.available()), (16, 11));
+}
+
+#[test]
+fn read_string_reports_available() {
+    let err = Cursor::new(b"").read_to_string_exact(2).unwrap_err();
+    assert_eq!(short_read(&err), ShortRead::new(2, 0));
+    assert_eq!(err.to_string(), "expected 2 bytes, found 0");
+}
+
+#[test]
+fn satisfiable_reads_succeed() {
+    let mut reader = BufReader::with_capacity(2, &b"HTTP/1.1"[..]);
+    assert!(reader.consume_prefix(b"HTTP/").unwrap());
+    assert_eq!(reader.read_to_string_exact(3).unwrap(), "1.1");
+}
+
+#[test]
+fn converts_to_io_error() {
+    let err = io::Error::from(ShortRead::new(4, 1));
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "expected 4 bytes, found 1");
+}
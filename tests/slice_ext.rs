@@ -0,0 +1,146 @@
+#![cfg(feature = "std")]
+
+use omnom::SliceExt;
+use std::collections::HashMap;
+
+#[derive(Eq, PartialEq, Debug)]
+struct Mime {
+    base_type: String,
+    sub_type: String,
+    parameters: HashMap<String, String>,
+}
+
+/// The mime example, parsed through `BufRead` and `BufReadExt`.
+fn parse_mime_io(s: &str) -> Option<Mime> {
+    use omnom::prelude::*;
+    use std::io::{BufRead, Cursor};
+
+    let mut s = Cursor::new(s);
+    let mut base_type = vec![];
+    if s.read_until(b'/', &mut base_type).ok()? == 0 || base_type.pop() != Some(b'/') {
+        return None;
+    }
+    let mut sub_type = vec![];
+    s.read_until(b';', &mut sub_type).ok()?;
+    if let Some(b';') = sub_type.last() {
+        sub_type.pop();
+    }
+
+    let mut parameters = HashMap::new();
+    while !s.fill_buf().ok()?.is_empty() {
+        s.skip_while(|b| b == b' ').ok()?;
+        let mut name = vec![];
+        s.read_while(&mut name, |b| b != b';' && b != b'=').ok()?;
+        let mut token = vec![];
+        s.read_until(b';', &mut token).ok()?;
+        if token.first() != Some(&b'=') {
+            continue;
+        }
+        if let Some(b';') = token.last() {
+            token.pop();
+        }
+        parameters.insert(
+            String::from_utf8(name).ok()?,
+            String::from_utf8(token[1..].to_vec()).ok()?,
+        );
+    }
+
+    Some(Mime {
+        base_type: String::from_utf8(base_type).ok()?,
+        sub_type: String::from_utf8(sub_type).ok()?,
+        parameters,
+    })
+}
+
+/// The same parser, ported to `SliceExt`.
+fn parse_mime_slice(s: &str) -> Option<Mime> {
+    let mut s = s.as_bytes();
+    let base_type = s.read_until(b'/');
+    let base_type = base_type.strip_suffix(b"/")?;
+    let sub_type = s.read_until(b';');
+    let sub_type = sub_type.strip_suffix(b";").unwrap_or(sub_type);
+
+    let mut parameters = HashMap::new();
+    while !s.is_empty() {
+        s.skip_while(|b| b == b' ');
+        let name = s.read_while(|b| b != b';' && b != b'=');
+        if !s.consume_prefix(b"=") {
+            s.skip_until(b';');
+            continue;
+        }
+        let value = s.read_until(b';');
+        let value = value.strip_suffix(b";").unwrap_or(value);
+        parameters.insert(
+            String::from_utf8(name.to_vec()).ok()?,
+            String::from_utf8(value.to_vec()).ok()?,
+        );
+    }
+
+    Some(Mime {
+        base_type: String::from_utf8(base_type.to_vec()).ok()?,
+        sub_type: String::from_utf8(sub_type.to_vec()).ok()?,
+        parameters,
+    })
+}
+
+#[test]
+fn mime_parsers_agree() {
+    let inputs = [
+        "text/html",
+        "text/html;",
+        "text/html; charset=utf-8;",
+        "text/html; charset=utf-8; foo; bar=baz",
+        "multipart/form-data;boundary=xyz",
+        "text",
+        "",
+    ];
+    for input in &inputs {
+        assert_eq!(parse_mime_io(input), parse_mime_slice(input), "{}", input);
+    }
+    let mime = parse_mime_slice("text/html; charset=utf-8;").unwrap();
+    assert_eq!(mime.parameters["charset"], "utf-8");
+}
+
+#[test]
+fn boundaries_at_end_of_slice() {
+    let mut input: &[u8] = b"abc";
+    assert_eq!(input.read_until(b'x'), b"abc");
+    assert!(input.is_empty());
+    assert_eq!(input.read_until(b'x'), b"");
+    assert_eq!(input.read_while(|_| true), b"");
+    assert_eq!(input.skip_until(b'x'), 0);
+    assert_eq!(input.skip_while(|_| true), 0);
+    assert!(input.consume_prefix(b""));
+    assert!(!input.consume_prefix(b"a"));
+    assert_eq!(input.read_be::<u8>(), None);
+
+    let mut input: &[u8] = b"ab";
+    assert!(!input.consume_prefix(b"abc"));
+    assert_eq!(input, b"ab");
+    assert_eq!(input.read_le::<u32>(), None);
+    assert_eq!(input.read_le::<u16>(), Some(u16::from_le_bytes(*b"ab")));
+    assert!(input.is_empty());
+}
+
+#[test]
+fn counts_match_io() {
+    use omnom::BufReadExt;
+    use std::io::Cursor;
+
+    let data = b"aaab-cd-";
+    let mut slice: &[u8] = data;
+    let mut cursor = Cursor::new(&data[..]);
+    assert_eq!(
+        SliceExt::skip_while(&mut slice, |b| b == b'a'),
+        BufReadExt::skip_while(&mut cursor, |b| b == b'a').unwrap()
+    );
+    assert_eq!(
+        SliceExt::skip_until(&mut slice, b'-'),
+        BufReadExt::skip_until(&mut cursor, b'-').unwrap()
+    );
+    let mut buf = vec![];
+    assert_eq!(
+        SliceExt::read_while(&mut slice, |b| b != b'-').len(),
+        cursor.read_while(&mut buf, |b| b != b'-').unwrap()
+    );
+}
@@ -0,0 +1,171 @@
+//! Checks that the native endian methods of every `ReadBytes` type use the
+//! order of the machine, and the other methods don't, without needing a
+//! machine of the other endianness.
+//!
+//! Each type is tested with values built from byte patterns, so a type
+//! added to the lists below is covered without writing values for it. The
+//! integer types come from the list their impls are generated from.
+
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{Int, MacAddr, MsGuid, SwapBytes, UInt, Q16_16, Q2_14, Q8_8};
+use std::any::type_name;
+use std::fmt::Debug;
+
+include!("../src/int_types.rs");
+
+/// Byte patterns that tell the positions of bytes apart, including their
+/// high bits.
+const PATTERNS: [fn(usize) -> u8; 3] = [|i| i as u8 + 1, |i| 0x80 | i as u8, |i| 0xff - i as u8];
+
+/// Returns the values of `B` whose big endian bytes are the patterns.
+///
+/// Types that saturate bytes out of their range, such as the epoch types,
+/// get the value their saturated bytes decode to, which round trips.
+fn values<B: ReadBytes + WriteBytes>() -> Vec<B> {
+    PATTERNS
+        .iter()
+        .map(|pattern| {
+            let mut bytes = <B as ReadBytes>::Bytes::default();
+            for (i, byte) in bytes.as_mut().iter_mut().enumerate() {
+                *byte = pattern(i);
+            }
+            let saturated = B::from_be_array(bytes).to_be_array();
+            let mut bytes = <B as ReadBytes>::Bytes::default();
+            bytes.as_mut().copy_from_slice(saturated.as_ref());
+            B::from_be_array(bytes)
+        })
+        .collect()
+}
+
+/// Check the native endian methods of `B` against the order of the machine.
+fn check_native<B>()
+where
+    B: ReadBytes + WriteBytes + Copy + PartialEq + Debug,
+{
+    for value in values::<B>() {
+        let mut ne = vec![];
+        ne.write_ne(value).unwrap();
+        let native = if cfg!(target_endian = "little") {
+            value.to_le_array()
+        } else {
+            value.to_be_array()
+        };
+        assert_eq!(
+            ne,
+            native.as_ref(),
+            "write_ne of {:?}: {}",
+            value,
+            type_name::<B>()
+        );
+        assert_eq!(value.to_ne_array().as_ref(), native.as_ref());
+        assert_eq!(ne.as_slice().read_ne::<B>().unwrap(), value);
+
+        let mut be = vec![];
+        be.write_be(value).unwrap();
+        assert_eq!(be, value.to_be_array().as_ref(), "write_be of {:?}", value);
+        assert_eq!(be.as_slice().read_be::<B>().unwrap(), value);
+
+        let mut le = vec![];
+        le.write_le(value).unwrap();
+        assert_eq!(le, value.to_le_array().as_ref(), "write_le of {:?}", value);
+        assert_eq!(le.as_slice().read_le::<B>().unwrap(), value);
+    }
+}
+
+/// Check that reading and writing the opposite of native endianness swaps
+/// the bytes of `B`.
+fn check_swapped<B>()
+where
+    B: ReadBytes + WriteBytes + SwapBytes + Copy + PartialEq + Debug,
+{
+    check_native::<B>();
+    for value in values::<B>() {
+        let mut ne = vec![];
+        ne.write_ne(value).unwrap();
+        let swapped: B = ne.as_slice().read_swapped().unwrap();
+        assert_eq!(
+            swapped,
+            value.to_opposite_endian(),
+            "read_swapped of {:?}: {}",
+            value,
+            type_name::<B>()
+        );
+
+        let mut buf = vec![];
+        buf.write_swapped(value).unwrap();
+        ne.reverse();
+        assert_eq!(buf, ne, "write_swapped of {:?}", value);
+        assert_eq!(buf.as_slice().read_swapped::<B>().unwrap(), value);
+        assert_eq!(value.to_opposite_endian().to_opposite_endian(), value);
+    }
+}
+
+macro_rules! check_each {
+    ($check:ident: $($T:ty),* $(,)?) => {
+        $($check::<$T>();)*
+    };
+}
+
+macro_rules! check_each_swapped {
+    ($($T:ty),*) => {
+        check_each!(check_swapped: $($T),*)
+    };
+}
+
+#[test]
+fn swapped_ints() {
+    int_types!(check_each_swapped);
+}
+
+/// The other `ReadBytes` types, which each have an impl of their own.
+#[test]
+fn native_others() {
+    check_each! {
+        check_native:
+        [u8; 3],
+        UInt<5>,
+        Int<3>,
+        Q8_8,
+        Q16_16,
+        Q2_14,
+        MacAddr,
+        MsGuid,
+    }
+}
+
+/// The epoch types, which saturate values out of `chrono`'s range.
+#[cfg(feature = "chrono")]
+#[test]
+fn native_epochs() {
+    use omnom::{EpochMillis, EpochNanos, EpochSeconds};
+
+    check_each!(check_native: EpochSeconds, EpochMillis, EpochNanos);
+}
+
+/// Floats aren't `ReadBytes`, but swap their bits without touching them,
+/// NaN payloads included.
+#[test]
+fn swapped_floats() {
+    for bits in [0x3f80_0000, 0x7fc0_0001, 0x8000_0001] {
+        let value = f32::from_bits(bits);
+        assert_eq!(value.to_opposite_endian().to_bits(), bits.swap_bytes());
+        assert_eq!(
+            value.to_opposite_endian().to_opposite_endian().to_bits(),
+            bits
+        );
+    }
+    for bits in [
+        0x3ff0_0000_0000_0000,
+        0x7ff8_0000_0000_0001,
+        0x0000_0000_0000_0001,
+    ] {
+        let value = f64::from_bits(bits);
+        assert_eq!(value.to_opposite_endian().to_bits(), bits.swap_bytes());
+        assert_eq!(
+            value.to_opposite_endian().to_opposite_endian().to_bits(),
+            bits
+        );
+    }
+}
@@ -0,0 +1,541 @@
+//! `BufReadExt` methods on readers limited with `Read::take`.
+//!
+//! The limit must read as the end of the stream, and no method may consume
+//! more than the limit allows, which would move the underlying reader past
+//! it.
+
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{BoolStyle, CopyEnd, LineResult, MacAddr, SkipOutcome, StopReason, Unread};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Take};
+use std::net::Ipv4Addr;
+use std::ops::ControlFlow;
+use std::sync::atomic::AtomicBool;
+
+/// `input` limited to `limit` bytes, over a buffer of 3 bytes.
+fn take(input: &[u8], limit: u64) -> Take<BufReader<&[u8]>> {
+    BufReader::with_capacity(3, input).take(limit)
+}
+
+/// Returns the bytes of the underlying reader after the limited reader.
+fn rest(reader: Take<BufReader<&[u8]>>) -> Vec<u8> {
+    let mut rest = vec![];
+    reader.into_inner().read_to_end(&mut rest).unwrap();
+    rest
+}
+
+#[test]
+fn read_while() {
+    // The limit falls in the middle of the token.
+    let mut reader = take(b"lorem ipsum", 3);
+    let mut buf = vec![];
+    let res = reader.read_while_peek(&mut buf, |b| b != b' ').unwrap();
+    assert_eq!(res, (3, None));
+    assert_eq!(buf, b"lor");
+    assert_eq!(rest(reader), b"em ipsum");
+
+    let mut reader = take(b"lorem ipsum", 8);
+    let mut buf = vec![];
+    assert_eq!(reader.read_while(&mut buf, |b| b != b' ').unwrap(), 5);
+    assert_eq!(reader.limit(), 3);
+    assert_eq!(reader.read_remaining(&mut buf).unwrap(), 3);
+    assert_eq!(buf, b"lorem ip");
+    assert_eq!(rest(reader), b"sum");
+
+    let mut reader = take(b"lorem", 4);
+    let mut buf = vec![];
+    let read = reader
+        .read_while_chunk(&mut buf, |chunk| chunk.len())
+        .unwrap();
+    assert_eq!(read, 4);
+    assert_eq!(rest(reader), b"m");
+}
+
+#[test]
+fn read_while_with_capacity() {
+    let mut reader = take(b"lorem ipsum", 3);
+    let mut buf = vec![];
+    let read = reader
+        .read_while_with_capacity(&mut buf, 16, |b| b != b' ')
+        .unwrap();
+    assert_eq!(read, 3);
+    assert_eq!(buf, b"lor");
+    assert!(buf.capacity() >= 16);
+    assert_eq!(rest(reader), b"em ipsum");
+}
+
+#[test]
+fn capped() {
+    let mut reader = take(b"lorem ipsum", 4);
+    let mut buf = vec![];
+    let res = reader.read_while_capped(&mut buf, |b| b != b' ').unwrap();
+    assert_eq!(res, (4, StopReason::Eof));
+
+    // A delimiter right after the limit isn't found.
+    let mut reader = take(b"lorem ipsum", 5);
+    let mut buf = vec![];
+    let res = reader.read_until_capped(b' ', &mut buf).unwrap();
+    assert_eq!(res, (5, StopReason::Eof));
+    assert_eq!(rest(reader), b" ipsum");
+
+    // A delimiter at the end of the limit is found.
+    let mut reader = take(b"lorem ipsum", 6);
+    let mut buf = vec![];
+    let res = reader.read_until_capped(b' ', &mut buf).unwrap();
+    assert_eq!(res, (6, StopReason::Delimiter));
+    assert_eq!(reader.limit(), 0);
+    assert_eq!(rest(reader), b"ipsum");
+}
+
+#[test]
+fn skip() {
+    let mut reader = take(b"lorem ipsum", 7);
+    reader.skip(2).unwrap();
+    assert_eq!(reader.limit(), 5);
+    reader.skip(10).unwrap();
+    assert_eq!(reader.limit(), 0);
+    assert_eq!(rest(reader), b"psum");
+
+    let mut reader = take(b"lorem", 0);
+    reader.skip(3).unwrap();
+    assert_eq!(rest(reader), b"lorem");
+}
+
+#[test]
+fn skip_while() {
+    let mut reader = take(b"     x", 4);
+    assert_eq!(reader.skip_while(|b| b == b' ').unwrap(), 4);
+    assert_eq!(rest(reader), b" x");
+
+    let mut reader = take(b"     x", 6);
+    assert_eq!(
+        reader.skip_while_peek(|b| b == b' ').unwrap(),
+        (5, Some(b'x'))
+    );
+    assert_eq!(reader.limit(), 1);
+
+    let mut reader = take(b"         x", 7);
+    let res = reader.skip_while_limited(3, |b| b == b' ').unwrap();
+    assert_eq!(res, SkipOutcome::LimitReached(3));
+    let res = reader.skip_while_limited(10, |b| b == b' ').unwrap();
+    assert_eq!(res, SkipOutcome::Eof(4));
+    assert_eq!(rest(reader), b"  x");
+
+    let mut reader = take(b"     x", 4);
+    let skipped = reader.skip_while_chunk(|chunk| chunk.len()).unwrap();
+    assert_eq!(skipped, 4);
+}
+
+#[test]
+fn skip_until() {
+    let mut reader = take(b"lorem-ipsum-dolor", 6);
+    assert_eq!(BufReadExt::skip_until(&mut reader, b'-').unwrap(), 6);
+    assert_eq!(BufReadExt::skip_until(&mut reader, b'-').unwrap(), 0);
+    assert_eq!(rest(reader), b"ipsum-dolor");
+
+    let mut reader = take(b"lorem-ipsum-dolor", 5);
+    assert_eq!(BufReadExt::skip_until(&mut reader, b'-').unwrap(), 5);
+    assert_eq!(rest(reader), b"-ipsum-dolor");
+
+    let mut reader = take(b"lorem-ipsum-dolor", 14);
+    assert_eq!(reader.skip_until_nth(b'-', 3).unwrap(), (14, 2));
+    assert_eq!(rest(reader), b"lor");
+
+    let mut reader = take(b"a,b;c", 3);
+    assert_eq!(reader.skip_until_any(b";").unwrap(), (3, None));
+    assert_eq!(rest(reader), b";c");
+}
+
+#[test]
+fn unicode_whitespace() {
+    let mut reader = take("\u{a0} \u{3000}x".as_bytes(), 3);
+    assert_eq!(reader.skip_unicode_whitespace().unwrap(), 3);
+    assert_eq!(rest(reader), "\u{3000}x".as_bytes());
+}
+
+#[test]
+fn byte_sets() {
+    let mut reader = take(b"aaab", 2);
+    let mut buf = vec![];
+    assert_eq!(reader.read_any_of(b"a", &mut buf).unwrap(), 2);
+    assert_eq!(reader.skip_any_of(b"a").unwrap(), 0);
+
+    let mut reader = take(b"k=v;x", 4);
+    let mut buf = vec![];
+    let res = reader.read_until_any(b";", &mut buf).unwrap();
+    assert_eq!(res, (4, Some(b';')));
+    assert_eq!(rest(reader), b"x");
+}
+
+#[test]
+fn until_nth() {
+    let mut reader = take(b"a\tb\tc\td", 4);
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b'\t', 2, &mut buf).unwrap(), (4, 2));
+    assert_eq!(buf, b"a\tb\t");
+
+    let mut reader = take(b"a\tb\tc\td", 3);
+    let mut buf = vec![];
+    assert_eq!(reader.read_until_nth(b'\t', 2, &mut buf).unwrap(), (3, 1));
+    assert_eq!(rest(reader), b"\tc\td");
+}
+
+#[test]
+fn lines() {
+    let mut reader = take(b"one\ntwo\n", 6);
+    let mut buf = vec![];
+    assert_eq!(
+        reader.read_line_max(&mut buf, 10).unwrap(),
+        LineResult::Line(4)
+    );
+    assert_eq!(reader.skip_line().unwrap(), 2);
+    assert_eq!(rest(reader), b"o\n");
+
+    // The newline at the end of the limit ends the line.
+    let mut reader = take(b"one\ntwo\n", 4);
+    let mut buf = vec![];
+    assert_eq!(reader.read_unfolded_line(&mut buf).unwrap(), 4);
+    assert_eq!(buf, b"one");
+    assert_eq!(rest(reader), b"two\n");
+
+    // The limit ends the last line in the middle.
+    let mut reader = take(b"a\nbb\ncc\nd", 6);
+    let mut lines = vec![];
+    let count = reader
+        .for_each_line(|line| {
+            lines.push(line.to_vec());
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(lines, [&b"a"[..], b"bb", b"c"]);
+    assert_eq!(rest(reader), b"c\nd");
+}
+
+#[test]
+fn records() {
+    let mut reader = take(b"a\\,b,c", 5);
+    let mut buf = vec![];
+    assert_eq!(
+        reader.read_record(b',', b'\\', &mut buf).unwrap(),
+        (5, true)
+    );
+    assert_eq!(buf, b"a,b");
+    assert_eq!(rest(reader), b"c");
+
+    let mut reader = take(b"(a(b))c", 5);
+    let err = reader.read_balanced(b'(', b')', &mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(rest(reader), b")c");
+
+    let mut reader = take(br#"")"(a))c"#, 6);
+    let mut buf = vec![];
+    let err = reader
+        .read_balanced_quoted(b'(', b')', &mut buf)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(rest(reader), b")c");
+
+    let mut reader = take(b"a%20b&c", 6);
+    let mut out = vec![];
+    let res = reader
+        .read_percent_decoded_until(b"&", &mut out, false)
+        .unwrap();
+    assert_eq!(res, (3, Some(b'&')));
+    assert_eq!(out, b"a b");
+    assert_eq!(rest(reader), b"c");
+}
+
+#[test]
+fn prefixes() {
+    let mut reader = take(b"GET /", 3);
+    assert!(reader.consume_prefix(b"GET").unwrap());
+    assert_eq!(rest(reader), b" /");
+
+    // The limit ends the stream in the middle of the prefix.
+    let mut reader = take(b"GET /", 3);
+    let err = reader.consume_prefix(b"GET ").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(rest(reader), b" /");
+
+    let mut reader = take(b"GET /", 2);
+    assert_eq!(
+        reader.compare_buffered(b"GET").unwrap(),
+        std::cmp::Ordering::Less
+    );
+    assert!(reader.fill_exact_buffered(&mut [0; 3]).is_err());
+    let mut buf = [0; 2];
+    reader.fill_exact_buffered(&mut buf).unwrap();
+    assert_eq!(&buf, b"GE");
+    assert_eq!(rest(reader), b"GET /");
+}
+
+#[test]
+fn copies() {
+    let mut reader = take(b"lorem\nipsum", 4);
+    let mut out = vec![];
+    assert_eq!(reader.copy_until(b'\n', &mut out).unwrap(), 4);
+    assert_eq!(rest(reader), b"m\nipsum");
+
+    let mut reader = take(b"lorem\nipsum", 8);
+    let mut out = vec![];
+    reader.copy_exact(5, &mut out).unwrap();
+    assert_eq!(out, b"lorem");
+    let err = reader.copy_exact(5, &mut out).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(rest(reader), b"sum");
+
+    let mut reader = take(b"lorem ipsum", 7);
+    assert_eq!(reader.skip_to_end().unwrap(), 7);
+    assert_eq!(rest(reader), b"psum");
+
+    let mut reader = take(b"a\r\nb\r\n", 5);
+    let mut out = vec![];
+    let res = reader.copy_replace(b"\r\n", b"\n", &mut out).unwrap();
+    assert_eq!(res, (4, 1));
+    assert_eq!(out, b"a\nb\r");
+    assert_eq!(rest(reader), b"\n");
+}
+
+#[test]
+fn copies_with_progress() {
+    let mut reader = take(b"lorem\nipsum", 4);
+    let mut out = vec![];
+    let mut last = 0;
+    let copied = reader
+        .copy_until_with_progress(b'\n', &mut out, |n| last = n)
+        .unwrap();
+    assert_eq!((copied, last), (4, 4));
+    assert_eq!(rest(reader), b"m\nipsum");
+
+    let mut reader = take(b"lorem ipsum", 4);
+    let mut out = vec![];
+    let mut last = 0;
+    let err = reader
+        .copy_exact_with_progress(5, &mut out, |n| last = n)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(last, 4);
+    assert_eq!(rest(reader), b"m ipsum");
+
+    let mut reader = take(b"lorem ipsum", 7);
+    let mut last = 0;
+    assert_eq!(reader.skip_to_end_with_progress(|n| last = n).unwrap(), 7);
+    assert_eq!(last, 7);
+    assert_eq!(rest(reader), b"psum");
+}
+
+#[test]
+fn cancellable() {
+    let cancel = AtomicBool::new(false);
+    let mut reader = take(b"lorem\nipsum", 4);
+    let mut out = vec![];
+    let res = reader
+        .copy_until_cancellable(b'\n', &mut out, &cancel)
+        .unwrap();
+    assert_eq!(res, (4, CopyEnd::Eof));
+    assert_eq!(out, b"lore");
+    assert_eq!(rest(reader), b"m\nipsum");
+
+    let mut reader = take(b"lorem ipsum", 7);
+    let res = reader.skip_to_end_cancellable(&cancel).unwrap();
+    assert_eq!(res, (7, CopyEnd::Eof));
+    assert_eq!(rest(reader), b"psum");
+}
+
+#[test]
+fn body() {
+    let mut reader = take(b"hello world", 8);
+    let mut body = reader.body_exact(5);
+    let mut buf = vec![];
+    body.read_to_end(&mut buf).unwrap();
+    body.finish().unwrap();
+    assert_eq!(buf, b"hello");
+
+    // The limit ends the stream in the middle of the body.
+    let mut body = reader.body_exact(5);
+    let err = body.read_to_end(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert!(body.finish().is_err());
+    assert_eq!(buf, b"hello wo");
+    assert_eq!(rest(reader), b"rld");
+}
+
+#[test]
+fn drain_buffered() {
+    let mut reader = take(b"lorem", 4);
+    reader.fill_buf().unwrap();
+    let mut out = vec![];
+    assert_eq!(reader.drain_buffered(&mut out), 3);
+    assert_eq!(out, b"lor");
+    assert_eq!(reader.limit(), 1);
+    assert_eq!(rest(reader), b"em");
+}
+
+#[test]
+fn counts() {
+    let mut reader = take(b"a,b,c,d", 4);
+    assert_eq!(reader.count_byte(b',').unwrap(), 2);
+    assert_eq!(rest(reader), b"c,d");
+
+    // The pattern straddles the limit.
+    let mut reader = take(b"abab", 3);
+    assert_eq!(reader.count_pattern(b"ab").unwrap(), 1);
+    assert_eq!(rest(reader), b"b");
+
+    let mut reader = take(b"1a2b3c", 4);
+    let digits = reader.count_matching(|b| b.is_ascii_digit()).unwrap();
+    assert_eq!(digits, 2);
+    assert_eq!(rest(reader), b"3c");
+}
+
+#[test]
+fn length_prefixed() {
+    let mut reader = take(b"\x03abcdef", 3);
+    let err = reader.read_delimited_message(&mut vec![], 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(rest(reader), b"cdef");
+
+    let mut reader = take(b"\x03www\x07example\x00", 4);
+    let err = reader.read_label_sequence(&mut vec![], b'.').unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(rest(reader), b"\x07example\x00");
+}
+
+#[test]
+fn numbers() {
+    let mut reader = take(b"12345", 3);
+    assert_eq!(reader.read_ascii_int::<u32>().unwrap(), 123);
+    assert_eq!(rest(reader), b"45");
+
+    let mut reader = take(b"8080", 3);
+    assert_eq!(reader.read_ascii_int_in(1..=65535_u32).unwrap(), 808);
+    assert_eq!(rest(reader), b"0");
+
+    let mut reader = take(b"-0xffff", 5);
+    assert_eq!(reader.read_number_radix().unwrap(), -0xff);
+    assert_eq!(rest(reader), b"ff");
+
+    let mut reader = take(b"0b1011", 4);
+    assert_eq!(reader.read_unsigned_radix().unwrap(), 0b10);
+    assert_eq!(rest(reader), b"11");
+}
+
+#[test]
+fn ascii_values() {
+    let mut reader = take(b"offline", 3);
+    assert!(!reader.read_bool_ascii(BoolStyle::ALL).unwrap());
+    assert_eq!(rest(reader), b"line");
+
+    let mut reader = take(b"10.0.0.12", 8);
+    assert_eq!(
+        reader.read_ipv4_ascii().unwrap(),
+        Ipv4Addr::new(10, 0, 0, 1)
+    );
+    assert_eq!(rest(reader), b"2");
+
+    let mut reader = take(b"02-42-ac-11-00-02 eth0", 17);
+    let mac = reader.read_mac_ascii().unwrap();
+    assert_eq!(mac, MacAddr([0x02, 0x42, 0xac, 0x11, 0x00, 0x02]));
+    assert_eq!(rest(reader), b" eth0");
+
+    let mut reader = take(b"02-42-ac-11-00-02", 16);
+    assert!(reader.read_mac_ascii().is_err());
+    assert_eq!(rest(reader), b"2");
+
+    let mut reader = take(b"2024-05-01T12:34:56Z INFO", 20);
+    let ts = reader.read_rfc3339().unwrap();
+    assert_eq!(ts.secs_since_epoch, 1_714_566_896);
+    assert_eq!(rest(reader), b" INFO");
+
+    let mut reader = take(b"2024-05-01T12:34:56Z INFO", 19);
+    assert!(reader.read_rfc3339().is_err());
+    assert_eq!(rest(reader), b"Z INFO");
+}
+
+#[test]
+fn chunks() {
+    let mut reader = take(b"abcdefg", 5);
+    let mut chunks = vec![];
+    let remainder = reader
+        .for_each_chunk(2, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(chunks, [b"ab", b"cd"]);
+    assert_eq!(remainder, b"e");
+    assert_eq!(rest(reader), b"fg");
+
+    let mut reader = take(b"abcdefg", 5);
+    let mut chunks = (&mut reader).chunks_exact(2);
+    assert_eq!(chunks.next().unwrap().unwrap(), b"ab");
+    assert_eq!(chunks.next().unwrap().unwrap(), b"cd");
+    assert!(chunks.next().is_none());
+    assert_eq!(chunks.remainder(), b"e");
+    assert_eq!(rest(reader), b"fg");
+}
+
+#[test]
+fn peeks() {
+    let mut reader = take(b"ab", 1);
+    assert_eq!(reader.peek_byte().unwrap(), Some(b'a'));
+    reader.consume(1);
+    assert_eq!(reader.peek_byte().unwrap(), None);
+    assert_eq!(rest(reader), b"b");
+}
+
+#[test]
+fn buffer_ahead() {
+    // The limit stops the lookahead, while the inner reader goes on.
+    let inner = Unread::new(BufReader::with_capacity(3, &b"lorem ipsum"[..]));
+    let mut reader = inner.take(8);
+    let mut buf = vec![];
+    assert_eq!(reader.fill_while(&mut buf, |_| true).unwrap(), 8);
+    assert_eq!(buf, b"lorem ip");
+    assert_eq!(reader.limit(), 8);
+
+    let mut buf = vec![];
+    assert_eq!(reader.fill_until(b' ', &mut buf).unwrap(), (6, true));
+    assert_eq!(buf, b"lorem ");
+    // A delimiter right after the limit isn't found.
+    let mut buf = vec![];
+    assert_eq!(reader.fill_until(b's', &mut buf).unwrap(), (8, false));
+    assert_eq!(buf, b"lorem ip");
+
+    let mut read = vec![];
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, b"lorem ip");
+    let mut rest = vec![];
+    reader.into_inner().read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"sum");
+
+    // The value straddles the inner reader's buffer, and then the limit.
+    let inner = Unread::new(BufReader::with_capacity(3, &b"\x01\x02\x03\x04\x05"[..]));
+    let mut reader = inner.take(4);
+    assert_eq!(reader.fill_be::<u32>().unwrap(), 0x0102_0304);
+    assert_eq!(reader.fill_le::<u16>().unwrap(), 0x0201);
+    assert_eq!(reader.fill_ne::<u16>().unwrap(), u16::from_ne_bytes([1, 2]));
+    assert_eq!(reader.peek(4).unwrap(), b"\x01\x02\x03\x04");
+    reader.consume(1);
+    let err = reader.fill_be::<u32>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(reader.limit(), 3);
+    assert_eq!(reader.into_inner().read_be::<u32>().unwrap(), 0x0203_0405);
+}
+
+#[test]
+fn zero_limit() {
+    let mut reader = take(b"lorem", 0);
+    let mut buf = vec![];
+    assert_eq!(reader.read_while(&mut buf, |_| true).unwrap(), 0);
+    assert_eq!(BufReadExt::skip_until(&mut reader, b'm').unwrap(), 0);
+    assert_eq!(reader.skip_while(|_| true).unwrap(), 0);
+    let res = reader.read_until_capped(b'e', &mut buf).unwrap();
+    assert_eq!(res, (0, StopReason::Eof));
+    assert!(!reader.consume_prefix(b"l").unwrap());
+    assert_eq!(reader.read_delimited_message(&mut buf, 10).unwrap(), None);
+    assert!(buf.is_empty());
+    assert_eq!(rest(reader), b"lorem");
+}
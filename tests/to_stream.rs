@@ -0,0 +1,109 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{FromStream, Int, MacAddr, ToStream};
+use std::fmt::Debug;
+use std::io::{self, BufRead, Cursor, ErrorKind, Write};
+
+/// Encode `value`, decode it again, and check that it round-trips. Returns
+/// the amount of bytes written.
+fn round_trip<T>(value: &T) -> usize
+where
+    T: FromStream + ToStream + PartialEq + Debug,
+{
+    let mut out = vec![];
+    let written = out.write_struct(value).unwrap();
+    assert_eq!(written, out.len());
+
+    let mut reader = Cursor::new(&out);
+    let decoded: T = reader.read_struct().unwrap();
+    assert_eq!(&decoded, value);
+    assert_eq!(reader.position() as usize, out.len());
+    written
+}
+
+#[derive(Debug, PartialEq)]
+struct Entry {
+    id: u32,
+    addr: Option<MacAddr>,
+}
+
+impl FromStream for Entry {
+    fn from_stream<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Self> {
+        Ok(Entry {
+            id: reader.read_struct()?,
+            addr: reader.read_struct()?,
+        })
+    }
+}
+
+impl ToStream for Entry {
+    fn to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        Ok(writer.write_struct(&self.id)? + writer.write_struct(&self.addr)?)
+    }
+}
+
+#[test]
+fn nested_composites_round_trip() {
+    assert_eq!(round_trip(&0xdead_beef_u32), 4);
+    assert_eq!(round_trip(&Int::<3>::try_new(-5).unwrap()), 3);
+    assert_eq!(round_trip(&None::<u64>), 1);
+    assert_eq!(round_trip(&Some(Some(7_i16))), 4);
+    assert_eq!(round_trip(&(*b"abc", (1_u8, Some((2_u16, None::<u8>))))), 8);
+
+    let entry = Entry {
+        id: 9,
+        addr: Some(MacAddr([1, 2, 3, 4, 5, 6])),
+    };
+    assert_eq!(round_trip(&entry), 11);
+    let tree = (
+        entry,
+        Some(Entry { id: 1, addr: None }),
+        (0_u8, 1_u16, 2_u32, 3_u64, 4_i8, 5_i16, 6_i32, 7_i64),
+    );
+    assert_eq!(round_trip(&tree), 11 + 6 + 30);
+}
+
+#[test]
+fn byte_counts_sum_across_nesting() {
+    let mut out = vec![];
+    let value = ((1_u8, 2_u16), Some((3_u32, [4_u8; 5])), None::<u128>);
+    let written = out.write_struct(&value).unwrap();
+    assert_eq!(written, (1 + 2) + (1 + 4 + 5) + 1);
+    assert_eq!(written, out.len());
+}
+
+/// A writer that accepts `limit` bytes and fails after that.
+struct FailAfter {
+    written: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for FailAfter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written.len() == self.limit {
+            return Err(io::Error::new(ErrorKind::BrokenPipe, "closed"));
+        }
+        let len = buf.len().min(self.limit - self.written.len());
+        self.written.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn failing_writer_mid_struct() {
+    let mut writer = FailAfter {
+        written: vec![],
+        limit: 6,
+    };
+    let value = (1_u16, 2_u32, Some(3_u8));
+    let err = writer.write_struct(&value).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    // The fields before the failing write stay written, which can include
+    // part of a field.
+    assert_eq!(writer.written, [0, 1, 0, 0, 0, 2]);
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "tokio-codec")]
+
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use omnom::{FixedCodec, LengthDelimitedValueCodec};
+use std::io::ErrorKind;
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[tokio::test]
+async fn fixed_codec_round_trip() {
+    let (client, server) = tokio::io::duplex(64);
+    let mut sink = FramedWrite::new(client, FixedCodec::<u32>::new_le());
+    let stream = FramedRead::new(server, FixedCodec::<u32>::new_le());
+
+    for n in &[1, 0xdead_beef, u32::MAX] {
+        sink.send(*n).await.unwrap();
+    }
+    drop(sink);
+
+    let values: Vec<u32> = stream.map(|v| v.unwrap()).collect().await;
+    assert_eq!(values, vec![1, 0xdead_beef, u32::MAX]);
+}
+
+#[tokio::test]
+async fn length_delimited_round_trip() {
+    let (client, server) = tokio::io::duplex(64);
+    let mut sink = FramedWrite::new(client, LengthDelimitedValueCodec::<u16, i64>::new_be());
+    let mut stream = FramedRead::new(server, LengthDelimitedValueCodec::<u16, i64>::new_be());
+
+    sink.send(-12).await.unwrap();
+    sink.send(i64::MAX).await.unwrap();
+    drop(sink);
+
+    assert_eq!(stream.next().await.unwrap().unwrap(), -12);
+    assert_eq!(stream.next().await.unwrap().unwrap(), i64::MAX);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn fragmented_delivery() {
+    let (mut client, server) = tokio::io::duplex(64);
+    let mut stream = FramedRead::new(server, LengthDelimitedValueCodec::<u8, u32>::new_be());
+
+    let writer = tokio::spawn(async move {
+        for byte in &[4, 0, 0, 1, 2, 4, 0, 0, 0, 3] {
+            client.write_all(&[*byte]).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+
+    assert_eq!(stream.next().await.unwrap().unwrap(), 0x0102);
+    assert_eq!(stream.next().await.unwrap().unwrap(), 3);
+    writer.await.unwrap();
+    assert!(stream.next().await.is_none());
+}
+
+#[test]
+fn incomplete_frames_leave_buffer_untouched() {
+    let mut codec = LengthDelimitedValueCodec::<u16, u32>::new_be();
+    let mut buf = BytesMut::from(&[0, 4, 0, 0][..]);
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+    assert_eq!(&buf[..], &[0, 4, 0, 0]);
+
+    let mut buf = BytesMut::from(&[0][..]);
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+    assert_eq!(&buf[..], &[0]);
+
+    let mut codec = FixedCodec::<u64>::new_be();
+    let mut buf = BytesMut::from(&[1, 2, 3][..]);
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+    assert_eq!(&buf[..], &[1, 2, 3]);
+}
+
+#[test]
+fn back_to_back_frames() {
+    let mut codec = LengthDelimitedValueCodec::<u8, u16>::new_le();
+    let mut buf = BytesMut::new();
+    codec.encode(1, &mut buf).unwrap();
+    codec.encode(2, &mut buf).unwrap();
+    codec.encode(3, &mut buf).unwrap();
+    assert_eq!(&buf[..], &[2, 1, 0, 2, 2, 0, 2, 3, 0]);
+
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(1));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(2));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(3));
+    assert_eq!(codec.decode(&mut buf).unwrap(), None);
+}
+
+#[test]
+fn oversized_lengths_error() {
+    let mut codec = LengthDelimitedValueCodec::<u32, u8>::new_be();
+    codec.set_max_frame_length(16);
+    let mut buf = BytesMut::from(&[0, 0, 0, 17][..]);
+    let err = codec.decode(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut codec = LengthDelimitedValueCodec::<u8, u64>::new_be();
+    codec.set_max_frame_length(4);
+    let err = codec.encode(1, &mut BytesMut::new()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn mismatched_payload_length_errors() {
+    let mut codec = LengthDelimitedValueCodec::<u8, u16>::new_be();
+    let mut buf = BytesMut::from(&[3, 0, 1, 2][..]);
+    let err = codec.decode(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut buf = BytesMut::from(&[1, 0][..]);
+    let err = codec.decode(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "tracing")]
+
+use omnom::prelude::*;
+use omnom::{Traced, TracedWriter};
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// An event as the fields it recorded, formatted as `name=value`.
+#[derive(Default)]
+struct Fields(Vec<String>);
+
+/// A subscriber that records the events at or above `level`.
+#[derive(Clone)]
+struct Recorder {
+    level: Level,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    fn new(level: Level) -> Self {
+        Self {
+            level,
+            events: Arc::default(),
+        }
+    }
+
+    fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push(format!("{}={}", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.level.into())
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        assert_eq!(event.metadata().target(), "omnom::traced");
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(fields.0.join(" "));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/// Parse a length-prefixed string from `reader`.
+fn parse<R: BufRead>(reader: &mut R) -> String {
+    let len: u8 = reader.read_be().unwrap();
+    let mut buf = vec![0; usize::from(len)];
+    reader.read_exact(&mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn reader_events() {
+    let recorder = Recorder::new(Level::TRACE);
+    let input = &b"\x0bhello world\x00"[..];
+    let mut reader = Traced::new(BufReader::with_capacity(8, input))
+        .label("in")
+        .preview_len(4);
+    let parsed = tracing::subscriber::with_default(recorder.clone(), || parse(&mut reader));
+    assert_eq!(parsed, "hello world");
+
+    let events = recorder.events();
+    assert_eq!(
+        events[..4],
+        [
+            "message=fill_buf label=in len=8",
+            "message=consume label=in len=1 preview=0b |.|",
+            "message=fill_buf label=in len=7",
+            "message=consume label=in len=7 preview=68 65 6c 6c |hell|...",
+        ]
+    );
+    let consumed: Vec<_> = events
+        .iter()
+        .filter_map(|event| event.strip_prefix("message=consume label=in "))
+        .map(|fields| fields.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(consumed, ["len=1", "len=7", "len=4"]);
+}
+
+#[test]
+fn writer_events() {
+    let recorder = Recorder::new(Level::TRACE);
+    let mut writer = TracedWriter::new(vec![]);
+    tracing::subscriber::with_default(recorder.clone(), || {
+        writer.write_be(0x0102_u16).unwrap();
+        writer.write_all(b"ok\r\n").unwrap();
+    });
+    assert_eq!(writer.get_ref(), b"\x01\x02ok\r\n");
+    assert_eq!(
+        recorder.events(),
+        [
+            "message=write len=2 preview=01 02 |..|",
+            "message=write len=4 preview=6f 6b 0d 0a |ok..|",
+        ]
+    );
+}
+
+#[test]
+fn disabled_level() {
+    let recorder = Recorder::new(Level::DEBUG);
+    let mut reader = Traced::new(&b"\x02hi"[..]);
+    let mut writer = TracedWriter::new(vec![]);
+    tracing::subscriber::with_default(recorder.clone(), || {
+        assert_eq!(parse(&mut reader), "hi");
+        writer.write_all(b"hi").unwrap();
+    });
+    assert!(recorder.events().is_empty());
+}
+
+#[test]
+fn no_subscriber() {
+    let mut reader = Traced::new(&b"\x02hi"[..]).label("in");
+    assert_eq!(parse(&mut reader), "hi");
+    assert!(reader.into_inner().is_empty());
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{Int, UInt};
+use std::io::{Cursor, ErrorKind};
+
+macro_rules! check_uint_boundaries {
+    ($($bytes:literal),*) => { $({
+        let max = u64::MAX >> (64 - 8 * $bytes);
+        assert_eq!(UInt::<$bytes>::MAX.get(), max);
+        for &value in &[0, 1, 0x80, max / 2, max / 2 + 1, max - 1, max] {
+            let uint = UInt::<$bytes>::try_new(value).unwrap();
+            let be = uint.to_be_array();
+            let le = uint.to_le_array();
+            assert_eq!(&be[..], &value.to_be_bytes()[8 - $bytes..]);
+            assert_eq!(&le[..], &value.to_le_bytes()[..$bytes]);
+            assert_eq!(u64::from(UInt::<$bytes>::from_be_array(be)), value);
+            assert_eq!(u64::from(UInt::<$bytes>::from_le_array(le)), value);
+
+            // Cross-check with the runtime-width reads.
+            assert_eq!(Cursor::new(be).read_uint_be($bytes).unwrap(), value);
+            assert_eq!(Cursor::new(le).read_uint_le($bytes).unwrap(), value);
+        }
+    })* };
+}
+
+#[test]
+fn uint_boundaries() {
+    check_uint_boundaries!(5, 6, 7);
+}
+
+macro_rules! check_int_boundaries {
+    ($($bytes:literal),*) => { $({
+        let max = i64::MAX >> (64 - 8 * $bytes);
+        let min = -max - 1;
+        assert_eq!(Int::<$bytes>::MAX.get(), max);
+        assert_eq!(Int::<$bytes>::MIN.get(), min);
+        for &value in &[min, min + 1, -1, 0, 1, max - 1, max] {
+            let int = Int::<$bytes>::try_new(value).unwrap();
+            let be = int.to_be_array();
+            let le = int.to_le_array();
+            assert_eq!(&be[..], &value.to_be_bytes()[8 - $bytes..]);
+            assert_eq!(&le[..], &value.to_le_bytes()[..$bytes]);
+            assert_eq!(i64::from(Int::<$bytes>::from_be_array(be)), value);
+            assert_eq!(i64::from(Int::<$bytes>::from_le_array(le)), value);
+            assert_eq!(Cursor::new(be).read_int_be($bytes).unwrap(), value);
+            assert_eq!(Cursor::new(le).read_int_le($bytes).unwrap(), value);
+        }
+    })* };
+}
+
+#[test]
+fn int_boundaries() {
+    check_int_boundaries!(5, 6, 7);
+}
+
+#[test]
+fn try_new_range() {
+    assert!(UInt::<5>::try_new(1 << 40).is_none());
+    assert!(UInt::<6>::try_new(1 << 48).is_none());
+    assert!(UInt::<7>::try_new(1 << 56).is_none());
+    assert!(UInt::<8>::try_new(u64::MAX).is_some());
+    assert!(Int::<5>::try_new(1 << 39).is_none());
+    assert!(Int::<5>::try_new(-(1 << 39) - 1).is_none());
+    assert!(Int::<5>::try_new(-(1 << 39)).is_some());
+    assert!(Int::<8>::try_new(i64::MIN).is_some());
+}
+
+#[test]
+fn sign_extension() {
+    assert_eq!(
+        Int::<5>::from_be_array([0x80, 0, 0, 0, 0]).get(),
+        -(1 << 39)
+    );
+    assert_eq!(
+        Int::<5>::from_be_array([0x7f, 0xff, 0xff, 0xff, 0xff]).get(),
+        (1 << 39) - 1
+    );
+    assert_eq!(Int::<6>::from_le_array([0xff; 6]).get(), -1);
+    assert_eq!(
+        Int::<7>::from_le_array([0, 0, 0, 0, 0, 0, 0x80]).get(),
+        -(1 << 55)
+    );
+    assert_eq!(Int::<1>::from_be_array([0x80]).get(), -128);
+}
+
+#[test]
+fn stream_round_trip() {
+    let mut cursor = Cursor::new(vec![]);
+    let uint = UInt::<6>::try_new(0x0123_4567_89ab).unwrap();
+    let int = Int::<7>::try_new(-0x0012_3456_789a).unwrap();
+    assert_eq!(cursor.write_be(uint).unwrap(), 6);
+    assert_eq!(cursor.write_le(uint).unwrap(), 6);
+    assert_eq!(cursor.write_be(int).unwrap(), 7);
+    assert_eq!(cursor.write_le(int).unwrap(), 7);
+    assert_eq!(cursor.write_ne(int).unwrap(), 7);
+
+    cursor.set_position(0);
+    assert_eq!(cursor.read_be::<UInt<6>>().unwrap(), uint);
+    assert_eq!(cursor.read_le::<UInt<6>>().unwrap(), uint);
+    assert_eq!(cursor.read_be::<Int<7>>().unwrap(), int);
+    assert_eq!(cursor.read_le::<Int<7>>().unwrap(), int);
+    assert_eq!(cursor.read_ne::<Int<7>>().unwrap(), int);
+    assert_eq!(
+        cursor.read_be::<UInt<5>>().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn runtime_width_range() {
+    let mut cursor = Cursor::new([0; 16]);
+    for &nbytes in &[0, 9] {
+        let err = cursor.read_uint_be(nbytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        let err = cursor.read_int_le(nbytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+    assert_eq!(cursor.position(), 0);
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::{DataReady, ShortRead, Unread};
+use std::io::{BufRead, BufReader, Read};
+
+#[test]
+fn value_across_the_seam() {
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"\x03\x04\x05"[..]));
+    reader.unread(&[0x01, 0x02]);
+    assert_eq!(reader.fill_buf().unwrap(), [0x01, 0x02]);
+    assert_eq!(reader.read_be::<u32>().unwrap(), 0x0102_0304);
+    assert_eq!(reader.read_be::<u8>().unwrap(), 0x05);
+
+    // `BufReadExt` methods see one stream, too.
+    let mut reader = Unread::new(BufReader::new(&b"lo world"[..]));
+    reader.unread(b"hel");
+    let mut word = vec![];
+    reader.read_while(&mut word, |b| b != b' ').unwrap();
+    assert_eq!(word, b"hello");
+}
+
+#[test]
+fn multiple_unreads() {
+    let mut reader = Unread::new(&b"4"[..]);
+    reader.unread(b"1");
+    reader.unread(b"23");
+    let mut first = [0; 2];
+    reader.read_exact(&mut first).unwrap();
+    assert_eq!(&first, b"12");
+
+    // Pushed after the unread `3`, before the inner reader.
+    reader.unread(b"x");
+    assert_eq!(reader.pushed_back(), b"3x");
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "3x4");
+}
+
+#[test]
+fn empty_unread() {
+    let mut reader = Unread::new(BufReader::new(&b"abc"[..]));
+    reader.unread(b"");
+    assert!(reader.pushed_back().is_empty());
+    assert!(!reader.has_data_ready());
+    assert_eq!(reader.fill_buf().unwrap(), b"abc");
+    assert!(reader.has_data_ready());
+}
+
+#[test]
+fn peek_cursor_across_chunks() {
+    let mut reader = Unread::new(BufReader::with_capacity(3, &b"identifier = 1"[..]));
+    let mut cursor = reader.peek_cursor();
+    let mut token = vec![];
+    // Resuming picks up where the last peek stopped.
+    cursor.fill_while(&mut token, |b| b != b'f').unwrap();
+    assert_eq!(cursor.next_byte().unwrap(), Some(b'f'));
+    cursor
+        .fill_while(&mut token, |b| b.is_ascii_alphabetic())
+        .unwrap();
+    assert_eq!(token, b"identiier");
+    assert_eq!(cursor.position(), 10);
+    let mut rest = [0; 4];
+    cursor.fill_exact(&mut rest).unwrap();
+    assert_eq!(&rest, b" = 1");
+    assert_eq!(cursor.next_byte().unwrap(), None);
+    assert_eq!(
+        cursor.fill_exact(&mut [0]).unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+    assert_eq!(cursor.position(), 14);
+}
+
+#[test]
+fn peek_cursor_commit() {
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"abcdef"[..]));
+    reader.unread(b"01");
+    let mut cursor = reader.peek_cursor();
+    let mut buf = [0; 5];
+    cursor.fill_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"01abc");
+    assert_eq!(cursor.commit(), 5);
+
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"def");
+}
+
+#[test]
+fn peek_cursor_drop_consumes_nothing() {
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"abcdef"[..]));
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'a');
+    {
+        let mut cursor = reader.peek_cursor();
+        cursor.fill_while(&mut vec![], |_| true).unwrap();
+        assert_eq!(cursor.position(), 5);
+    }
+
+    // Peeking again starts from the reader's position.
+    assert_eq!(reader.peek_cursor().next_byte().unwrap(), Some(b'b'));
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"bcdef");
+}
+
+#[test]
+fn peek_cursor_fill_until() {
+    let input = &b"first line\nsecond line\nrest"[..];
+    let mut reader = Unread::new(BufReader::with_capacity(3, input));
+
+    let mut peeked = vec![];
+    let res = reader.peek_cursor().fill_until(b'\n', &mut peeked).unwrap();
+    assert_eq!(res, (11, true));
+    let mut read = vec![];
+    reader.read_until(b'\n', &mut read).unwrap();
+    assert_eq!(read, peeked);
+
+    // Peek two lines ahead, then read them back one at a time.
+    let mut peeked = vec![];
+    let mut cursor = reader.peek_cursor();
+    assert_eq!(cursor.fill_until(b'\n', &mut peeked).unwrap(), (12, true));
+    assert_eq!(cursor.fill_until(b'\n', &mut peeked).unwrap(), (4, false));
+    assert_eq!(cursor.position(), 16);
+    assert_eq!(peeked, b"second line\nrest");
+
+    let mut read = vec![];
+    reader.read_until(b'\n', &mut read).unwrap();
+    reader.read_until(b'\n', &mut read).unwrap();
+    assert_eq!(read, peeked);
+}
+
+#[test]
+fn peek_past_buffer_capacity() {
+    let mut reader = Unread::new(BufReader::with_capacity(3, &b"RIFF\x04\0\0\0WAVE"[..]));
+    assert_eq!(reader.peek(4).unwrap(), b"RIFF");
+    assert_eq!(reader.peek(12).unwrap(), b"RIFF\x04\0\0\0WAVE");
+    assert_eq!(reader.read_be::<[u8; 4]>().unwrap(), *b"RIFF");
+
+    // Peeking after a read starts at the next unread byte.
+    assert_eq!(reader.peek(4).unwrap(), b"\x04\0\0\0");
+    assert_eq!(reader.read_le::<u32>().unwrap(), 4);
+    assert_eq!(reader.peek(0).unwrap(), b"");
+}
+
+#[test]
+fn peek_past_eof() {
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"abcde"[..]));
+    reader.unread(b"x");
+    let err = reader.peek(7).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    let short = err.get_ref().unwrap().downcast_ref::<ShortRead>().unwrap();
+    assert_eq!((short.needed(), short.available()), (7, 6));
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "xabcde");
+}
+
+#[test]
+fn unread_after_peek() {
+    // The pushed-back byte goes in front of the bytes that were peeked at.
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"abcdef"[..]));
+    assert_eq!(reader.peek(4).unwrap(), b"abcd");
+    assert_eq!(reader.read_be::<u8>().unwrap(), b'a');
+    reader.unread(b"a");
+    let mut all = vec![];
+    reader.read_to_end(&mut all).unwrap();
+    assert_eq!(all, b"abcdef");
+
+    let mut reader = Unread::new(BufReader::with_capacity(2, &b"cdef"[..]));
+    reader.unread(b"a");
+    let mut peeked = vec![];
+    reader
+        .peek_cursor()
+        .fill_while(&mut peeked, |b| b != b'e')
+        .unwrap();
+    assert_eq!(peeked, b"acd");
+    reader.unread(b"b");
+    let mut all = vec![];
+    reader.read_to_end(&mut all).unwrap();
+    assert_eq!(all, b"abcdef");
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+
+use omnom::Utf8Validator;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read};
+
+fn validate(input: &[u8], capacity: usize) -> (Vec<u8>, io::Result<usize>) {
+    let mut reader = Utf8Validator::new(BufReader::with_capacity(capacity, input));
+    let mut out = vec![];
+    let res = reader.read_to_end(&mut out);
+    (out, res)
+}
+
+fn error(input: &[u8], capacity: usize) -> String {
+    let (_, res) = validate(input, capacity);
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    err.to_string()
+}
+
+#[test]
+fn split_at_every_boundary() {
+    let text = "a ñ € 𝄞 z".as_bytes();
+    for capacity in 1..=text.len() {
+        let (out, res) = validate(text, capacity);
+        assert_eq!(res.unwrap(), text.len());
+        assert_eq!(out, text);
+    }
+
+    // A partial character isn't exposed before it's complete.
+    let mut reader = Utf8Validator::new(BufReader::with_capacity(2, "a€".as_bytes()));
+    assert_eq!(reader.fill_buf().unwrap(), b"a");
+    reader.consume(1);
+    assert_eq!(reader.fill_buf().unwrap(), "€".as_bytes());
+}
+
+#[test]
+fn invalid_continuation() {
+    for capacity in [1, 2, 64] {
+        assert_eq!(
+            error(b"ab\xe2\x28\xa1", capacity),
+            "invalid UTF-8 at byte 2"
+        );
+        assert_eq!(error(b"\x80", capacity), "invalid UTF-8 at byte 0");
+    }
+    let (out, _) = validate(b"ab\xe2\x28\xa1", 1);
+    assert_eq!(out, b"ab");
+}
+
+#[test]
+fn overlong_encoding() {
+    // `/` encoded in two bytes instead of one.
+    assert_eq!(error(b"x\xc0\xaf", 64), "invalid UTF-8 at byte 1");
+    // A surrogate isn't a valid scalar value either.
+    assert_eq!(error(b"\xed\xa0\x80", 1), "invalid UTF-8 at byte 0");
+}
+
+#[test]
+fn truncated_at_eof() {
+    for capacity in [1, 2, 64] {
+        assert_eq!(
+            error("abc€".as_bytes().split_last().unwrap().1, capacity),
+            "incomplete UTF-8 character at byte 3"
+        );
+    }
+}
+
+#[test]
+fn offset_accuracy() {
+    let mut input = "é".repeat(1000).into_bytes();
+    input.push(0xff);
+    for capacity in [1, 3, 7, 4096] {
+        assert_eq!(error(&input, capacity), "invalid UTF-8 at byte 2000");
+        let (out, _) = validate(&input, capacity);
+        assert_eq!(out.len(), 2000);
+    }
+
+    // The error sticks.
+    let mut reader = Utf8Validator::new(&b"\xff"[..]);
+    assert!(reader.fill_buf().is_err());
+    assert!(reader.fill_buf().is_err());
+}
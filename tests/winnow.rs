@@ -0,0 +1,83 @@
+#![cfg(feature = "winnow")]
+
+use omnom::{parse_partial, ParseError};
+use std::io::{BufRead, BufReader, ErrorKind};
+use winnow::binary::{be_u16, length_take};
+use winnow::error::{ContextError, ErrMode, StrContext};
+use winnow::stream::Partial;
+use winnow::token::literal;
+use winnow::Parser;
+
+type Input<'a> = Partial<&'a [u8]>;
+
+/// A record: the tag `R`, a big endian length and that many bytes of data.
+fn record(input: &mut Input<'_>) -> Result<Vec<u8>, ErrMode<ContextError>> {
+    literal(b"R")
+        .context(StrContext::Label("record tag"))
+        .parse_next(input)?;
+    let data = length_take(be_u16).parse_next(input)?;
+    Ok(data.to_vec())
+}
+
+#[test]
+fn multi_chunk_record() {
+    let mut input = vec![b'R', 0, 20];
+    input.extend(0..20);
+    input.push(b'!');
+    let mut reader = BufReader::with_capacity(4, &input[..]);
+
+    let data = parse_partial(&mut reader, record).unwrap();
+    assert_eq!(data, (0..20).collect::<Vec<u8>>());
+    assert_eq!(reader.fill_buf().unwrap(), b"!");
+}
+
+#[test]
+fn error_offsets() {
+    let mut input = vec![];
+    input.extend(&[b'R', 0, 3, 1, 2, 3]);
+    input.extend(&[b'X', 0, 0]);
+    let mut reader = BufReader::with_capacity(4, &input[..]);
+
+    // Offsets count from where each call started.
+    parse_partial(&mut reader, record).unwrap();
+    let err = parse_partial(&mut reader, record).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let inner = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    assert_eq!(inner.offset(), Some(0));
+    assert!(inner.to_string().starts_with("unexpected bytes at byte 0"));
+
+    // Offsets span the chunks accumulated within a call.
+    let terminated = |i: &mut Input<'_>| {
+        let data = record(i)?;
+        literal(b";").parse_next(i)?;
+        Ok::<_, ErrMode<ContextError>>(data)
+    };
+    let mut reader = BufReader::with_capacity(2, &b"R\x00\x02ab?"[..]);
+    let err = parse_partial(&mut reader, terminated).unwrap_err();
+    let inner = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    assert_eq!(inner.offset(), Some(5));
+
+    let mut reader = BufReader::with_capacity(2, &b"R\x00\x05abc"[..]);
+    let err = parse_partial(&mut reader, record).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn many_records() {
+    let mut input = vec![];
+    for i in 0..1000_u16 {
+        let len = i % 7;
+        input.push(b'R');
+        input.extend(&len.to_be_bytes());
+        input.extend(vec![len as u8; len as usize]);
+    }
+    let mut reader = BufReader::with_capacity(5, &input[..]);
+
+    for i in 0..1000_u16 {
+        let len = i % 7;
+        let data = parse_partial(&mut reader, record).unwrap();
+        assert_eq!(data, vec![len as u8; len as usize]);
+        assert_eq!(reader.capacity(), 5);
+    }
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+
+use omnom::prelude::*;
+use omnom::ByteSet;
+use std::io::{self, Write};
+
+/// A writer that records each write separately.
+#[derive(Default)]
+struct Writes(Vec<Vec<u8>>);
+
+impl Write for Writes {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.push(buf.to_vec());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn utf8_chars() {
+    let mut buf = vec![];
+    for ch in ['$', '£', 'ह', '€', '𐍈', '\u{10ffff}'] {
+        assert_eq!(buf.write_utf8_char(ch).unwrap(), ch.len_utf8());
+    }
+    assert_eq!(buf, "$£ह€𐍈\u{10ffff}".as_bytes());
+}
+
+#[test]
+fn escaped_round_trip() {
+    let special = ByteSet::new(b",\\");
+    let fields = ["plain", "a,b", r"back\slash", r",\,\", ""];
+    let mut buf = vec![];
+    for field in &fields {
+        buf.write_str_escaped(field, b'\\', &special).unwrap();
+        buf.push(b',');
+    }
+
+    let mut reader = &buf[..];
+    for field in &fields {
+        let mut out = vec![];
+        let (_, found) = reader.read_record(b',', b'\\', &mut out).unwrap();
+        assert!(found);
+        assert_eq!(out, field.as_bytes());
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn no_escapes_single_write() {
+    let mut writer = Writes::default();
+    let len = writer
+        .write_str_escaped("nothing to see here", b'\\', &ByteSet::new(b"\"\\"))
+        .unwrap();
+    assert_eq!(len, 19);
+    assert_eq!(writer.0, [b"nothing to see here"]);
+
+    let mut writer = Writes::default();
+    writer
+        .write_str_escaped("a\"bc\"", b'\\', &ByteSet::new(b"\"\\"))
+        .unwrap();
+    assert_eq!(writer.0, [&b"a"[..], b"\\\"", b"bc", b"\\\""]);
+}
+
+#[test]
+fn every_byte_escaped() {
+    let all: String = (0..0x80_u8).map(char::from).collect();
+    let mut buf = vec![];
+    let len = buf.write_str_escaped(&all, 0x7f, &ByteSet::ALL).unwrap();
+    assert_eq!(len, 256);
+    assert!(buf.chunks(2).zip(0..).all(|(pair, b)| pair == [0x7f, b]));
+
+    // Multi-byte characters are escaped byte by byte if their bytes are in
+    // the set.
+    let mut buf = vec![];
+    buf.write_str_escaped("é", b'%', &ByteSet::range(0x80..=0xff))
+        .unwrap();
+    assert_eq!(buf, b"%\xc3%\xa9");
+}